@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use flate2::read::GzDecoder;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tempfile::TempDir;
+
+/// Source tree extracted from an archive into a temporary directory.
+/// The directory is removed when this value is dropped.
+pub struct ExtractedArchive {
+    _temp_dir: TempDir,
+    root: PathBuf,
+}
+
+impl ExtractedArchive {
+    /// Directory containing the archived sources, with any single
+    /// top-level wrapper directory (e.g. `repo-1.0/`) already stripped
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+}
+
+/// Extract a `.tar`, `.tar.gz` or `.tgz` archive into a temporary directory
+pub fn extract_archive(archive_path: &Path) -> Result<ExtractedArchive> {
+    let file = File::open(archive_path).context(format!(
+        "Failed to open archive: {}",
+        archive_path.display()
+    ))?;
+
+    let name = archive_path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("");
+    let reader: Box<dyn Read> = if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+        Box::new(GzDecoder::new(file))
+    } else if name.ends_with(".tar") {
+        Box::new(file)
+    } else {
+        anyhow::bail!(
+            "Unsupported archive format: {}. Use: .tar, .tar.gz, .tgz",
+            archive_path.display()
+        );
+    };
+
+    let temp_dir = tempfile::tempdir().context("Failed to create temporary directory")?;
+    tar::Archive::new(reader)
+        .unpack(temp_dir.path())
+        .context(format!(
+            "Failed to extract archive: {}",
+            archive_path.display()
+        ))?;
+
+    let root = archive_root(temp_dir.path())?;
+
+    Ok(ExtractedArchive {
+        _temp_dir: temp_dir,
+        root,
+    })
+}
+
+/// The single top-level directory when it is all the archive holds, so
+/// archives created as `tar czf repo.tar.gz repo/` index the same as flat
+/// ones. Only that one wrapper is skipped: a project whose own layout is a
+/// lone `src/` keeps it.
+fn archive_root(dir: &Path) -> Result<PathBuf> {
+    let entries: Vec<_> = fs::read_dir(dir)?.filter_map(|e| e.ok()).collect();
+    match entries.as_slice() {
+        [only] if only.file_type()?.is_dir() => Ok(only.path()),
+        _ => Ok(dir.to_path_buf()),
+    }
+}
@@ -211,77 +211,43 @@ impl BenchmarkMetrics {
         );
 
         // Timing breakdown
-        let total_sec = self.timing_ms.total as f64 / 1000.0;
-        println!("{:<18} {:.1}s total", "Timing:".bold(), total_sec);
+        println!(
+            "{:<18} {} total",
+            "Timing:".bold(),
+            humanize_duration(Duration::from_millis(self.timing_ms.total))
+        );
 
         // Always show discovery if it was measured (even if fast)
-        if self.timing_ms.discovery > 0 || total_sec < 1.0 {
-            let pct = percentage(self.timing_ms.discovery, self.timing_ms.total);
-            let discovery_sec = self.timing_ms.discovery as f64 / 1000.0;
-            if discovery_sec < 0.1 {
-                println!("  {:<16} <0.1s ({:.1}%)", "Discovery:".dimmed(), pct);
-            } else {
+        let phases = [
+            ("Discovery:", self.timing_ms.discovery),
+            ("Parsing:", self.timing_ms.parsing),
+            ("Merging:", self.timing_ms.merging),
+            ("Indexing:", self.timing_ms.index_build),
+            ("Saving:", self.timing_ms.serialization),
+        ];
+        for (i, (label, ms)) in phases.into_iter().enumerate() {
+            if ms > 0 || (i == 0 && self.timing_ms.total < 1000) {
                 println!(
-                    "  {:<16} {:.1}s ({:.1}%)",
-                    "Discovery:".dimmed(),
-                    discovery_sec,
-                    pct
+                    "  {:<16} {} ({:.1}%)",
+                    label.dimmed(),
+                    humanize_duration(Duration::from_millis(ms)),
+                    percentage(ms, self.timing_ms.total)
                 );
             }
         }
 
-        if self.timing_ms.parsing > 0 {
-            let pct = percentage(self.timing_ms.parsing, self.timing_ms.total);
-            println!(
-                "  {:<16} {:.1}s ({:.1}%)",
-                "Parsing:".dimmed(),
-                self.timing_ms.parsing as f64 / 1000.0,
-                pct
-            );
-        }
-
-        if self.timing_ms.merging > 0 {
-            let pct = percentage(self.timing_ms.merging, self.timing_ms.total);
-            println!(
-                "  {:<16} {:.1}s ({:.1}%)",
-                "Merging:".dimmed(),
-                self.timing_ms.merging as f64 / 1000.0,
-                pct
-            );
-        }
-
-        if self.timing_ms.index_build > 0 {
-            let pct = percentage(self.timing_ms.index_build, self.timing_ms.total);
-            println!(
-                "  {:<16} {:.1}s ({:.1}%)",
-                "Indexing:".dimmed(),
-                self.timing_ms.index_build as f64 / 1000.0,
-                pct
-            );
-        }
-
-        if self.timing_ms.serialization > 0 {
-            let pct = percentage(self.timing_ms.serialization, self.timing_ms.total);
-            println!(
-                "  {:<16} {:.1}s ({:.1}%)",
-                "Saving:".dimmed(),
-                self.timing_ms.serialization as f64 / 1000.0,
-                pct
-            );
-        }
-
         // Memory
         println!(
-            "{:<18} {:.1} MB peak",
+            "{:<18} {} peak",
             "Memory:".bold(),
-            self.memory_mb.peak_mb
+            humanize_bytes(mb_to_bytes(self.memory_mb.peak_mb))
         );
 
         // Output
         println!(
-            "{:<18} {:.2} MB ({:.0}% compression)",
+            "{:<18} {} ({:.0}% compression)",
             "Output:".bold(),
-            self.output.file_size_mb,
+            humanize_bytes(mb_to_bytes(self.output.file_size_mb)),
             (1.0 - self.output.compression_ratio) * 100.0
         );
 
@@ -302,6 +268,41 @@ impl BenchmarkMetrics {
     }
 }
 
+/// Duration for display: `850µs`, `450ms`, `1.2s`, `3m 05s`
+pub fn humanize_duration(duration: Duration) -> String {
+    let micros = duration.as_micros();
+    if micros < 1_000 {
+        format!("{}µs", micros)
+    } else if micros < 1_000_000 {
+        format!("{}ms", micros / 1_000)
+    } else if duration.as_secs() < 60 {
+        format!("{:.1}s", duration.as_secs_f64())
+    } else {
+        let secs = duration.as_secs();
+        format!("{}m {:02}s", secs / 60, secs % 60)
+    }
+}
+
+/// Byte count for display (binary units): `512 B`, `780 KB`, `3.4 MB`, `1.2 GB`
+pub fn humanize_bytes(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+    if bytes < KB {
+        format!("{} B", bytes)
+    } else if bytes < MB {
+        format!("{} KB", bytes / KB)
+    } else if bytes < GB {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    } else {
+        format!("{:.1} GB", bytes as f64 / GB as f64)
+    }
+}
+
+fn mb_to_bytes(mb: f64) -> u64 {
+    (mb * 1_048_576.0) as u64
+}
+
 fn format_number(n: usize) -> String {
     let s = n.to_string();
     let chars: Vec<char> = s.chars().collect();
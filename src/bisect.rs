@@ -0,0 +1,147 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::Path;
+use std::process::Command;
+
+use crate::core::CodeGraph;
+
+/// Result of bisecting a commit range for package dependency cycles
+#[derive(Debug, Clone, PartialEq)]
+pub enum BisectOutcome {
+    /// The good commit already has cycles, so the range can't be bisected
+    AlreadyPresent { cycles: Vec<Vec<String>> },
+    /// The bad commit has no cycles
+    NotIntroduced,
+    /// First commit in the range whose graph has a package cycle
+    Introduced {
+        commit: String,
+        summary: String,
+        cycles: Vec<Vec<String>>,
+    },
+}
+
+/// Binary-search the commits between `good` and `bad` for the first one
+/// whose package graph contains a cycle.
+///
+/// Each probed commit is exported with `git archive` into a temporary
+/// directory and handed to `index`, so the working tree of `repo` is never
+/// touched. `repo` may be a subdirectory of the repository; the same
+/// subdirectory is indexed at every commit. Commits are taken from
+/// `git rev-list --ancestry-path good..bad`, and cycles are assumed to
+/// persist once introduced.
+pub fn bisect_cycle<F>(repo: &Path, good: &str, bad: &str, mut index: F) -> Result<BisectOutcome>
+where
+    F: FnMut(&Path) -> Result<CodeGraph>,
+{
+    let good = git(
+        repo,
+        &["rev-parse", "--verify", &format!("{}^{{commit}}", good)],
+    )
+    .with_context(|| format!("Unknown good revision: {}", good))?;
+    let bad = git(
+        repo,
+        &["rev-parse", "--verify", &format!("{}^{{commit}}", bad)],
+    )
+    .with_context(|| format!("Unknown bad revision: {}", bad))?;
+    let prefix = git(repo, &["rev-parse", "--show-prefix"])?;
+
+    let mut cycles_at = |commit: &str| -> Result<Vec<Vec<String>>> {
+        let checkout = export_commit(repo, commit)?;
+        let graph = index(&checkout.path().join(&prefix))
+            .with_context(|| format!("Failed to index commit {}", commit))?;
+        Ok(graph.find_package_cycles())
+    };
+
+    let good_cycles = cycles_at(&good)?;
+    if !good_cycles.is_empty() {
+        return Ok(BisectOutcome::AlreadyPresent {
+            cycles: good_cycles,
+        });
+    }
+
+    let candidates: Vec<String> = git(
+        repo,
+        &[
+            "rev-list",
+            "--ancestry-path",
+            "--reverse",
+            &format!("{}..{}", good, bad),
+        ],
+    )?
+    .lines()
+    .map(str::to_string)
+    .collect();
+    if candidates.last() != Some(&bad) {
+        anyhow::bail!("{} is not an ancestor of {}", good, bad);
+    }
+
+    let mut probed: HashMap<usize, Vec<Vec<String>>> = HashMap::new();
+    let bad_cycles = cycles_at(&bad)?;
+    if bad_cycles.is_empty() {
+        return Ok(BisectOutcome::NotIntroduced);
+    }
+    probed.insert(candidates.len() - 1, bad_cycles);
+
+    // Invariant: candidates[hi] has cycles, everything before lo has none
+    let (mut lo, mut hi) = (0, candidates.len() - 1);
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let cycles = cycles_at(&candidates[mid])?;
+        if cycles.is_empty() {
+            lo = mid + 1;
+        } else {
+            probed.insert(mid, cycles);
+            hi = mid;
+        }
+    }
+
+    let commit = candidates[hi].clone();
+    let summary = git(repo, &["log", "-1", "--format=%s", &commit])?;
+    Ok(BisectOutcome::Introduced {
+        commit,
+        summary,
+        cycles: probed.remove(&hi).unwrap_or_default(),
+    })
+}
+
+/// Extract the full tree of `commit` into a temporary directory
+fn export_commit(repo: &Path, commit: &str) -> Result<tempfile::TempDir> {
+    let toplevel = git(repo, &["rev-parse", "--show-toplevel"])?;
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(&toplevel)
+        .args(["archive", "--format=tar", commit])
+        .output()
+        .context("Failed to run git archive")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git archive failed for {}: {}",
+            commit,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    let dir = tempfile::tempdir()?;
+    tar::Archive::new(&output.stdout[..])
+        .unpack(dir.path())
+        .with_context(|| format!("Failed to extract commit {}", commit))?;
+    Ok(dir)
+}
+
+/// Run a git command in `repo` and return its trimmed stdout
+fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .context("Failed to run git")?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
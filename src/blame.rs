@@ -0,0 +1,123 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::core::CodeGraph;
+
+/// Commit that last touched a single line, as reported by `git blame`
+#[derive(Debug, Clone)]
+pub struct BlameLine {
+    pub commit: String,
+    pub author: String,
+    /// Author time (seconds since the Unix epoch)
+    pub author_time: i64,
+}
+
+/// Annotate nodes with the most recent commit touching their line range.
+///
+/// Sets `last_commit`, `last_author` and `last_commit_date` (Unix seconds)
+/// in node metadata. Blame runs once per file rather than once per node, but
+/// is still expensive on large repositories. Files that aren't tracked by git
+/// are skipped. Returns the number of annotated nodes.
+pub fn annotate_graph(graph: &mut CodeGraph) -> Result<usize> {
+    let mut by_file: HashMap<PathBuf, Vec<usize>> = HashMap::new();
+    for (idx, node) in graph.nodes.iter().enumerate() {
+        by_file.entry(node.file_path.clone()).or_default().push(idx);
+    }
+
+    let mut annotated = 0;
+
+    for (file, node_indices) in by_file {
+        let lines = match blame_file(&file) {
+            Ok(lines) => lines,
+            Err(_) => continue,
+        };
+
+        for idx in node_indices {
+            let node = &mut graph.nodes[idx];
+            let start = node.line.max(1);
+            let end = node.end_line.max(start).min(lines.len());
+
+            let latest = lines
+                .get(start - 1..end)
+                .and_then(|range| range.iter().max_by_key(|l| l.author_time));
+
+            if let Some(latest) = latest {
+                node.metadata
+                    .insert("last_commit".to_string(), latest.commit.clone());
+                node.metadata
+                    .insert("last_author".to_string(), latest.author.clone());
+                node.metadata.insert(
+                    "last_commit_date".to_string(),
+                    latest.author_time.to_string(),
+                );
+                annotated += 1;
+            }
+        }
+    }
+
+    Ok(annotated)
+}
+
+/// Blame every line of a file (index 0 is line 1)
+pub fn blame_file(file: &Path) -> Result<Vec<BlameLine>> {
+    let dir = match file.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    let name = file.file_name().context("Blame target has no file name")?;
+
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .arg("blame")
+        .arg("--line-porcelain")
+        .arg("--")
+        .arg(name)
+        .output()
+        .context("Failed to run git blame")?;
+
+    if !output.status.success() {
+        anyhow::bail!(
+            "git blame failed for {}: {}",
+            file.display(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+
+    Ok(parse_line_porcelain(&String::from_utf8_lossy(
+        &output.stdout,
+    )))
+}
+
+/// Parse `git blame --line-porcelain` output. Each line gets a full header
+/// block (commit, author, author-time, ...) followed by the tab-prefixed content.
+fn parse_line_porcelain(output: &str) -> Vec<BlameLine> {
+    let mut lines = Vec::new();
+    let mut commit = String::new();
+    let mut author = String::new();
+    let mut author_time = 0;
+
+    for line in output.lines() {
+        if line.starts_with('\t') {
+            lines.push(BlameLine {
+                commit: std::mem::take(&mut commit),
+                author: std::mem::take(&mut author),
+                author_time,
+            });
+        } else if let Some(value) = line.strip_prefix("author ") {
+            author = value.to_string();
+        } else if let Some(value) = line.strip_prefix("author-time ") {
+            author_time = value.parse().unwrap_or(0);
+        } else if commit.is_empty() {
+            if let Some(hash) = line.split_whitespace().next() {
+                if hash.len() >= 40 && hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                    commit = hash.to_string();
+                }
+            }
+        }
+    }
+
+    lines
+}
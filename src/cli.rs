@@ -16,6 +16,27 @@ pub struct Cli {
     /// Quiet mode (errors only)
     #[arg(short, long, global = true)]
     pub quiet: bool,
+
+    /// Rebuild index caches on load and fail if the cached copy diverges
+    #[arg(long, global = true, hide = true)]
+    pub validate_cache: bool,
+
+    /// Explain on stderr whether the .idx index cache was found, valid and used
+    #[arg(long, global = true)]
+    pub explain_cache: bool,
+
+    /// Emit newline-delimited JSON progress events to stderr (for IDEs)
+    #[arg(long, global = true)]
+    pub json_events: bool,
+
+    /// Never run git (timestamp change detection, no commit hash); for sandboxes
+    #[arg(
+        long,
+        global = true,
+        env = "CODENAV_NO_GIT",
+        value_parser = clap::builder::BoolishValueParser::new()
+    )]
+    pub no_git: bool,
 }
 
 #[derive(Subcommand)]
@@ -23,21 +44,26 @@ pub enum Commands {
     /// Index a codebase to build a navigable code graph
     Index {
         /// Directory to parse
-        directory: PathBuf,
+        #[arg(required_unless_present = "from_archive")]
+        directory: Option<PathBuf>,
+
+        /// Index sources from a .tar, .tar.gz or .tgz archive instead of a directory
+        #[arg(long, conflicts_with_all = ["directory", "incremental"])]
+        from_archive: Option<PathBuf>,
 
         /// Output file
         #[arg(short, long, default_value = "codenav.bin")]
         output: PathBuf,
 
-        /// Language: go, typescript, python (auto-detect if not specified)
+        /// Language: go, typescript, python, cpp, scala, rust (auto-detect if not specified)
         #[arg(short, long)]
         language: Option<String>,
 
-        /// Exclude files matching pattern (can be specified multiple times)
+        /// Exclude files matching a glob, relative to DIRECTORY (e.g. vendor, '**/*.pb.go'; repeatable)
         #[arg(short, long)]
         exclude: Vec<String>,
 
-        /// Include test files
+        /// Include test files (_test.go, test_*.py, *.test.ts, *.spec.ts), skipped by default
         #[arg(long)]
         include_tests: bool,
 
@@ -49,6 +75,78 @@ pub enum Commands {
         #[arg(long)]
         force: bool,
 
+        /// With --incremental, report changed/deleted files without reparsing or writing
+        #[arg(long, requires = "incremental")]
+        dry_run: bool,
+
+        /// JSON file with per-file (glob) and per-node metadata to merge into the graph
+        #[arg(long)]
+        metadata_file: Option<PathBuf>,
+
+        /// Collapse identical functions copied across files (e.g. vendored code) into one node
+        #[arg(long)]
+        dedup_identical: bool,
+
+        /// Use content hashes of (package, name, signature, parameters) as node IDs
+        /// instead of file:name:line, so identical code lines up across graphs
+        #[arg(long, conflicts_with = "incremental")]
+        content_ids: bool,
+
+        /// Stop recording call edges from a function after N distinct callees
+        #[arg(long)]
+        max_edges_per_node: Option<usize>,
+
+        /// Parse serially when the directory has fewer than N source files (default: 100; 0 = always parallel)
+        #[arg(long, value_name = "N")]
+        parallel_threshold: Option<usize>,
+
+        /// Index files that aren't valid UTF-8 (e.g. Latin-1) by replacing invalid bytes, instead of skipping them
+        #[arg(long)]
+        lossy_encoding: bool,
+
+        /// Print at most N per-file warnings, then summarize how many were suppressed
+        #[arg(long, value_name = "N", default_value = "50")]
+        max_warnings: usize,
+
+        /// Record the last commit/author touching each function (runs git blame per file)
+        #[arg(long, conflicts_with = "from_archive")]
+        blame: bool,
+
+        /// Embed the lookup indices in the graph file instead of a separate .idx cache
+        #[arg(long)]
+        embed_indices: bool,
+
+        /// Save the graph zstd-compressed at level N (1-22): smaller files, slower saves
+        #[arg(
+            long,
+            value_name = "N",
+            env = "CODENAV_COMPRESSION_LEVEL",
+            value_parser = clap::value_parser!(i32).range(1..=22),
+            conflicts_with_all = ["embed_indices", "shard_by"]
+        )]
+        compression_level: Option<i32>,
+
+        /// Store fan-in/fan-out in node metadata so query and analyze skip recomputing them
+        #[arg(long)]
+        precompute_degrees: bool,
+
+        /// Write a provenance manifest (tool version, file hashes, graph fingerprint) to this path
+        #[arg(long, value_name = "FILE")]
+        manifest: Option<PathBuf>,
+
+        /// Split the graph into one file per top-level package (only key: package)
+        #[arg(long, value_name = "KEY", requires = "shards_dir")]
+        shard_by: Option<String>,
+
+        /// Directory for --shard-by graph files and their manifest.json (instead of --output)
+        #[arg(
+            long,
+            value_name = "DIR",
+            requires = "shard_by",
+            conflicts_with = "incremental"
+        )]
+        shards_dir: Option<PathBuf>,
+
         /// Enable comprehensive benchmarking and output detailed metrics
         #[arg(long)]
         benchmark: bool,
@@ -64,10 +162,15 @@ pub enum Commands {
         #[arg(short, long, default_value = "codenav.bin")]
         graph: PathBuf,
 
-        /// Output format: table, json, tree
+        /// Output format: table, json, tree, shell
         #[arg(short, long, default_value = "table")]
         output: String,
 
+        /// Load a sharded index (see index --shard-by) instead of --graph;
+        /// only the shards needed for --package or an exact --name are read
+        #[arg(long, value_name = "DIR")]
+        shards_dir: Option<PathBuf>,
+
         /// Show count only
         #[arg(short, long)]
         count: bool,
@@ -80,7 +183,11 @@ pub enum Commands {
         #[arg(long)]
         name: Option<String>,
 
-        /// Filter by type: function, method, handler
+        /// Treat --name as a regular expression (e.g. '^handle', 'Get|Set')
+        #[arg(long, requires = "name")]
+        regex: bool,
+
+        /// Filter by type: function, method, handler, middleware, class, enum, type_alias, namespace
         #[arg(long)]
         r#type: Option<String>,
 
@@ -92,9 +199,58 @@ pub enum Commands {
         #[arg(long)]
         file: Option<String>,
 
-        /// Filter by tag
+        /// Filter by tags, comma-separated (e.g. handler,deprecated)
+        #[arg(long, alias = "tag", value_delimiter = ',')]
+        tags: Vec<String>,
+
+        /// How multiple --tags combine: and (all tags), or (any tag)
+        #[arg(long, default_value = "and")]
+        tag_mode: String,
+
+        /// Filter by documentation content (case-insensitive substring): doc
+        /// comments in Go and TypeScript, docstrings in Python
+        #[arg(long)]
+        doc_contains: Option<String>,
+
+        /// Minimum combined complexity (fan-in + fan-out); computed per node, O(n)
+        #[arg(long)]
+        min_complexity: Option<usize>,
+
+        /// Maximum combined complexity (fan-in + fan-out); computed per node, O(n)
+        #[arg(long)]
+        max_complexity: Option<usize>,
+
+        /// Only leaf functions: no calls, or only calls to code outside the graph
+        #[arg(long)]
+        leaves: bool,
+
+        /// Only isolated nodes: no callers and no callees (often dead code or parser gaps)
+        #[arg(long)]
+        isolated: bool,
+
+        /// Order results: topo (callees before callers, name order within cycles)
+        #[arg(long)]
+        sort: Option<String>,
+
+        /// Drop call edges originating in generated files
+        #[arg(long)]
+        exclude_generated_edges: bool,
+
+        /// Drop Python dunder methods (`__init__`, `__str__`, ...) and calls to them
+        #[arg(long)]
+        exclude_dunder: bool,
+
+        /// For a single match, also show its top callers and callees
+        #[arg(long)]
+        relations: bool,
+
+        /// Group results: package, file (table and json output)
         #[arg(long)]
-        tag: Option<String>,
+        group_by: Option<String>,
+
+        /// With --group-by, show at most N nodes per group
+        #[arg(long, requires = "group_by")]
+        limit_per_group: Option<usize>,
     },
 
     /// Trace function dependencies (what does this call?)
@@ -107,10 +263,18 @@ pub enum Commands {
         #[arg(long)]
         from: String,
 
-        /// Traversal depth (default: 1)
+        /// Traversal depth in call edges (default: 1)
         #[arg(short, long, default_value = "1")]
         depth: usize,
 
+        /// Skip results shallower than N levels (1 skips the direct callees); --depth stays the max
+        #[arg(long, value_name = "N", default_value = "0")]
+        min_depth: usize,
+
+        /// Follow at most K callees per function (first by name); cut nodes show "(+N more)"
+        #[arg(long, value_name = "K")]
+        max_branching: Option<usize>,
+
         /// Output format: tree, json, dot
         #[arg(short, long, default_value = "tree")]
         output: String,
@@ -119,9 +283,13 @@ pub enum Commands {
         #[arg(long)]
         show_lines: bool,
 
-        /// Filter by pattern
+        /// Keep only branches reaching callees that match: package:NAME, or a name substring
         #[arg(short, long)]
         filter: Option<String>,
+
+        /// Trace callers transitively instead: everything affected by changing the function
+        #[arg(long, conflicts_with_all = ["max_branching", "filter"])]
+        reverse: bool,
     },
 
     /// Find what calls a function (reverse dependencies)
@@ -146,6 +314,53 @@ pub enum Commands {
         show_lines: bool,
     },
 
+    /// Find types that implement an interface (or trait)
+    Implementers {
+        /// Graph file
+        #[arg(short, long, default_value = "codenav.bin")]
+        graph: PathBuf,
+
+        /// Interface name, optionally qualified (e.g. shapes.Shape)
+        interface: String,
+
+        /// Output format: table, json
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+
+    /// Cross-reference a symbol: its definitions, call sites and callees
+    Xref {
+        /// Graph file
+        #[arg(short, long, default_value = "codenav.bin")]
+        graph: PathBuf,
+
+        /// Function or method name
+        name: String,
+
+        /// Output format: table, json
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+
+    /// Select the tests that transitively call changed functions (test-impact analysis)
+    AffectedTests {
+        /// Graph file
+        #[arg(short, long, default_value = "codenav.bin")]
+        graph: PathBuf,
+
+        /// Changed function names or node IDs
+        changed_functions: Vec<String>,
+
+        /// Also treat functions added, renamed or changed since this older graph
+        /// as changed, plus the callers of functions it had that are now gone
+        #[arg(long, value_name = "OLD_GRAPH")]
+        since: Option<PathBuf>,
+
+        /// Output format: table, json, names (one test name per line)
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+
     /// Find call paths between two functions (default: shortest path)
     Path {
         /// Graph file
@@ -172,24 +387,94 @@ pub enum Commands {
         #[arg(long, default_value = "10")]
         max_depth: usize,
 
-        /// Output format: tree, json
+        /// Output format: tree, json, jumplist (file:line per step)
         #[arg(short, long, default_value = "tree")]
         output: String,
     },
 
+    /// Interactive prompt: load the graph once and run query, callers,
+    /// trace and path commands against it
+    Shell {
+        /// Graph file
+        #[arg(short, long, default_value = "codenav.bin")]
+        graph: PathBuf,
+    },
+
+    /// Explain how two functions are connected, with the call site of each hop
+    Explain {
+        /// Graph file
+        #[arg(short, long, default_value = "codenav.bin")]
+        graph: PathBuf,
+
+        /// Starting function
+        #[arg(long)]
+        from: String,
+
+        /// Target function
+        #[arg(long)]
+        to: String,
+
+        /// Maximum search depth
+        #[arg(long, default_value = "10")]
+        max_depth: usize,
+
+        /// Output format: text, json
+        #[arg(short, long, default_value = "text")]
+        output: String,
+    },
+
+    /// Summarize the graph: counts per node type, edge type and package, fan-in/out
+    Stats {
+        /// Graph file
+        #[arg(short, long, default_value = "codenav.bin")]
+        graph: PathBuf,
+
+        /// Show the N largest packages
+        #[arg(long, default_value = "10")]
+        top: usize,
+
+        /// Output format: table, json
+        #[arg(short, long, default_value = "table")]
+        output: String,
+    },
+
     /// Analyze graph for metrics and insights
     Analyze {
         /// Graph file
         #[arg(short, long, default_value = "codenav.bin")]
         graph: PathBuf,
 
-        /// Analysis type: complexity, coupling, hotspots, circular
+        /// Analysis type: complexity, coupling, hotspots, centrality, dead-code, circular, file-layout, abstractness, package-metrics, topo-order, nesting, unused-params
         analysis_type: String,
 
+        /// File to inspect (required for file-layout)
+        #[arg(long)]
+        file: Option<String>,
+
         /// Threshold for reporting
         #[arg(long)]
         threshold: Option<usize>,
 
+        /// Analyze only the subgraph reachable from this function (see extract)
+        #[arg(long)]
+        scope_from: Option<String>,
+
+        /// Call depth of the --scope-from subgraph
+        #[arg(long, default_value = "2", requires = "scope_from")]
+        scope_depth: usize,
+
+        /// With hotspots: don't count calls made from test code
+        #[arg(long)]
+        exclude_test_callers: bool,
+
+        /// With centrality: estimate from at most N source functions instead of all of them
+        #[arg(long, value_name = "N")]
+        samples: Option<usize>,
+
+        /// With dead-code: treat functions with this name as entrypoints too (repeatable)
+        #[arg(long = "entrypoint", value_name = "NAME")]
+        entrypoints: Vec<String>,
+
         /// Limit results
         #[arg(long)]
         limit: Option<usize>,
@@ -209,7 +494,7 @@ pub enum Commands {
         #[arg(short, long)]
         output: PathBuf,
 
-        /// Format: graphml, dot, csv
+        /// Format: graphml, dot, csv, parquet, markdown, mermaid, plantuml, cypher, cytoscape, sqlite
         #[arg(short, long)]
         format: String,
 
@@ -220,6 +505,31 @@ pub enum Commands {
         /// Exclude test files
         #[arg(long)]
         exclude_tests: bool,
+
+        /// Drop edges whose target is not among the exported nodes (no dangling edges in GraphML/DOT)
+        #[arg(long)]
+        drop_dangling_edges: bool,
+
+        /// Drop call edges originating in generated files
+        #[arg(long)]
+        exclude_generated_edges: bool,
+
+        /// Drop Python dunder methods (`__init__`, `__str__`, ...) and calls to them
+        #[arg(long)]
+        exclude_dunder: bool,
+
+        /// Keep only calls from a public function to a public function (other
+        /// edge types are kept)
+        #[arg(long)]
+        only_public_edges: bool,
+
+        /// With --format dot: single-line DOT without styling, for embedding
+        #[arg(long)]
+        compact: bool,
+
+        /// With --format dot, graphml or mermaid: embed a legend of node types and edge labels
+        #[arg(long, conflicts_with = "compact")]
+        with_legend: bool,
     },
 
     /// Extract focused subgraph rooted at a node
@@ -232,13 +542,52 @@ pub enum Commands {
         #[arg(long)]
         from: String,
 
-        /// Traversal depth (default: 2)
+        /// Traversal depth in call edges, same as trace (default: 2)
         #[arg(short, long, default_value = "2")]
         depth: usize,
 
+        /// Edges to follow: out (callees), in (callers), both
+        #[arg(long, default_value = "out")]
+        direction: String,
+
+        /// Also include the direct callers of the starting node, for context
+        #[arg(long)]
+        include_callers: bool,
+
         /// Output file
         #[arg(short, long)]
         output: PathBuf,
+
+        /// Save the subgraph zstd-compressed at level N (1-22)
+        #[arg(
+            long,
+            value_name = "N",
+            env = "CODENAV_COMPRESSION_LEVEL",
+            value_parser = clap::value_parser!(i32).range(1..=22)
+        )]
+        compression_level: Option<i32>,
+    },
+
+    /// Find functions with signatures similar to a given function
+    Similar {
+        /// Graph file
+        #[arg(short, long, default_value = "codenav.bin")]
+        graph: PathBuf,
+
+        /// Function or method name
+        function: String,
+
+        /// Minimum similarity score (0.0 - 1.0)
+        #[arg(long, default_value = "0.5")]
+        threshold: f32,
+
+        /// Limit results
+        #[arg(long)]
+        limit: Option<usize>,
+
+        /// Output format: table, json
+        #[arg(short, long, default_value = "table")]
+        output: String,
     },
 
     /// Compare two graphs to detect changes
@@ -265,8 +614,45 @@ pub enum Commands {
         #[arg(long)]
         complexity_threshold: Option<usize>,
 
+        /// Exit non-zero if any function's complexity (fan-in + fan-out) grew by at least T
+        #[arg(long, value_name = "T")]
+        fail_on_complexity_increase: Option<usize>,
+
         /// Output format: table, json
         #[arg(short, long, default_value = "table")]
         output: String,
     },
+
+    /// Combine several graph files into one (node IDs present in more than one are kept once)
+    Merge {
+        /// Graph files to combine, e.g. one per language
+        #[arg(required = true, num_args = 2..)]
+        inputs: Vec<PathBuf>,
+
+        /// Output graph file
+        #[arg(short, long, default_value = "codenav.bin")]
+        output: PathBuf,
+    },
+
+    /// Find the commit that introduced a package dependency cycle (git bisect)
+    BisectCycle {
+        /// Directory inside the git repository to index at each commit
+        #[arg(default_value = ".")]
+        directory: PathBuf,
+
+        /// Language: go, typescript, python, cpp, scala, rust (default: go)
+        #[arg(short, long)]
+        language: Option<String>,
+
+        /// Known commit without cycles
+        #[arg(long)]
+        good: String,
+
+        /// Known commit with a cycle
+        #[arg(long, default_value = "HEAD")]
+        bad: String,
+    },
+
+    /// Print the JSON Schema (draft 2020-12) of the exported graph format
+    Schema,
 }
@@ -0,0 +1,453 @@
+//! Query, trace, callers and path lookups shared by the one-shot
+//! subcommands and the interactive `codenav shell`.
+
+use crate::core::{CodeGraph, Edge, Node, NodeType, TraceResult, CUSTOM_NODE_TYPES};
+use anyhow::{Context, Result};
+use colored::Colorize;
+use std::io::{BufRead, Write};
+
+/// Default `trace` depth, matching `codenav trace --depth`
+pub const DEFAULT_TRACE_DEPTH: usize = 1;
+
+/// Default `path` search depth, matching `codenav path --max-depth`
+pub const DEFAULT_PATH_MAX_DEPTH: usize = 10;
+
+const SHELL_HELP: &str = "\
+Commands:
+  query [name=N] [type=T] [package=P] [file=F] [limit=L]
+                        Find nodes (name accepts * wildcards)
+  callers <function>    Who calls a function
+  trace <function> [depth]
+                        Dependencies of a function (default depth 1)
+  path <from> <to> [max_depth]
+                        Shortest call path (default max depth 10)
+  help                  Show this help
+  quit                  Leave the shell (also: exit, Ctrl-D)";
+
+/// Parse a `--type` value: a built-in node type or a custom subtype
+pub fn parse_node_type(value: &str) -> Result<NodeType> {
+    Ok(match value {
+        "function" => NodeType::Function,
+        "method" => NodeType::Method,
+        "handler" => NodeType::HttpHandler,
+        "middleware" => NodeType::Middleware,
+        "class" => NodeType::Class,
+        custom if CUSTOM_NODE_TYPES.contains(&custom) => NodeType::Custom(custom.to_string()),
+        _ => anyhow::bail!("Unknown node type: {}", value),
+    })
+}
+
+/// The node a trace or path starts from: the first definition of `name`
+pub fn find_start<'a>(graph: &'a CodeGraph, name: &str) -> Result<&'a Node> {
+    graph
+        .get_nodes_by_name(name)
+        .into_iter()
+        .next()
+        .with_context(|| format!("Function not found: {}", name))
+}
+
+/// Paths from `from_id` to `to`, shortest first: up to `limit` paths, every
+/// path with `all`, or just the shortest one (BFS) by default
+pub fn find_paths(
+    graph: &CodeGraph,
+    from_id: &str,
+    to: &str,
+    limit: Option<usize>,
+    all: bool,
+    max_depth: usize,
+) -> Vec<Vec<String>> {
+    let limit = match (limit, all) {
+        (Some(n), _) => n,
+        (None, true) => usize::MAX,
+        (None, false) => {
+            return graph
+                .find_shortest_path(from_id, to, max_depth)
+                .into_iter()
+                .collect();
+        }
+    };
+    let mut paths = graph.find_paths_limited(from_id, to, max_depth, limit);
+    paths.sort_by_key(|p| p.len());
+    paths
+}
+
+/// Name/type/package/file filters of a shell `query`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NodeQuery {
+    pub name: Option<String>,
+    pub node_type: Option<NodeType>,
+    pub package: Option<String>,
+    pub file: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl NodeQuery {
+    /// Matching nodes, starting from the name or type index when possible
+    pub fn run<'a>(&self, graph: &'a CodeGraph) -> Vec<&'a Node> {
+        let mut nodes: Vec<&Node> = match (&self.name, &self.node_type) {
+            (Some(name), _) if !name.contains('*') => graph.get_nodes_by_name(name),
+            (_, Some(node_type)) => graph.get_nodes_by_type(node_type),
+            _ => graph.nodes.iter().collect(),
+        };
+
+        if let Some(name) = self.name.as_ref().filter(|n| n.contains('*')) {
+            let pattern = name.replace('*', "");
+            nodes.retain(|n| n.name.contains(&pattern));
+        }
+        if let Some(node_type) = &self.node_type {
+            nodes.retain(|n| n.node_type == *node_type);
+        }
+        if let Some(package) = &self.package {
+            nodes.retain(|n| n.package == *package);
+        }
+        if let Some(file) = &self.file {
+            nodes.retain(|n| n.file_path.to_string_lossy().contains(file.as_str()));
+        }
+        if let Some(limit) = self.limit {
+            nodes.truncate(limit);
+        }
+        nodes
+    }
+}
+
+/// Print nodes in the `query --output table` layout
+pub fn print_node_table(nodes: &[&Node]) {
+    if nodes.is_empty() {
+        println!("{}", "No nodes found".yellow());
+        return;
+    }
+
+    println!(
+        "{:<40} {:<15} {:<30} {:<10}",
+        "Name".bold(),
+        "Type".bold(),
+        "Package".bold(),
+        "Line".bold()
+    );
+    println!("{}", "-".repeat(95));
+
+    for node in nodes {
+        let type_str = match &node.node_type {
+            NodeType::Function => "Function".green(),
+            NodeType::Method => "Method".blue(),
+            NodeType::HttpHandler => "HTTP Handler".yellow(),
+            NodeType::Middleware => "Middleware".magenta(),
+            NodeType::Class => "Class".cyan(),
+            NodeType::Custom(kind) => kind.as_str().normal(),
+        };
+
+        println!(
+            "{:<40} {:<15} {:<30} {:<10}",
+            node.display_name(),
+            format!("{}", type_str),
+            node.package,
+            node.line
+        );
+    }
+
+    println!();
+    println!(
+        "{} {} nodes found",
+        "→".blue(),
+        nodes.len().to_string().cyan()
+    );
+}
+
+/// Print dependencies in the `trace --output tree` layout. Callees cut by
+/// `--max-branching` are noted as `(+N more)`; `root_omitted` counts those of
+/// the start node.
+pub fn print_trace_tree(from: &str, traces: &[TraceResult], show_lines: bool, root_omitted: usize) {
+    let more = |omitted: usize| {
+        if omitted > 0 {
+            format!(" (+{} more)", omitted).yellow().to_string()
+        } else {
+            String::new()
+        }
+    };
+
+    println!(
+        "{}{}",
+        format!("Dependencies of {}", from).bold(),
+        more(root_omitted)
+    );
+    println!();
+
+    // Indent relative to the shallowest result, so `--min-depth` trees start flush
+    let base = traces.iter().map(|trace| trace.depth).min().unwrap_or(0);
+    for trace in traces {
+        let indent = "  ".repeat(trace.depth - base);
+        let line_info = if show_lines {
+            format!(" ({}:{})", trace.file_path.display(), trace.line)
+        } else {
+            String::new()
+        };
+
+        println!(
+            "{}├─ {}{}{}",
+            indent,
+            trace.to_name.cyan(),
+            line_info.dimmed(),
+            more(trace.omitted_callees.unwrap_or(0))
+        );
+    }
+
+    println!();
+    println!("{} {} dependencies found", "→".blue(), traces.len());
+}
+
+/// Print a `trace --reverse` result: callers indented under the function
+/// they call, relative to the shallowest result
+pub fn print_impact_tree(graph: &CodeGraph, to: &str, traces: &[TraceResult], show_lines: bool) {
+    println!("{}", format!("Transitive callers of {}", to).bold());
+    println!();
+
+    let base = traces.iter().map(|trace| trace.depth).min().unwrap_or(0);
+    for trace in traces {
+        let indent = "  ".repeat(trace.depth - base);
+        let line_info = if show_lines {
+            format!(" ({}:{})", trace.file_path.display(), trace.line)
+        } else {
+            String::new()
+        };
+        let caller = graph
+            .get_node_by_id(&trace.from_id)
+            .map_or(trace.from_id.as_str(), |node| node.name.as_str());
+        println!("{}├─ {}{}", indent, caller.cyan(), line_info.dimmed());
+    }
+
+    println!();
+    println!("{} {} callers found", "→".blue(), traces.len());
+}
+
+/// Print callers in the `callers --output tree` layout
+pub fn print_callers_tree(graph: &CodeGraph, function: &str, callers: &[&Edge], show_lines: bool) {
+    println!("{}", format!("Callers of {}", function).bold());
+    println!();
+
+    for caller in callers {
+        let line_info = if show_lines {
+            format!(" ({}:{})", caller.file_path.display(), caller.line)
+        } else {
+            String::new()
+        };
+
+        // Try to get the calling function name from the node
+        if let Some(node) = graph.get_node_by_id(&caller.from) {
+            println!("├─ {}{}", node.name.cyan(), line_info.dimmed());
+        } else {
+            println!("├─ {}{}", caller.from.cyan(), line_info.dimmed());
+        }
+    }
+
+    println!();
+    println!("{} {} callers found", "→".blue(), callers.len());
+}
+
+/// Print callers in the `callers --output table` layout
+pub fn print_callers_table(graph: &CodeGraph, callers: &[&Edge]) {
+    println!(
+        "{:<40} {:<30} {:<10}",
+        "Caller".bold(),
+        "File".bold(),
+        "Line".bold()
+    );
+    println!("{}", "-".repeat(80));
+
+    for caller in callers {
+        let caller_name = graph
+            .get_node_by_id(&caller.from)
+            .map(|n| n.name.as_str())
+            .unwrap_or(&caller.from);
+
+        println!(
+            "{:<40} {:<30} {:<10}",
+            caller_name,
+            caller
+                .file_path
+                .file_name()
+                .and_then(|n| n.to_str())
+                .unwrap_or(""),
+            caller.line
+        );
+    }
+
+    println!();
+    println!("{} {} callers found", "→".blue(), callers.len());
+}
+
+/// Print paths in the `path --output tree` layout
+pub fn print_paths_tree(from: &str, to: &str, paths: &[Vec<String>]) {
+    println!("{}", format!("Paths from {} to {}", from, to).bold());
+    println!();
+
+    for (idx, path) in paths.iter().enumerate() {
+        println!("{} Path {} (length: {})", "→".blue(), idx + 1, path.len());
+        for (i, step) in path.iter().enumerate() {
+            let prefix = if i == path.len() - 1 {
+                "└─"
+            } else {
+                "├─"
+            };
+            println!("  {} {}", prefix, step.cyan());
+        }
+        println!();
+    }
+
+    println!("{} {} paths found", "→".blue(), paths.len());
+}
+
+/// One line of input to `codenav shell`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShellCommand {
+    Query(NodeQuery),
+    Callers {
+        function: String,
+    },
+    Trace {
+        from: String,
+        depth: usize,
+    },
+    Path {
+        from: String,
+        to: String,
+        max_depth: usize,
+    },
+    Help,
+    Quit,
+}
+
+/// Parse a shell line; blank lines and `#` comments yield `None`
+pub fn parse_line(line: &str) -> Result<Option<ShellCommand>> {
+    let mut words = line.split_whitespace();
+    let Some(command) = words.next().filter(|w| !w.starts_with('#')) else {
+        return Ok(None);
+    };
+    let args: Vec<&str> = words.collect();
+
+    let depth_arg = |arg: Option<&&str>, default: usize| -> Result<usize> {
+        arg.map_or(Ok(default), |d| {
+            d.parse()
+                .with_context(|| format!("Invalid depth: {} (expected a number)", d))
+        })
+    };
+
+    let parsed = match (command, args.as_slice()) {
+        ("query", filters) => {
+            let mut query = NodeQuery::default();
+            for filter in filters {
+                let Some((key, value)) = filter.split_once('=') else {
+                    anyhow::bail!("Expected key=value, got: {}", filter);
+                };
+                match key {
+                    "name" => query.name = Some(value.to_string()),
+                    "type" => query.node_type = Some(parse_node_type(value)?),
+                    "package" => query.package = Some(value.to_string()),
+                    "file" => query.file = Some(value.to_string()),
+                    "limit" => {
+                        query.limit = Some(
+                            value
+                                .parse()
+                                .with_context(|| format!("Invalid limit: {}", value))?,
+                        )
+                    }
+                    _ => anyhow::bail!(
+                        "Unknown query filter: {}. Use: name, type, package, file, limit",
+                        key
+                    ),
+                }
+            }
+            ShellCommand::Query(query)
+        }
+        ("callers", [function]) => ShellCommand::Callers {
+            function: function.to_string(),
+        },
+        ("trace", [from, rest @ ..]) if rest.len() <= 1 => ShellCommand::Trace {
+            from: from.to_string(),
+            depth: depth_arg(rest.first(), DEFAULT_TRACE_DEPTH)?,
+        },
+        ("path", [from, to, rest @ ..]) if rest.len() <= 1 => ShellCommand::Path {
+            from: from.to_string(),
+            to: to.to_string(),
+            max_depth: depth_arg(rest.first(), DEFAULT_PATH_MAX_DEPTH)?,
+        },
+        ("help" | "?", []) => ShellCommand::Help,
+        ("quit" | "exit", []) => ShellCommand::Quit,
+        ("callers" | "trace" | "path" | "help" | "?" | "quit" | "exit", _) => {
+            anyhow::bail!("Wrong arguments for {}. Type `help` for usage", command)
+        }
+        _ => anyhow::bail!("Unknown command: {}. Type `help` for usage", command),
+    };
+    Ok(Some(parsed))
+}
+
+/// Run one shell command against the loaded graph
+pub fn execute(graph: &CodeGraph, command: &ShellCommand) -> Result<()> {
+    match command {
+        ShellCommand::Query(query) => print_node_table(&query.run(graph)),
+        ShellCommand::Callers { function } => {
+            let callers = graph.find_callers(function);
+            if callers.is_empty() {
+                println!("{}", format!("No callers found for {}", function).yellow());
+            } else {
+                print_callers_tree(graph, function, &callers, true);
+            }
+        }
+        ShellCommand::Trace { from, depth } => {
+            let start = find_start(graph, from)?;
+            let traces = graph.trace_dependencies(&start.id, *depth);
+            if traces.is_empty() {
+                println!("{}", "No dependencies found".yellow());
+            } else {
+                print_trace_tree(from, &traces, false, 0);
+            }
+        }
+        ShellCommand::Path {
+            from,
+            to,
+            max_depth,
+        } => {
+            let start = find_start(graph, from)?;
+            let paths = find_paths(graph, &start.id, to, None, false, *max_depth);
+            if paths.is_empty() {
+                println!(
+                    "{}",
+                    format!("No path found from {} to {}", from, to).yellow()
+                );
+            } else {
+                print_paths_tree(from, to, &paths);
+            }
+        }
+        ShellCommand::Help => println!("{}", SHELL_HELP),
+        ShellCommand::Quit => {}
+    }
+    Ok(())
+}
+
+/// Read commands from `input` until `quit` or end of input. Errors in a
+/// single command are reported and the loop carries on.
+pub fn run_shell(graph: &CodeGraph, input: impl BufRead, prompt: bool) -> Result<()> {
+    let mut lines = input.lines();
+    loop {
+        if prompt {
+            print!("{} ", "codenav>".bold());
+            std::io::stdout().flush()?;
+        }
+        let Some(line) = lines.next() else {
+            if prompt {
+                println!();
+            }
+            return Ok(());
+        };
+
+        match parse_line(&line?) {
+            Ok(Some(ShellCommand::Quit)) => return Ok(()),
+            Ok(Some(command)) => {
+                if let Err(e) = execute(graph, &command) {
+                    eprintln!("{} {}", "error:".red(), e);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => eprintln!("{} {}", "error:".red(), e),
+        }
+    }
+}
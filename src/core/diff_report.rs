@@ -0,0 +1,223 @@
+use super::graph::{CodeGraph, GraphDiff};
+use super::node::{Node, NodeType};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Version of the `diff --output json` schema. Bump on any breaking change
+/// (renamed/removed fields or changed meaning); adding fields is non-breaking.
+///
+/// 2: `node_type` of custom nodes is an object (`{"custom": "enum"}`).
+pub const DIFF_REPORT_SCHEMA_VERSION: u32 = 2;
+
+/// Stable, versioned JSON report produced by `diff --output json`.
+///
+/// Unlike [`GraphDiff`], whose fields follow internal needs, this is the
+/// documented contract for CI tooling. Lists are sorted by node ID.
+/// A node whose ID changed only because it moved (same package, name and
+/// type; different file or line) is reported under `moved`, and one with
+/// the same body under a new name under `renamed`, not as a removal plus an
+/// addition.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffReport {
+    pub schema_version: u32,
+    pub summary: DiffSummary,
+    pub added: Vec<NodeRef>,
+    pub removed: Vec<NodeRef>,
+    pub changed: Vec<SignatureChange>,
+    pub moved: Vec<NodeMove>,
+    pub renamed: Vec<NodeRename>,
+    pub complexity: Vec<ComplexityDelta>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiffSummary {
+    pub added: usize,
+    pub removed: usize,
+    pub changed: usize,
+    pub moved: usize,
+    pub renamed: usize,
+    pub added_edges: usize,
+    pub removed_edges: usize,
+}
+
+/// A node as it appears in one of the two graphs
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeRef {
+    pub id: String,
+    pub name: String,
+    #[serde(rename = "type")]
+    pub node_type: NodeType,
+    pub package: String,
+    pub file: String,
+    pub line: usize,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SignatureChange {
+    pub id: String,
+    pub name: String,
+    pub old_signature: String,
+    pub new_signature: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeMove {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub node_type: NodeType,
+    pub package: String,
+    pub old: NodeRef,
+    pub new: NodeRef,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeRename {
+    pub old: NodeRef,
+    pub new: NodeRef,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComplexityDelta {
+    pub id: String,
+    pub name: String,
+    pub old_fan_in: usize,
+    pub new_fan_in: usize,
+    pub old_fan_out: usize,
+    pub new_fan_out: usize,
+    /// Change in fan-in + fan-out (positive = more complex)
+    pub delta: i32,
+}
+
+impl DiffReport {
+    /// Build the report for `old.diff(new)`. With `complexity_threshold`,
+    /// only complexity deltas of at least that magnitude are included.
+    pub fn new(
+        old: &CodeGraph,
+        new: &CodeGraph,
+        diff: &GraphDiff,
+        complexity_threshold: Option<usize>,
+    ) -> Self {
+        let mut removed: Vec<NodeRef> = diff
+            .removed_nodes
+            .iter()
+            .filter_map(|id| old.get_node_by_id(id))
+            .map(NodeRef::from)
+            .collect();
+        let mut added: Vec<NodeRef> = diff
+            .added_nodes
+            .iter()
+            .filter_map(|id| new.get_node_by_id(id))
+            .map(NodeRef::from)
+            .collect();
+        removed.sort_by(|a, b| a.id.cmp(&b.id));
+        added.sort_by(|a, b| a.id.cmp(&b.id));
+
+        // Pair removals with additions of the same package/name/type
+        let mut removed_by_key: HashMap<(String, String, NodeType), Vec<NodeRef>> = HashMap::new();
+        for node in removed.into_iter().rev() {
+            removed_by_key
+                .entry(node.move_key())
+                .or_default()
+                .push(node);
+        }
+
+        let mut moved = Vec::new();
+        let mut still_added = Vec::new();
+        for node in added {
+            match removed_by_key.get_mut(&node.move_key()).and_then(Vec::pop) {
+                Some(old_ref) => moved.push(NodeMove {
+                    name: node.name.clone(),
+                    node_type: node.node_type.clone(),
+                    package: node.package.clone(),
+                    old: old_ref,
+                    new: node,
+                }),
+                None => still_added.push(node),
+            }
+        }
+        let mut still_removed: Vec<NodeRef> = removed_by_key.into_values().flatten().collect();
+        still_removed.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let renamed: Vec<NodeRename> = diff
+            .renamed_nodes
+            .iter()
+            .filter_map(|r| {
+                Some(NodeRename {
+                    old: NodeRef::from(old.get_node_by_id(&r.old_id)?),
+                    new: NodeRef::from(new.get_node_by_id(&r.new_id)?),
+                })
+            })
+            .collect();
+
+        let mut changed: Vec<SignatureChange> = diff
+            .changed_nodes
+            .iter()
+            .filter(|c| c.old_signature != c.new_signature)
+            .map(|c| SignatureChange {
+                id: c.node_id.clone(),
+                name: c.node_name.clone(),
+                old_signature: c.old_signature.clone(),
+                new_signature: c.new_signature.clone(),
+            })
+            .collect();
+        changed.sort_by(|a, b| a.id.cmp(&b.id));
+
+        let mut complexity: Vec<ComplexityDelta> = diff
+            .complexity_changes
+            .iter()
+            .filter(|c| complexity_threshold.is_none_or(|t| c.change.unsigned_abs() as usize >= t))
+            .map(|c| ComplexityDelta {
+                id: c.node_id.clone(),
+                name: c.node_name.clone(),
+                old_fan_in: c.old_fan_in,
+                new_fan_in: c.new_fan_in,
+                old_fan_out: c.old_fan_out,
+                new_fan_out: c.new_fan_out,
+                delta: c.change,
+            })
+            .collect();
+        complexity.sort_by(|a, b| a.id.cmp(&b.id));
+
+        DiffReport {
+            schema_version: DIFF_REPORT_SCHEMA_VERSION,
+            summary: DiffSummary {
+                added: still_added.len(),
+                removed: still_removed.len(),
+                changed: changed.len(),
+                moved: moved.len(),
+                renamed: renamed.len(),
+                added_edges: diff.added_edges_count,
+                removed_edges: diff.removed_edges_count,
+            },
+            added: still_added,
+            removed: still_removed,
+            changed,
+            moved,
+            renamed,
+            complexity,
+        }
+    }
+}
+
+impl NodeRef {
+    fn move_key(&self) -> (String, String, NodeType) {
+        (
+            self.package.clone(),
+            self.name.clone(),
+            self.node_type.clone(),
+        )
+    }
+}
+
+impl From<&Node> for NodeRef {
+    fn from(node: &Node) -> Self {
+        NodeRef {
+            id: node.id.clone(),
+            name: node.name.clone(),
+            node_type: node.node_type.clone(),
+            package: node.package.clone(),
+            file: node.file_path.to_string_lossy().to_string(),
+            line: node.line,
+        }
+    }
+}
@@ -8,12 +8,16 @@ pub enum EdgeType {
     Calls,
     Imports,
     Implements,
+    Extends,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Edge {
     pub from: String,
     pub to: String,
+    /// Resolved target node ID, when the callee name maps to a single node
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_id: Option<String>,
     #[serde(rename = "type")]
     pub edge_type: EdgeType,
     pub call_site: String,
@@ -35,6 +39,7 @@ impl Edge {
         Self {
             from,
             to,
+            to_id: None,
             edge_type,
             call_site,
             file_path,
@@ -42,4 +47,10 @@ impl Edge {
             metadata: HashMap::new(),
         }
     }
+
+    /// Whether the call sits behind a branch or loop in its caller (`None`
+    /// for edges indexed before this was recorded)
+    pub fn is_conditional(&self) -> Option<bool> {
+        self.metadata.get("conditional")?.parse().ok()
+    }
 }
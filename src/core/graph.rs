@@ -1,11 +1,16 @@
-use super::edge::Edge;
-use super::node::{Node, NodeType};
+use super::edge::{Edge, EdgeType};
+use super::node::{is_dunder, is_test_file, Node, NodeType, TEST_TAG};
 use crate::serializer::index_cache::SerializedIndices;
+use regex::Regex;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::hash_map::DefaultHasher;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::hash::{Hash, Hasher};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Edge metadata listing the node IDs an ambiguous call could resolve to
+const CANDIDATES_KEY: &str = "candidates";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphMetadata {
@@ -18,6 +23,9 @@ pub struct GraphMetadata {
     #[serde(default)]
     pub file_metadata: HashMap<String, FileMetadata>,
     pub git_commit_hash: Option<String>,
+    /// Files detected as generated code during parsing
+    #[serde(default)]
+    pub generated_files: HashSet<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -25,6 +33,10 @@ pub struct GraphStats {
     pub total_nodes: usize,
     pub total_edges: usize,
     pub files_parsed: usize,
+    /// Nodes with no edges in or out, counted at index time; many of them
+    /// usually mean calls the parser couldn't resolve
+    #[serde(default)]
+    pub isolated_nodes: usize,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,12 +57,16 @@ pub struct CodeGraph {
     pub node_by_id: HashMap<String, usize>,
     #[serde(skip, default)]
     pub outgoing: HashMap<String, Vec<usize>>,
+    /// Callee name -> edges into it. `Imports` edges are left out: their
+    /// target is a module path, not a node name.
     #[serde(skip, default)]
     pub incoming: HashMap<String, Vec<usize>>,
     #[serde(skip, default)]
     pub by_name: HashMap<String, Vec<usize>>,
     #[serde(skip, default)]
     pub by_type: HashMap<NodeType, Vec<usize>>,
+    #[serde(skip, default)]
+    pub by_tag: HashMap<String, Vec<usize>>,
 
     // Track if indices need rebuilding (Phase 1 optimization)
     #[serde(skip, default)]
@@ -72,9 +88,11 @@ impl CodeGraph {
                     total_nodes: 0,
                     total_edges: 0,
                     files_parsed: 0,
+                    isolated_nodes: 0,
                 },
                 file_metadata: HashMap::new(),
                 git_commit_hash: None,
+                generated_files: HashSet::new(),
             },
             nodes: Vec::new(),
             edges: Vec::new(),
@@ -83,6 +101,7 @@ impl CodeGraph {
             incoming: HashMap::new(),
             by_name: HashMap::new(),
             by_type: HashMap::new(),
+            by_tag: HashMap::new(),
             indices_dirty: false,
         }
     }
@@ -107,9 +126,11 @@ impl CodeGraph {
                     total_nodes: 0,
                     total_edges: 0,
                     files_parsed: 0,
+                    isolated_nodes: 0,
                 },
                 file_metadata: HashMap::new(),
                 git_commit_hash: None,
+                generated_files: HashSet::new(),
             },
             nodes: Vec::with_capacity(estimated_nodes),
             edges: Vec::with_capacity(estimated_edges),
@@ -118,6 +139,7 @@ impl CodeGraph {
             incoming: HashMap::with_capacity(estimated_edges / 2),
             by_name: HashMap::with_capacity(estimated_nodes / 2),
             by_type: HashMap::with_capacity(10),
+            by_tag: HashMap::new(),
             indices_dirty: false,
         }
     }
@@ -127,6 +149,9 @@ impl CodeGraph {
         let id = node.id.clone();
         let name = node.name.clone();
         let node_type = node.node_type.clone();
+        for tag in &node.tags {
+            self.by_tag.entry(tag.clone()).or_default().push(idx);
+        }
 
         self.nodes.push(node);
         self.node_by_id.insert(id, idx);
@@ -141,13 +166,42 @@ impl CodeGraph {
         let from = edge.from.clone();
         let to = edge.to.clone();
 
+        let is_import = edge.edge_type == EdgeType::Imports;
+
         self.edges.push(edge);
         self.outgoing.entry(from).or_default().push(idx);
-        self.incoming.entry(to).or_default().push(idx);
+        if !is_import {
+            self.incoming.entry(to).or_default().push(idx);
+        }
 
         self.metadata.stats.total_edges = self.edges.len();
     }
 
+    /// Record an import of `target` (a package or module path) by the file
+    /// at `file_path`. Import edges originate from the file itself: `from` is
+    /// the file path and the importing package is kept in the `package`
+    /// metadata.
+    pub fn add_import(
+        &mut self,
+        file_path: &Path,
+        package: &str,
+        target: String,
+        import_text: String,
+        line: usize,
+    ) {
+        let mut edge = Edge::new(
+            file_path.display().to_string(),
+            target,
+            EdgeType::Imports,
+            import_text,
+            file_path.to_path_buf(),
+            line,
+        );
+        edge.metadata
+            .insert("package".to_string(), package.to_string());
+        self.add_edge(edge);
+    }
+
     /// Ensure indices are up-to-date (Phase 1 optimization: lazy rebuilding)
     pub fn ensure_indices(&mut self) {
         if self.indices_dirty {
@@ -160,6 +214,7 @@ impl CodeGraph {
         self.node_by_id.clear();
         self.by_name.clear();
         self.by_type.clear();
+        self.by_tag.clear();
         self.outgoing.clear();
         self.incoming.clear();
 
@@ -171,6 +226,9 @@ impl CodeGraph {
                 .entry(node.node_type.clone())
                 .or_default()
                 .push(idx);
+            for tag in &node.tags {
+                self.by_tag.entry(tag.clone()).or_default().push(idx);
+            }
         }
 
         // Build edge indexes
@@ -179,7 +237,9 @@ impl CodeGraph {
                 .entry(edge.from.clone())
                 .or_default()
                 .push(idx);
-            self.incoming.entry(edge.to.clone()).or_default().push(idx);
+            if edge.edge_type != EdgeType::Imports {
+                self.incoming.entry(edge.to.clone()).or_default().push(idx);
+            }
         }
 
         self.indices_dirty = false;
@@ -200,6 +260,9 @@ impl CodeGraph {
                 .entry(node.node_type.clone())
                 .or_default()
                 .push(idx);
+            for tag in &node.tags {
+                self.by_tag.entry(tag.clone()).or_default().push(idx);
+            }
             self.nodes.push(node);
         }
 
@@ -210,13 +273,67 @@ impl CodeGraph {
                 .entry(edge.from.clone())
                 .or_default()
                 .push(idx);
-            self.incoming.entry(edge.to.clone()).or_default().push(idx);
+            if edge.edge_type != EdgeType::Imports {
+                self.incoming.entry(edge.to.clone()).or_default().push(idx);
+            }
             self.edges.push(edge);
         }
 
         self.metadata
             .file_metadata
             .extend(other.metadata.file_metadata);
+        self.metadata
+            .generated_files
+            .extend(other.metadata.generated_files);
+    }
+
+    /// [`merge`](CodeGraph::merge) that skips nodes whose ID is already
+    /// present, along with their outgoing edges, so graphs using content IDs
+    /// (see [`assign_content_ids`](CodeGraph::assign_content_ids)) or
+    /// indexing the same files unify shared code instead of duplicating it
+    pub fn merge_dedup(&mut self, mut other: CodeGraph) {
+        self.ensure_indices();
+        let skipped: HashSet<String> = other
+            .nodes
+            .iter()
+            .filter(|n| self.node_by_id.contains_key(&n.id))
+            .map(|n| n.id.clone())
+            .collect();
+        other.nodes.retain(|n| !skipped.contains(&n.id));
+        other.edges.retain(|e| !skipped.contains(&e.from));
+        self.merge(other);
+    }
+
+    /// Combine several indexes (e.g. one per language of a monorepo) with
+    /// [`merge_dedup`](CodeGraph::merge_dedup), so a node ID present in more
+    /// than one is kept once; so is an edge present in more than one (imports
+    /// of a file indexed twice start at the file, not a node). The metadata of
+    /// the first graph is kept, with its language replaced by the distinct
+    /// languages joined with `,` and its stats recomputed. Returns `None` for
+    /// no graphs.
+    pub fn merge_all(graphs: impl IntoIterator<Item = CodeGraph>) -> Option<CodeGraph> {
+        let mut graphs = graphs.into_iter();
+        let mut merged = graphs.next()?;
+        let mut languages = vec![merged.metadata.language.clone()];
+        let mut files_parsed = merged.metadata.stats.files_parsed;
+        let edge_key = |e: &Edge| (e.from.clone(), e.to.clone(), e.edge_type.clone(), e.line);
+        let mut seen_edges: HashSet<_> = merged.edges.iter().map(edge_key).collect();
+        for mut graph in graphs {
+            if !languages.contains(&graph.metadata.language) {
+                languages.push(graph.metadata.language.clone());
+            }
+            files_parsed += graph.metadata.stats.files_parsed;
+            graph.edges.retain(|e| seen_edges.insert(edge_key(e)));
+            merged.merge_dedup(graph);
+        }
+
+        merged.metadata.language = languages.join(",");
+        merged.metadata.stats.total_nodes = merged.nodes.len();
+        merged.metadata.stats.total_edges = merged.edges.len();
+        merged.metadata.stats.files_parsed = files_parsed;
+        merged.metadata.stats.isolated_nodes =
+            merged.filter_isolated(merged.nodes.iter().collect()).len();
+        Some(merged)
     }
 
     pub fn get_node_by_id(&self, id: &str) -> Option<&Node> {
@@ -235,6 +352,41 @@ impl CodeGraph {
             .unwrap_or_default()
     }
 
+    /// Nodes whose name matches `pattern` anywhere (anchor with `^`/`$`), in
+    /// graph order. Scans every node.
+    pub fn get_nodes_by_name_regex(&self, pattern: &Regex) -> Vec<&Node> {
+        self.nodes
+            .iter()
+            .filter(|n| pattern.is_match(&n.name))
+            .collect()
+    }
+
+    /// Nodes carrying all (`TagMode::And`) or any (`TagMode::Or`) of `tags`,
+    /// in graph order. Uses the tag index: set intersection or union.
+    pub fn get_nodes_by_tags(&self, tags: &[&str], mode: TagMode) -> Vec<&Node> {
+        let mut sets = tags.iter().map(|tag| {
+            self.by_tag
+                .get(*tag)
+                .map(|indices| indices.iter().copied().collect::<HashSet<usize>>())
+                .unwrap_or_default()
+        });
+
+        let Some(first) = sets.next() else {
+            return Vec::new();
+        };
+        let matched = sets.fold(first, |acc, set| match mode {
+            TagMode::And => &acc & &set,
+            TagMode::Or => &acc | &set,
+        });
+
+        let mut indices: Vec<usize> = matched.into_iter().collect();
+        indices.sort_unstable();
+        indices
+            .into_iter()
+            .filter_map(|idx| self.nodes.get(idx))
+            .collect()
+    }
+
     pub fn get_nodes_by_type(&self, node_type: &NodeType) -> Vec<&Node> {
         self.by_type
             .get(node_type)
@@ -271,29 +423,238 @@ impl CodeGraph {
             .unwrap_or_default()
     }
 
-    /// Trace dependencies: find all nodes reachable from a given node up to a certain depth
+    /// Trace dependencies: find all nodes reachable from a given node up to a certain depth.
+    /// Depth counts call edges: depth N reports edges from nodes at most N-1 hops away,
+    /// reaching nodes up to N hops away (same convention as `extract_subgraph`)
     pub fn trace_dependencies(&self, from_id: &str, max_depth: usize) -> Vec<TraceResult> {
+        self.trace_dependencies_bounded(from_id, max_depth, None)
+    }
+
+    /// [`trace_dependencies`](CodeGraph::trace_dependencies) following at
+    /// most `max_branching` outgoing edges per node, the first by callee name
+    /// (then line). A result whose callee had edges cut records how many in
+    /// `omitted_callees`; for the start node, compare its outgoing edge count
+    /// with the cap.
+    pub fn trace_dependencies_bounded(
+        &self,
+        from_id: &str,
+        max_depth: usize,
+        max_branching: Option<usize>,
+    ) -> Vec<TraceResult> {
         let mut results = Vec::new();
         let mut visited = std::collections::HashSet::new();
-        self.trace_recursive(from_id, 0, max_depth, &mut visited, &mut results);
+        self.trace_recursive(
+            from_id,
+            0,
+            max_depth,
+            max_branching,
+            &mut visited,
+            &mut results,
+        );
+        results
+    }
+
+    /// [`trace_dependencies_bounded`](CodeGraph::trace_dependencies_bounded)
+    /// keeping only results at least `min_depth` levels deep; 1 skips the
+    /// direct callees. `max_depth` still bounds the walk.
+    pub fn trace_dependencies_from_depth(
+        &self,
+        from_id: &str,
+        min_depth: usize,
+        max_depth: usize,
+        max_branching: Option<usize>,
+    ) -> Vec<TraceResult> {
+        let mut results = self.trace_dependencies_bounded(from_id, max_depth, max_branching);
+        results.retain(|trace| trace.depth >= min_depth);
         results
     }
 
+    /// Prune a trace (in the preorder [`trace_dependencies`] returns) to the
+    /// branches leading to a callee matching `pattern`: `package:NAME` for
+    /// callees defined in package NAME, otherwise a substring of the callee
+    /// name. Callers on the way to a match are kept so the tree stays whole.
+    ///
+    /// [`trace_dependencies`]: CodeGraph::trace_dependencies
+    pub fn filter_trace(&self, traces: Vec<TraceResult>, pattern: &str) -> Vec<TraceResult> {
+        let matches = |trace: &TraceResult| match pattern.strip_prefix("package:") {
+            Some(package) => self
+                .get_nodes_by_name(&trace.to_name)
+                .iter()
+                .any(|node| node.package == package),
+            None => trace.to_name.contains(pattern),
+        };
+
+        // Walking backwards, a result's subtree has been seen just before it;
+        // `below[d]` records whether anything kept sits at depth d there
+        let mut keep = vec![false; traces.len()];
+        let mut below: Vec<bool> = Vec::new();
+        for (i, trace) in traces.iter().enumerate().rev() {
+            let depth = trace.depth;
+            if below.len() <= depth {
+                below.resize(depth + 1, false);
+            }
+            keep[i] = matches(trace) || below[depth + 1..].iter().any(|&kept| kept);
+            below.truncate(depth + 1);
+            below[depth] |= keep[i];
+        }
+
+        traces
+            .into_iter()
+            .zip(keep)
+            .filter_map(|(trace, keep)| keep.then_some(trace))
+            .collect()
+    }
+
+    /// Node indices an edge points at: the resolved `to_id` when it is set
+    /// and still in the graph, otherwise every node named `to`
+    fn edge_targets(&self, edge: &Edge) -> &[usize] {
+        if let Some(idx) = edge.to_id.as_ref().and_then(|id| self.node_by_id.get(id)) {
+            return std::slice::from_ref(idx);
+        }
+        self.by_name.get(&edge.to).map_or(&[], Vec::as_slice)
+    }
+
+    /// ID of the node an edge points at, for exporters: its resolved target,
+    /// or the only node with the callee's name. `None` for external or
+    /// ambiguous callees.
+    pub fn edge_target_id(&self, edge: &Edge) -> Option<&str> {
+        match self.edge_targets(edge) {
+            [idx] => Some(&self.nodes[*idx].id),
+            _ => None,
+        }
+    }
+
+    /// Set `to_id` on every edge whose callee name resolves to one node.
+    ///
+    /// Overloaded names are narrowed to methods of the receiver's type for
+    /// receiver calls (`self.f()`, `c.f()`), then to candidates in the
+    /// caller's file, then its package. Edges that stay ambiguous get no
+    /// `to_id`; their candidate node IDs are listed, comma-separated, in the
+    /// `candidates` metadata. Previous resolutions are discarded, so this can
+    /// be re-run after incremental updates. Returns the number of resolved
+    /// edges.
+    pub fn resolve_edges(&mut self) -> usize {
+        self.resolve_edges_where(|_| true)
+    }
+
+    /// [`resolve_edges`](CodeGraph::resolve_edges) limited to the edges
+    /// matching `select`; others keep their current resolution
+    fn resolve_edges_where(&mut self, select: impl Fn(&Edge) -> bool) -> usize {
+        self.ensure_indices();
+
+        let mut resolved = 0;
+        for i in 0..self.edges.len() {
+            if !select(&self.edges[i]) {
+                continue;
+            }
+            let resolution = self.resolve_edge(&self.edges[i]);
+            let edge = &mut self.edges[i];
+            edge.metadata.remove(CANDIDATES_KEY);
+            edge.to_id = match resolution {
+                Ok(to_id) => to_id,
+                Err(candidates) => {
+                    edge.metadata
+                        .insert(CANDIDATES_KEY.to_string(), candidates.join(","));
+                    None
+                }
+            };
+            resolved += usize::from(edge.to_id.is_some());
+        }
+        resolved
+    }
+
+    /// `Ok(Some(id))` for a resolved edge, `Ok(None)` when no node carries
+    /// the callee name, `Err(candidates)` when several still do
+    fn resolve_edge(&self, edge: &Edge) -> Result<Option<String>, Vec<String>> {
+        // Import targets are module paths, not node names
+        if edge.edge_type == EdgeType::Imports {
+            return Ok(None);
+        }
+        let Some(candidates) = self.by_name.get(&edge.to) else {
+            return Ok(None);
+        };
+        let unique = |keep: &dyn Fn(&Node) -> bool| {
+            let mut matching = candidates
+                .iter()
+                .map(|&idx| &self.nodes[idx])
+                .filter(|node| keep(node));
+            match (matching.next(), matching.next()) {
+                (Some(node), None) => Some(node.id.clone()),
+                _ => None,
+            }
+        };
+
+        let caller = self.get_node_by_id(&edge.from);
+        let caller_package = caller.map(|n| n.package.as_str());
+        // `self.f()` / `this.f()` from a method calls a method of the same
+        // type. Other operands (`conn.f()`, `s.listener.f()`) only resolve
+        // through a receiver type the parser recorded (Go types its receiver
+        // variable that way).
+        let receiver_call = matches!(
+            call_operand(&edge.call_site, &edge.to),
+            Some("self" | "this")
+        );
+        let receiver_type = edge
+            .metadata
+            .get("receiver")
+            .map(String::as_str)
+            .or_else(|| caller.and_then(Node::owner_type).filter(|_| receiver_call));
+
+        let resolved = unique(&|_| true)
+            .or_else(|| receiver_type.and_then(|ty| unique(&|node| node.owner_type() == Some(ty))))
+            .or_else(|| unique(&|node| node.file_path == edge.file_path))
+            .or_else(|| unique(&|node| Some(node.package.as_str()) == caller_package));
+
+        match resolved {
+            Some(id) => Ok(Some(id)),
+            None => Err(candidates
+                .iter()
+                .map(|&idx| self.nodes[idx].id.clone())
+                .collect()),
+        }
+    }
+
+    /// Call edges into one node: edges resolved to it, plus unresolved edges
+    /// naming it. Unlike [`find_callers`], calls resolved to another node of
+    /// the same name are left out.
+    ///
+    /// [`find_callers`]: CodeGraph::find_callers
+    pub fn find_callers_of(&self, node_id: &str) -> Vec<&Edge> {
+        let Some(node) = self.get_node_by_id(node_id) else {
+            return Vec::new();
+        };
+        self.find_callers(&node.name)
+            .into_iter()
+            .filter(|e| e.to_id.as_deref().is_none_or(|id| id == node_id))
+            .collect()
+    }
+
+    /// Returns how many of `node_id`'s outgoing edges `max_branching` cut
     fn trace_recursive(
         &self,
         node_id: &str,
         depth: usize,
         max_depth: usize,
+        max_branching: Option<usize>,
         visited: &mut std::collections::HashSet<String>,
         results: &mut Vec<TraceResult>,
-    ) {
+    ) -> usize {
         if depth >= max_depth || visited.contains(node_id) {
-            return;
+            return 0;
         }
 
         visited.insert(node_id.to_string());
 
-        for edge in self.get_outgoing_edges(node_id) {
+        let mut edges = self.get_outgoing_edges(node_id);
+        let mut omitted = 0;
+        if let Some(limit) = max_branching {
+            edges.sort_by(|a, b| (&a.to, a.line).cmp(&(&b.to, b.line)));
+            omitted = edges.len().saturating_sub(limit);
+            edges.truncate(limit);
+        }
+
+        for edge in edges {
+            let idx = results.len();
             results.push(TraceResult {
                 from_id: edge.from.clone(),
                 to_name: edge.to.clone(),
@@ -302,22 +663,73 @@ impl CodeGraph {
                 file_path: edge.file_path.clone(),
                 line: edge.line,
                 depth,
+                omitted_callees: None,
             });
 
             // Try to find the target node and recurse
-            if let Some(target_nodes) = self.by_name.get(&edge.to) {
-                for &target_idx in target_nodes {
-                    if let Some(target_node) = self.nodes.get(target_idx) {
-                        self.trace_recursive(
-                            &target_node.id,
-                            depth + 1,
-                            max_depth,
-                            visited,
-                            results,
-                        );
-                    }
+            let mut target_omitted = 0;
+            for &target_idx in self.edge_targets(edge) {
+                if let Some(target_node) = self.nodes.get(target_idx) {
+                    target_omitted += self.trace_recursive(
+                        &target_node.id,
+                        depth + 1,
+                        max_depth,
+                        max_branching,
+                        visited,
+                        results,
+                    );
                 }
             }
+            if target_omitted > 0 {
+                results[idx].omitted_callees = Some(target_omitted);
+            }
+        }
+        omitted
+    }
+
+    /// Reverse of [`trace_dependencies`](CodeGraph::trace_dependencies):
+    /// everything that transitively calls the node `to_id`, up to
+    /// `max_depth` call edges away. Each result is a call edge (`from_id` is
+    /// the caller) in depth-first preorder; depth 0 holds the direct callers.
+    /// Callers are found per node ([`find_callers_of`]), so other functions
+    /// sharing a name don't leak in, and each node is expanded once, so
+    /// recursion and mutual calls terminate.
+    ///
+    /// [`find_callers_of`]: CodeGraph::find_callers_of
+    pub fn trace_callers(&self, to_id: &str, max_depth: usize) -> Vec<TraceResult> {
+        let mut results = Vec::new();
+        let mut visited = std::collections::HashSet::new();
+        self.trace_callers_recursive(to_id, 0, max_depth, &mut visited, &mut results);
+        results
+    }
+
+    fn trace_callers_recursive(
+        &self,
+        to_id: &str,
+        depth: usize,
+        max_depth: usize,
+        visited: &mut std::collections::HashSet<String>,
+        results: &mut Vec<TraceResult>,
+    ) {
+        if depth >= max_depth || !visited.insert(to_id.to_string()) {
+            return;
+        }
+
+        for edge in self.find_callers_of(to_id) {
+            results.push(TraceResult {
+                from_id: edge.from.clone(),
+                to_name: edge.to.clone(),
+                edge_type: edge.edge_type.clone(),
+                call_site: edge.call_site.clone(),
+                file_path: edge.file_path.clone(),
+                line: edge.line,
+                depth,
+                omitted_callees: None,
+            });
+
+            if self.node_by_id.contains_key(&edge.from) {
+                self.trace_callers_recursive(&edge.from, depth + 1, max_depth, visited, results);
+            }
         }
     }
 
@@ -334,6 +746,325 @@ impl CodeGraph {
             .unwrap_or_default()
     }
 
+    /// One-shot overview for `codenav stats`: node counts per type, edge
+    /// counts per type, node counts per package (largest first), distinct
+    /// source files, average fan-in/fan-out and the node with the highest
+    /// combined fan-in + fan-out
+    pub fn summary(&self) -> GraphSummary {
+        let mut summary = GraphSummary {
+            total_nodes: self.nodes.len(),
+            total_edges: self.edges.len(),
+            ..Default::default()
+        };
+
+        let mut packages: HashMap<&str, usize> = HashMap::new();
+        let mut files = HashSet::new();
+        let (mut fan_in, mut fan_out) = (0, 0);
+        for node in &self.nodes {
+            *summary
+                .nodes_by_type
+                .entry(node.node_type.to_string())
+                .or_default() += 1;
+            *packages.entry(&node.package).or_default() += 1;
+            files.insert(&node.file_path);
+
+            let metrics = self.get_complexity(&node.id);
+            fan_in += metrics.fan_in;
+            fan_out += metrics.fan_out;
+            let busier = summary.most_connected.as_ref().is_none_or(|best| {
+                (metrics.combined(), &best.name) > (best.fan_in + best.fan_out, &node.name)
+            });
+            if busier {
+                summary.most_connected = Some(ConnectedNode {
+                    id: node.id.clone(),
+                    name: node.name.clone(),
+                    fan_in: metrics.fan_in,
+                    fan_out: metrics.fan_out,
+                });
+            }
+        }
+        for edge in &self.edges {
+            *summary
+                .edges_by_type
+                .entry(format!("{:?}", edge.edge_type))
+                .or_default() += 1;
+            files.insert(&edge.file_path);
+        }
+
+        summary.files = files.len();
+        if !self.nodes.is_empty() {
+            summary.avg_fan_in = fan_in as f64 / self.nodes.len() as f64;
+            summary.avg_fan_out = fan_out as f64 / self.nodes.len() as f64;
+        }
+        summary.packages = packages
+            .into_iter()
+            .map(|(package, nodes)| PackageSize {
+                package: package.to_string(),
+                nodes,
+            })
+            .collect();
+        summary.packages.sort_by(|a, b| {
+            b.nodes
+                .cmp(&a.nodes)
+                .then_with(|| a.package.cmp(&b.package))
+        });
+        summary
+    }
+
+    /// Cross-reference report for `name`: its definitions, every call site
+    /// calling it, and every call made from its definitions
+    pub fn xref(&self, name: &str) -> Xref<'_> {
+        let definitions = self.get_nodes_by_name(name);
+        let callers = self
+            .find_callers(name)
+            .into_iter()
+            .filter(|e| e.edge_type == EdgeType::Calls)
+            .collect();
+        let callees = definitions
+            .iter()
+            .flat_map(|node| self.get_outgoing_edges(&node.id))
+            .filter(|e| e.edge_type == EdgeType::Calls)
+            .collect();
+
+        Xref {
+            name: name.to_string(),
+            definitions,
+            callers,
+            callees,
+        }
+    }
+
+    /// Test functions that transitively call any of `changed` (node IDs or
+    /// function names), sorted by file and line. A changed test selects
+    /// itself. Tests are nodes tagged [`TEST_TAG`]; run [`tag_tests`] first
+    /// on graphs indexed before tagging existed.
+    ///
+    /// [`tag_tests`]: CodeGraph::tag_tests
+    pub fn affected_tests<S: AsRef<str>>(&self, changed: &[S]) -> Vec<&Node> {
+        let mut visited: HashSet<&str> = HashSet::new();
+        let mut queue: VecDeque<&str> = VecDeque::new();
+        for key in changed {
+            let key = key.as_ref();
+            let start = match self.get_node_by_id(key) {
+                Some(node) => vec![node],
+                None => self.get_nodes_by_name(key),
+            };
+            for node in start {
+                if visited.insert(&node.id) {
+                    queue.push_back(&node.id);
+                }
+            }
+        }
+
+        // Reverse reachability over call edges
+        while let Some(node_id) = queue.pop_front() {
+            for edge in self.find_callers_of(node_id) {
+                if edge.edge_type != EdgeType::Calls {
+                    continue;
+                }
+                if let Some(caller) = self.get_node_by_id(&edge.from) {
+                    if visited.insert(&caller.id) {
+                        queue.push_back(&caller.id);
+                    }
+                }
+            }
+        }
+
+        let mut tests: Vec<&Node> = visited
+            .into_iter()
+            .filter_map(|id| self.get_node_by_id(id))
+            .filter(|node| node.tags.iter().any(|t| t == TEST_TAG))
+            .collect();
+        tests.sort_by(|a, b| (&a.file_path, a.line).cmp(&(&b.file_path, b.line)));
+        tests
+    }
+
+    /// IDs of nodes that changed since `old`, for [`affected_tests`]: added
+    /// nodes, renamed nodes under their new ID, nodes whose signature, span
+    /// or `body_hash` (recorded by the parsers) differ, and the surviving
+    /// callers of removed nodes. A node hashed on one side only counts as
+    /// changed.
+    ///
+    /// [`affected_tests`]: CodeGraph::affected_tests
+    pub fn changed_since(&self, old: &CodeGraph) -> Vec<String> {
+        let diff = old.diff(self);
+        let mut changed: BTreeSet<String> = diff.added_nodes.into_iter().collect();
+        changed.extend(diff.changed_nodes.into_iter().map(|c| c.node_id));
+        changed.extend(diff.renamed_nodes.into_iter().map(|r| r.new_id));
+
+        for node in &self.nodes {
+            let Some(old_node) = old.get_node_by_id(&node.id) else {
+                continue;
+            };
+            if old_node.end_line != node.end_line
+                || old_node.metadata.get("body_hash") != node.metadata.get("body_hash")
+            {
+                changed.insert(node.id.clone());
+            }
+        }
+
+        for removed in &diff.removed_nodes {
+            for edge in old.find_callers_of(removed) {
+                if self.get_node_by_id(&edge.from).is_some() {
+                    changed.insert(edge.from.clone());
+                }
+            }
+        }
+        changed.into_iter().collect()
+    }
+
+    /// IDs of every node reachable from the `roots` node IDs over call
+    /// edges, roots included
+    pub fn reachable_from(&self, roots: &[String]) -> HashSet<String> {
+        let mut seen: HashSet<usize> = HashSet::new();
+        let mut queue: VecDeque<usize> = roots
+            .iter()
+            .filter_map(|id| self.node_by_id.get(id).copied())
+            .collect();
+        while let Some(idx) = queue.pop_front() {
+            if !seen.insert(idx) {
+                continue;
+            }
+            for edge in self.get_outgoing_edges(&self.nodes[idx].id) {
+                if edge.edge_type == EdgeType::Calls {
+                    queue.extend(self.edge_targets(edge));
+                }
+            }
+        }
+        seen.into_iter()
+            .map(|idx| self.nodes[idx].id.clone())
+            .collect()
+    }
+
+    /// Functions and methods that no entrypoint can reach.
+    ///
+    /// Uncalled nodes are roots: `main`, `init`, tests (tagged `test`), HTTP
+    /// handlers and any name in `entrypoints` are live, the others are dead.
+    /// Everything the dead roots call that no live root reaches is dead too.
+    /// Results are sorted by file and line.
+    pub fn find_dead_code(&self, entrypoints: &[String]) -> Vec<&Node> {
+        let is_entrypoint = |node: &Node| {
+            matches!(node.name.as_str(), "main" | "init")
+                || node.node_type == NodeType::HttpHandler
+                || node.tags.iter().any(|t| t == TEST_TAG)
+                || entrypoints.contains(&node.name)
+        };
+        let is_code = |node: &Node| {
+            matches!(
+                node.node_type,
+                NodeType::Function | NodeType::Method | NodeType::Middleware
+            )
+        };
+
+        let (live_roots, dead_roots): (Vec<&Node>, Vec<&Node>) = self
+            .nodes
+            .iter()
+            .filter(|node| {
+                is_entrypoint(node)
+                    || (is_code(node)
+                        && !self
+                            .find_callers_of(&node.id)
+                            .iter()
+                            .any(|e| e.edge_type == EdgeType::Calls && e.from != node.id))
+            })
+            .partition(|node| is_entrypoint(node));
+
+        let ids = |nodes: Vec<&Node>| nodes.iter().map(|n| n.id.clone()).collect::<Vec<_>>();
+        let live = self.reachable_from(&ids(live_roots));
+        let mut dead: Vec<&Node> = self
+            .reachable_from(&ids(dead_roots))
+            .into_iter()
+            .filter(|id| !live.contains(id))
+            .filter_map(|id| self.get_node_by_id(&id))
+            .filter(|node| is_code(node))
+            .collect();
+        dead.sort_by(|a, b| (&a.file_path, a.line).cmp(&(&b.file_path, b.line)));
+        dead
+    }
+
+    /// Types with an `Implements` edge to `interface`, in edge order.
+    ///
+    /// A qualified name (`shapes.Shape`, `shapes::Shape`) matches against the
+    /// qualifier spelled out in the implementing declaration, or, for a bare
+    /// name, the package of the single interface node the edge resolves to.
+    pub fn find_implementers(&self, interface: &str) -> Vec<(&Node, &Edge)> {
+        let (qualifier, simple) = match interface.rfind(['.', ':']) {
+            Some(pos) => (
+                Some(interface[..pos].trim_end_matches(':')),
+                &interface[pos + 1..],
+            ),
+            None => (None, interface),
+        };
+
+        let matches_qualifier = |edge: &Edge| {
+            let Some(qualifier) = qualifier else {
+                return true;
+            };
+            // Type arguments aren't part of the name: `a.Shape[T]`
+            let spelled = edge.call_site.split(['[', '<']).next().unwrap_or("").trim();
+            if spelled.contains(['.', ':']) {
+                // `a::B` and `a.B` spell the same path
+                let (spelled, wanted) = (spelled.replace("::", "."), interface.replace("::", "."));
+                return spelled == wanted || spelled.ends_with(&format!(".{}", wanted));
+            }
+            match self.edge_targets(edge) {
+                [idx] => {
+                    let package = &self.nodes[*idx].package;
+                    package == qualifier
+                        || package.ends_with(&format!(".{}", qualifier))
+                        || package.ends_with(&format!("::{}", qualifier))
+                }
+                _ => false,
+            }
+        };
+
+        let mut implementers = Vec::new();
+        let mut seen = HashSet::new();
+        for name in [interface, simple] {
+            for &idx in self.incoming.get(name).into_iter().flatten() {
+                let edge = &self.edges[idx];
+                if edge.edge_type != EdgeType::Implements
+                    || !seen.insert(idx)
+                    || (name == simple && !matches_qualifier(edge))
+                {
+                    continue;
+                }
+                if let Some(node) = self.get_node_by_id(&edge.from) {
+                    implementers.push((node, edge));
+                }
+            }
+        }
+        implementers
+    }
+
+    /// Top `limit` callers and callees of a node, by name, ranked by how
+    /// many call edges connect them (ties broken by name)
+    pub fn node_relations(&self, node_id: &str, limit: usize) -> Option<NodeRelations> {
+        self.get_node_by_id(node_id)?;
+
+        let callers = self.find_callers_of(node_id).into_iter().map(|e| {
+            self.get_node_by_id(&e.from)
+                .map_or(e.from.as_str(), |n| n.name.as_str())
+        });
+        let callees = self
+            .get_outgoing_edges(node_id)
+            .into_iter()
+            .map(|e| e.to.as_str());
+
+        Some(NodeRelations {
+            callers: RelationCount::top(callers, limit),
+            callees: RelationCount::top(callees, limit),
+        })
+    }
+
+    /// Find classes that directly extend the given base class
+    pub fn find_subclasses(&self, class_name: &str) -> Vec<&Edge> {
+        self.find_callers(class_name)
+            .into_iter()
+            .filter(|e| e.edge_type == super::EdgeType::Extends)
+            .collect()
+    }
+
     /// Find all paths from one node to another
     pub fn find_paths(&self, from_id: &str, to_name: &str, max_depth: usize) -> Vec<Vec<String>> {
         self.find_paths_limited(from_id, to_name, max_depth, usize::MAX)
@@ -421,39 +1152,47 @@ impl CodeGraph {
             if let Some(edge_indices) = self.outgoing.get(&current_node.id) {
                 for &edge_idx in edge_indices {
                     if let Some(edge) = self.edges.get(edge_idx) {
-                        // Check if we reached the target
+                        // Check if we reached the target. Every node with the
+                        // target name ends its own path; a node already on the
+                        // path only counts when it is the caller itself (direct
+                        // recursion), otherwise the path would loop.
                         if edge.to == target_name {
-                            let mut complete_path = current_path.clone();
-                            // Find the target node index
-                            if let Some(target_indices) = self.by_name.get(&edge.to) {
-                                if let Some(&target_idx) = target_indices.first() {
-                                    complete_path.push(target_idx);
-                                    paths.push(complete_path);
+                            for &target_idx in self.edge_targets(edge) {
+                                if target_idx != current_idx && visited.contains(&target_idx) {
+                                    continue;
+                                }
+                                let mut complete_path = current_path.clone();
+                                complete_path.push(target_idx);
+                                paths.push(complete_path);
+
+                                if paths.len() >= max_paths {
+                                    break;
                                 }
                             }
+                            if paths.len() >= max_paths {
+                                break;
+                            }
                             continue;
                         }
 
                         // Continue exploring
-                        if let Some(next_indices) = self.by_name.get(&edge.to) {
-                            for &next_idx in next_indices {
-                                if !visited.contains(&next_idx) {
-                                    current_path.push(next_idx);
-                                    self.find_paths_recursive_indexed(
-                                        next_idx,
-                                        target_name,
-                                        current_path,
-                                        visited,
-                                        paths,
-                                        max_depth,
-                                        depth + 1,
-                                        max_paths,
-                                    );
-                                    current_path.pop();
-
-                                    if paths.len() >= max_paths {
-                                        break;
-                                    }
+                        for &next_idx in self.edge_targets(edge) {
+                            if !visited.contains(&next_idx) {
+                                current_path.push(next_idx);
+                                self.find_paths_recursive_indexed(
+                                    next_idx,
+                                    target_name,
+                                    current_path,
+                                    visited,
+                                    paths,
+                                    max_depth,
+                                    depth + 1,
+                                    max_paths,
+                                );
+                                current_path.pop();
+
+                                if paths.len() >= max_paths {
+                                    break;
                                 }
                             }
                         }
@@ -477,59 +1216,140 @@ impl CodeGraph {
         to_name: &str,
         max_depth: usize,
     ) -> Option<Vec<String>> {
-        use std::collections::{HashMap, VecDeque};
+        // Names of every step after the start, ending with the target
+        let edges = self.find_shortest_path_edges(from_id, to_name, max_depth)?;
+        Some(edges.iter().map(|e| e.to.clone()).collect())
+    }
 
-        let mut queue = VecDeque::new();
-        let mut parent: HashMap<String, (String, String)> = HashMap::new(); // node_id -> (parent_id, edge_name)
-        let mut visited = std::collections::HashSet::new();
-        let mut depth_map: HashMap<String, usize> = HashMap::new();
+    /// Find the shortest path as node references, starting with the `from_id`
+    /// node and ending with the target. A target that isn't defined in the
+    /// graph (e.g. an external call) is omitted.
+    pub fn find_shortest_path_nodes(
+        &self,
+        from_id: &str,
+        to_name: &str,
+        max_depth: usize,
+    ) -> Option<Vec<&Node>> {
+        let edges = self.find_shortest_path_edges(from_id, to_name, max_depth)?;
 
-        queue.push_back(from_id.to_string());
-        visited.insert(from_id.to_string());
-        depth_map.insert(from_id.to_string(), 0);
+        let mut nodes: Vec<&Node> = edges
+            .iter()
+            .filter_map(|e| self.get_node_by_id(&e.from))
+            .collect();
+        // The node the last hop reaches, not any node named `to_name`; an
+        // empty path starts at the target
+        let target_node = match edges.last() {
+            Some(last) => self
+                .edge_targets(last)
+                .first()
+                .and_then(|&idx| self.nodes.get(idx)),
+            None => self.get_node_by_id(from_id),
+        };
+        nodes.extend(target_node);
 
-        while let Some(current_id) = queue.pop_front() {
-            let current_depth = *depth_map.get(&current_id).unwrap_or(&0);
+        Some(nodes)
+    }
 
-            // Don't explore beyond max depth
-            if current_depth >= max_depth {
-                continue;
-            }
+    /// Find the shortest path as the edges traversed, one per hop: the first
+    /// starts at `from_id` and the last points at `to_name`
+    pub fn find_shortest_path_edges(
+        &self,
+        from_id: &str,
+        to_name: &str,
+        max_depth: usize,
+    ) -> Option<Vec<&Edge>> {
+        let edge_indices = self.shortest_path_edge_indices(from_id, to_name, max_depth)?;
+        Some(edge_indices.iter().map(|&idx| &self.edges[idx]).collect())
+    }
 
-            for edge in self.get_outgoing_edges(&current_id) {
-                // Check if we reached the target
-                if edge.to == to_name {
-                    // Reconstruct path from parent map
-                    let mut path = Vec::new();
-                    let mut current = current_id.clone();
+    /// Explain the shortest path hop by hop, with the call site behind each hop
+    pub fn explain_path(
+        &self,
+        from_id: &str,
+        to_name: &str,
+        max_depth: usize,
+    ) -> Option<Vec<PathHop>> {
+        let edges = self.find_shortest_path_edges(from_id, to_name, max_depth)?;
+
+        let hops = edges
+            .into_iter()
+            .map(|edge| {
+                let caller = self.get_node_by_id(&edge.from);
+                let callee = self
+                    .edge_targets(edge)
+                    .first()
+                    .and_then(|&idx| self.nodes.get(idx));
+
+                PathHop {
+                    caller: caller.map_or_else(|| edge.from.clone(), |n| n.name.clone()),
+                    callee: edge.to.clone(),
+                    call_site: edge.call_site.clone(),
+                    file_path: edge.file_path.clone(),
+                    line: edge.line,
+                    caller_signature: caller.map(|n| n.signature.clone()),
+                    callee_signature: callee.map(|n| n.signature.clone()),
+                }
+            })
+            .collect();
+
+        Some(hops)
+    }
+
+    /// BFS returning the indices of the edges along the shortest path
+    fn shortest_path_edge_indices(
+        &self,
+        from_id: &str,
+        to_name: &str,
+        max_depth: usize,
+    ) -> Option<Vec<usize>> {
+        use std::collections::{HashMap, VecDeque};
+
+        let mut queue = VecDeque::new();
+        // node_id -> (parent_id, index of the edge that reached it)
+        let mut parent: HashMap<String, (String, usize)> = HashMap::new();
+        let mut visited = std::collections::HashSet::new();
+        let mut depth_map: HashMap<String, usize> = HashMap::new();
+
+        queue.push_back(from_id.to_string());
+        visited.insert(from_id.to_string());
+        depth_map.insert(from_id.to_string(), 0);
 
-                    // Trace back from current node to start
-                    while let Some((parent_id, edge_name)) = parent.get(&current) {
-                        path.push(edge_name.clone());
+        while let Some(current_id) = queue.pop_front() {
+            let current_depth = *depth_map.get(&current_id).unwrap_or(&0);
+
+            // Don't explore beyond max depth
+            if current_depth >= max_depth {
+                continue;
+            }
+
+            for &edge_idx in self.outgoing.get(&current_id).into_iter().flatten() {
+                let Some(edge) = self.edges.get(edge_idx) else {
+                    continue;
+                };
+
+                // Check if we reached the target
+                if edge.to == to_name {
+                    // Reconstruct the hops by walking parents back to the start
+                    let mut path = vec![edge_idx];
+                    let mut current = current_id.clone();
+                    while let Some((parent_id, via_edge)) = parent.get(&current) {
+                        path.push(*via_edge);
                         current = parent_id.clone();
                     }
 
-                    // Reverse to get path from start to current
+                    // Reverse to get hops from start to target
                     path.reverse();
 
-                    // Add the final edge to target
-                    path.push(edge.to.clone());
-
                     return Some(path);
                 }
 
                 // Continue BFS to intermediate nodes
-                if let Some(target_indices) = self.by_name.get(&edge.to) {
-                    for &idx in target_indices {
-                        if let Some(next_node) = self.nodes.get(idx) {
-                            if visited.insert(next_node.id.clone()) {
-                                parent.insert(
-                                    next_node.id.clone(),
-                                    (current_id.clone(), edge.to.clone()),
-                                );
-                                depth_map.insert(next_node.id.clone(), current_depth + 1);
-                                queue.push_back(next_node.id.clone());
-                            }
+                for &idx in self.edge_targets(edge) {
+                    if let Some(next_node) = self.nodes.get(idx) {
+                        if visited.insert(next_node.id.clone()) {
+                            parent.insert(next_node.id.clone(), (current_id.clone(), edge_idx));
+                            depth_map.insert(next_node.id.clone(), current_depth + 1);
+                            queue.push_back(next_node.id.clone());
                         }
                     }
                 }
@@ -539,30 +1359,326 @@ impl CodeGraph {
         None // No path found
     }
 
-    /// Calculate complexity metrics for a node
+    /// Calculate complexity metrics for a node. Uses degrees stored by
+    /// [`CodeGraph::precompute_degrees`] when present.
     pub fn get_complexity(&self, node_id: &str) -> ComplexityMetrics {
-        let fan_out = self.get_outgoing_edges(node_id).len();
-        let fan_in = self
-            .find_callers(
-                self.get_node_by_id(node_id)
-                    .map(|n| n.name.as_str())
-                    .unwrap_or(""),
-            )
-            .len();
+        let node = self.get_node_by_id(node_id);
+        let (fan_in, fan_out) = match node.and_then(Node::precomputed_degrees) {
+            Some(degrees) => degrees,
+            None => self.compute_degrees(node_id),
+        };
 
         ComplexityMetrics {
             fan_in,
             fan_out,
-            cyclomatic: fan_out + 1, // Simplified
+            cyclomatic: node.map_or(0, |n| n.decision_points) + 1,
+        }
+    }
+
+    /// (fan-in, fan-out) from the incoming/outgoing indexes
+    fn compute_degrees(&self, node_id: &str) -> (usize, usize) {
+        (
+            self.find_callers_of(node_id).len(),
+            self.get_outgoing_edges(node_id).len(),
+        )
+    }
+
+    /// Store `fan_in`/`fan_out` in every node's metadata so complexity reads
+    /// them directly instead of looking up the indexes per node
+    pub fn precompute_degrees(&mut self) {
+        self.ensure_indices();
+        for idx in 0..self.nodes.len() {
+            self.store_degrees(idx);
+        }
+    }
+
+    /// Whether degrees were precomputed when this graph was indexed
+    pub fn has_precomputed_degrees(&self) -> bool {
+        self.nodes.iter().any(|n| n.metadata.contains_key("fan_in"))
+    }
+
+    /// Names whose degrees change when `file_path` is reparsed: the nodes
+    /// defined in it (fan-out) and everything they call (fan-in). Collect
+    /// this before removing the file and again after reparsing it.
+    pub fn degree_dependents(&self, file_path: &str) -> HashSet<String> {
+        let in_file = |path: &PathBuf| path.to_string_lossy() == file_path;
+        self.nodes
+            .iter()
+            .filter(|n| in_file(&n.file_path))
+            .map(|n| n.name.clone())
+            .chain(
+                self.edges
+                    .iter()
+                    .filter(|e| in_file(&e.file_path))
+                    .map(|e| e.to.clone()),
+            )
+            .collect()
+    }
+
+    /// Recompute stored degrees for the nodes with the given names
+    pub fn refresh_degrees(&mut self, names: &HashSet<String>) {
+        self.ensure_indices();
+        let indices: Vec<usize> = names
+            .iter()
+            .filter_map(|name| self.by_name.get(name))
+            .flatten()
+            .copied()
+            .collect();
+        for idx in indices {
+            self.store_degrees(idx);
+        }
+    }
+
+    fn store_degrees(&mut self, idx: usize) {
+        let node = &self.nodes[idx];
+        let (fan_in, fan_out) = self.compute_degrees(&node.id);
+        let metadata = &mut self.nodes[idx].metadata;
+        metadata.insert("fan_in".to_string(), fan_in.to_string());
+        metadata.insert("fan_out".to_string(), fan_out.to_string());
+    }
+
+    /// Keep only nodes whose combined complexity falls within `[min, max]`
+    /// (either bound may be omitted). Complexity is computed per candidate,
+    /// so this is O(n) in the number of candidates.
+    pub fn filter_by_complexity<'a>(
+        &self,
+        candidates: Vec<&'a Node>,
+        min: Option<usize>,
+        max: Option<usize>,
+    ) -> Vec<&'a Node> {
+        candidates
+            .into_iter()
+            .filter(|node| {
+                let combined = self.get_complexity(&node.id).combined();
+                min.is_none_or(|min| combined >= min) && max.is_none_or(|max| combined <= max)
+            })
+            .collect()
+    }
+
+    /// Keep only leaf nodes: those whose calls (if any) all go to functions
+    /// outside the graph, via the outgoing-edge index
+    pub fn filter_leaves<'a>(&self, candidates: Vec<&'a Node>) -> Vec<&'a Node> {
+        candidates
+            .into_iter()
+            .filter(|node| {
+                !self
+                    .get_outgoing_edges(&node.id)
+                    .iter()
+                    .any(|edge| edge.edge_type == EdgeType::Calls && self.calls_into_graph(edge))
+            })
+            .collect()
+    }
+
+    /// Keep only isolated nodes: no edges out (by ID) and none in (by name),
+    /// checked against the edge indexes
+    pub fn filter_isolated<'a>(&self, candidates: Vec<&'a Node>) -> Vec<&'a Node> {
+        candidates
+            .into_iter()
+            .filter(|node| {
+                !self.outgoing.contains_key(&node.id) && !self.incoming.contains_key(&node.name)
+            })
+            .collect()
+    }
+
+    /// Whether a call edge lands on a node in this graph
+    fn calls_into_graph(&self, edge: &Edge) -> bool {
+        match &edge.to_id {
+            Some(id) => self.node_by_id.contains_key(id),
+            None => self.by_name.contains_key(&edge.to),
+        }
+    }
+
+    /// Functions whose nesting depth exceeds `threshold`, deepest first
+    pub fn find_deeply_nested(&self, threshold: usize) -> Vec<(&Node, usize)> {
+        let mut results: Vec<_> = self
+            .nodes
+            .iter()
+            .filter_map(|node| Some((node, node.max_nesting()?)))
+            .filter(|(_, depth)| *depth > threshold)
+            .collect();
+
+        results.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.id.cmp(&b.0.id)));
+        results
+    }
+
+    /// Functions with parameters their body never uses, by file and line
+    pub fn find_unused_params(&self) -> Vec<(&Node, Vec<&str>)> {
+        let mut results: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|node| (node, node.unused_params()))
+            .filter(|(_, params)| !params.is_empty())
+            .collect();
+
+        results.sort_by(|a, b| {
+            (&a.0.file_path, a.0.line, &a.0.id).cmp(&(&b.0.file_path, b.0.line, &b.0.id))
+        });
+        results
+    }
+
+    /// Packages imported by each package, from the `Imports` edges the
+    /// parsers record. Empty when the graph has no import edges.
+    pub fn package_imports(&self) -> BTreeMap<String, BTreeSet<String>> {
+        let mut imports: BTreeMap<String, BTreeSet<String>> = BTreeMap::new();
+        for edge in &self.edges {
+            if edge.edge_type != EdgeType::Imports {
+                continue;
+            }
+            if let Some(package) = edge.metadata.get("package") {
+                imports
+                    .entry(package.clone())
+                    .or_default()
+                    .insert(edge.to.clone());
+            }
+        }
+        imports
+    }
+
+    /// Package dependency cycles: strongly connected groups of packages that
+    /// reach each other through resolved cross-package call edges. Each cycle
+    /// lists its packages sorted; cycles are sorted by their first package.
+    pub fn find_package_cycles(&self) -> Vec<Vec<String>> {
+        package_cycles(&self.package_call_dependencies())
+    }
+
+    /// Packages each package calls into. A callee counts through its
+    /// resolved `to_id`, or its name when exactly one node has it; calls to
+    /// names defined in several places (`Close`, `New`) are skipped rather
+    /// than linked to every package defining one.
+    fn package_call_dependencies(&self) -> HashMap<&str, HashSet<&str>> {
+        let mut deps: HashMap<&str, HashSet<&str>> = HashMap::new();
+        for edge in &self.edges {
+            let Some(from) = self.get_node_by_id(&edge.from) else {
+                continue;
+            };
+            let [to] = self.edge_targets(edge) else {
+                continue;
+            };
+            let to = &self.nodes[*to];
+            if to.package != from.package {
+                deps.entry(from.package.as_str())
+                    .or_default()
+                    .insert(to.package.as_str());
+            }
+        }
+        deps
+    }
+
+    /// Packages in build order, each after the packages it depends on
+    /// through cross-package calls or imports of other indexed packages (an
+    /// import matches a package by name or by its last path segment, so
+    /// `example.com/app/store` matches `store`). Ties are broken by name
+    /// (Kahn's algorithm). When cycles prevent an ordering, returns them
+    /// instead, as in [`CodeGraph::find_package_cycles`].
+    pub fn topological_order(&self) -> Result<Vec<String>, Vec<Vec<String>>> {
+        let packages: BTreeSet<&str> = self.nodes.iter().map(|n| n.package.as_str()).collect();
+        let mut deps = self.package_call_dependencies();
+        for edge in &self.edges {
+            if edge.edge_type != EdgeType::Imports {
+                continue;
+            }
+            let Some(from) = edge.metadata.get("package").map(String::as_str) else {
+                continue;
+            };
+            let last_segment = edge.to.rsplit('/').next().unwrap_or(&edge.to);
+            let to = [edge.to.as_str(), last_segment]
+                .into_iter()
+                .find_map(|name| packages.get(name).copied());
+            if let (Some(from), Some(to)) = (packages.get(from).copied(), to) {
+                if from != to {
+                    deps.entry(from).or_default().insert(to);
+                }
+            }
+        }
+
+        let (order, unordered) = kahn_order(&packages, &deps);
+        if unordered.is_empty() {
+            Ok(order.into_iter().map(str::to_string).collect())
+        } else {
+            Err(package_cycles(&deps))
+        }
+    }
+
+    /// Sort `nodes` so callees come before their callers, considering only
+    /// call edges between the given nodes. A call cycle is placed as one
+    /// unit after everything it calls; ties, and the members of a cycle, go
+    /// in name order.
+    pub fn sort_topologically<'a>(&self, nodes: Vec<&'a Node>) -> Vec<&'a Node> {
+        let keys: HashMap<&str, (&str, &str)> = nodes
+            .iter()
+            .map(|n| (n.id.as_str(), (n.name.as_str(), n.id.as_str())))
+            .collect();
+        let mut deps: HashMap<(&str, &str), HashSet<(&str, &str)>> = HashMap::new();
+        for node in &nodes {
+            let key = keys[node.id.as_str()];
+            for edge in self.get_outgoing_edges(&node.id) {
+                if edge.edge_type != EdgeType::Calls {
+                    continue;
+                }
+                for &idx in self.edge_targets(edge) {
+                    if let Some(&callee) = keys.get(self.nodes[idx].id.as_str()) {
+                        if callee != key {
+                            deps.entry(key).or_default().insert(callee);
+                        }
+                    }
+                }
+            }
         }
+
+        // Order the call cycles as units, each known by its first member
+        let items: BTreeSet<(&str, &str)> = keys.values().copied().collect();
+        let components = strongly_connected(&items, &deps);
+        let component_of: HashMap<(&str, &str), (&str, &str)> = components
+            .iter()
+            .flat_map(|members| members.iter().map(|&member| (member, members[0])))
+            .collect();
+        let mut condensed: HashMap<(&str, &str), HashSet<(&str, &str)>> = HashMap::new();
+        for (key, callees) in &deps {
+            for callee in callees {
+                if component_of[key] != component_of[callee] {
+                    condensed
+                        .entry(component_of[key])
+                        .or_default()
+                        .insert(component_of[callee]);
+                }
+            }
+        }
+        let members: HashMap<(&str, &str), Vec<(&str, &str)>> = components
+            .into_iter()
+            .map(|members| (members[0], members))
+            .collect();
+        let units: BTreeSet<(&str, &str)> = members.keys().copied().collect();
+
+        let by_key: HashMap<(&str, &str), &'a Node> = nodes
+            .iter()
+            .map(|&n| ((n.name.as_str(), n.id.as_str()), n))
+            .collect();
+        kahn_order(&units, &condensed)
+            .0
+            .into_iter()
+            .flat_map(|unit| &members[&unit])
+            .map(|key| by_key[key])
+            .collect()
     }
 
     /// Find hotspots (most called functions)
-    pub fn find_hotspots(&self, limit: usize) -> Vec<HotspotResult> {
+    /// Most-called names by incoming edge count, imports excluded. With
+    /// `exclude_test_callers`, edges from nodes tagged `test` are not counted.
+    pub fn find_hotspots(&self, limit: usize, exclude_test_callers: bool) -> Vec<HotspotResult> {
         let mut hotspots: std::collections::HashMap<String, usize> =
             std::collections::HashMap::new();
 
         for edge in &self.edges {
+            if edge.edge_type == EdgeType::Imports {
+                continue;
+            }
+            if exclude_test_callers
+                && self
+                    .get_node_by_id(&edge.from)
+                    .is_some_and(|caller| caller.tags.iter().any(|t| t == TEST_TAG))
+            {
+                continue;
+            }
             *hotspots.entry(edge.to.clone()).or_insert(0) += 1;
         }
 
@@ -574,17 +1690,394 @@ impl CodeGraph {
             })
             .collect();
 
-        results.sort_by(|a, b| b.call_count.cmp(&a.call_count));
+        results.sort_by_key(|r| std::cmp::Reverse(r.call_count));
         results.truncate(limit);
         results
     }
 
-    /// Extract a subgraph rooted at a specific node with given depth
+    /// Betweenness centrality of every node over call edges (Brandes'
+    /// algorithm): how many shortest call paths between other functions pass
+    /// through it. Returns `(node_id, score)`, highest first.
+    pub fn betweenness_centrality(&self) -> Vec<(String, f64)> {
+        self.betweenness_centrality_sampled(None)
+    }
+
+    /// [`betweenness_centrality`](CodeGraph::betweenness_centrality) with at
+    /// most `samples` source nodes. Brandes runs per weakly connected
+    /// component; samples are spread over components by size (at least one
+    /// each), taken at even intervals, and scores are scaled up by the
+    /// component's sampling ratio so they estimate the exact values.
+    pub fn betweenness_centrality_sampled(&self, samples: Option<usize>) -> Vec<(String, f64)> {
+        let n = self.nodes.len();
+        let mut callees: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for edge in &self.edges {
+            if edge.edge_type != EdgeType::Calls {
+                continue;
+            }
+            let Some(&from) = self.node_by_id.get(&edge.from) else {
+                continue;
+            };
+            for &to in self.edge_targets(edge) {
+                if to != from && !callees[from].contains(&to) {
+                    callees[from].push(to);
+                }
+            }
+        }
+
+        // Weakly connected components; paths never cross them
+        let mut neighbors: Vec<Vec<usize>> = callees.clone();
+        for (from, targets) in callees.iter().enumerate() {
+            for &to in targets {
+                neighbors[to].push(from);
+            }
+        }
+        let mut component = vec![usize::MAX; n];
+        let mut components: Vec<Vec<usize>> = Vec::new();
+        for start in 0..n {
+            if component[start] != usize::MAX {
+                continue;
+            }
+            let id = components.len();
+            let mut members = vec![start];
+            component[start] = id;
+            let mut i = 0;
+            while i < members.len() {
+                for &next in &neighbors[members[i]] {
+                    if component[next] == usize::MAX {
+                        component[next] = id;
+                        members.push(next);
+                    }
+                }
+                i += 1;
+            }
+            components.push(members);
+        }
+
+        let mut scores = vec![0.0f64; n];
+        for members in &components {
+            // A node needs a caller and a callee to lie between two others
+            if members.len() < 3 {
+                continue;
+            }
+            let budget = samples.map_or(members.len(), |s| {
+                (s * members.len()).div_ceil(n).clamp(1, members.len())
+            });
+            let step = members.len() as f64 / budget as f64;
+            let scale = members.len() as f64 / budget as f64;
+
+            for k in 0..budget {
+                let source = members[(k as f64 * step) as usize];
+                for (node, delta) in Self::brandes_dependencies(source, &callees) {
+                    scores[node] += delta * scale;
+                }
+            }
+        }
+
+        let mut results: Vec<(String, f64)> = self
+            .nodes
+            .iter()
+            .zip(scores)
+            .map(|(node, score)| (node.id.clone(), score))
+            .collect();
+        results.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        results
+    }
+
+    /// Single-source step of Brandes' algorithm: BFS shortest-path counts
+    /// from `source`, then each node's dependency (share of those paths
+    /// passing through it), accumulated in reverse BFS order
+    fn brandes_dependencies(source: usize, callees: &[Vec<usize>]) -> Vec<(usize, f64)> {
+        let mut order = Vec::new();
+        let mut predecessors: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut paths: HashMap<usize, f64> = HashMap::from([(source, 1.0)]);
+        let mut distance: HashMap<usize, usize> = HashMap::from([(source, 0)]);
+        let mut queue = VecDeque::from([source]);
+
+        while let Some(v) = queue.pop_front() {
+            order.push(v);
+            for &w in &callees[v] {
+                if !distance.contains_key(&w) {
+                    distance.insert(w, distance[&v] + 1);
+                    queue.push_back(w);
+                }
+                if distance[&w] == distance[&v] + 1 {
+                    *paths.entry(w).or_insert(0.0) += paths[&v];
+                    predecessors.entry(w).or_default().push(v);
+                }
+            }
+        }
+
+        let mut dependency: HashMap<usize, f64> = HashMap::new();
+        let mut results = Vec::new();
+        for &w in order.iter().rev() {
+            let delta_w = dependency.get(&w).copied().unwrap_or(0.0);
+            for &v in predecessors.get(&w).map_or(&[][..], Vec::as_slice) {
+                *dependency.entry(v).or_insert(0.0) += paths[&v] / paths[&w] * (1.0 + delta_w);
+            }
+            if w != source && delta_w > 0.0 {
+                results.push((w, delta_w));
+            }
+        }
+        results
+    }
+
+    /// Find functions whose signatures resemble the given node's signature.
+    /// Scores combine parameter type sequence (edit distance), return types
+    /// (Jaccard) and name token overlap (Jaccard), each in the 0.0-1.0 range;
+    /// two empty lists score 0, since having nothing in common says nothing.
+    /// Only callables (functions, methods, handlers, middleware) are
+    /// compared. Returns matches scoring at least `threshold`, highest first.
+    pub fn find_similar_signatures(&self, node_id: &str, threshold: f32) -> Vec<(&Node, f32)> {
+        let target = match self.get_node_by_id(node_id) {
+            Some(node) => node,
+            None => return Vec::new(),
+        };
+
+        let target_params: Vec<&str> = target
+            .parameters
+            .iter()
+            .map(|p| p.param_type.as_str())
+            .collect();
+        let target_returns: HashSet<&str> = target.returns.iter().map(|r| r.as_str()).collect();
+        let target_tokens = name_tokens(&target.name);
+
+        let mut results: Vec<(&Node, f32)> = self
+            .nodes
+            .iter()
+            .filter(|n| n.id != target.id)
+            .filter(|n| !matches!(n.node_type, NodeType::Class | NodeType::Custom(_)))
+            .map(|n| {
+                let params: Vec<&str> =
+                    n.parameters.iter().map(|p| p.param_type.as_str()).collect();
+                let returns: HashSet<&str> = n.returns.iter().map(|r| r.as_str()).collect();
+                let tokens = name_tokens(&n.name);
+
+                let score = 0.4 * sequence_similarity(&target_params, &params)
+                    + 0.2 * jaccard(&target_returns, &returns)
+                    + 0.4 * jaccard(&target_tokens, &tokens);
+                (n, score)
+            })
+            .filter(|(_, score)| *score >= threshold)
+            .collect();
+
+        results.sort_by(|a, b| b.1.total_cmp(&a.1));
+        results
+    }
+
+    /// Robert Martin's coupling metrics per package: afferent coupling Ca
+    /// (calls in from other packages), efferent coupling Ce (calls out to
+    /// other packages) and instability I = Ce / (Ca + Ce). Only `Calls`
+    /// edges between packages count. A callee is the node its `to_id`
+    /// resolves to, or the one node with its name; calls to names defined
+    /// several times or not at all are ignored. Packages without
+    /// cross-package calls have I = 0. Sorted by descending instability.
+    pub fn package_coupling(&self) -> Vec<PackageMetrics> {
+        let mut metrics: HashMap<&str, PackageMetrics> = HashMap::new();
+        for node in &self.nodes {
+            metrics
+                .entry(node.package.as_str())
+                .or_insert_with(|| PackageMetrics {
+                    package: node.package.clone(),
+                    ..Default::default()
+                });
+        }
+
+        for edge in &self.edges {
+            if edge.edge_type != EdgeType::Calls {
+                continue;
+            }
+            let Some(from_package) = self.get_node_by_id(&edge.from).map(|n| n.package.as_str())
+            else {
+                continue;
+            };
+            let [to] = self.edge_targets(edge) else {
+                continue;
+            };
+            let to_package = self.nodes[*to].package.as_str();
+            if to_package == from_package {
+                continue;
+            }
+
+            if let Some(m) = metrics.get_mut(from_package) {
+                m.efferent += 1;
+            }
+            if let Some(m) = metrics.get_mut(to_package) {
+                m.afferent += 1;
+            }
+        }
+
+        let mut results: Vec<PackageMetrics> = metrics
+            .into_values()
+            .map(|mut m| {
+                let coupling = m.afferent + m.efferent;
+                m.instability = if coupling == 0 {
+                    0.0
+                } else {
+                    m.efferent as f64 / coupling as f64
+                };
+                m
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.instability
+                .total_cmp(&a.instability)
+                .then_with(|| a.package.cmp(&b.package))
+        });
+        results
+    }
+
+    /// Robert Martin's package metrics: abstractness A (abstract types / all
+    /// types), instability I (see [`CodeGraph::package_coupling`]) and
+    /// distance from the main sequence D = |A + I - 1|. Packages without
+    /// types have A = 0. Sorted by descending distance.
+    pub fn package_abstractness(&self) -> Vec<PackageAbstractness> {
+        let mut metrics: HashMap<&str, PackageAbstractness> = HashMap::new();
+
+        for node in &self.nodes {
+            let m = metrics
+                .entry(node.package.as_str())
+                .or_insert_with(|| PackageAbstractness {
+                    package: node.package.clone(),
+                    ..Default::default()
+                });
+            if node.is_abstract_type() {
+                m.abstract_types += 1;
+            } else if node.is_type() {
+                m.concrete_types += 1;
+            }
+        }
+
+        for coupling in self.package_coupling() {
+            if let Some(m) = metrics.get_mut(coupling.package.as_str()) {
+                m.afferent = coupling.afferent;
+                m.efferent = coupling.efferent;
+                m.instability = coupling.instability;
+            }
+        }
+
+        let mut results: Vec<PackageAbstractness> = metrics
+            .into_values()
+            .map(|mut m| {
+                let types = m.abstract_types + m.concrete_types;
+                m.abstractness = if types == 0 {
+                    0.0
+                } else {
+                    m.abstract_types as f64 / types as f64
+                };
+                m.distance = (m.abstractness + m.instability - 1.0).abs();
+                m
+            })
+            .collect();
+
+        results.sort_by(|a, b| {
+            b.distance
+                .total_cmp(&a.distance)
+                .then_with(|| a.package.cmp(&b.package))
+        });
+        results
+    }
+
+    /// The file holding nodes whose path ends with `file`, compared by whole
+    /// components: `api/server.go` matches `src/api/server.go` but not
+    /// `src/myapi/server.go`. `None` when no node's file matches; an error
+    /// listing the candidates when several files do.
+    pub fn find_file(&self, file: &str) -> anyhow::Result<Option<&Path>> {
+        let matches: BTreeSet<&Path> = self
+            .nodes
+            .iter()
+            .map(|n| n.file_path.as_path())
+            .filter(|path| path.ends_with(file))
+            .collect();
+        match matches.len() {
+            0 => Ok(None),
+            1 => Ok(matches.into_iter().next()),
+            _ => anyhow::bail!(
+                "{} matches several files, give more of its path: {}",
+                file,
+                matches
+                    .iter()
+                    .map(|path| path.display().to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+
+    /// Describe how functions are laid out within a file (see
+    /// [`find_file`](CodeGraph::find_file)): each function's line span, the
+    /// gap before it, and how much of the file they cover. `total_lines`
+    /// defaults to the last function's end line when unknown.
+    pub fn file_layout(&self, file: &Path, total_lines: Option<usize>) -> Option<FileLayout> {
+        let mut nodes: Vec<&Node> = self.nodes.iter().filter(|n| n.file_path == file).collect();
+
+        if nodes.is_empty() {
+            return None;
+        }
+
+        nodes.sort_by_key(|n| (n.line, n.end_line));
+
+        let last_line = nodes.iter().map(|n| n.end_line).max().unwrap_or(0);
+        let total_lines = total_lines.unwrap_or(last_line).max(last_line);
+
+        let mut functions = Vec::with_capacity(nodes.len());
+        let mut covered_lines = 0;
+        let mut covered_until = 0;
+
+        for node in &nodes {
+            functions.push(FunctionSpan {
+                name: node.name.clone(),
+                line: node.line,
+                end_line: node.end_line,
+                gap_before: node.line.saturating_sub(covered_until + 1),
+            });
+
+            // Nested functions overlap their parents; only count new lines
+            if node.end_line > covered_until {
+                let start = node.line.max(covered_until + 1);
+                covered_lines += node.end_line + 1 - start;
+                covered_until = node.end_line;
+            }
+        }
+
+        let coverage_percent = if total_lines == 0 {
+            0.0
+        } else {
+            covered_lines as f64 / total_lines as f64 * 100.0
+        };
+
+        Some(FileLayout {
+            file: nodes[0].file_path.to_string_lossy().to_string(),
+            total_lines,
+            function_lines: covered_lines,
+            coverage_percent,
+            functions,
+        })
+    }
+
+    /// Extract a subgraph rooted at a specific node with given depth.
+    /// Depth counts call edges, matching `trace_dependencies`: the subgraph holds
+    /// nodes up to N hops away and the edges followed to reach them
     pub fn extract_subgraph(&self, from_name: &str, max_depth: usize) -> CodeGraph {
+        self.extract_subgraph_directed(from_name, max_depth, TraversalDirection::Out, false)
+    }
+
+    /// Extract a subgraph following callees (`Out`), callers (`In`) or both.
+    /// Depth follows the same convention as `extract_subgraph`. With
+    /// `include_callers`, the direct callers of the starting nodes and their
+    /// call edges are added too (one hop, not expanded further), to show
+    /// where a callee-only extract is entered from.
+    pub fn extract_subgraph_directed(
+        &self,
+        from_name: &str,
+        max_depth: usize,
+        direction: TraversalDirection,
+        include_callers: bool,
+    ) -> CodeGraph {
         let mut extracted_nodes = Vec::new();
         let mut extracted_edges = Vec::new();
-        let mut visited = HashSet::new();
         let mut node_ids_to_include = HashSet::new();
+        let mut edge_indices_to_include = HashSet::new();
 
         // Find starting nodes by name
         if let Some(start_nodes) = self.by_name.get(from_name) {
@@ -594,23 +2087,54 @@ impl CodeGraph {
                         &start_node.id,
                         0,
                         max_depth,
-                        &mut visited,
+                        direction,
                         &mut node_ids_to_include,
+                        &mut edge_indices_to_include,
                     );
                 }
             }
+
+            if include_callers {
+                let start_ids: HashSet<&str> = start_nodes
+                    .iter()
+                    .filter_map(|&idx| self.nodes.get(idx))
+                    .map(|node| node.id.as_str())
+                    .collect();
+                for &edge_idx in self.incoming.get(from_name).into_iter().flatten() {
+                    let Some(edge) = self.edges.get(edge_idx) else {
+                        continue;
+                    };
+                    // Same-named callee resolved to a different node
+                    if edge
+                        .to_id
+                        .as_deref()
+                        .is_some_and(|id| !start_ids.contains(id))
+                    {
+                        continue;
+                    }
+                    if self.node_by_id.contains_key(&edge.from) {
+                        node_ids_to_include.insert(edge.from.clone());
+                        edge_indices_to_include.insert(edge_idx);
+                    }
+                }
+            }
         }
 
-        // Collect nodes that should be included
+        // Collect nodes that should be included. Precomputed degrees describe
+        // the full graph, so they are dropped rather than left stale.
         for node in &self.nodes {
             if node_ids_to_include.contains(&node.id) {
-                extracted_nodes.push(node.clone());
+                let mut node = node.clone();
+                node.metadata.remove("fan_in");
+                node.metadata.remove("fan_out");
+                extracted_nodes.push(node);
             }
         }
 
-        // Collect edges where from is in the subgraph
-        for edge in &self.edges {
-            if node_ids_to_include.contains(&edge.from) {
+        // Collect the edges followed during traversal (edges beyond the
+        // outermost nodes exceed max_depth and are left out)
+        for (idx, edge) in self.edges.iter().enumerate() {
+            if edge_indices_to_include.contains(&idx) {
                 extracted_edges.push(edge.clone());
             }
         }
@@ -626,9 +2150,11 @@ impl CodeGraph {
                     total_nodes: extracted_nodes.len(),
                     total_edges: extracted_edges.len(),
                     files_parsed: 0,
+                    isolated_nodes: 0,
                 },
                 file_metadata: HashMap::new(),
                 git_commit_hash: None,
+                generated_files: self.metadata.generated_files.clone(),
             },
             nodes: extracted_nodes,
             edges: extracted_edges,
@@ -637,6 +2163,7 @@ impl CodeGraph {
             incoming: Default::default(),
             by_name: Default::default(),
             by_type: Default::default(),
+            by_tag: Default::default(),
             indices_dirty: true,
         };
 
@@ -645,11 +2172,16 @@ impl CodeGraph {
     }
 
     /// Filter graph based on criteria, returning a new filtered graph
+    ///
+    /// Edges are kept when their caller is retained. With
+    /// `drop_dangling_edges`, an edge must also point at a retained node: its
+    /// resolved target, or any retained node with the callee's name.
     pub fn filter(
         &self,
         package_filter: Option<&str>,
         type_filter: Option<&NodeType>,
         exclude_tests: bool,
+        drop_dangling_edges: bool,
     ) -> CodeGraph {
         let mut filtered_nodes = Vec::new();
         let mut filtered_node_ids = HashSet::new();
@@ -671,111 +2203,555 @@ impl CodeGraph {
                 }
             }
 
-            // Exclude tests
-            if exclude_tests {
-                let file_path_str = node.file_path.to_string_lossy();
-                if file_path_str.contains("_test") || file_path_str.contains(".test.") {
-                    include = false;
-                }
+            // Exclude tests
+            if exclude_tests && is_test_file(&node.file_path) {
+                include = false;
+            }
+
+            if include {
+                filtered_nodes.push(node.clone());
+                filtered_node_ids.insert(node.id.clone());
+            }
+        }
+
+        let retained_names: HashSet<&str> =
+            filtered_nodes.iter().map(|n| n.name.as_str()).collect();
+        let target_retained = |e: &Edge| match &e.to_id {
+            Some(to_id) => filtered_node_ids.contains(to_id),
+            None => retained_names.contains(e.to.as_str()),
+        };
+
+        let filtered_edges: Vec<_> = self
+            .edges
+            .iter()
+            .filter(|e| filtered_node_ids.contains(&e.from))
+            .filter(|e| !drop_dangling_edges || target_retained(e))
+            .cloned()
+            .collect();
+
+        let mut filtered_graph = CodeGraph {
+            metadata: crate::core::GraphMetadata {
+                version: self.metadata.version.clone(),
+                generated_at: chrono::Utc::now().to_rfc3339(),
+                generator: "code-navigator-filter".to_string(),
+                language: self.metadata.language.clone(),
+                root_path: self.metadata.root_path.clone(),
+                stats: crate::core::GraphStats {
+                    total_nodes: filtered_nodes.len(),
+                    total_edges: filtered_edges.len(),
+                    files_parsed: 0,
+                    isolated_nodes: 0,
+                },
+                file_metadata: HashMap::new(),
+                git_commit_hash: None,
+                generated_files: self.metadata.generated_files.clone(),
+            },
+            nodes: filtered_nodes,
+            edges: filtered_edges,
+            node_by_id: Default::default(),
+            outgoing: Default::default(),
+            incoming: Default::default(),
+            by_name: Default::default(),
+            by_type: Default::default(),
+            by_tag: Default::default(),
+            indices_dirty: true,
+        };
+
+        filtered_graph.build_indexes();
+        filtered_graph
+    }
+
+    fn extract_recursive(
+        &self,
+        node_id: &str,
+        depth: usize,
+        max_depth: usize,
+        direction: TraversalDirection,
+        visited: &mut HashSet<String>,
+        edge_indices_to_include: &mut HashSet<usize>,
+    ) {
+        if !visited.insert(node_id.to_string()) {
+            return;
+        }
+
+        // Same convention as trace_recursive: depth N follows N call edges,
+        // so nodes at max_depth are included but not expanded
+        if depth >= max_depth {
+            return;
+        }
+
+        // Callees: edges leaving this node, resolved to nodes by ID or name
+        if matches!(
+            direction,
+            TraversalDirection::Out | TraversalDirection::Both
+        ) {
+            for &edge_idx in self.outgoing.get(node_id).into_iter().flatten() {
+                let Some(edge) = self.edges.get(edge_idx) else {
+                    continue;
+                };
+                edge_indices_to_include.insert(edge_idx);
+
+                for &target_idx in self.edge_targets(edge) {
+                    if let Some(target_node) = self.nodes.get(target_idx) {
+                        self.extract_recursive(
+                            &target_node.id,
+                            depth + 1,
+                            max_depth,
+                            direction,
+                            visited,
+                            edge_indices_to_include,
+                        );
+                    }
+                }
+            }
+        }
+
+        // Callers: edges pointing at this node's name, from their source node
+        if matches!(direction, TraversalDirection::In | TraversalDirection::Both) {
+            let Some(node) = self.get_node_by_id(node_id) else {
+                return;
+            };
+            for &edge_idx in self.incoming.get(&node.name).into_iter().flatten() {
+                let Some(edge) = self.edges.get(edge_idx) else {
+                    continue;
+                };
+                // Same-named callee resolved to a different node
+                if edge.to_id.as_ref().is_some_and(|id| id != node_id) {
+                    continue;
+                }
+                edge_indices_to_include.insert(edge_idx);
+
+                if self.node_by_id.contains_key(&edge.from) {
+                    self.extract_recursive(
+                        &edge.from,
+                        depth + 1,
+                        max_depth,
+                        direction,
+                        visited,
+                        edge_indices_to_include,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Remove all nodes and edges from a specific file.
+    ///
+    /// Calls from other files into the removed nodes are kept (their source
+    /// still makes them) but unresolved: `to_id` and `candidates` referring
+    /// to a removed node are cleared. Returns the names of the removed nodes.
+    pub fn remove_nodes_from_file(&mut self, file_path: &str) -> HashSet<String> {
+        let file_path_normalized = file_path.to_string();
+
+        // Find nodes to remove
+        let nodes_to_remove: HashSet<String> = self
+            .nodes
+            .iter()
+            .filter(|n| n.file_path.to_string_lossy() == file_path_normalized)
+            .map(|n| n.id.clone())
+            .collect();
+        let removed_names: HashSet<String> = self
+            .nodes
+            .iter()
+            .filter(|n| nodes_to_remove.contains(&n.id))
+            .map(|n| n.name.clone())
+            .collect();
+
+        // Remove nodes
+        self.nodes.retain(|n| !nodes_to_remove.contains(&n.id));
+
+        // Remove edges where from node is being removed, and the file's imports
+        self.edges
+            .retain(|e| !nodes_to_remove.contains(&e.from) && e.from != file_path_normalized);
+
+        // Incoming calls must not keep pointing at the removed nodes
+        for edge in &mut self.edges {
+            if edge
+                .to_id
+                .as_ref()
+                .is_some_and(|id| nodes_to_remove.contains(id))
+            {
+                edge.to_id = None;
+            }
+            if edge.metadata.get(CANDIDATES_KEY).is_some_and(|candidates| {
+                candidates.split(',').any(|id| nodes_to_remove.contains(id))
+            }) {
+                edge.metadata.remove(CANDIDATES_KEY);
+            }
+        }
+
+        // Rebuild indexes after removal
+        self.build_indexes();
+        removed_names
+    }
+
+    /// Replace a file's nodes and edges with a fresh parse: remove them,
+    /// run `parse` (e.g. `|g| parser.parse_file(path, g)`), then re-resolve
+    /// calls to every name the file defined before or after, so callers in
+    /// other files follow renames and moves. The file's old nodes stay
+    /// removed if `parse` fails.
+    pub fn reindex_file(
+        &mut self,
+        file_path: &Path,
+        parse: impl FnOnce(&mut CodeGraph) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let file_str = file_path.to_string_lossy().to_string();
+        let mut affected = self.remove_nodes_from_file(&file_str);
+
+        let result = parse(self);
+        self.build_indexes();
+        affected.extend(
+            self.nodes
+                .iter()
+                .filter(|n| n.file_path.to_string_lossy() == file_str)
+                .map(|n| n.name.clone()),
+        );
+
+        self.resolve_edges_where(|e| affected.contains(&e.to));
+        result
+    }
+
+    /// Rewrite file paths (and the node IDs derived from them) to be relative
+    /// to `root`. Used when the indexed directory is temporary, e.g. an
+    /// extracted archive, so the graph doesn't reference paths that vanish.
+    pub fn relativize_paths(&mut self, root: &std::path::Path) {
+        let prefix = format!(
+            "{}{}",
+            root.to_string_lossy().trim_end_matches(['/', '\\']),
+            std::path::MAIN_SEPARATOR
+        );
+        let strip = |s: &str| s.strip_prefix(&prefix).unwrap_or(s).to_string();
+
+        for node in &mut self.nodes {
+            node.id = strip(&node.id);
+            node.file_path = PathBuf::from(strip(&node.file_path.to_string_lossy()));
+        }
+
+        for edge in &mut self.edges {
+            edge.from = strip(&edge.from);
+            edge.file_path = PathBuf::from(strip(&edge.file_path.to_string_lossy()));
+        }
+
+        self.metadata.generated_files = self
+            .metadata
+            .generated_files
+            .iter()
+            .map(|f| strip(f))
+            .collect();
+
+        self.metadata.file_metadata = std::mem::take(&mut self.metadata.file_metadata)
+            .into_values()
+            .map(|mut meta| {
+                meta.path = strip(&meta.path);
+                meta.node_ids = meta.node_ids.iter().map(|id| strip(id)).collect();
+                (meta.path.clone(), meta)
+            })
+            .collect();
+
+        self.build_indexes();
+    }
+
+    /// Collapse identical nodes found in several files (e.g. vendored copies)
+    /// into one representative: the copy with the smallest file path.
+    ///
+    /// Nodes are identical when name, signature, parameters, returns, length
+    /// and outgoing call sites all match. The other copies' `file:line`
+    /// locations are recorded in the representative's `duplicates` metadata
+    /// (comma-separated). Their edges are redirected to the representative,
+    /// where they coincide with its own edges and are merged away.
+    /// Returns the number of nodes removed.
+    pub fn dedup_identical_nodes(&mut self) -> usize {
+        type ContentKey = (
+            String,
+            String,
+            Vec<(String, String)>,
+            Vec<String>,
+            usize,
+            Vec<String>,
+        );
+
+        let mut groups: HashMap<ContentKey, Vec<usize>> = HashMap::new();
+        for (idx, node) in self.nodes.iter().enumerate() {
+            let mut call_sites: Vec<String> = self
+                .get_outgoing_edges(&node.id)
+                .iter()
+                .map(|e| format!("{}|{}", e.to, e.call_site))
+                .collect();
+            call_sites.sort();
+
+            let key = (
+                node.name.clone(),
+                node.signature.clone(),
+                node.parameters
+                    .iter()
+                    .map(|p| (p.name.clone(), p.param_type.clone()))
+                    .collect(),
+                node.returns.clone(),
+                node.end_line.saturating_sub(node.line),
+                call_sites,
+            );
+            groups.entry(key).or_default().push(idx);
+        }
+
+        let mut removed_ids: HashMap<String, String> = HashMap::new(); // duplicate -> representative
+        for mut members in groups.into_values() {
+            // Copies within a single file aren't vendored duplicates
+            let files: HashSet<_> = members.iter().map(|&i| &self.nodes[i].file_path).collect();
+            if files.len() < 2 {
+                continue;
             }
 
-            if include {
-                filtered_nodes.push(node.clone());
-                filtered_node_ids.insert(node.id.clone());
+            members.sort_by(|&a, &b| {
+                let (a, b) = (&self.nodes[a], &self.nodes[b]);
+                (&a.file_path, a.line).cmp(&(&b.file_path, b.line))
+            });
+
+            let representative = members[0];
+            let duplicates: Vec<usize> = members[1..]
+                .iter()
+                .copied()
+                .filter(|&i| self.nodes[i].file_path != self.nodes[representative].file_path)
+                .collect();
+
+            let locations: Vec<String> = duplicates
+                .iter()
+                .map(|&i| self.nodes[i].location())
+                .collect();
+            let rep_id = self.nodes[representative].id.clone();
+            for &i in &duplicates {
+                removed_ids.insert(self.nodes[i].id.clone(), rep_id.clone());
             }
+            self.nodes[representative]
+                .metadata
+                .insert("duplicates".to_string(), locations.join(","));
         }
 
-        // Filter edges to only include edges where both from and to are in filtered nodes
-        let filtered_edges: Vec<_> = self
-            .edges
+        if removed_ids.is_empty() {
+            return 0;
+        }
+
+        self.nodes.retain(|n| !removed_ids.contains_key(&n.id));
+
+        // Redirect edges from removed copies to their representative,
+        // skipping those that duplicate an edge it already has
+        let (redirected, mut kept): (Vec<Edge>, Vec<Edge>) = std::mem::take(&mut self.edges)
+            .into_iter()
+            .partition(|e| removed_ids.contains_key(&e.from));
+        let existing: HashSet<(String, String, String)> = kept
             .iter()
-            .filter(|e| filtered_node_ids.contains(&e.from))
-            .cloned()
+            .map(|e| (e.from.clone(), e.to.clone(), e.call_site.clone()))
             .collect();
+        for mut edge in redirected {
+            edge.from = removed_ids[&edge.from].clone();
+            if !existing.contains(&(edge.from.clone(), edge.to.clone(), edge.call_site.clone())) {
+                kept.push(edge);
+            }
+        }
+        self.edges = kept;
 
-        let mut filtered_graph = CodeGraph {
-            metadata: crate::core::GraphMetadata {
-                version: self.metadata.version.clone(),
-                generated_at: chrono::Utc::now().to_rfc3339(),
-                generator: "code-navigator-filter".to_string(),
-                language: self.metadata.language.clone(),
-                root_path: self.metadata.root_path.clone(),
-                stats: crate::core::GraphStats {
-                    total_nodes: filtered_nodes.len(),
-                    total_edges: filtered_edges.len(),
-                    files_parsed: 0,
-                },
-                file_metadata: HashMap::new(),
-                git_commit_hash: None,
-            },
-            nodes: filtered_nodes,
-            edges: filtered_edges,
-            node_by_id: Default::default(),
-            outgoing: Default::default(),
-            incoming: Default::default(),
-            by_name: Default::default(),
-            by_type: Default::default(),
-            indices_dirty: true,
-        };
+        for meta in self.metadata.file_metadata.values_mut() {
+            meta.node_ids.retain(|id| !removed_ids.contains_key(id));
+        }
 
-        filtered_graph.build_indexes();
-        filtered_graph
+        self.metadata.stats.total_nodes = self.nodes.len();
+        self.metadata.stats.total_edges = self.edges.len();
+        self.build_indexes();
+
+        removed_ids.len()
     }
 
-    fn extract_recursive(
-        &self,
-        node_id: &str,
-        depth: usize,
-        max_depth: usize,
-        visited: &mut HashSet<String>,
-        node_ids_to_include: &mut HashSet<String>,
-    ) {
-        if depth > max_depth || visited.contains(node_id) {
-            return;
+    /// Replace path-based node IDs (`file:name:line`) with content hashes of
+    /// `(package, name, signature, parameters)`, so identical functions in
+    /// graphs built from different checkouts share an ID and [`merge_dedup`]
+    /// and [`diff`] line them up. The hash is SHA-256, so IDs are stable
+    /// across platforms and releases.
+    ///
+    /// Edge `from`, resolved `to_id`, ambiguity `candidates` and file
+    /// metadata are rewritten to match; `to` stays the callee name, and
+    /// import edges keep their file path as `from`. The hash
+    /// ignores location and body, so distinct functions with the same
+    /// signature collide: within one graph the later copies (in file/line
+    /// order) get a `-2`, `-3`, ... suffix, which only lines up across
+    /// graphs when the copies appear in the same order. Returns the number
+    /// of suffixed IDs.
+    ///
+    /// [`merge_dedup`]: CodeGraph::merge_dedup
+    /// [`diff`]: CodeGraph::diff
+    pub fn assign_content_ids(&mut self) -> usize {
+        let mut order: Vec<usize> = (0..self.nodes.len()).collect();
+        order.sort_by(|&a, &b| {
+            let (a, b) = (&self.nodes[a], &self.nodes[b]);
+            (&a.file_path, a.line).cmp(&(&b.file_path, b.line))
+        });
+
+        let mut new_ids: HashMap<String, String> = HashMap::new();
+        let mut used: HashSet<String> = HashSet::new();
+        let mut collisions = 0;
+        for idx in order {
+            let node = &self.nodes[idx];
+            // Fields are NUL-terminated so their boundaries can't shift
+            let mut hasher = Sha256::new();
+            for field in [&node.package, &node.name, &node.signature] {
+                hasher.update(field.as_bytes());
+                hasher.update([0]);
+            }
+            for parameter in &node.parameters {
+                hasher.update(parameter.name.as_bytes());
+                hasher.update([0]);
+                hasher.update(parameter.param_type.as_bytes());
+                hasher.update([0]);
+            }
+            let base: String = hasher.finalize()[..8]
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect();
+
+            let mut id = base.clone();
+            let mut suffix = 2;
+            while used.contains(&id) {
+                id = format!("{}-{}", base, suffix);
+                suffix += 1;
+            }
+            collisions += usize::from(suffix > 2);
+            used.insert(id.clone());
+            new_ids.insert(node.id.clone(), id);
         }
 
-        visited.insert(node_id.to_string());
-        node_ids_to_include.insert(node_id.to_string());
+        let rename = |id: &str| new_ids.get(id).cloned().unwrap_or_else(|| id.to_string());
+        for node in &mut self.nodes {
+            node.id = rename(&node.id);
+        }
+        for edge in &mut self.edges {
+            edge.from = rename(&edge.from);
+            edge.to_id = edge.to_id.as_deref().map(rename);
+            if let Some(candidates) = edge.metadata.get_mut(CANDIDATES_KEY) {
+                *candidates = candidates
+                    .split(',')
+                    .map(rename)
+                    .collect::<Vec<_>>()
+                    .join(",");
+            }
+        }
+        for meta in self.metadata.file_metadata.values_mut() {
+            meta.node_ids = meta.node_ids.iter().map(|id| rename(id)).collect();
+        }
 
-        // Traverse outgoing edges
-        for edge in self.get_outgoing_edges(node_id) {
-            // Try to find target nodes by name
-            if let Some(target_nodes) = self.by_name.get(&edge.to) {
-                for &target_idx in target_nodes {
-                    if let Some(target_node) = self.nodes.get(target_idx) {
-                        self.extract_recursive(
-                            &target_node.id,
-                            depth + 1,
-                            max_depth,
-                            visited,
-                            node_ids_to_include,
-                        );
-                    }
-                }
+        self.build_indexes();
+        collisions
+    }
+
+    /// Add a tag to a node (no-op if the node doesn't exist or already has it)
+    pub fn add_tag(&mut self, node_id: &str, tag: &str) {
+        if let Some(&idx) = self.node_by_id.get(node_id) {
+            let tags = &mut self.nodes[idx].tags;
+            if !tags.iter().any(|t| t == tag) {
+                tags.push(tag.to_string());
+                self.by_tag.entry(tag.to_string()).or_default().push(idx);
             }
         }
     }
 
-    /// Remove all nodes and edges from a specific file
-    pub fn remove_nodes_from_file(&mut self, file_path: &str) {
-        let file_path_normalized = file_path.to_string();
+    /// Tag every node defined in a test file with `test`. Returns the number
+    /// of newly tagged nodes.
+    pub fn tag_tests(&mut self) -> usize {
+        let mut tagged = 0;
+        for idx in 0..self.nodes.len() {
+            let node = &mut self.nodes[idx];
+            if is_test_file(&node.file_path) && !node.tags.iter().any(|t| t == TEST_TAG) {
+                node.tags.push(TEST_TAG.to_string());
+                self.by_tag
+                    .entry(TEST_TAG.to_string())
+                    .or_default()
+                    .push(idx);
+                tagged += 1;
+            }
+        }
+        tagged
+    }
 
-        // Find nodes to remove
-        let nodes_to_remove: Vec<String> = self
+    /// Record that a file was detected as generated code
+    pub fn mark_generated_file(&mut self, file_path: &std::path::Path) {
+        self.metadata
+            .generated_files
+            .insert(file_path.to_string_lossy().to_string());
+    }
+
+    /// Check whether a file was recorded as generated code
+    pub fn is_generated_file(&self, file_path: &std::path::Path) -> bool {
+        self.metadata
+            .generated_files
+            .contains(file_path.to_string_lossy().as_ref())
+    }
+
+    /// Drop edges whose call site lives in a generated file.
+    /// Calls from hand-written code into generated code are kept.
+    pub fn remove_generated_edges(&mut self) {
+        if self.metadata.generated_files.is_empty() {
+            return;
+        }
+
+        let generated = &self.metadata.generated_files;
+        self.edges
+            .retain(|e| !generated.contains(e.file_path.to_string_lossy().as_ref()));
+        self.edges_removed();
+    }
+
+    /// Drop Python dunder methods (`__init__`, `__str__`, ...) together with
+    /// their outgoing edges and every edge calling a dunder name
+    pub fn remove_dunder(&mut self) {
+        let removed: HashSet<String> = self
             .nodes
             .iter()
-            .filter(|n| n.file_path.to_string_lossy() == file_path_normalized)
+            .filter(|n| is_dunder(&n.name))
             .map(|n| n.id.clone())
             .collect();
 
-        // Remove nodes
-        self.nodes.retain(|n| !nodes_to_remove.contains(&n.id));
+        self.nodes.retain(|n| !removed.contains(&n.id));
+        self.edges
+            .retain(|e| !removed.contains(&e.from) && !is_dunder(&e.to));
+        self.metadata.stats.total_nodes = self.nodes.len();
+        self.edges_removed();
+    }
+
+    /// Keep only call edges between public functions: the caller must be
+    /// public and the callee must resolve to a public node in the graph.
+    /// Calls to private helpers and to external code are dropped; edges other
+    /// than `Calls` (imports, implements, extends) are kept.
+    pub fn retain_public_edges(&mut self) {
+        let public_ids: HashSet<String> = self
+            .nodes
+            .iter()
+            .filter(|n| n.is_public())
+            .map(|n| n.id.clone())
+            .collect();
+        let public_names: HashSet<String> = self
+            .nodes
+            .iter()
+            .filter(|n| n.is_public())
+            .map(|n| n.name.clone())
+            .collect();
 
-        // Remove edges where from node is being removed
-        self.edges.retain(|e| !nodes_to_remove.contains(&e.from));
+        // Resolved calls must land on a public node; only unresolved ones
+        // fall back to matching a public node by name
+        self.edges.retain(|e| {
+            e.edge_type != EdgeType::Calls
+                || (public_ids.contains(&e.from)
+                    && match &e.to_id {
+                        Some(to_id) => public_ids.contains(to_id),
+                        None => public_names.contains(&e.to),
+                    })
+        });
+        self.edges_removed();
+    }
 
-        // Rebuild indexes after removal
+    /// Rebuild the indexes after dropping edges, recomputing stored degrees
+    /// so `fan_in`/`fan_out` metadata doesn't count the dropped ones
+    fn edges_removed(&mut self) {
+        self.metadata.stats.total_edges = self.edges.len();
         self.build_indexes();
+        if self.has_precomputed_degrees() {
+            self.precompute_degrees();
+        }
     }
 
     /// Track which nodes came from which file (for incremental updates)
@@ -840,10 +2816,8 @@ impl CodeGraph {
                 }
 
                 // Check complexity changes
-                let old_fan_in = self.incoming.get(&old_node.id).map_or(0, |v| v.len());
-                let old_fan_out = self.outgoing.get(&old_node.id).map_or(0, |v| v.len());
-                let new_fan_in = other.incoming.get(&old_node.id).map_or(0, |v| v.len());
-                let new_fan_out = other.outgoing.get(&old_node.id).map_or(0, |v| v.len());
+                let (old_fan_in, old_fan_out) = self.compute_degrees(&old_node.id);
+                let (new_fan_in, new_fan_out) = other.compute_degrees(&old_node.id);
 
                 let old_total = (old_fan_in + old_fan_out) as i32;
                 let new_total = (new_fan_in + new_fan_out) as i32;
@@ -866,16 +2840,71 @@ impl CodeGraph {
         let added_edges_count = other.edges.len().saturating_sub(self.edges.len());
         let removed_edges_count = self.edges.len().saturating_sub(other.edges.len());
 
+        // Like git's rename detection: a removed and an added node with the
+        // same body fingerprint but different names are one renamed node.
+        // Only unambiguous (one-to-one) matches count.
+        let mut candidates: HashMap<u64, (Vec<&Node>, Vec<&Node>)> = HashMap::new();
+        for node in removed_nodes
+            .iter()
+            .filter_map(|id| self.get_node_by_id(id))
+        {
+            if let Some(fingerprint) = self.body_fingerprint(node) {
+                candidates.entry(fingerprint).or_default().0.push(node);
+            }
+        }
+        for node in added_nodes.iter().filter_map(|id| other.get_node_by_id(id)) {
+            if let Some(fingerprint) = other.body_fingerprint(node) {
+                candidates.entry(fingerprint).or_default().1.push(node);
+            }
+        }
+        let mut renamed_nodes: Vec<RenamedNode> = candidates
+            .into_values()
+            .filter_map(|pair| match pair {
+                (old, new) if old.len() == 1 && new.len() == 1 && old[0].name != new[0].name => {
+                    Some(RenamedNode {
+                        old_id: old[0].id.clone(),
+                        new_id: new[0].id.clone(),
+                        old_name: old[0].name.clone(),
+                        new_name: new[0].name.clone(),
+                    })
+                }
+                _ => None,
+            })
+            .collect();
+        renamed_nodes.sort_by(|a, b| a.old_id.cmp(&b.old_id));
+        removed_nodes.retain(|id| !renamed_nodes.iter().any(|r| r.old_id == *id));
+        added_nodes.retain(|id| !renamed_nodes.iter().any(|r| r.new_id == *id));
+
         GraphDiff {
             added_nodes,
             removed_nodes,
             changed_nodes,
+            renamed_nodes,
             added_edges_count,
             removed_edges_count,
             complexity_changes,
         }
     }
 
+    /// Hash of what survives a rename: type, package, parameters, returns
+    /// and the `body_hash` the parsers record (which ignores the function's
+    /// own name, so recursive calls don't count). `None` for nodes without a
+    /// body hash.
+    fn body_fingerprint(&self, node: &Node) -> Option<u64> {
+        let body_hash = node.metadata.get("body_hash")?;
+
+        let mut hasher = DefaultHasher::new();
+        node.node_type.hash(&mut hasher);
+        node.package.hash(&mut hasher);
+        for parameter in &node.parameters {
+            parameter.name.hash(&mut hasher);
+            parameter.param_type.hash(&mut hasher);
+        }
+        node.returns.hash(&mut hasher);
+        body_hash.hash(&mut hasher);
+        Some(hasher.finish())
+    }
+
     /// Compute a hash of the graph structure for cache validation
     /// Uses fast hashing to detect if graph has changed
     pub fn compute_hash(&self) -> String {
@@ -908,6 +2937,39 @@ impl CodeGraph {
         format!("{:x}", hasher.finish())
     }
 
+    /// SHA-256 of every node and edge, in graph order. Unlike
+    /// [`compute_hash`], which samples the ends of the graph for cheap cache
+    /// validation, any change to a node's identity or signature, or to an
+    /// edge, changes it; and it is the same across platforms and releases.
+    ///
+    /// [`compute_hash`]: CodeGraph::compute_hash
+    pub fn fingerprint(&self) -> String {
+        // NUL-terminated fields, so their boundaries can't shift
+        let mut hasher = Sha256::new();
+        let mut field = |text: &str| {
+            hasher.update(text.as_bytes());
+            hasher.update([0]);
+        };
+
+        for node in &self.nodes {
+            field(&node.id);
+            field(&node.signature);
+            field(&node.end_line.to_string());
+        }
+        for edge in &self.edges {
+            field(&edge.from);
+            field(&edge.to);
+            field(&format!("{:?}", edge.edge_type));
+            field(&edge.line.to_string());
+        }
+
+        hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
     /// Extract indices to SerializedIndices for caching
     pub fn extract_indices(&self) -> SerializedIndices {
         SerializedIndices::from_graph(
@@ -917,6 +2979,7 @@ impl CodeGraph {
             &self.node_by_id,
             &self.by_name,
             &self.by_type,
+            &self.by_tag,
             &self.outgoing,
             &self.incoming,
         )
@@ -927,6 +2990,7 @@ impl CodeGraph {
         self.node_by_id = indices.node_by_id;
         self.by_name = indices.by_name;
         self.by_type = indices.by_type;
+        self.by_tag = indices.by_tag;
         self.outgoing = indices.outgoing;
         self.incoming = indices.incoming;
         self.indices_dirty = false;
@@ -942,31 +3006,227 @@ pub struct TraceResult {
     pub file_path: std::path::PathBuf,
     pub line: usize,
     pub depth: usize,
+    /// Callees of `to_name` left out by `trace --max-branching`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub omitted_callees: Option<usize>,
+}
+
+/// How multiple tags combine in [`CodeGraph::get_nodes_by_tags`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagMode {
+    /// Node must carry every tag
+    And,
+    /// Node must carry at least one tag
+    Or,
+}
+
+/// Which edges a traversal follows from each node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalDirection {
+    /// Callees (outgoing edges)
+    Out,
+    /// Callers (incoming edges)
+    In,
+    /// Callers and callees
+    Both,
+}
+
+/// One hop of an explained path: the edge connecting a caller to its callee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathHop {
+    pub caller: String,
+    pub callee: String,
+    pub call_site: String,
+    pub file_path: std::path::PathBuf,
+    pub line: usize,
+    pub caller_signature: Option<String>,
+    /// None when the callee isn't defined in the graph (e.g. an external call)
+    pub callee_signature: Option<String>,
+}
+
+impl PathHop {
+    /// Human-readable rationale, e.g. "A calls B at file:line via `b()`"
+    pub fn describe(&self) -> String {
+        format!(
+            "{} calls {} at {}:{} via `{}`",
+            self.caller,
+            self.callee,
+            self.file_path.display(),
+            self.line,
+            self.call_site.trim()
+        )
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComplexityMetrics {
     pub fan_in: usize,
     pub fan_out: usize,
+    /// Decision points in the body + 1 (1 for graphs indexed before they were counted)
     pub cyclomatic: usize,
 }
 
+impl ComplexityMetrics {
+    /// Combined complexity (fan-in + fan-out), as used to rank nodes in `analyze complexity`
+    pub fn combined(&self) -> usize {
+        self.fan_in + self.fan_out
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct HotspotResult {
     pub name: String,
     pub call_count: usize,
 }
 
+/// Graph overview, see [`CodeGraph::summary`]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphSummary {
+    pub total_nodes: usize,
+    pub total_edges: usize,
+    /// Distinct source files defining nodes or holding edges
+    pub files: usize,
+    /// Node and edge counts keyed by variant name (`HttpHandler`, `Calls`)
+    pub nodes_by_type: BTreeMap<String, usize>,
+    pub edges_by_type: BTreeMap<String, usize>,
+    /// Every package, largest first
+    pub packages: Vec<PackageSize>,
+    pub avg_fan_in: f64,
+    pub avg_fan_out: f64,
+    /// Highest fan-in + fan-out (ties by name); `None` for an empty graph
+    pub most_connected: Option<ConnectedNode>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageSize {
+    pub package: String,
+    pub nodes: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectedNode {
+    pub id: String,
+    pub name: String,
+    pub fan_in: usize,
+    pub fan_out: usize,
+}
+
+/// Definitions and call sites of one symbol, see [`CodeGraph::xref`]
+#[derive(Debug, Clone, Serialize)]
+pub struct Xref<'a> {
+    pub name: String,
+    pub definitions: Vec<&'a Node>,
+    /// Incoming call edges, i.e. every place the symbol is called
+    pub callers: Vec<&'a Edge>,
+    /// Outgoing call edges of the definitions
+    pub callees: Vec<&'a Edge>,
+}
+
+/// Callers and callees of one node, see [`CodeGraph::node_relations`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeRelations {
+    pub callers: Vec<RelationCount>,
+    pub callees: Vec<RelationCount>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RelationCount {
+    pub name: String,
+    pub calls: usize,
+}
+
+impl RelationCount {
+    fn top<'a>(names: impl Iterator<Item = &'a str>, limit: usize) -> Vec<RelationCount> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for name in names {
+            *counts.entry(name).or_default() += 1;
+        }
+
+        let mut ranked: Vec<RelationCount> = counts
+            .into_iter()
+            .map(|(name, calls)| RelationCount {
+                name: name.to_string(),
+                calls,
+            })
+            .collect();
+        ranked.sort_by(|a, b| b.calls.cmp(&a.calls).then_with(|| a.name.cmp(&b.name)));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageMetrics {
+    pub package: String,
+    pub afferent: usize,  // Ca: incoming edges from other packages
+    pub efferent: usize,  // Ce: outgoing edges to other packages
+    pub instability: f64, // I = Ce / (Ca + Ce)
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PackageAbstractness {
+    pub package: String,
+    pub abstract_types: usize,
+    pub concrete_types: usize,
+    pub afferent: usize, // Incoming calls from other packages
+    pub efferent: usize, // Outgoing calls to other packages
+    pub abstractness: f64,
+    pub instability: f64,
+    pub distance: f64, // Distance from the main sequence |A + I - 1|
+}
+
+impl PackageAbstractness {
+    /// Stable, concrete packages (low A, low I) are rigid and hard to change
+    pub fn in_zone_of_pain(&self) -> bool {
+        self.distance >= 0.5 && self.abstractness + self.instability < 1.0
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileLayout {
+    pub file: String,
+    pub total_lines: usize,
+    pub function_lines: usize,
+    pub coverage_percent: f64,
+    pub functions: Vec<FunctionSpan>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FunctionSpan {
+    pub name: String,
+    pub line: usize,
+    pub end_line: usize,
+    pub gap_before: usize, // Lines since the previous function ended
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GraphDiff {
     pub added_nodes: Vec<String>,   // Node IDs
     pub removed_nodes: Vec<String>, // Node IDs
     pub changed_nodes: Vec<NodeChange>,
+    /// Removed/added pairs with the same body under a new name; these are
+    /// left out of `added_nodes` and `removed_nodes`
+    #[serde(default)]
+    pub renamed_nodes: Vec<RenamedNode>,
     pub added_edges_count: usize,
     pub removed_edges_count: usize,
     pub complexity_changes: Vec<ComplexityChange>,
 }
 
+impl GraphDiff {
+    /// Complexity changes that grew by at least `threshold` (fan-in +
+    /// fan-out), largest first
+    pub fn complexity_increases(&self, threshold: usize) -> Vec<&ComplexityChange> {
+        let mut increases: Vec<_> = self
+            .complexity_changes
+            .iter()
+            .filter(|c| c.change > 0 && c.change >= threshold as i32)
+            .collect();
+        increases.sort_by(|a, b| b.change.cmp(&a.change).then(a.node_id.cmp(&b.node_id)));
+        increases
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeChange {
     pub node_id: String,
@@ -977,6 +3237,14 @@ pub struct NodeChange {
     pub new_line: usize,
 }
 
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RenamedNode {
+    pub old_id: String,
+    pub new_id: String,
+    pub old_name: String,
+    pub new_name: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ComplexityChange {
     pub node_id: String,
@@ -987,3 +3255,197 @@ pub struct ComplexityChange {
     pub new_fan_out: usize,
     pub change: i32, // positive = increased, negative = decreased
 }
+
+/// Kahn's algorithm: `items` ordered so each comes after its `deps`, ties in
+/// key order. Returns the order and the items left unordered by cycles.
+fn kahn_order<K: Ord + Hash + Copy>(
+    items: &BTreeSet<K>,
+    deps: &HashMap<K, HashSet<K>>,
+) -> (Vec<K>, Vec<K>) {
+    let mut remaining: BTreeMap<K, usize> = items
+        .iter()
+        .map(|&item| (item, deps.get(&item).map_or(0, HashSet::len)))
+        .collect();
+    let mut dependents: HashMap<K, Vec<K>> = HashMap::new();
+    for (&item, targets) in deps {
+        for &target in targets {
+            dependents.entry(target).or_default().push(item);
+        }
+    }
+
+    let mut ready: BTreeSet<K> = remaining
+        .iter()
+        .filter(|(_, &count)| count == 0)
+        .map(|(&item, _)| item)
+        .collect();
+    let mut order = Vec::with_capacity(items.len());
+    while let Some(item) = ready.pop_first() {
+        remaining.remove(&item);
+        order.push(item);
+        for &dependent in dependents.get(&item).into_iter().flatten() {
+            if let Some(count) = remaining.get_mut(&dependent) {
+                *count = count.saturating_sub(1);
+                if *count == 0 {
+                    ready.insert(dependent);
+                }
+            }
+        }
+    }
+
+    (order, remaining.into_keys().collect())
+}
+
+/// The expression a method is called on: `s.listener` for
+/// `s.listener.Close()`, `this` for `this->run()`; `None` for plain calls
+fn call_operand<'a>(call_site: &'a str, callee: &str) -> Option<&'a str> {
+    let head = call_site.split(['(', '<']).next()?.trim_end();
+    let operand = head.strip_suffix(callee)?;
+    let operand = operand
+        .strip_suffix('.')
+        .or_else(|| operand.strip_suffix("->"))
+        .or_else(|| operand.strip_suffix("::"))?;
+    Some(operand.trim())
+}
+
+/// Strongly connected groups of more than one package in a package
+/// dependency map, each sorted, sorted by their first package
+fn package_cycles(deps: &HashMap<&str, HashSet<&str>>) -> Vec<Vec<String>> {
+    let packages: BTreeSet<&str> = deps.keys().copied().collect();
+    let mut cycles: Vec<Vec<String>> = strongly_connected(&packages, deps)
+        .into_iter()
+        .filter(|component| component.len() > 1)
+        .map(|component| component.into_iter().map(str::to_string).collect())
+        .collect();
+    cycles.sort();
+    cycles
+}
+
+/// Strongly connected components of everything reachable from `roots`,
+/// each sorted. Components come out dependencies first.
+fn strongly_connected<K: Ord + Hash + Copy>(
+    roots: &BTreeSet<K>,
+    deps: &HashMap<K, HashSet<K>>,
+) -> Vec<Vec<K>> {
+    // Tarjan's algorithm with an explicit stack of (item, next dependency)
+    let sorted_deps: HashMap<K, Vec<K>> = deps
+        .iter()
+        .map(|(&item, targets)| {
+            let mut targets: Vec<K> = targets.iter().copied().collect();
+            targets.sort_unstable();
+            (item, targets)
+        })
+        .collect();
+
+    let mut index: HashMap<K, usize> = HashMap::new();
+    let mut lowlink: HashMap<K, usize> = HashMap::new();
+    let mut on_stack: HashSet<K> = HashSet::new();
+    let mut stack: Vec<K> = Vec::new();
+    let mut components = Vec::new();
+
+    for &root in roots {
+        if index.contains_key(&root) {
+            continue;
+        }
+
+        let mut work: Vec<(K, usize)> = vec![(root, 0)];
+        while let Some((item, next)) = work.pop() {
+            if next == 0 {
+                let i = index.len();
+                index.insert(item, i);
+                lowlink.insert(item, i);
+                stack.push(item);
+                on_stack.insert(item);
+            }
+
+            let targets = sorted_deps.get(&item).map_or(&[][..], Vec::as_slice);
+            if let Some(&target) = targets.get(next) {
+                work.push((item, next + 1));
+                if !index.contains_key(&target) {
+                    work.push((target, 0));
+                } else if on_stack.contains(&target) {
+                    let low = lowlink[&item].min(index[&target]);
+                    lowlink.insert(item, low);
+                }
+                continue;
+            }
+
+            // All dependencies visited: propagate lowlink, maybe pop a component
+            if let Some(&(parent, _)) = work.last() {
+                let low = lowlink[&parent].min(lowlink[&item]);
+                lowlink.insert(parent, low);
+            }
+            if lowlink[&item] == index[&item] {
+                let mut component = Vec::new();
+                while let Some(member) = stack.pop() {
+                    on_stack.remove(&member);
+                    component.push(member);
+                    if member == item {
+                        break;
+                    }
+                }
+                component.sort();
+                components.push(component);
+            }
+        }
+    }
+
+    components
+}
+
+/// Split an identifier into lowercase tokens on camelCase and snake_case boundaries
+fn name_tokens(name: &str) -> HashSet<String> {
+    let mut tokens = HashSet::new();
+    let mut current = String::new();
+    let mut prev_lower = false;
+
+    for ch in name.chars() {
+        if ch == '_' || ch == '-' || ch == '.' {
+            if !current.is_empty() {
+                tokens.insert(std::mem::take(&mut current));
+            }
+            prev_lower = false;
+            continue;
+        }
+        if ch.is_uppercase() && prev_lower && !current.is_empty() {
+            tokens.insert(std::mem::take(&mut current));
+        }
+        prev_lower = ch.is_lowercase() || ch.is_ascii_digit();
+        current.extend(ch.to_lowercase());
+    }
+    if !current.is_empty() {
+        tokens.insert(current);
+    }
+
+    tokens
+}
+
+/// Jaccard index of two sets (0.0 when both are empty)
+fn jaccard<T: Eq + Hash>(a: &HashSet<T>, b: &HashSet<T>) -> f32 {
+    if a.is_empty() && b.is_empty() {
+        return 0.0;
+    }
+    let intersection = a.intersection(b).count();
+    let union = a.union(b).count();
+    intersection as f32 / union as f32
+}
+
+/// Normalized edit-distance similarity of two sequences (1.0 = identical,
+/// 0.0 when both are empty)
+fn sequence_similarity(a: &[&str], b: &[&str]) -> f32 {
+    let max_len = a.len().max(b.len());
+    if max_len == 0 {
+        return 0.0;
+    }
+
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, x) in a.iter().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, y) in b.iter().enumerate() {
+            let cost = if x == y { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+
+    1.0 - prev[b.len()] as f32 / max_len as f32
+}
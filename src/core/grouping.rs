@@ -0,0 +1,59 @@
+use super::node::Node;
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+/// Key used by `query --group-by`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GroupBy {
+    Package,
+    File,
+}
+
+impl GroupBy {
+    fn key(self, node: &Node) -> String {
+        match self {
+            GroupBy::Package => node.package.clone(),
+            GroupBy::File => node.file_path.display().to_string(),
+        }
+    }
+}
+
+/// Query results sharing one package or file
+#[derive(Debug, Clone, Serialize)]
+pub struct NodeGroup<'a> {
+    pub group: String,
+    /// Shown nodes, at most `limit_per_group`, in result order
+    pub nodes: Vec<&'a Node>,
+    /// Matching nodes left out by `limit_per_group`
+    pub more: usize,
+}
+
+impl NodeGroup<'_> {
+    /// "(+K more)" when the group was capped
+    pub fn overflow_note(&self) -> Option<String> {
+        (self.more > 0).then(|| format!("(+{} more)", self.more))
+    }
+}
+
+/// Group nodes by package or file (groups sorted by key, nodes keep their
+/// order), showing at most `limit_per_group` nodes per group
+pub fn group_nodes<'a>(
+    nodes: &[&'a Node],
+    group_by: GroupBy,
+    limit_per_group: Option<usize>,
+) -> Vec<NodeGroup<'a>> {
+    let mut grouped: BTreeMap<String, Vec<&'a Node>> = BTreeMap::new();
+    for &node in nodes {
+        grouped.entry(group_by.key(node)).or_default().push(node);
+    }
+
+    grouped
+        .into_iter()
+        .map(|(group, mut nodes)| {
+            let shown = limit_per_group.unwrap_or(nodes.len()).min(nodes.len());
+            let more = nodes.len() - shown;
+            nodes.truncate(shown);
+            NodeGroup { group, nodes, more }
+        })
+        .collect()
+}
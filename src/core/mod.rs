@@ -1,9 +1,18 @@
+pub mod diff_report;
 pub mod edge;
 pub mod graph;
+pub mod grouping;
 pub mod node;
 
+pub use diff_report::{DiffReport, DIFF_REPORT_SCHEMA_VERSION};
 pub use edge::{Edge, EdgeType};
 pub use graph::{
-    CodeGraph, ComplexityMetrics, GraphMetadata, GraphStats, HotspotResult, TraceResult,
+    CodeGraph, ComplexityMetrics, ConnectedNode, FileLayout, FunctionSpan, GraphMetadata,
+    GraphStats, GraphSummary, HotspotResult, NodeRelations, PackageAbstractness, PackageMetrics,
+    PackageSize, PathHop, RelationCount, TagMode, TraceResult, TraversalDirection, Xref,
+};
+pub use grouping::{group_nodes, GroupBy, NodeGroup};
+pub use node::{
+    is_dunder, is_test_file, Node, NodeType, Parameter, ABSTRACT_TAG, CUSTOM_NODE_TYPES,
+    DUNDER_TAG, TEST_TAG,
 };
-pub use node::{Node, NodeType, Parameter};
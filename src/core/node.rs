@@ -1,6 +1,35 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::PathBuf;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Tag marking abstract type definitions
+pub const ABSTRACT_TAG: &str = "abstract";
+
+/// Tag marking nodes defined in test files, see [`is_test_file`]
+pub const TEST_TAG: &str = "test";
+
+/// Whether `path` looks like a test file: `foo_test.go`, `test_foo.py`,
+/// `foo.test.ts` or `foo.spec.ts`
+pub fn is_test_file(path: &Path) -> bool {
+    let path_str = path.to_string_lossy();
+    let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    path_str.contains("_test")
+        || path_str.contains(".test.")
+        || file_name.starts_with("test_")
+        || file_name.contains(".spec.")
+}
+
+/// Tag marking Python dunder methods (`__init__`, `__str__`, ...)
+pub const DUNDER_TAG: &str = "dunder";
+
+/// Whether `name` is a Python dunder/magic name such as `__init__`
+pub fn is_dunder(name: &str) -> bool {
+    name.len() > 4 && name.starts_with("__") && name.ends_with("__")
+}
+
+/// Subtypes of [`NodeType::Custom`] that parsers emit and `--type` accepts
+pub const CUSTOM_NODE_TYPES: &[&str] = &["enum", "type_alias", "namespace"];
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Hash)]
 #[serde(rename_all = "snake_case")]
@@ -9,6 +38,29 @@ pub enum NodeType {
     Method,
     HttpHandler,
     Middleware,
+    Class,
+    /// Non-callable declaration surfaced for navigation, named by its
+    /// subtype (one of [`CUSTOM_NODE_TYPES`], e.g. a TypeScript `enum`)
+    Custom(String),
+}
+
+impl fmt::Display for NodeType {
+    /// Variant name (`HttpHandler`), or the subtype of a custom node in the
+    /// same PascalCase (`type_alias` -> `TypeAlias`)
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NodeType::Custom(kind) => {
+                for part in kind.split('_') {
+                    let mut chars = part.chars();
+                    if let Some(first) = chars.next() {
+                        write!(f, "{}{}", first.to_uppercase(), chars.as_str())?;
+                    }
+                }
+                Ok(())
+            }
+            other => write!(f, "{:?}", other),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,6 +90,10 @@ pub struct Node {
     pub tags: Vec<String>,
     #[serde(default)]
     pub metadata: HashMap<String, String>,
+    /// Branches in the body (if, loops, cases, catch, `&&`/`||`, ...);
+    /// cyclomatic complexity is this plus one
+    #[serde(default)]
+    pub decision_points: usize,
 }
 
 impl Node {
@@ -66,6 +122,95 @@ impl Node {
             documentation: None,
             tags: Vec::new(),
             metadata: HashMap::new(),
+            decision_points: 0,
+        }
+    }
+
+    /// Editor-friendly location of the node, formatted as `file:line`
+    pub fn location(&self) -> String {
+        format!("{}:{}", self.file_path.display(), self.line)
+    }
+
+    /// Whether the node is a type definition (class, ...)
+    pub fn is_type(&self) -> bool {
+        self.node_type == NodeType::Class
+    }
+
+    /// Whether the node is an abstract type (e.g. a TypeScript abstract class)
+    pub fn is_abstract_type(&self) -> bool {
+        self.is_type() && self.tags.iter().any(|t| t == ABSTRACT_TAG)
+    }
+
+    /// Whether the node is part of its module's public API, judged by each
+    /// language's convention: exported (capitalized) names in Go, no leading
+    /// underscore in Python (dunder methods count as public), and no
+    /// `private`/`protected` modifier or `#`/`_` prefix elsewhere.
+    pub fn is_public(&self) -> bool {
+        let ext = self
+            .file_path
+            .extension()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+
+        match ext {
+            "go" => self.name.starts_with(|c: char| c.is_uppercase()),
+            "py" => !self.name.starts_with('_') || is_dunder(&self.name),
+            _ => {
+                !self.name.starts_with(['#', '_'])
+                    && !self.signature.starts_with("private ")
+                    && !self.signature.starts_with("protected ")
+            }
         }
     }
+
+    /// Deepest control-flow nesting in the body, recorded by the parser as
+    /// `max_nesting` metadata (`None` for graphs indexed before it existed)
+    pub fn max_nesting(&self) -> Option<usize> {
+        self.metadata.get("max_nesting")?.parse().ok()
+    }
+
+    /// Parameters the body never mentions, recorded by the parser as
+    /// `unused_params` metadata (empty for graphs indexed before it existed)
+    pub fn unused_params(&self) -> Vec<&str> {
+        self.metadata
+            .get("unused_params")
+            .map_or_else(Vec::new, |params| params.split(',').collect())
+    }
+
+    /// Fan-in and fan-out stored by `index --precompute-degrees`, if present
+    pub fn precomputed_degrees(&self) -> Option<(usize, usize)> {
+        let fan_in = self.metadata.get("fan_in")?.parse().ok()?;
+        let fan_out = self.metadata.get("fan_out")?.parse().ok()?;
+        Some((fan_in, fan_out))
+    }
+
+    /// Receiver type of a Go method (`Server` for `(s *Server) Handle`)
+    pub fn receiver(&self) -> Option<&str> {
+        self.metadata.get("receiver").map(String::as_str)
+    }
+
+    /// Type a method belongs to: its enclosing class or its Go receiver
+    pub fn owner_type(&self) -> Option<&str> {
+        self.metadata
+            .get("class")
+            .map(String::as_str)
+            .or_else(|| self.receiver())
+    }
+
+    /// Name qualified by the receiver for Go methods (`Server.Handle`),
+    /// otherwise the plain name
+    pub fn display_name(&self) -> String {
+        match self.receiver() {
+            Some(receiver) => format!("{}.{}", receiver, self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Case-insensitive substring search over the node's documentation
+    pub fn doc_contains(&self, text: &str) -> bool {
+        let needle = text.to_lowercase();
+        self.documentation
+            .as_deref()
+            .is_some_and(|doc| doc.to_lowercase().contains(&needle))
+    }
 }
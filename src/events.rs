@@ -0,0 +1,67 @@
+use serde::Serialize;
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Structured progress event, written as one JSON object per line
+/// (`{"event":"file_parsed","path":"src/main.go"}`) for tools embedding
+/// the CLI. Field names are part of the `--json-events` contract.
+#[derive(Debug, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum Event<'a> {
+    /// A command started
+    Start { command: &'a str },
+    /// A source file was parsed into the graph
+    FileParsed { path: &'a Path },
+    /// A source file could not be parsed and was skipped
+    FileFailed { path: &'a Path, error: String },
+    /// A graph was loaded from disk or stdin
+    GraphLoaded { nodes: usize, edges: usize },
+    /// The command finished; `results` is set for commands that return matches
+    Done {
+        nodes: usize,
+        edges: usize,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        results: Option<usize>,
+        duration_ms: u128,
+    },
+}
+
+/// Thread-safe sink for [`Event`]s. Cloning shares the underlying writer,
+/// so parallel parsers can report into the same stream.
+#[derive(Clone)]
+pub struct EventSink {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+}
+
+impl EventSink {
+    pub fn new(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(Box::new(writer))),
+        }
+    }
+
+    /// Sink writing to stderr, leaving stdout free for command results
+    pub fn stderr() -> Self {
+        Self::new(std::io::stderr())
+    }
+
+    /// Write one event line. Errors are ignored: progress reporting must
+    /// never fail the command it describes.
+    pub fn emit(&self, event: &Event) {
+        let Ok(line) = serde_json::to_string(event) else {
+            return;
+        };
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writeln!(writer, "{}", line);
+            let _ = writer.flush();
+        }
+    }
+}
+
+impl fmt::Debug for EventSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventSink").finish_non_exhaustive()
+    }
+}
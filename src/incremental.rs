@@ -0,0 +1,198 @@
+use anyhow::Result;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::core::CodeGraph;
+use crate::parser::{FileFilter, GoParser, PythonParser, TypeScriptParser};
+
+/// Files an incremental update would touch, as detected against an existing graph
+#[derive(Debug, Clone)]
+pub struct ChangeSet {
+    /// How changes were detected: "git" or "timestamps"
+    pub detection_method: &'static str,
+    /// New or modified source files to reparse
+    pub changed_files: Vec<PathBuf>,
+    /// Files recorded in the graph that no longer exist
+    pub deleted_files: Vec<String>,
+}
+
+impl ChangeSet {
+    /// Number of existing nodes that would be dropped for each changed or deleted file
+    pub fn nodes_to_remove(&self, existing_graph: &CodeGraph) -> Vec<(String, usize)> {
+        self.changed_files
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .chain(self.deleted_files.iter().cloned())
+            .map(|file| {
+                let count = existing_graph
+                    .nodes
+                    .iter()
+                    .filter(|n| n.file_path.to_string_lossy() == file)
+                    .count();
+                (file, count)
+            })
+            .collect()
+    }
+
+    /// Changed files to reparse: those `filter` keeps, judging test files by
+    /// `language`'s own convention as its parser's `parse_directory` does
+    pub fn files_to_reparse(&self, filter: &FileFilter, language: &str) -> HashSet<&PathBuf> {
+        let is_test_file: fn(&Path) -> bool = match language {
+            "go" => GoParser::is_test_file,
+            "typescript" | "ts" | "javascript" | "js" => TypeScriptParser::is_test_file,
+            "python" | "py" => PythonParser::is_test_file,
+            // No test-file convention for the other languages
+            _ => |_| false,
+        };
+        self.changed_files
+            .iter()
+            .filter(|file| !filter.skips(file, is_test_file(file)))
+            .collect()
+    }
+}
+
+/// Detect changed and deleted files, trying git first (unless `use_git` is
+/// false) and falling back to timestamps
+pub fn detect_changes(
+    directory: &Path,
+    existing_graph: &CodeGraph,
+    file_extensions: &[&str],
+    use_git: bool,
+) -> Result<ChangeSet> {
+    let git_files = if use_git {
+        detect_changed_files_git(directory, file_extensions).ok()
+    } else {
+        None
+    };
+    let (changed_files, detection_method) = match git_files {
+        Some(files) => (files, "git"),
+        None => (
+            detect_changed_files_timestamp(directory, existing_graph, file_extensions)?,
+            "timestamps",
+        ),
+    };
+
+    Ok(ChangeSet {
+        detection_method,
+        changed_files,
+        deleted_files: detect_deleted_files(directory, existing_graph),
+    })
+}
+
+/// Detect changed files using git
+pub fn detect_changed_files_git(
+    directory: &Path,
+    file_extensions: &[&str],
+) -> Result<Vec<PathBuf>> {
+    // Get files changed compared to HEAD (includes both staged and unstaged)
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .arg("diff")
+        .arg("--name-only")
+        .arg("HEAD")
+        .output()?;
+
+    if !output.status.success() {
+        anyhow::bail!("Git command failed");
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let mut changed_files = Vec::new();
+
+    for line in stdout.lines() {
+        let path = directory.join(line);
+        if has_extension(&path, file_extensions) && path.exists() {
+            changed_files.push(path);
+        }
+    }
+
+    // Also check for untracked files
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(directory)
+        .arg("ls-files")
+        .arg("--others")
+        .arg("--exclude-standard")
+        .output()?;
+
+    if output.status.success() {
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        for line in stdout.lines() {
+            let path = directory.join(line);
+            if has_extension(&path, file_extensions)
+                && path.exists()
+                && !changed_files.contains(&path)
+            {
+                changed_files.push(path);
+            }
+        }
+    }
+
+    Ok(changed_files)
+}
+
+/// Detect changed files using timestamps (fallback when git is not available)
+pub fn detect_changed_files_timestamp(
+    directory: &Path,
+    existing_graph: &CodeGraph,
+    file_extensions: &[&str],
+) -> Result<Vec<PathBuf>> {
+    use std::fs;
+    use walkdir::WalkDir;
+
+    let mut changed_files = Vec::new();
+
+    for entry in WalkDir::new(directory)
+        .into_iter()
+        .filter_map(|e| e.ok())
+        .filter(|e| has_extension(e.path(), file_extensions))
+    {
+        let path = entry.path();
+        let path_str = path.to_string_lossy().to_string();
+
+        // Check if file is new or modified
+        if let Some(file_meta) = existing_graph.metadata.file_metadata.get(&path_str) {
+            // File exists in graph, check if modified
+            if let Ok(metadata) = fs::metadata(path) {
+                if let Ok(modified) = metadata.modified() {
+                    let modified_str = format!("{:?}", modified);
+                    if modified_str != file_meta.last_modified {
+                        changed_files.push(path.to_path_buf());
+                    }
+                }
+            }
+        } else {
+            // New file
+            changed_files.push(path.to_path_buf());
+        }
+    }
+
+    Ok(changed_files)
+}
+
+/// Detect deleted files by comparing stored file metadata with current directory
+pub fn detect_deleted_files(directory: &Path, existing_graph: &CodeGraph) -> Vec<String> {
+    let mut deleted_files = Vec::new();
+
+    for file_path in existing_graph.metadata.file_metadata.keys() {
+        // Paths are stored as the parser saw them (usually already prefixed
+        // with the indexed directory), so check them as-is first
+        let stored_path = Path::new(file_path);
+        let exists = stored_path.exists()
+            || (!stored_path.is_absolute() && directory.join(stored_path).exists());
+
+        if !exists {
+            deleted_files.push(file_path.clone());
+        }
+    }
+
+    deleted_files
+}
+
+fn has_extension(path: &Path, file_extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| file_extensions.contains(&ext))
+}
@@ -1,7 +1,15 @@
+pub mod archive;
 pub mod benchmark;
+pub mod bisect;
+pub mod blame;
+pub mod commands;
 pub mod core;
+pub mod events;
+pub mod incremental;
+pub mod manifest;
 pub mod parser;
 pub mod serializer;
+pub mod sidecar;
 
 #[cfg(test)]
 mod tests {
@@ -27,6 +35,33 @@ mod tests {
         assert_eq!(node.package, "main");
     }
 
+    #[test]
+    fn test_doc_contains() {
+        let make = |name: &str, doc: Option<&str>| {
+            let mut node = Node::new(
+                format!("test.go:{}:1", name),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("test.go"),
+                1,
+                5,
+                "main".to_string(),
+                format!("func {}()", name),
+            );
+            node.documentation = doc.map(|d| d.to_string());
+            node
+        };
+
+        let legacy = make("legacyCharge", Some("Deprecated: use Charge instead."));
+        let charge = make("Charge", Some("Charge bills the customer."));
+        let undocumented = make("helper", None);
+
+        assert!(legacy.doc_contains("deprecated"));
+        assert!(legacy.doc_contains("USE CHARGE"));
+        assert!(!charge.doc_contains("deprecated"));
+        assert!(!undocumented.doc_contains("deprecated"));
+    }
+
     #[test]
     fn test_create_edge() {
         let edge = Edge::new(
@@ -291,6 +326,59 @@ mod tests {
         assert_eq!(trace.len(), 3);
     }
 
+    #[test]
+    fn test_extract_and_trace_depth_agree() {
+        let graph = create_test_graph_with_calls();
+
+        for depth in 0..=4 {
+            let trace = graph.trace_dependencies("test:a:1", depth);
+            let mut traced: std::collections::HashSet<String> =
+                trace.iter().map(|t| t.to_name.clone()).collect();
+            traced.insert("funcA".to_string());
+
+            let subgraph = graph.extract_subgraph("funcA", depth);
+            let extracted: std::collections::HashSet<String> =
+                subgraph.nodes.iter().map(|n| n.name.clone()).collect();
+
+            assert_eq!(extracted, traced, "depth {}", depth);
+            assert_eq!(subgraph.edges.len(), trace.len(), "depth {}", depth);
+        }
+
+        // Depth 2 on A -> B -> C -> D reaches C but does not follow C -> D
+        let subgraph = graph.extract_subgraph("funcA", 2);
+        assert_eq!(subgraph.nodes.len(), 3);
+        assert!(subgraph.get_outgoing_edges("test:c:20").is_empty());
+    }
+
+    #[test]
+    fn test_extract_subgraph_direction() {
+        use crate::core::TraversalDirection;
+
+        let graph = create_test_graph_with_calls();
+        let names = |direction| -> Vec<String> {
+            let subgraph = graph.extract_subgraph_directed("funcC", 5, direction, false);
+            let mut names: Vec<String> = subgraph.nodes.iter().map(|n| n.name.clone()).collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            names(TraversalDirection::In),
+            vec!["funcA", "funcB", "funcC"]
+        );
+        assert_eq!(names(TraversalDirection::Out), vec!["funcC", "funcD"]);
+        assert_eq!(
+            names(TraversalDirection::Both),
+            vec!["funcA", "funcB", "funcC", "funcD"]
+        );
+
+        // Upstream extraction keeps the caller edges it followed
+        let upstream = graph.extract_subgraph_directed("funcC", 1, TraversalDirection::In, false);
+        assert_eq!(upstream.nodes.len(), 2);
+        assert_eq!(upstream.edges.len(), 1);
+        assert_eq!(upstream.edges[0].from, "test:b:10");
+    }
+
     #[test]
     fn test_find_shortest_path() {
         let graph = create_test_graph_with_calls();
@@ -307,6 +395,65 @@ mod tests {
         assert_eq!(path[2], "funcD");
     }
 
+    #[test]
+    fn test_shortest_path_jumplist() {
+        let graph = create_test_graph_with_calls();
+
+        let steps = graph
+            .find_shortest_path_nodes("test:a:1", "funcD", 10)
+            .unwrap();
+        let jumplist: Vec<String> = steps.iter().map(|n| n.location()).collect();
+        assert_eq!(
+            jumplist,
+            vec!["test.go:1", "test.go:10", "test.go:20", "test.go:30"]
+        );
+
+        assert!(graph
+            .find_shortest_path_nodes("test:d:30", "funcA", 10)
+            .is_none());
+
+        // With a second funcD, the last step is the one the call resolves to
+        let mut graph = graph;
+        graph.add_node(Node::new(
+            "other.go:funcD:5".to_string(),
+            "funcD".to_string(),
+            NodeType::Function,
+            PathBuf::from("other.go"),
+            5,
+            8,
+            "other".to_string(),
+            "func funcD()".to_string(),
+        ));
+        for edge in graph.edges.iter_mut().filter(|e| e.to == "funcD") {
+            edge.to_id = Some("other.go:funcD:5".to_string());
+        }
+        let steps = graph
+            .find_shortest_path_nodes("test:a:1", "funcD", 10)
+            .unwrap();
+        assert_eq!(steps.last().unwrap().location(), "other.go:5");
+    }
+
+    #[test]
+    fn test_explain_path_call_sites() {
+        let graph = create_test_graph_with_calls();
+
+        let hops = graph.explain_path("test:a:1", "funcD", 10).unwrap();
+        let explanation: Vec<String> = hops.iter().map(|hop| hop.describe()).collect();
+
+        assert_eq!(
+            explanation,
+            vec![
+                "funcA calls funcB at test.go:3 via `funcB()`",
+                "funcB calls funcC at test.go:12 via `funcC()`",
+                "funcC calls funcD at test.go:22 via `funcD()`",
+            ]
+        );
+        assert_eq!(hops[0].caller_signature.as_deref(), Some("func funcA() {}"));
+        assert_eq!(hops[2].callee_signature.as_deref(), Some("func funcD() {}"));
+
+        assert!(graph.explain_path("test:d:30", "funcA", 10).is_none());
+    }
+
     #[test]
     fn test_find_shortest_path_no_path() {
         let graph = create_test_graph_with_calls();
@@ -426,6 +573,61 @@ mod tests {
         assert_eq!(complexity.fan_in, 0); // Called by none
     }
 
+    #[test]
+    fn test_filter_by_complexity_range() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+
+        for (idx, name) in ["hub", "mid", "leaf", "isolated"].iter().enumerate() {
+            graph.add_node(Node::new(
+                format!("test:{}:{}", name, idx * 10),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("test.go"),
+                idx * 10,
+                idx * 10 + 5,
+                "main".to_string(),
+                format!("func {}() {{}}", name),
+            ));
+        }
+
+        // hub calls mid, leaf and itself; mid calls leaf
+        for (from, to) in [
+            ("test:hub:0", "mid"),
+            ("test:hub:0", "leaf"),
+            ("test:mid:10", "leaf"),
+            ("test:hub:0", "hub"),
+        ] {
+            graph.add_edge(Edge::new(
+                from.to_string(),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("test.go"),
+                1,
+            ));
+        }
+
+        let combined = |id: &str| graph.get_complexity(id).combined();
+        assert_eq!(combined("test:hub:0"), 4);
+        assert_eq!(combined("test:mid:10"), 2);
+        assert_eq!(combined("test:leaf:20"), 2);
+        assert_eq!(combined("test:isolated:30"), 0);
+
+        let names = |min, max| -> Vec<String> {
+            let candidates = graph.nodes.iter().collect();
+            graph
+                .filter_by_complexity(candidates, min, max)
+                .iter()
+                .map(|n| n.name.clone())
+                .collect()
+        };
+
+        assert_eq!(names(Some(1), Some(3)), vec!["mid", "leaf"]);
+        assert_eq!(names(Some(3), None), vec!["hub"]);
+        assert_eq!(names(None, Some(0)), vec!["isolated"]);
+        assert_eq!(names(None, None).len(), 4);
+    }
+
     #[test]
     fn test_find_hotspots() {
         let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
@@ -492,7 +694,7 @@ mod tests {
             ));
         }
 
-        let hotspots = graph.find_hotspots(5);
+        let hotspots = graph.find_hotspots(5, false);
         assert!(hotspots.len() > 0);
         assert_eq!(hotspots[0].name, "popularFunc");
         assert_eq!(hotspots[0].call_count, 3);
@@ -665,4 +867,4140 @@ mod tests {
         let helpers = graph.get_nodes_by_name("helper");
         assert_eq!(helpers.len(), 2);
     }
+
+    #[test]
+    fn test_remove_generated_edges() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+
+        graph.add_node(Node::new(
+            "handler.go:serve:1".to_string(),
+            "serve".to_string(),
+            NodeType::Function,
+            PathBuf::from("handler.go"),
+            1,
+            10,
+            "api".to_string(),
+            "func serve() {}".to_string(),
+        ));
+        graph.add_node(Node::new(
+            "api.pb.go:Marshal:1".to_string(),
+            "Marshal".to_string(),
+            NodeType::Function,
+            PathBuf::from("api.pb.go"),
+            1,
+            10,
+            "api".to_string(),
+            "func Marshal() {}".to_string(),
+        ));
+
+        // Hand-written code calling into generated code
+        graph.add_edge(Edge::new(
+            "handler.go:serve:1".to_string(),
+            "Marshal".to_string(),
+            EdgeType::Calls,
+            "Marshal()".to_string(),
+            PathBuf::from("handler.go"),
+            5,
+        ));
+        // Generated code calling its own internals
+        graph.add_edge(Edge::new(
+            "api.pb.go:Marshal:1".to_string(),
+            "encodeVarint".to_string(),
+            EdgeType::Calls,
+            "encodeVarint()".to_string(),
+            PathBuf::from("api.pb.go"),
+            4,
+        ));
+
+        graph.mark_generated_file(std::path::Path::new("api.pb.go"));
+        graph.remove_generated_edges();
+
+        assert_eq!(graph.edges.len(), 1);
+        assert_eq!(graph.edges[0].to, "Marshal");
+        assert!(graph.get_outgoing_edges("api.pb.go:Marshal:1").is_empty());
+        assert_eq!(graph.find_callers("Marshal").len(), 1);
+    }
+
+    #[test]
+    fn test_parser_marks_generated_files() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let generated = dir.path().join("models.go");
+        std::fs::write(
+            &generated,
+            "// Code generated by protoc-gen-go. DO NOT EDIT.\npackage models\n\nfunc Reset() {}\n",
+        )
+        .unwrap();
+        let handwritten = dir.path().join("service.go");
+        std::fs::write(
+            &handwritten,
+            "// Run resets the models. DO NOT EDIT the order of calls.\n\
+             // Code generated files are reset first.\n\
+             package models\n\nfunc Run() { Reset() }\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        let mut parser = GoParser::new().unwrap();
+        parser.parse_file(&generated, &mut graph).unwrap();
+        parser.parse_file(&handwritten, &mut graph).unwrap();
+
+        assert!(graph.is_generated_file(&generated));
+        assert!(!graph.is_generated_file(&handwritten));
+    }
+
+    #[test]
+    fn test_find_similar_signatures() {
+        use crate::core::Parameter;
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+
+        let mut make = |id: &str, name: &str, params: &[&str], returns: &[&str]| {
+            let mut node = Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("users.go"),
+                1,
+                10,
+                "users".to_string(),
+                format!("func {}()", name),
+            );
+            node.parameters = params
+                .iter()
+                .enumerate()
+                .map(|(i, t)| Parameter {
+                    name: format!("p{}", i),
+                    param_type: t.to_string(),
+                })
+                .collect();
+            node.returns = returns.iter().map(|r| r.to_string()).collect();
+            graph.add_node(node);
+        };
+
+        make(
+            "a",
+            "validateUserEmail",
+            &["string", "int"],
+            &["bool", "error"],
+        );
+        make(
+            "b",
+            "validate_user_email",
+            &["string", "int"],
+            &["bool", "error"],
+        );
+        make("c", "renderDashboard", &["http.ResponseWriter"], &[]);
+        make("d", "start", &[], &[]);
+        make("e", "stop", &[], &[]);
+        graph.add_node(Node::new(
+            "f".to_string(),
+            "ValidateUserEmail".to_string(),
+            NodeType::Class,
+            PathBuf::from("users.go"),
+            20,
+            30,
+            "users".to_string(),
+            "type ValidateUserEmail struct".to_string(),
+        ));
+
+        // The type shares every name token but isn't a callable
+        let similar = graph.find_similar_signatures("a", 0.0);
+        assert_eq!(similar.len(), 4);
+        assert_eq!(similar[0].0.id, "b");
+        assert!(similar[0].1 > similar[1].1);
+        assert!((similar[0].1 - 1.0).abs() < f32::EPSILON);
+
+        let above_threshold = graph.find_similar_signatures("a", 0.5);
+        assert_eq!(above_threshold.len(), 1);
+        assert!(graph.find_similar_signatures("missing", 0.0).is_empty());
+
+        // No parameters, returns or name tokens in common: nothing similar
+        let similar = graph.find_similar_signatures("d", 0.0);
+        let stop = similar.iter().find(|(n, _)| n.id == "e").unwrap();
+        assert_eq!(stop.1, 0.0);
+    }
+
+    #[test]
+    fn test_file_layout_coverage() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+
+        // Lines 11-20 and 31-40 are functions, with a nested closure at 15-17
+        for (id, name, line, end_line) in [
+            ("layout.go:first:11", "first", 11, 20),
+            ("layout.go:closure:15", "closure", 15, 17),
+            ("layout.go:second:31", "second", 31, 40),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("src/layout.go"),
+                line,
+                end_line,
+                "main".to_string(),
+                format!("func {}() {{}}", name),
+            ));
+        }
+
+        let path = graph.find_file("layout.go").unwrap().unwrap().to_path_buf();
+        let layout = graph.file_layout(&path, Some(50)).unwrap();
+        assert_eq!(layout.total_lines, 50);
+        assert_eq!(layout.function_lines, 20);
+        assert!((layout.coverage_percent - 40.0).abs() < f64::EPSILON);
+
+        let gaps: Vec<usize> = layout.functions.iter().map(|f| f.gap_before).collect();
+        assert_eq!(gaps, vec![10, 0, 10]);
+
+        assert!(graph.find_file("missing.go").unwrap().is_none());
+        // Whole path components only
+        assert!(graph.find_file("out.go").unwrap().is_none());
+        assert!(graph.find_file("src/layout.go").unwrap().is_some());
+
+        // Two files named layout.go: the bare name is ambiguous
+        graph.add_node(Node::new(
+            "vendor/layout.go:third:1".to_string(),
+            "third".to_string(),
+            NodeType::Function,
+            PathBuf::from("vendor/layout.go"),
+            1,
+            5,
+            "vendor".to_string(),
+            "func third() {}".to_string(),
+        ));
+        let err = graph.find_file("layout.go").unwrap_err().to_string();
+        assert!(err.contains("src/layout.go") && err.contains("vendor/layout.go"));
+        assert_eq!(
+            graph.find_file("vendor/layout.go").unwrap(),
+            Some(std::path::Path::new("vendor/layout.go"))
+        );
+    }
+
+    #[test]
+    fn test_index_from_archive() {
+        use crate::parser::GoParser;
+        use flate2::write::GzEncoder;
+
+        let dir = tempfile::tempdir().unwrap();
+        let archive_path = dir.path().join("repo.tar.gz");
+
+        // Archive with a wrapper directory, as produced by `tar czf repo.tar.gz repo-1.0/`
+        let encoder = GzEncoder::new(
+            std::fs::File::create(&archive_path).unwrap(),
+            flate2::Compression::default(),
+        );
+        let mut builder = tar::Builder::new(encoder);
+        for (path, source) in [
+            (
+                "repo-1.0/main.go",
+                "package main\n\nfunc main() { serve() }\n",
+            ),
+            (
+                "repo-1.0/server/server.go",
+                "package server\n\nfunc serve() {}\n",
+            ),
+        ] {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(source.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, source.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap();
+
+        let extracted = crate::archive::extract_archive(&archive_path).unwrap();
+        let mut graph = CodeGraph::new("repo".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_directory(extracted.root(), &mut graph)
+            .unwrap();
+        graph.relativize_paths(extracted.root());
+
+        assert_eq!(graph.nodes.len(), 2);
+        let serve = graph.get_nodes_by_name("serve")[0];
+        let server_go = PathBuf::from("server").join("server.go");
+        assert_eq!(serve.file_path, server_go);
+        assert_eq!(serve.id, format!("{}:serve:3", server_go.display()));
+        assert_eq!(graph.get_outgoing_edges("main.go:main:3").len(), 1);
+
+        // Temporary extraction is cleaned up on drop
+        let root = extracted.root().to_path_buf();
+        drop(extracted);
+        assert!(!root.exists());
+
+        // Only the wrapper is skipped, not a lone directory inside it
+        let archive_path = dir.path().join("nested.tar");
+        let mut builder = tar::Builder::new(std::fs::File::create(&archive_path).unwrap());
+        let source = "package app\n\nfunc Run() {}\n";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(source.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, "repo/src/app/app.go", source.as_bytes())
+            .unwrap();
+        builder.finish().unwrap();
+        let extracted = crate::archive::extract_archive(&archive_path).unwrap();
+        assert!(extracted.root().ends_with("repo"));
+    }
+
+    #[test]
+    fn test_python_class_extends_edge() {
+        use crate::parser::PythonParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("models.py");
+        std::fs::write(
+            &file,
+            "class Base:\n    pass\n\nclass User(Base, mixins.Auditable, metaclass=Meta):\n    def save(self):\n        pass\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "python".to_string());
+        PythonParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        assert_eq!(graph.get_nodes_by_type(&NodeType::Class).len(), 2);
+
+        let subclasses = graph.find_subclasses("Base");
+        assert_eq!(subclasses.len(), 1);
+        let user = graph.get_node_by_id(&subclasses[0].from).unwrap();
+        assert_eq!(user.name, "User");
+        assert_eq!(graph.find_subclasses("Auditable").len(), 1);
+        assert!(graph.find_subclasses("Meta").is_empty());
+    }
+
+    #[test]
+    fn test_typescript_class_extends_edge() {
+        use crate::parser::{Language, TypeScriptParser};
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("shapes.ts");
+        std::fs::write(
+            &file,
+            "class Shape {}\n\nclass Circle extends Shape implements Drawable {\n  area(): number { return 0; }\n}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "typescript".to_string());
+        TypeScriptParser::new(Language::TypeScript)
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        assert_eq!(graph.get_nodes_by_type(&NodeType::Class).len(), 2);
+        assert_eq!(graph.get_nodes_by_name("area").len(), 1);
+
+        let subclasses = graph.find_subclasses("Shape");
+        assert_eq!(subclasses.len(), 1);
+        let circle = graph.get_node_by_id(&subclasses[0].from).unwrap();
+        assert_eq!(circle.name, "Circle");
+        assert!(graph.find_subclasses("Drawable").is_empty());
+    }
+
+    #[test]
+    fn test_sidecar_metadata_merge() {
+        use crate::sidecar::SidecarMetadata;
+
+        let mut graph = CodeGraph::new("/repo".to_string(), "go".to_string());
+        for (name, package, file) in [
+            ("Charge", "billing", "/repo/billing/charge.go"),
+            ("Refund", "billing", "/repo/billing/refund.go"),
+            ("Login", "auth", "/repo/auth/login.go"),
+            ("Post", "ledger", "/repo/billing/ledger/post.go"),
+        ] {
+            graph.add_node(Node::new(
+                format!("{}:{}:1", file, name),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from(file),
+                1,
+                10,
+                package.to_string(),
+                format!("func {}()", name),
+            ));
+        }
+
+        let sidecar: SidecarMetadata = serde_json::from_str(
+            r#"{
+                "files": { "billing/*.go": { "owner": "payments-team" } },
+                "nodes": { "billing.Charge": { "criticality": "high", "owner": "checkout-team" } }
+            }"#,
+        )
+        .unwrap();
+
+        let enriched = sidecar.apply(&mut graph).unwrap();
+        assert_eq!(enriched, 2);
+
+        let charge = graph.get_nodes_by_name("Charge")[0];
+        assert_eq!(charge.metadata["owner"], "checkout-team");
+        assert_eq!(charge.metadata["criticality"], "high");
+
+        let refund = graph.get_nodes_by_name("Refund")[0];
+        assert_eq!(refund.metadata["owner"], "payments-team");
+        assert!(!refund.metadata.contains_key("criticality"));
+
+        assert!(graph.get_nodes_by_name("Login")[0].metadata.is_empty());
+        // `*` stays within one directory
+        assert!(graph.get_nodes_by_name("Post")[0].metadata.is_empty());
+
+        // Keys the indexer writes itself cannot be overridden
+        let reserved: SidecarMetadata =
+            serde_json::from_str(r#"{ "nodes": { "auth.Login": { "fan_in": "0" } } }"#).unwrap();
+        let err = reserved.apply(&mut graph).unwrap_err();
+        assert!(err.to_string().contains("reserved key 'fan_in'"));
+        assert!(graph.get_nodes_by_name("Login")[0].metadata.is_empty());
+    }
+
+    #[test]
+    fn test_incremental_dry_run_reports_changes() {
+        use crate::incremental;
+        use crate::parser::GoParser;
+        use crate::serializer::fast_compressed;
+
+        let dir = tempfile::tempdir().unwrap();
+        let kept = dir.path().join("kept.go");
+        let removed = dir.path().join("removed.go");
+        std::fs::write(&kept, "package main\n\nfunc Kept() {}\n").unwrap();
+        std::fs::write(&removed, "package main\n\nfunc A() {}\n\nfunc B() {}\n").unwrap();
+
+        let mut graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_directory(dir.path(), &mut graph)
+            .unwrap();
+        for path in [&kept, &removed] {
+            let modified = std::fs::metadata(path).unwrap().modified().unwrap();
+            graph.track_file_metadata(path, format!("{:?}", modified));
+        }
+
+        let graph_file = dir.path().join("codenav.bin");
+        fast_compressed::save_to_file(&graph, &graph_file.to_string_lossy()).unwrap();
+        let saved_bytes = std::fs::read(&graph_file).unwrap();
+
+        std::fs::remove_file(&removed).unwrap();
+        let added = dir.path().join("added.go");
+        std::fs::write(&added, "package main\n\nfunc Added() {}\n").unwrap();
+
+        let existing = fast_compressed::load_from_file(&graph_file.to_string_lossy()).unwrap();
+        let changes = incremental::detect_changes(dir.path(), &existing, &["go"], true).unwrap();
+
+        assert!(changes.changed_files.contains(&added));
+        assert!(!changes.changed_files.contains(&kept));
+        assert_eq!(
+            changes.deleted_files,
+            vec![removed.to_string_lossy().to_string()]
+        );
+
+        let removals = changes.nodes_to_remove(&existing);
+        let removed_count = removals
+            .iter()
+            .find(|(file, _)| *file == removed.to_string_lossy())
+            .map(|(_, count)| *count);
+        assert_eq!(removed_count, Some(2));
+
+        // Detection alone leaves the graph file untouched
+        assert_eq!(std::fs::read(&graph_file).unwrap(), saved_bytes);
+    }
+
+    #[test]
+    fn test_incremental_skips_changed_test_files_by_default() {
+        use crate::incremental;
+        use crate::parser::ParseOptions;
+
+        let dir = tempfile::tempdir().unwrap();
+        let graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        let source = dir.path().join("billing.go");
+        let test = dir.path().join("billing_test.go");
+        std::fs::write(&source, "package billing\n\nfunc Charge() {}\n").unwrap();
+        std::fs::write(&test, "package billing\n\nfunc TestCharge() { Charge() }\n").unwrap();
+
+        let changes = incremental::detect_changes(dir.path(), &graph, &["go"], false).unwrap();
+        assert_eq!(changes.changed_files.len(), 2);
+
+        // Same files as a full index: the test file only with --include-tests
+        let reparse = |include_tests: bool| {
+            let options = ParseOptions {
+                include_tests,
+                ..ParseOptions::default()
+            };
+            let filter = options.file_filter(dir.path()).unwrap();
+            let mut files: Vec<PathBuf> = changes
+                .files_to_reparse(&filter, "go")
+                .into_iter()
+                .cloned()
+                .collect();
+            files.sort();
+            files
+        };
+        assert_eq!(reparse(false), vec![source.clone()]);
+        assert_eq!(reparse(true), vec![source, test]);
+    }
+
+    #[test]
+    fn test_parse_deeply_nested_source() {
+        use crate::parser::GoParser;
+
+        // Generated code can nest far deeper than hand-written code; native
+        // recursion over a tree this deep would overflow the test thread's stack
+        let depth = 50_000;
+        let nested = format!("{}0{}", "(".repeat(depth), ")".repeat(depth));
+        let source = format!(
+            "package main\n\nfunc wrap(x int) int {{ return x }}\n\nfunc deep() int {{\n\treturn wrap({})\n}}\n\nfunc after() {{ wrap(1) }}\n",
+            nested
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("deep.go");
+        std::fs::write(&file, source).unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        assert_eq!(graph.nodes.len(), 3);
+        let deep_id = format!("{}:deep:5", file.display());
+        assert_eq!(graph.get_outgoing_edges(&deep_id).len(), 1);
+        assert_eq!(graph.find_callers("wrap").len(), 2);
+    }
+
+    #[test]
+    fn test_blame_annotates_last_author() {
+        use crate::blame;
+        use crate::parser::GoParser;
+        use std::process::Command;
+
+        let dir = tempfile::tempdir().unwrap();
+        // Explicit dates keep the two commits ordered even within the same second
+        let git = |args: &[&str], author: &str, date: &str| {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(["-c", &format!("user.name={}", author)])
+                .args(["-c", "user.email=dev@example.com"])
+                .args(["-c", "commit.gpgsign=false"])
+                .args(args)
+                .env("GIT_AUTHOR_DATE", date)
+                .env("GIT_COMMITTER_DATE", date)
+                .status()
+                .expect("git is required for this test");
+            assert!(status.success());
+        };
+
+        let file = dir.path().join("main.go");
+        git(&["init", "-q"], "alice", "2020-01-01T00:00:00Z");
+        std::fs::write(
+            &file,
+            "package main\n\nfunc Old() {}\n\nfunc Changed() {\n}\n",
+        )
+        .unwrap();
+        git(&["add", "."], "alice", "2020-01-01T00:00:00Z");
+        git(
+            &["commit", "-q", "-m", "initial"],
+            "alice",
+            "2020-01-01T00:00:00Z",
+        );
+
+        // Only Changed() is touched by the second author
+        std::fs::write(
+            &file,
+            "package main\n\nfunc Old() {}\n\nfunc Changed() {\n\tOld()\n}\n",
+        )
+        .unwrap();
+        git(
+            &["commit", "-q", "-am", "update"],
+            "bob",
+            "2024-06-01T00:00:00Z",
+        );
+
+        let mut graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        assert_eq!(blame::annotate_graph(&mut graph).unwrap(), 2);
+
+        let old = &graph.get_nodes_by_name("Old")[0];
+        let changed = &graph.get_nodes_by_name("Changed")[0];
+        assert_eq!(old.metadata.get("last_author").unwrap(), "alice");
+        assert_eq!(changed.metadata.get("last_author").unwrap(), "bob");
+        assert_eq!(changed.metadata.get("last_commit").unwrap().len(), 40);
+        assert_ne!(
+            old.metadata.get("last_commit"),
+            changed.metadata.get("last_commit")
+        );
+    }
+
+    #[test]
+    fn test_cpp_out_of_line_method_calls_free_function() {
+        use crate::parser::CppParser;
+
+        let source = r#"
+namespace geo {
+
+double square(double x) { return x * x; }
+
+class Shape {
+public:
+    double area() const { return square(side); }
+    double scaled(double factor) const;
+    double side;
+};
+
+template <typename T>
+T clamp(T value, T lo, T hi) { return value < lo ? lo : (value > hi ? hi : value); }
+
+}
+
+double geo::Shape::scaled(double factor) const {
+    return geo::square(factor) * area();
+}
+
+void visit(int n) {
+    struct Visitor {
+        void step() { advance(); }
+    };
+    auto each = [&](int i) { record(i); };
+    each(n);
+}
+"#;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("shape.h");
+        std::fs::write(&file, source).unwrap();
+        assert!(CppParser::EXTENSIONS.contains(&"h"));
+
+        let mut graph = CodeGraph::new("test".to_string(), "cpp".to_string());
+        CppParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        let square = &graph.get_nodes_by_name("square")[0];
+        assert_eq!(square.node_type, NodeType::Function);
+        assert_eq!(square.package, "geo");
+
+        let area = &graph.get_nodes_by_name("area")[0];
+        assert_eq!(area.node_type, NodeType::Method);
+        assert_eq!(area.metadata.get("class").unwrap(), "Shape");
+
+        // Out-of-line definition is qualified by its class and namespace
+        let scaled = &graph.get_nodes_by_name("scaled")[0];
+        assert_eq!(scaled.node_type, NodeType::Method);
+        assert_eq!(scaled.package, "geo");
+        assert_eq!(scaled.metadata.get("class").unwrap(), "Shape");
+        assert_eq!(scaled.parameters[0].name, "factor");
+        assert_eq!(scaled.parameters[0].param_type, "double");
+
+        let callees: Vec<&str> = graph
+            .get_outgoing_edges(&scaled.id)
+            .iter()
+            .map(|e| e.to.as_str())
+            .collect();
+        assert_eq!(callees, vec!["square", "area"]);
+
+        let clamp = &graph.get_nodes_by_name("clamp")[0];
+        assert_eq!(clamp.node_type, NodeType::Function);
+        assert_eq!(clamp.metadata.get("template_params").unwrap(), "typename T");
+
+        // Lambda calls belong to the enclosing function, local class method
+        // calls to the method only
+        let callees = |name: &str| -> Vec<String> {
+            let id = &graph.get_nodes_by_name(name)[0].id;
+            graph
+                .get_outgoing_edges(id)
+                .iter()
+                .map(|e| e.to.clone())
+                .collect()
+        };
+        assert_eq!(callees("visit"), vec!["record", "each"]);
+        assert_eq!(callees("step"), vec!["advance"]);
+    }
+
+    #[test]
+    fn test_diff_report_json_schema() {
+        use crate::core::{DiffReport, DIFF_REPORT_SCHEMA_VERSION};
+
+        let function = |name: &str, file: &str, line: usize, signature: &str| {
+            Node::new(
+                format!("{}:{}:{}", file, name, line),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from(file),
+                line,
+                line + 2,
+                "main".to_string(),
+                signature.to_string(),
+            )
+        };
+
+        let mut old = CodeGraph::new("test".to_string(), "go".to_string());
+        old.add_node(function("Keep", "a.go", 1, "func Keep()"));
+        old.add_node(function("Move", "a.go", 10, "func Move()"));
+        old.add_node(function("Gone", "a.go", 20, "func Gone()"));
+
+        let mut new = CodeGraph::new("test".to_string(), "go".to_string());
+        new.add_node(function("Keep", "a.go", 1, "func Keep(ctx Context)"));
+        new.add_node(function("Move", "b.go", 5, "func Move()"));
+        new.add_node(function("Fresh", "a.go", 30, "func Fresh()"));
+
+        let diff = old.diff(&new);
+        let report = DiffReport::new(&old, &new, &diff, None);
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["schema_version"], DIFF_REPORT_SCHEMA_VERSION);
+        let mut keys: Vec<&str> = json
+            .as_object()
+            .unwrap()
+            .keys()
+            .map(|k| k.as_str())
+            .collect();
+        keys.sort();
+        assert_eq!(
+            keys,
+            vec![
+                "added",
+                "changed",
+                "complexity",
+                "moved",
+                "removed",
+                "renamed",
+                "schema_version",
+                "summary"
+            ]
+        );
+
+        assert_eq!(json["added"][0]["name"], "Fresh");
+        assert_eq!(json["removed"][0]["name"], "Gone");
+        assert_eq!(
+            json["changed"][0]["new_signature"],
+            "func Keep(ctx Context)"
+        );
+        assert_eq!(json["moved"][0]["old"]["file"], "a.go");
+        assert_eq!(json["moved"][0]["new"]["file"], "b.go");
+        assert_eq!(json["summary"]["moved"], 1);
+    }
+
+    #[test]
+    fn test_max_edges_per_node_caps_fan_out() {
+        use crate::parser::{GoParser, ParseOptions, TRUNCATED_EDGES_TAG};
+
+        let calls: String = (0..50).map(|i| format!("\tcallee{}()\n", i)).collect();
+        let source = format!(
+            "package main\n\nfunc generated() {{\n{}}}\n\nfunc small() {{\n\ta()\n\ta()\n\tb()\n}}\n",
+            calls
+        );
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("gen.go");
+        std::fs::write(&file, source).unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .with_options(ParseOptions {
+                max_edges_per_node: Some(10),
+                ..Default::default()
+            })
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        let generated = &graph.get_nodes_by_name("generated")[0];
+        assert_eq!(graph.get_outgoing_edges(&generated.id).len(), 10);
+        assert!(generated.tags.iter().any(|t| t == TRUNCATED_EDGES_TAG));
+
+        // Functions under the cap are untouched (repeat calls still recorded)
+        let small = &graph.get_nodes_by_name("small")[0];
+        assert_eq!(graph.get_outgoing_edges(&small.id).len(), 3);
+        assert!(small.tags.is_empty());
+    }
+
+    #[test]
+    fn test_dedup_identical_nodes_across_files() {
+        use crate::parser::GoParser;
+
+        let source = "package util\n\nfunc Clamp(v int, lo int, hi int) int {\n\treturn min(max(v, lo), hi)\n}\n";
+        let dir = tempfile::tempdir().unwrap();
+        for vendor in ["a", "b"] {
+            let vendor_dir = dir.path().join(vendor);
+            std::fs::create_dir(&vendor_dir).unwrap();
+            std::fs::write(vendor_dir.join("util.go"), source).unwrap();
+        }
+        std::fs::write(
+            dir.path().join("main.go"),
+            "package main\n\nfunc main() {\n\tClamp(1, 2, 3)\n}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_directory(dir.path(), &mut graph)
+            .unwrap();
+        assert_eq!(graph.get_nodes_by_name("Clamp").len(), 2);
+        let edges_before = graph.edges.len();
+
+        assert_eq!(graph.dedup_identical_nodes(), 1);
+
+        let clamp = graph.get_nodes_by_name("Clamp");
+        assert_eq!(clamp.len(), 1);
+        let kept = dir.path().join("a").join("util.go");
+        let duplicate = dir.path().join("b").join("util.go");
+        assert_eq!(clamp[0].file_path, kept);
+        assert_eq!(
+            clamp[0].metadata.get("duplicates").unwrap(),
+            &format!("{}:3", duplicate.display())
+        );
+
+        // The copy's min/max calls merge into the representative's edges
+        assert_eq!(graph.get_outgoing_edges(&clamp[0].id).len(), 2);
+        assert_eq!(graph.edges.len(), edges_before - 2);
+        assert_eq!(graph.find_callers("Clamp").len(), 1);
+        assert_eq!(graph.get_nodes_by_name("main").len(), 1);
+    }
+
+    #[test]
+    fn test_package_abstractness() {
+        use crate::core::ABSTRACT_TAG;
+
+        let mut graph = CodeGraph::new("test".to_string(), "typescript".to_string());
+        let mut add = |id: &str, name: &str, node_type: NodeType, package: &str, abstract_type| {
+            let mut node = Node::new(
+                id.to_string(),
+                name.to_string(),
+                node_type,
+                PathBuf::from(format!("{}.ts", package)),
+                1,
+                5,
+                package.to_string(),
+                String::new(),
+            );
+            if abstract_type {
+                node.tags.push(ABSTRACT_TAG.to_string());
+            }
+            graph.add_node(node);
+        };
+
+        // "models" is all concrete and only depended upon
+        add("m:User", "User", NodeType::Class, "models", false);
+        add("m:Order", "Order", NodeType::Class, "models", false);
+        add("m:load", "load", NodeType::Function, "models", false);
+        // "api" has an abstract base and a concrete handler, and calls into models
+        add("a:Base", "BaseHandler", NodeType::Class, "api", true);
+        add("a:Impl", "UserHandler", NodeType::Class, "api", false);
+        add("a:handle", "handle", NodeType::Function, "api", false);
+
+        graph.add_edge(Edge::new(
+            "a:handle".to_string(),
+            "load".to_string(),
+            EdgeType::Calls,
+            "load()".to_string(),
+            PathBuf::from("api.ts"),
+            2,
+        ));
+
+        let metrics = graph.package_abstractness();
+        let models = metrics.iter().find(|m| m.package == "models").unwrap();
+        let api = metrics.iter().find(|m| m.package == "api").unwrap();
+
+        assert_eq!(models.concrete_types, 2);
+        assert_eq!(models.abstractness, 0.0);
+        assert_eq!(models.instability, 0.0);
+        assert!(models.in_zone_of_pain());
+
+        assert_eq!(api.abstractness, 0.5);
+        assert_eq!(api.instability, 1.0);
+        assert!(!api.in_zone_of_pain());
+        assert_eq!(metrics[0].package, "models");
+    }
+
+    #[test]
+    fn test_retain_public_edges() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for (name, file) in [
+            ("HandleLogin", "api.go"),
+            ("validate", "api.go"),
+            ("FindUser", "store.go"),
+        ] {
+            graph.add_node(Node::new(
+                format!("{}:{}:1", file, name),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from(file),
+                1,
+                5,
+                "main".to_string(),
+                String::new(),
+            ));
+        }
+        for callee in ["validate", "FindUser", "fmt.Println"] {
+            graph.add_edge(Edge::new(
+                "api.go:HandleLogin:1".to_string(),
+                callee.to_string(),
+                EdgeType::Calls,
+                format!("{}()", callee),
+                PathBuf::from("api.go"),
+                2,
+            ));
+        }
+        graph.add_edge(Edge::new(
+            "api.go:validate:1".to_string(),
+            "FindUser".to_string(),
+            EdgeType::Calls,
+            "FindUser()".to_string(),
+            PathBuf::from("api.go"),
+            3,
+        ));
+        // Non-call edges are not subject to the filter
+        graph.add_import(
+            std::path::Path::new("api.go"),
+            "api",
+            "net/http".to_string(),
+            "\"net/http\"".to_string(),
+            1,
+        );
+        graph.precompute_degrees();
+
+        graph.retain_public_edges();
+
+        assert_eq!(graph.edges.len(), 2);
+        assert_eq!(graph.edges[0].from, "api.go:HandleLogin:1");
+        assert_eq!(graph.edges[0].to, "FindUser");
+        assert_eq!(graph.edges[1].edge_type, EdgeType::Imports);
+        // Nodes are kept; only the edges are pruned
+        assert_eq!(graph.nodes.len(), 3);
+
+        // Stored degrees follow the pruned edges
+        let degree = |id: &str, key: &str| graph.get_node_by_id(id).unwrap().metadata[key].clone();
+        assert_eq!(degree("api.go:HandleLogin:1", "fan_out"), "1");
+        assert_eq!(degree("store.go:FindUser:1", "fan_in"), "1");
+        assert_eq!(degree("api.go:validate:1", "fan_in"), "0");
+    }
+
+    #[test]
+    fn test_retain_public_edges_follows_resolved_targets() {
+        let mut graph = CodeGraph::new("test".to_string(), "typescript".to_string());
+        for (id, file, signature) in [
+            ("api.ts:handle:1", "api.ts", "handle()"),
+            ("api.ts:save:5", "api.ts", "save()"),
+            ("store.ts:save:3", "store.ts", "private save()"),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                id.split(':').nth(1).unwrap().to_string(),
+                NodeType::Method,
+                PathBuf::from(file),
+                1,
+                5,
+                "app".to_string(),
+                signature.to_string(),
+            ));
+        }
+        let call = |line: usize, to_id: Option<&str>| {
+            let mut edge = Edge::new(
+                "api.ts:handle:1".to_string(),
+                "save".to_string(),
+                EdgeType::Calls,
+                "save()".to_string(),
+                PathBuf::from("api.ts"),
+                line,
+            );
+            edge.to_id = to_id.map(str::to_string);
+            edge
+        };
+        graph.add_edge(call(2, Some("store.ts:save:3")));
+        graph.add_edge(call(3, Some("api.ts:save:5")));
+        graph.add_edge(call(4, None));
+
+        graph.retain_public_edges();
+
+        // The call resolved to the private save is dropped despite the public
+        // one sharing its name; the unresolved call matches by name
+        let lines: Vec<usize> = graph.edges.iter().map(|e| e.line).collect();
+        assert_eq!(lines, vec![3, 4]);
+    }
+
+    #[test]
+    fn test_json_events_during_index() {
+        use crate::events::EventSink;
+        use crate::parser::{GoParser, ParseOptions};
+        use std::io::Write;
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Captured(Arc<Mutex<Vec<u8>>>);
+        impl Write for Captured {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("a.go"),
+            "package main\n\nfunc A() { B() }\n",
+        )
+        .unwrap();
+        std::fs::write(dir.path().join("b.go"), "package main\n\nfunc B() {}\n").unwrap();
+
+        let stderr = Captured::default();
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .with_options(ParseOptions {
+                events: Some(EventSink::new(stderr.clone())),
+                ..Default::default()
+            })
+            .parse_directory(dir.path(), &mut graph)
+            .unwrap();
+
+        let output = String::from_utf8(stderr.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<serde_json::Value> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).expect("each line is a JSON event"))
+            .collect();
+
+        assert_eq!(events.len(), 2);
+        assert!(events.iter().all(|e| e["event"] == "file_parsed"));
+        let mut paths: Vec<&str> = events.iter().map(|e| e["path"].as_str().unwrap()).collect();
+        paths.sort();
+        assert!(paths[0].ends_with("a.go") && paths[1].ends_with("b.go"));
+    }
+
+    #[test]
+    fn test_find_paths_with_recursive_same_named_functions() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for (id, name, file) in [
+            ("a.go:main:1", "main", "a.go"),
+            ("a.go:walk:5", "walk", "a.go"),
+            ("b.go:walk:1", "walk", "b.go"),
+            ("b.go:leaf:9", "leaf", "b.go"),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from(file),
+                1,
+                3,
+                "main".to_string(),
+                String::new(),
+            ));
+        }
+        for (from, to) in [
+            ("a.go:main:1", "walk"),
+            // a.go's walk is recursive; b.go's walk is a different function
+            ("a.go:walk:5", "walk"),
+            ("b.go:walk:1", "leaf"),
+        ] {
+            graph.add_edge(Edge::new(
+                from.to_string(),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("a.go"),
+                2,
+            ));
+        }
+
+        let mut to_leaf = graph.find_paths("a.go:main:1", "leaf", 5);
+        to_leaf.sort();
+        assert_eq!(
+            to_leaf,
+            vec![
+                vec!["main", "walk", "leaf"],
+                vec!["main", "walk", "walk", "leaf"],
+            ]
+        );
+
+        // One path per definition of the target name
+        assert_eq!(graph.find_paths("a.go:main:1", "walk", 5).len(), 2);
+
+        // The recursive call reaches both itself and the other walk
+        let recursive = graph.find_paths("a.go:walk:5", "walk", 5);
+        assert_eq!(recursive.len(), 2);
+        assert!(recursive.iter().all(|p| p == &vec!["walk", "walk"]));
+    }
+
+    #[test]
+    fn test_max_nesting_recorded() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("nested.go");
+        std::fs::write(
+            &file,
+            r#"package main
+
+func nested(items []int) {
+	for _, item := range items {
+		if item > 0 {
+			if item%2 == 0 {
+				println(item)
+			} else if item%3 == 0 {
+				println(-item)
+			}
+		}
+	}
+}
+
+func flat() {
+	println("flat")
+}
+"#,
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        let nested = graph.get_nodes_by_name("nested")[0];
+        assert_eq!(nested.max_nesting(), Some(3));
+        assert_eq!(graph.get_nodes_by_name("flat")[0].max_nesting(), Some(0));
+
+        let deep = graph.find_deeply_nested(2);
+        assert_eq!(deep.len(), 1);
+        assert_eq!(deep[0].0.name, "nested");
+    }
+
+    #[test]
+    fn test_query_by_tags_and_or() {
+        use crate::core::TagMode;
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for (name, tags) in [
+            ("oldLogin", vec!["handler", "deprecated"]),
+            ("login", vec!["handler"]),
+            ("legacyHash", vec!["deprecated"]),
+            ("helper", vec![]),
+        ] {
+            let mut node = Node::new(
+                format!("api.go:{}:1", name),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("api.go"),
+                1,
+                3,
+                "api".to_string(),
+                String::new(),
+            );
+            node.tags = tags.into_iter().map(String::from).collect();
+            graph.add_node(node);
+        }
+        // Tags added after insertion are indexed too
+        graph.add_tag("api.go:helper:1", "handler");
+
+        let names = |nodes: Vec<&Node>| nodes.iter().map(|n| n.name.clone()).collect::<Vec<_>>();
+
+        assert_eq!(
+            names(graph.get_nodes_by_tags(&["handler", "deprecated"], TagMode::And)),
+            vec!["oldLogin"]
+        );
+        assert_eq!(
+            names(graph.get_nodes_by_tags(&["handler", "deprecated"], TagMode::Or)),
+            vec!["oldLogin", "login", "legacyHash", "helper"]
+        );
+        assert!(graph
+            .get_nodes_by_tags(&["handler", "missing"], TagMode::And)
+            .is_empty());
+
+        // The index survives a rebuild
+        graph.build_indexes();
+        assert_eq!(graph.get_nodes_by_tags(&["handler"], TagMode::And).len(), 3);
+    }
+
+    #[test]
+    fn test_node_relations() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for name in [
+            "handleLogin",
+            "handleSignup",
+            "authenticate",
+            "hashPassword",
+        ] {
+            graph.add_node(Node::new(
+                format!("auth.go:{}:1", name),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("auth.go"),
+                1,
+                5,
+                "auth".to_string(),
+                String::new(),
+            ));
+        }
+        for (from, to, line) in [
+            ("handleLogin", "authenticate", 2),
+            ("handleLogin", "authenticate", 3),
+            ("handleSignup", "authenticate", 2),
+            ("authenticate", "hashPassword", 2),
+            ("authenticate", "log.Printf", 3),
+        ] {
+            graph.add_edge(Edge::new(
+                format!("auth.go:{}:1", from),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("auth.go"),
+                line,
+            ));
+        }
+
+        let relations = graph.node_relations("auth.go:authenticate:1", 5).unwrap();
+        let callers: Vec<_> = relations
+            .callers
+            .iter()
+            .map(|r| (r.name.as_str(), r.calls))
+            .collect();
+        let callees: Vec<_> = relations
+            .callees
+            .iter()
+            .map(|r| (r.name.as_str(), r.calls))
+            .collect();
+
+        assert_eq!(callers, vec![("handleLogin", 2), ("handleSignup", 1)]);
+        assert_eq!(callees, vec![("hashPassword", 1), ("log.Printf", 1)]);
+        assert!(graph.node_relations("missing", 5).is_none());
+    }
+
+    #[test]
+    fn test_scoped_complexity_analysis() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for name in ["handleLogin", "authenticate", "query", "reportJob"] {
+            graph.add_node(Node::new(
+                format!("app.go:{}:1", name),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("app.go"),
+                1,
+                5,
+                "app".to_string(),
+                String::new(),
+            ));
+        }
+        for (from, to) in [
+            ("handleLogin", "authenticate"),
+            ("authenticate", "query"),
+            // Outside the handleLogin scope
+            ("reportJob", "query"),
+        ] {
+            graph.add_edge(Edge::new(
+                format!("app.go:{}:1", from),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("app.go"),
+                2,
+            ));
+        }
+
+        // What `analyze complexity --scope-from handleLogin --scope-depth 2` analyzes
+        let scoped = graph.extract_subgraph("handleLogin", 2);
+        let mut analyzed: Vec<&str> = scoped.nodes.iter().map(|n| n.name.as_str()).collect();
+        analyzed.sort();
+        assert_eq!(analyzed, vec!["authenticate", "handleLogin", "query"]);
+
+        // Fan-in only counts callers inside the scope
+        let query_id = "app.go:query:1";
+        assert_eq!(graph.get_complexity(query_id).fan_in, 2);
+        assert_eq!(scoped.get_complexity(query_id).fan_in, 1);
+    }
+
+    #[test]
+    fn test_scala_object_method_calls_other_object() {
+        use crate::parser::ScalaParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("Billing.scala");
+        std::fs::write(
+            &file,
+            r#"package com.example
+package billing
+
+trait Priced { def price: Int }
+
+class Invoice(total: Int) extends Document with Priced with Serializable {
+  def price: Int = total
+}
+
+object Billing {
+  def charge(amount: Int): Int = {
+    val fee = Fees.compute(amount)
+    amount + fee
+  }
+}
+
+object Fees {
+  def compute(amount: Int): Int = amount / 10
+}
+"#,
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "scala".to_string());
+        ScalaParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        let charge = graph.get_nodes_by_name("charge")[0];
+        assert_eq!(charge.node_type, NodeType::Method);
+        assert_eq!(charge.package, "com.example.billing");
+        assert_eq!(
+            charge.metadata.get("class").map(String::as_str),
+            Some("Billing")
+        );
+        assert_eq!(charge.parameters[0].name, "amount");
+        assert_eq!(charge.returns, vec!["Int"]);
+
+        let callees: Vec<&str> = graph
+            .get_outgoing_edges(&charge.id)
+            .iter()
+            .map(|e| e.to.as_str())
+            .collect();
+        assert_eq!(callees, vec!["compute"]);
+        assert_eq!(graph.find_callers("compute").len(), 1);
+
+        // Objects, classes and traits are type nodes; traits are abstract
+        assert_eq!(graph.get_nodes_by_type(&NodeType::Class).len(), 4);
+        assert!(graph.get_nodes_by_name("Priced")[0].is_abstract_type());
+
+        let invoice = graph.get_nodes_by_name("Invoice")[0];
+        let parents: Vec<(EdgeType, &str)> = graph
+            .get_outgoing_edges(&invoice.id)
+            .iter()
+            .map(|e| (e.edge_type.clone(), e.to.as_str()))
+            .collect();
+        assert_eq!(
+            parents,
+            vec![
+                (EdgeType::Extends, "Document"),
+                (EdgeType::Implements, "Priced"),
+                (EdgeType::Implements, "Serializable"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bisect_cycle_finds_introducing_commit() {
+        use crate::bisect::{self, BisectOutcome};
+        use crate::parser::GoParser;
+        use std::process::Command;
+
+        let dir = tempfile::tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let output = Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(["-c", "user.name=dev", "-c", "user.email=dev@example.com"])
+                .args(["-c", "commit.gpgsign=false"])
+                .args(args)
+                .env("GIT_AUTHOR_DATE", "2020-01-01T00:00:00Z")
+                .env("GIT_COMMITTER_DATE", "2020-01-01T00:00:00Z")
+                .output()
+                .expect("git is required for this test");
+            assert!(output.status.success());
+            String::from_utf8(output.stdout).unwrap().trim().to_string()
+        };
+        let commit = |path: &str, source: &str, message: &str| {
+            let file = dir.path().join(path);
+            std::fs::create_dir_all(file.parent().unwrap()).unwrap();
+            std::fs::write(file, source).unwrap();
+            git(&["add", "."]);
+            git(&["commit", "-q", "-m", message]);
+            git(&["rev-parse", "HEAD"])
+        };
+
+        git(&["init", "-q"]);
+        commit("b/b.go", "package b\n\nfunc B() {}\n", "add b");
+        let first = commit(
+            "a/a.go",
+            "package a\n\nfunc A() {\n\tb.B()\n}\n",
+            "a calls b",
+        );
+        commit("README", "docs\n", "docs");
+        let culprit = commit(
+            "b/b.go",
+            "package b\n\nfunc B() {\n\ta.A()\n}\n",
+            "b calls back into a",
+        );
+        commit("README", "more docs\n", "more docs");
+
+        let index = |root: &std::path::Path| {
+            let mut graph = CodeGraph::new(root.to_string_lossy().to_string(), "go".to_string());
+            GoParser::new()?.parse_directory(root, &mut graph)?;
+            Ok(graph)
+        };
+
+        let outcome = bisect::bisect_cycle(dir.path(), &first, "HEAD", index).unwrap();
+        assert_eq!(
+            outcome,
+            BisectOutcome::Introduced {
+                commit: culprit.clone(),
+                summary: "b calls back into a".to_string(),
+                cycles: vec![vec!["a".to_string(), "b".to_string()]],
+            }
+        );
+
+        let outcome = bisect::bisect_cycle(dir.path(), &culprit, "HEAD", index).unwrap();
+        assert!(matches!(outcome, BisectOutcome::AlreadyPresent { .. }));
+
+        let outcome = bisect::bisect_cycle(dir.path(), &first, "HEAD~2", index).unwrap();
+        assert_eq!(outcome, BisectOutcome::NotIntroduced);
+    }
+
+    #[test]
+    fn test_resolved_edges_carry_target_id() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for (id, name, file, package) in [
+            ("a.go:main:1", "main", "a.go", "app"),
+            ("a.go:walk:5", "walk", "a.go", "app"),
+            ("b.go:walk:1", "walk", "b.go", "app"),
+            ("b.go:leaf:9", "leaf", "b.go", "app"),
+            ("c.go:dup:1", "dup", "c.go", "one"),
+            ("d.go:dup:1", "dup", "d.go", "two"),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from(file),
+                1,
+                3,
+                package.to_string(),
+                String::new(),
+            ));
+        }
+        for (from, to, file) in [
+            // Overloaded: the definition in the caller's own file wins
+            ("a.go:main:1", "walk", "a.go"),
+            ("b.go:walk:1", "leaf", "b.go"),
+            // Two candidates outside the caller's file and package
+            ("a.go:main:1", "dup", "a.go"),
+            ("a.go:main:1", "fmt.Println", "a.go"),
+        ] {
+            graph.add_edge(Edge::new(
+                from.to_string(),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from(file),
+                2,
+            ));
+        }
+
+        assert_eq!(graph.resolve_edges(), 2);
+        let to_ids: Vec<Option<&str>> = graph.edges.iter().map(|e| e.to_id.as_deref()).collect();
+        assert_eq!(
+            to_ids,
+            vec![Some("a.go:walk:5"), Some("b.go:leaf:9"), None, None]
+        );
+        for edge in graph.edges.iter().filter(|e| e.to_id.is_some()) {
+            assert!(graph.get_node_by_id(edge.to_id.as_ref().unwrap()).is_some());
+        }
+
+        // Traversal follows to_id instead of every node named "walk", so the
+        // other walk (and the leaf it calls) is unreachable from main
+        assert!(graph.find_paths("a.go:main:1", "leaf", 5).is_empty());
+        assert_eq!(graph.find_paths("a.go:main:1", "walk", 5).len(), 1);
+        let traced: Vec<_> = graph
+            .trace_dependencies("a.go:main:1", 3)
+            .into_iter()
+            .map(|r| r.to_name)
+            .collect();
+        assert!(!traced.contains(&"leaf".to_string()));
+
+        // Unresolved graphs still fall back to name lookup
+        for edge in &mut graph.edges {
+            edge.to_id = None;
+        }
+        assert_eq!(graph.find_paths("a.go:main:1", "leaf", 5).len(), 1);
+    }
+
+    #[test]
+    fn test_precomputed_degrees_follow_incremental_updates() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.go");
+        let b = dir.path().join("b.go");
+        std::fs::write(
+            &a,
+            "package main\n\nfunc main() {\n\thelper()\n\tutil()\n}\n\nfunc util() {}\n",
+        )
+        .unwrap();
+        std::fs::write(&b, "package main\n\nfunc helper() {\n\tutil()\n}\n").unwrap();
+
+        let mut graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        let mut parser = GoParser::new().unwrap();
+        parser.parse_directory(dir.path(), &mut graph).unwrap();
+        graph.precompute_degrees();
+
+        let assert_degrees_match = |graph: &CodeGraph| {
+            for node in &graph.nodes {
+                let on_the_fly = (
+                    graph.find_callers(&node.name).len(),
+                    graph.get_outgoing_edges(&node.id).len(),
+                );
+                assert_eq!(
+                    node.precomputed_degrees(),
+                    Some(on_the_fly),
+                    "{}",
+                    node.name
+                );
+            }
+        };
+        assert_degrees_match(&graph);
+        let util = graph.get_nodes_by_name("util")[0].id.clone();
+        assert_eq!(graph.get_complexity(&util).fan_in, 2);
+
+        // helper stops calling util: util's fan-in drops although a.go is untouched
+        let b_path = b.to_string_lossy().to_string();
+        let mut stale = graph.degree_dependents(&b_path);
+        graph.remove_nodes_from_file(&b_path);
+        std::fs::write(&b, "package main\n\nfunc helper() {}\n").unwrap();
+        parser.parse_file(&b, &mut graph).unwrap();
+        stale.extend(graph.degree_dependents(&b_path));
+        graph.refresh_degrees(&stale);
+
+        assert_degrees_match(&graph);
+        let util = graph.get_nodes_by_name("util")[0].id.clone();
+        assert_eq!(graph.get_complexity(&util).fan_in, 1);
+    }
+
+    #[test]
+    fn test_find_implementers() {
+        let mut graph = CodeGraph::new("test".to_string(), "scala".to_string());
+        for (id, name, package) in [
+            ("shapes.scala:Shape:1", "Shape", "shapes"),
+            ("circle.scala:Circle:1", "Circle", "geometry"),
+            ("square.scala:Square:1", "Square", "geometry"),
+            ("blob.scala:Blob:1", "Blob", "geometry"),
+            ("other.scala:Shape:1", "Shape", "legacy"),
+            ("old.scala:OldShape:1", "OldShape", "legacy"),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Class,
+                PathBuf::from(id.split(':').next().unwrap()),
+                1,
+                3,
+                package.to_string(),
+                String::new(),
+            ));
+        }
+        for (from, to, edge_type, spelled) in [
+            (
+                "circle.scala:Circle:1",
+                "Shape",
+                EdgeType::Implements,
+                "shapes.Shape",
+            ),
+            (
+                "square.scala:Square:1",
+                "Shape",
+                EdgeType::Implements,
+                "Shape[Int]",
+            ),
+            // Extending is not implementing
+            ("blob.scala:Blob:1", "Shape", EdgeType::Extends, "Shape"),
+            // Same simple name, different package
+            (
+                "old.scala:OldShape:1",
+                "Shape",
+                EdgeType::Implements,
+                "legacy.Shape",
+            ),
+        ] {
+            let mut edge = Edge::new(
+                from.to_string(),
+                to.to_string(),
+                edge_type,
+                spelled.to_string(),
+                PathBuf::from(from.split(':').next().unwrap()),
+                1,
+            );
+            if from == "square.scala:Square:1" {
+                edge.to_id = Some("shapes.scala:Shape:1".to_string());
+            }
+            graph.add_edge(edge);
+        }
+
+        let names = |interface: &str| -> Vec<String> {
+            graph
+                .find_implementers(interface)
+                .into_iter()
+                .map(|(node, _)| node.name.clone())
+                .collect()
+        };
+
+        assert_eq!(names("Shape"), vec!["Circle", "Square", "OldShape"]);
+        // Qualified: spelled out at the declaration, or resolved to the package
+        assert_eq!(names("shapes.Shape"), vec!["Circle", "Square"]);
+        assert_eq!(names("shapes::Shape"), vec!["Circle", "Square"]);
+        assert_eq!(names("legacy.Shape"), vec!["OldShape"]);
+        assert!(names("Circle").is_empty());
+    }
+
+    #[test]
+    fn test_change_detection_without_git() {
+        use crate::incremental;
+        use std::process::Command;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a.go"), "package main\n\nfunc A() {}\n").unwrap();
+        for args in [
+            &["init", "-q"][..],
+            &["add", "."],
+            &["commit", "-q", "-m", "initial"],
+        ] {
+            let status = Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(["-c", "user.name=dev", "-c", "user.email=dev@example.com"])
+                .args(["-c", "commit.gpgsign=false"])
+                .args(args)
+                .status()
+                .expect("git is required for this test");
+            assert!(status.success());
+        }
+
+        // The graph has no file timestamps, so only git knows a.go is unchanged
+        let graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+
+        let with_git = incremental::detect_changes(dir.path(), &graph, &["go"], true).unwrap();
+        assert_eq!(with_git.detection_method, "git");
+        assert!(with_git.changed_files.is_empty());
+
+        let without_git = incremental::detect_changes(dir.path(), &graph, &["go"], false).unwrap();
+        assert_eq!(without_git.detection_method, "timestamps");
+        assert_eq!(without_git.changed_files, vec![dir.path().join("a.go")]);
+    }
+
+    #[test]
+    fn test_group_nodes_limit_per_group() {
+        use crate::core::{group_nodes, GroupBy};
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for (i, package) in ["big", "big", "small", "big", "big", "big"]
+            .iter()
+            .enumerate()
+        {
+            graph.add_node(Node::new(
+                format!("{}.go:f{}:1", package, i),
+                format!("f{}", i),
+                NodeType::Function,
+                PathBuf::from(format!("{}.go", package)),
+                1,
+                3,
+                package.to_string(),
+                String::new(),
+            ));
+        }
+        let nodes: Vec<&Node> = graph.nodes.iter().collect();
+
+        let groups = group_nodes(&nodes, GroupBy::Package, Some(2));
+        assert_eq!(groups.len(), 2);
+
+        let big = &groups[0];
+        assert_eq!(big.group, "big");
+        let names: Vec<&str> = big.nodes.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["f0", "f1"]);
+        assert_eq!(big.more, 3);
+        assert_eq!(big.overflow_note().as_deref(), Some("(+3 more)"));
+
+        let small = &groups[1];
+        assert_eq!(small.nodes.len(), 1);
+        assert_eq!(small.overflow_note(), None);
+
+        // Without a cap every node is shown
+        let uncapped = group_nodes(&nodes, GroupBy::File, None);
+        assert_eq!(uncapped[0].group, "big.go");
+        assert_eq!(uncapped[0].nodes.len(), 5);
+        assert_eq!(uncapped[0].more, 0);
+    }
+
+    #[test]
+    fn test_cyclomatic_complexity_counts_decision_points() {
+        use crate::parser::{GoParser, Language, PythonParser, TypeScriptParser};
+
+        // Two `if`s and one `for` in each language: cyclomatic == 4
+        let dir = tempfile::tempdir().unwrap();
+        let sources = [
+            (
+                "branchy.go",
+                "package main\n\nfunc branchy(xs []int) {\n\tfor _, x := range xs {\n\t\tif x > 0 {\n\t\t\tprintln(x)\n\t\t}\n\t}\n\tif len(xs) == 0 {\n\t\tprintln(\"empty\")\n\t}\n}\n",
+            ),
+            (
+                "branchy.py",
+                "def branchy(xs):\n    for x in xs:\n        if x > 0:\n            print(x)\n    if not xs:\n        print('empty')\n",
+            ),
+            (
+                "branchy.ts",
+                "function branchy(xs: number[]) {\n  for (const x of xs) {\n    if (x > 0) {\n      console.log(x);\n    }\n  }\n  if (xs.length === 0) {\n    console.log('empty');\n  }\n}\n",
+            ),
+        ];
+
+        let mut graph = CodeGraph::new("test".to_string(), "mixed".to_string());
+        for (name, source) in sources {
+            let file = dir.path().join(name);
+            std::fs::write(&file, source).unwrap();
+            match name.rsplit('.').next().unwrap() {
+                "go" => GoParser::new().unwrap().parse_file(&file, &mut graph),
+                "py" => PythonParser::new().unwrap().parse_file(&file, &mut graph),
+                _ => TypeScriptParser::new(Language::TypeScript)
+                    .unwrap()
+                    .parse_file(&file, &mut graph),
+            }
+            .unwrap();
+        }
+
+        let branchy = graph.get_nodes_by_name("branchy");
+        assert_eq!(branchy.len(), 3);
+        for node in branchy {
+            assert_eq!(node.decision_points, 3, "{}", node.file_path.display());
+            assert_eq!(graph.get_complexity(&node.id).cyclomatic, 4);
+        }
+
+        // Short-circuit operators branch too
+        let file = dir.path().join("logic.go");
+        std::fs::write(
+            &file,
+            "package main\n\nfunc logic(a, b, c bool) bool {\n\treturn a && (b || c)\n}\n",
+        )
+        .unwrap();
+        GoParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+        let logic = graph.get_nodes_by_name("logic")[0];
+        assert_eq!(graph.get_complexity(&logic.id).cyclomatic, 3);
+    }
+
+    #[test]
+    fn test_index_manifest_records_files_and_fingerprint() {
+        use crate::manifest::IndexManifest;
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("b.go"),
+            "package main\n\nfunc helper() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("a.go"),
+            "package main\n\nfunc main() {\n\thelper()\n}\n",
+        )
+        .unwrap();
+
+        // Skipped by default, so not part of the index
+        std::fs::write(
+            dir.path().join("a_test.go"),
+            "package main\n\nfunc TestMain() {}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        let options = crate::parser::ParseOptions::default();
+        let mut parser = GoParser::new().unwrap().with_options(options.clone());
+        parser.parse_directory(dir.path(), &mut graph).unwrap();
+
+        let files = options.parsed_files.paths();
+        let manifest = IndexManifest::from_graph(&graph, dir.path(), &files).unwrap();
+        assert_eq!(manifest.tool_version, env!("CARGO_PKG_VERSION"));
+        assert_eq!(manifest.file_count, 2);
+        assert_eq!(manifest.node_count, graph.nodes.len());
+        assert_eq!(manifest.edge_count, graph.edges.len());
+        let paths: Vec<&str> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert!(paths[0].ends_with("a.go") && paths[1].ends_with("b.go"));
+        // SHA-256 of "package main\n\nfunc helper() {}\n", as sha256sum prints it
+        assert_eq!(
+            manifest.files[1].hash,
+            "5d5f2cafcd1163a80d928155cedcc163945532ecb2917a139f7e02e5294dd7a4"
+        );
+        assert_eq!(manifest.graph_fingerprint, graph.fingerprint());
+        assert_eq!(manifest.graph_fingerprint.len(), 64);
+
+        // Deterministic for the same tree
+        let again = IndexManifest::from_graph(&graph, dir.path(), &files).unwrap();
+        assert_eq!(again.files[1].hash, manifest.files[1].hash);
+
+        // Any change to the graph changes the fingerprint
+        let fingerprint = graph.fingerprint();
+        graph.edges[0].line += 1;
+        assert_ne!(graph.fingerprint(), fingerprint);
+    }
+
+    #[test]
+    fn test_rust_parser_extracts_functions_methods_and_calls() {
+        use crate::parser::RustParser;
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/simple-rust/src/calculator.rs");
+        let mut graph = CodeGraph::new("test".to_string(), "rust".to_string());
+        RustParser::new()
+            .unwrap()
+            .parse_file(&fixture, &mut graph)
+            .unwrap();
+
+        let run = graph.get_nodes_by_name("run")[0];
+        assert_eq!(run.node_type, NodeType::Function);
+        assert_eq!(run.package, "crate::calculator");
+        assert_eq!(run.returns, vec!["i32"]);
+
+        let add = graph.get_nodes_by_name("add")[0];
+        assert_eq!(add.node_type, NodeType::Method);
+        assert_eq!(
+            add.metadata.get("class").map(String::as_str),
+            Some("Calculator")
+        );
+        let params: Vec<&str> = add.parameters.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(params, vec!["self", "a", "b"]);
+
+        let fmt = graph.get_nodes_by_name("fmt")[0];
+        assert_eq!(fmt.node_type, NodeType::Method);
+        assert_eq!(
+            fmt.metadata.get("trait").map(String::as_str),
+            Some("Display")
+        );
+
+        // Inline modules extend the file's module path
+        let double = graph.get_nodes_by_name("double")[0];
+        assert_eq!(double.package, "crate::calculator::helpers");
+        assert_eq!(graph.get_complexity(&double.id).cyclomatic, 3);
+
+        // Method, associated-function and path calls; macros are skipped
+        let callees = |id: &str| -> Vec<String> {
+            graph
+                .get_outgoing_edges(id)
+                .iter()
+                .map(|e| e.to.clone())
+                .collect()
+        };
+        assert_eq!(callees(&add.id), vec!["log_operation"]);
+        assert_eq!(callees(&run.id), vec!["new", "double", "add"]);
+        assert!(callees(&fmt.id).is_empty());
+        assert_eq!(graph.find_callers("log_operation").len(), 1);
+    }
+
+    #[test]
+    fn test_exclude_dunder_drops_magic_methods() {
+        use crate::core::DUNDER_TAG;
+        use crate::parser::PythonParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("models.py");
+        std::fs::write(
+            &file,
+            "class Base:\n    def __init__(self):\n        self.setup()\n\n    def setup(self):\n        pass\n\nclass User(Base):\n    def __init__(self, name):\n        super().__init__()\n        self.name = name\n\n    def __str__(self):\n        return self.name\n\n    def rename(self, name):\n        self.__init__(name)\n        self.setup()\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "python".to_string());
+        PythonParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        let init = graph.get_nodes_by_name("__init__");
+        assert_eq!(init.len(), 2);
+        assert!(init[0].tags.iter().any(|t| t == DUNDER_TAG));
+        assert!(graph.get_nodes_by_name("setup")[0].tags.is_empty());
+        assert_eq!(graph.find_callers("__init__").len(), 2);
+
+        graph.remove_dunder();
+
+        assert!(graph.get_nodes_by_name("__init__").is_empty());
+        assert!(graph.get_nodes_by_name("__str__").is_empty());
+        assert!(graph.find_callers("__init__").is_empty());
+        assert!(graph.edges.iter().all(|e| !crate::core::is_dunder(&e.to)));
+        // `setup` keeps its caller outside the dropped constructor
+        assert_eq!(graph.find_callers("setup").len(), 1);
+        assert_eq!(graph.get_nodes_by_name("rename").len(), 1);
+    }
+
+    fn import_targets(graph: &CodeGraph) -> Vec<(&str, usize)> {
+        graph
+            .edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Imports)
+            .map(|e| (e.to.as_str(), e.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_go_import_edges() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.go");
+        std::fs::write(
+            &file,
+            "package main\n\nimport \"fmt\"\n\nimport (\n\tlog \"github.com/acme/log\"\n\t_ `net/http/pprof`\n)\n\nfunc main() {\n\tfmt.Println(\"hi\")\n}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        assert_eq!(
+            import_targets(&graph),
+            vec![
+                ("fmt", 3),
+                ("github.com/acme/log", 6),
+                ("net/http/pprof", 7)
+            ]
+        );
+        let aliased = &graph.edges[1];
+        assert_eq!(aliased.call_site, "log \"github.com/acme/log\"");
+        assert_eq!(aliased.from, file.display().to_string());
+        assert_eq!(graph.package_imports()["main"].len(), 3);
+
+        // Import targets are never resolved to same-named nodes
+        graph.resolve_edges();
+        assert!(graph
+            .edges
+            .iter()
+            .all(|e| e.edge_type != EdgeType::Imports || e.to_id.is_none()));
+
+        // Re-indexing a file drops its imports along with its nodes
+        graph.remove_nodes_from_file(&file.to_string_lossy());
+        assert!(import_targets(&graph).is_empty());
+    }
+
+    #[test]
+    fn test_python_import_edges() {
+        use crate::parser::PythonParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.py");
+        std::fs::write(
+            &file,
+            "import os.path, json as j\nfrom .models import User\nfrom collections import OrderedDict\n\ndef main():\n    import sys\n\ndef sys():\n    pass\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "python".to_string());
+        PythonParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        assert_eq!(
+            import_targets(&graph),
+            vec![
+                ("os.path", 1),
+                ("json", 1),
+                (".models", 2),
+                ("collections", 3),
+                ("sys", 6)
+            ]
+        );
+        let relative = graph.edges.iter().find(|e| e.to == ".models").unwrap();
+        assert_eq!(relative.call_site, "from .models import User");
+
+        // Importing a module is not calling a function of the same name
+        let sys = graph.get_nodes_by_name("sys")[0].clone();
+        assert!(graph.find_callers("sys").is_empty());
+        assert!(graph.trace_callers(&sys.id, 2).is_empty());
+        assert!(graph.find_hotspots(10, false).is_empty());
+        assert_eq!(graph.filter_isolated(vec![&sys]).len(), 1);
+        graph.precompute_degrees();
+        assert_eq!(
+            graph.get_node_by_id(&sys.id).unwrap().metadata["fan_in"],
+            "0"
+        );
+    }
+
+    #[test]
+    fn test_typescript_import_edges() {
+        use crate::parser::{Language, TypeScriptParser};
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("app.ts");
+        std::fs::write(
+            &file,
+            "import { login } from './auth';\nimport * as React from \"react\";\nimport './styles.css';\nimport fs = require('fs');\n\nexport function main() {\n  login();\n}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "typescript".to_string());
+        TypeScriptParser::new(Language::TypeScript)
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        assert_eq!(
+            import_targets(&graph),
+            vec![("./auth", 1), ("react", 2), ("./styles.css", 3), ("fs", 4)]
+        );
+        let package = graph.get_nodes_by_name("main")[0].package.clone();
+        assert_eq!(graph.package_imports()[&package].len(), 4);
+        // Calls are still recorded next to the imports
+        assert_eq!(graph.find_callers("login").len(), 1);
+    }
+
+    #[test]
+    fn test_xref_lists_definition_callers_and_callees() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("main.go");
+        std::fs::write(
+            &file,
+            "package main\n\nimport \"fmt\"\n\nfunc main() {\n\thelper()\n}\n\nfunc run() {\n\thelper()\n}\n\nfunc helper() {\n\tfmt.Println(\"hi\")\n}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&file, &mut graph)
+            .unwrap();
+
+        let xref = graph.xref("helper");
+        assert_eq!(xref.definitions.len(), 1);
+        assert_eq!(xref.definitions[0].line, 13);
+
+        let call_sites: Vec<(&str, usize)> = xref
+            .callers
+            .iter()
+            .map(|e| (graph.get_node_by_id(&e.from).unwrap().name.as_str(), e.line))
+            .collect();
+        assert_eq!(call_sites, vec![("main", 6), ("run", 10)]);
+        assert_eq!(xref.callers[0].call_site, "helper()");
+
+        let callees: Vec<&str> = xref.callees.iter().map(|e| e.to.as_str()).collect();
+        assert_eq!(callees, vec!["Println"]);
+
+        // Unknown symbols produce an empty report
+        let missing = graph.xref("nope");
+        assert!(missing.definitions.is_empty() && missing.callers.is_empty());
+    }
+
+    #[test]
+    fn test_resolved_callers_are_not_cross_linked() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let files = [
+            (
+                "billing/billing.go",
+                "package billing\n\nfunc Charge() {\n\thelper()\n}\n\nfunc helper() {}\n",
+            ),
+            (
+                "shipping/shipping.go",
+                "package shipping\n\nfunc Ship() {\n\thelper()\n\thelper()\n}\n\nfunc helper() {}\n",
+            ),
+            (
+                "store/store.go",
+                "package store\n\ntype Cart struct{}\ntype Order struct{}\n\nfunc (c *Cart) Close() {}\nfunc (o *Order) Close() {}\n\nfunc (c *Cart) Checkout() {\n\tc.Close()\n}\n\nfunc Flush() {\n\thelper()\n}\n",
+            ),
+        ];
+        for (path, source) in files {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+
+        let mut graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_directory(dir.path(), &mut graph)
+            .unwrap();
+
+        let helper_in = |graph: &CodeGraph, package: &str| {
+            graph
+                .get_nodes_by_name("helper")
+                .into_iter()
+                .find(|n| n.package == package)
+                .unwrap()
+                .id
+                .clone()
+        };
+        let billing = helper_in(&graph, "billing");
+        let shipping = helper_in(&graph, "shipping");
+
+        // By name, every helper call is linked to both helpers
+        assert_eq!(graph.find_callers_of(&billing).len(), 4);
+        assert_eq!(graph.get_complexity(&shipping).fan_in, 4);
+
+        graph.resolve_edges();
+
+        let caller_names = |id: &str| -> Vec<String> {
+            let mut names: Vec<String> = graph
+                .find_callers_of(id)
+                .iter()
+                .map(|e| graph.get_node_by_id(&e.from).unwrap().name.clone())
+                .collect();
+            names.sort();
+            names
+        };
+        // Flush's helper() matches neither package, so it stays unresolved
+        assert_eq!(caller_names(&billing), vec!["Charge", "Flush"]);
+        assert_eq!(caller_names(&shipping), vec!["Flush", "Ship", "Ship"]);
+        assert_eq!(graph.get_complexity(&billing).fan_in, 2);
+
+        let flush = graph.get_nodes_by_name("Flush")[0].id.clone();
+        let unresolved = graph.get_outgoing_edges(&flush)[0];
+        assert!(unresolved.to_id.is_none());
+        let mut candidates: Vec<&str> = unresolved.metadata["candidates"].split(',').collect();
+        candidates.sort();
+        let mut expected = vec![billing.as_str(), shipping.as_str()];
+        expected.sort();
+        assert_eq!(candidates, expected);
+
+        // The receiver call resolves to the caller's own type, not Order.Close
+        let checkout = graph.get_nodes_by_name("Checkout")[0];
+        assert_eq!(checkout.receiver(), Some("Cart"));
+        let close = graph.get_outgoing_edges(&checkout.id)[0];
+        let target = graph.get_node_by_id(close.to_id.as_ref().unwrap()).unwrap();
+        assert_eq!(target.receiver(), Some("Cart"));
+        assert!(!close.metadata.contains_key("candidates"));
+    }
+
+    #[test]
+    fn test_go_methods_keep_receiver_identity() {
+        use crate::parser::GoParser;
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/go-receivers/transport.go");
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&fixture, &mut graph)
+            .unwrap();
+
+        let handles = graph.get_nodes_by_name("Handle");
+        assert_eq!(handles.len(), 2);
+        assert_ne!(handles[0].id, handles[1].id);
+        let mut receivers: Vec<&str> = handles.iter().filter_map(|n| n.receiver()).collect();
+        receivers.sort();
+        assert_eq!(receivers, vec!["Client", "Server"]);
+        let server_handle = handles
+            .iter()
+            .find(|n| n.receiver() == Some("Server"))
+            .unwrap();
+        assert!(server_handle.id.contains(":Server.Handle:"));
+        assert_eq!(server_handle.display_name(), "Server.Handle");
+        assert_eq!(server_handle.parameters[0].name, "req");
+        let server_handle = server_handle.id.clone();
+
+        // Calls record the receiver type of parameters and the receiver itself
+        let forward = graph.get_nodes_by_name("Forward")[0].id.clone();
+        let receivers: Vec<Option<&str>> = graph
+            .get_outgoing_edges(&forward)
+            .iter()
+            .map(|e| e.metadata.get("receiver").map(String::as_str))
+            .collect();
+        assert_eq!(receivers, vec![Some("Client"), Some("Server")]);
+
+        graph.resolve_edges();
+        let targets: Vec<Option<&str>> = graph
+            .get_outgoing_edges(&forward)
+            .iter()
+            .map(|e| {
+                graph
+                    .get_node_by_id(e.to_id.as_ref().unwrap())
+                    .and_then(|n| n.receiver())
+            })
+            .collect();
+        assert_eq!(targets, vec![Some("Client"), Some("Server")]);
+        assert_eq!(graph.find_callers_of(&server_handle).len(), 1);
+    }
+
+    #[test]
+    fn test_hotspots_exclude_test_callers() {
+        use crate::core::{TagMode, TEST_TAG};
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("billing.go"),
+            "package billing\n\nfunc Charge() {\n\tround()\n}\n\nfunc round() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("billing_test.go"),
+            "package billing\n\nfunc TestRound() {\n\tround()\n}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        let mut parser = GoParser::new().unwrap();
+        for file in ["billing.go", "billing_test.go"] {
+            parser
+                .parse_file(&dir.path().join(file), &mut graph)
+                .unwrap();
+        }
+        assert_eq!(graph.tag_tests(), 1);
+        assert_eq!(graph.tag_tests(), 0);
+        let tagged = graph.get_nodes_by_tags(&[TEST_TAG], TagMode::And);
+        assert_eq!(tagged[0].name, "TestRound");
+
+        let count = |hotspots: Vec<crate::core::HotspotResult>| {
+            hotspots
+                .into_iter()
+                .find(|h| h.name == "round")
+                .map(|h| h.call_count)
+        };
+        assert_eq!(count(graph.find_hotspots(10, false)), Some(2));
+        assert_eq!(count(graph.find_hotspots(10, true)), Some(1));
+    }
+
+    #[test]
+    fn test_go_http_handlers_and_middleware() {
+        use crate::parser::GoParser;
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/go-http/server.go");
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&fixture, &mut graph)
+            .unwrap();
+
+        let node_type = |name: &str| graph.get_nodes_by_name(name)[0].node_type.clone();
+        assert_eq!(node_type("Health"), NodeType::HttpHandler);
+        assert_eq!(node_type("ServeHTTP"), NodeType::HttpHandler);
+        assert_eq!(node_type("Logging"), NodeType::Middleware);
+        assert_eq!(node_type("Routes"), NodeType::Function);
+
+        let handlers = graph.get_nodes_by_type(&NodeType::HttpHandler);
+        assert_eq!(handlers.len(), 2);
+        // Handler methods keep their receiver
+        let serve = graph.get_nodes_by_name("ServeHTTP")[0];
+        assert_eq!(serve.receiver(), Some("API"));
+    }
+
+    #[test]
+    fn test_single_file_index_same_serial_and_parallel() {
+        use crate::parser::{GoParser, ParseOptions};
+
+        let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/go-http");
+        let index = |parallel_threshold| {
+            let options = ParseOptions {
+                parallel_threshold,
+                ..Default::default()
+            };
+            let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+            GoParser::new()
+                .unwrap()
+                .with_options(options)
+                .parse_directory(&dir, &mut graph)
+                .unwrap();
+            graph
+        };
+
+        // Default threshold parses a one-file directory serially; 0 forces
+        // the parallel path
+        assert!(ParseOptions::default().parse_serially(1));
+        assert!(!ParseOptions {
+            parallel_threshold: Some(0),
+            ..Default::default()
+        }
+        .parse_serially(1));
+
+        let serial = index(None);
+        let parallel = index(Some(0));
+        assert!(!serial.nodes.is_empty());
+        assert_eq!(
+            serde_json::to_value(&serial.nodes).unwrap(),
+            serde_json::to_value(&parallel.nodes).unwrap()
+        );
+        assert_eq!(
+            serde_json::to_value(&serial.edges).unwrap(),
+            serde_json::to_value(&parallel.edges).unwrap()
+        );
+        assert_eq!(
+            serial.metadata.stats.files_parsed,
+            parallel.metadata.stats.files_parsed
+        );
+    }
+
+    #[test]
+    fn test_affected_tests_follow_reverse_calls() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("billing.go"),
+            "package billing\n\nfunc Charge() {\n\tround()\n}\n\nfunc round() {}\n\nfunc Refund() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("billing_test.go"),
+            "package billing\n\nfunc TestCharge() {\n\tCharge()\n}\n\nfunc TestRefund() {\n\tRefund()\n}\n\nfunc TestNothing() {}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        let mut parser = GoParser::new().unwrap();
+        for file in ["billing.go", "billing_test.go"] {
+            parser
+                .parse_file(&dir.path().join(file), &mut graph)
+                .unwrap();
+        }
+        graph.tag_tests();
+
+        let names = |tests: Vec<&Node>| tests.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+        // round is reached through Charge only
+        assert_eq!(names(graph.affected_tests(&["round"])), vec!["TestCharge"]);
+        assert_eq!(
+            names(graph.affected_tests(&["round", "Refund"])),
+            vec!["TestCharge", "TestRefund"]
+        );
+        // A changed test selects itself; unknown names select nothing
+        assert_eq!(
+            names(graph.affected_tests(&["TestNothing"])),
+            vec!["TestNothing"]
+        );
+        assert!(graph.affected_tests(&["missing"]).is_empty());
+    }
+
+    #[test]
+    fn test_typescript_enums_type_aliases_and_namespaces() {
+        use crate::parser::{Language, TypeScriptParser};
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/ts-declarations/shapes.ts");
+        let mut graph = CodeGraph::new("test".to_string(), "typescript".to_string());
+        TypeScriptParser::new(Language::TypeScript)
+            .unwrap()
+            .parse_file(&fixture, &mut graph)
+            .unwrap();
+
+        let node_type = |name: &str| graph.get_nodes_by_name(name)[0].node_type.clone();
+        assert_eq!(node_type("Color"), NodeType::Custom("enum".to_string()));
+        assert_eq!(
+            node_type("Point"),
+            NodeType::Custom("type_alias".to_string())
+        );
+        assert_eq!(
+            node_type("Geometry"),
+            NodeType::Custom("namespace".to_string())
+        );
+        // Functions inside a namespace are still extracted
+        assert_eq!(node_type("distance"), NodeType::Function);
+
+        let enums = graph.get_nodes_by_type(&NodeType::Custom("enum".to_string()));
+        assert_eq!(enums.len(), 1);
+        assert_eq!(enums[0].node_type.to_string(), "Enum");
+        assert_eq!(node_type("Point").to_string(), "TypeAlias");
+
+        // JSONL keeps the subtype; the pre-PascalCase spelling still loads
+        let line = |kind: &str| {
+            format!(
+                r#"{{"type":"node","id":"a.ts:Point:1","name":"Point","node_type":"{}","file_path":"a.ts","line":1,"end_line":1,"package":"a","signature":"type Point"}}"#,
+                kind
+            )
+        };
+        for kind in ["TypeAlias", "type_alias"] {
+            let loaded =
+                crate::serializer::jsonl::load_from_jsonl_reader(line(kind).as_bytes()).unwrap();
+            assert_eq!(
+                loaded.nodes[0].node_type,
+                NodeType::Custom("type_alias".to_string())
+            );
+        }
+
+        // Declarations are not part of the call graph
+        for name in ["Color", "Point", "Geometry"] {
+            let id = &graph.get_nodes_by_name(name)[0].id;
+            assert!(graph.get_outgoing_edges(id).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_filter_drops_dangling_edges() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("api.go"),
+            "package api\n\nfunc Handle() {\n\tvalidate()\n\tstore.Save()\n}\n\nfunc validate() {}\n",
+        )
+        .unwrap();
+        std::fs::write(
+            dir.path().join("store.go"),
+            "package store\n\nfunc Save() {}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        let mut parser = GoParser::new().unwrap();
+        for file in ["api.go", "store.go"] {
+            parser
+                .parse_file(&dir.path().join(file), &mut graph)
+                .unwrap();
+        }
+
+        let callees = |g: &CodeGraph| {
+            let mut callees: Vec<String> = g.edges.iter().map(|e| e.to.clone()).collect();
+            callees.sort();
+            callees
+        };
+
+        // Default keeps every edge from a retained caller
+        let kept = graph.filter(Some("api"), None, false, false);
+        assert_eq!(callees(&kept), vec!["Save", "validate"]);
+
+        // Cross-package call to store.Save no longer resolves to a node
+        let pruned = graph.filter(Some("api"), None, false, true);
+        assert_eq!(callees(&pruned), vec!["validate"]);
+        assert_eq!(pruned.metadata.stats.total_edges, 1);
+    }
+
+    #[test]
+    fn test_diff_complexity_increase_gate() {
+        let dir = tempfile::tempdir().unwrap();
+        let index = |source: &str| {
+            let file = dir.path().join("app.go");
+            std::fs::write(&file, source).unwrap();
+            let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+            crate::parser::GoParser::new()
+                .unwrap()
+                .parse_file(&file, &mut graph)
+                .unwrap();
+            graph
+        };
+
+        let old = index("package app\n\nfunc Run() {\n\tstep()\n}\n");
+        let new = index("package app\n\nfunc Run() {\n\tstep()\n\ta()\n\tb()\n\tc()\n}\n");
+        let diff = old.diff(&new);
+
+        // Run's fan-out grew by 3: fails at threshold 2, passes at 5
+        let increases = diff.complexity_increases(2);
+        assert_eq!(increases.len(), 1);
+        assert_eq!(increases[0].node_name, "Run");
+        assert_eq!(increases[0].change, 3);
+        assert!(diff.complexity_increases(5).is_empty());
+
+        // Decreases never trip the gate
+        assert!(new.diff(&old).complexity_increases(0).is_empty());
+
+        // Fan-in counts callers of the node, not edges keyed by its ID
+        let old = index("package app\n\nfunc step() {}\n\nfunc Run() {\n\tstep()\n}\n");
+        let new = index(
+            "package app\n\nfunc step() {}\n\nfunc Run() {\n\tstep()\n}\n\nfunc Again() {\n\tstep()\n}\n",
+        );
+        let diff = old.diff(&new);
+        let step = diff
+            .complexity_changes
+            .iter()
+            .find(|c| c.node_name == "step")
+            .unwrap();
+        assert_eq!((step.old_fan_in, step.new_fan_in), (1, 2));
+        assert_eq!(step.change, 1);
+    }
+
+    #[test]
+    fn test_query_names_by_regex() {
+        use regex::Regex;
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for (i, name) in [
+            "handleLogin",
+            "handleLogout",
+            "rehandle",
+            "getUser",
+            "setUser",
+            "getUserName",
+        ]
+        .iter()
+        .enumerate()
+        {
+            graph.add_node(Node::new(
+                format!("api.go:{}:{}", name, i),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("api.go"),
+                i,
+                i,
+                "api".to_string(),
+                format!("func {}()", name),
+            ));
+        }
+
+        let names = |pattern: &str| {
+            graph
+                .get_nodes_by_name_regex(&Regex::new(pattern).unwrap())
+                .iter()
+                .map(|n| n.name.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(names("^handle"), vec!["handleLogin", "handleLogout"]);
+        assert_eq!(
+            names("handle"),
+            vec!["handleLogin", "handleLogout", "rehandle"]
+        );
+        assert_eq!(names("^(get|set)User$"), vec!["getUser", "setUser"]);
+        assert_eq!(names("Login|Name"), vec!["handleLogin", "getUserName"]);
+    }
+
+    #[test]
+    fn test_shell_line_parser() {
+        use crate::commands::{parse_line, NodeQuery, ShellCommand};
+
+        assert_eq!(
+            parse_line("trace Foo 3").unwrap(),
+            Some(ShellCommand::Trace {
+                from: "Foo".to_string(),
+                depth: 3
+            })
+        );
+        assert_eq!(
+            parse_line("  trace Foo").unwrap(),
+            Some(ShellCommand::Trace {
+                from: "Foo".to_string(),
+                depth: 1
+            })
+        );
+        assert_eq!(
+            parse_line("path main saveUser").unwrap(),
+            Some(ShellCommand::Path {
+                from: "main".to_string(),
+                to: "saveUser".to_string(),
+                max_depth: 10
+            })
+        );
+        assert_eq!(
+            parse_line("query name=Foo type=method limit=5").unwrap(),
+            Some(ShellCommand::Query(NodeQuery {
+                name: Some("Foo".to_string()),
+                node_type: Some(NodeType::Method),
+                limit: Some(5),
+                ..Default::default()
+            }))
+        );
+        assert_eq!(parse_line("").unwrap(), None);
+        assert_eq!(parse_line("# comment").unwrap(), None);
+        assert!(parse_line("trace Foo deep").is_err());
+        assert!(parse_line("callers").is_err());
+        assert!(parse_line("query kind=function").is_err());
+        assert!(parse_line("frobnicate").is_err());
+    }
+
+    #[test]
+    fn test_merge_unifies_identical_code_with_content_ids() {
+        use crate::parser::GoParser;
+
+        let fixture =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/go-http");
+        let checkouts: Vec<_> = (0..2).map(|_| tempfile::tempdir().unwrap()).collect();
+        let index = |content_ids: bool| {
+            let graphs: Vec<CodeGraph> = checkouts
+                .iter()
+                .map(|dir| {
+                    for entry in std::fs::read_dir(&fixture).unwrap() {
+                        let entry = entry.unwrap();
+                        std::fs::copy(entry.path(), dir.path().join(entry.file_name())).unwrap();
+                    }
+                    let mut graph =
+                        CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+                    GoParser::new()
+                        .unwrap()
+                        .parse_directory(dir.path(), &mut graph)
+                        .unwrap();
+                    graph.resolve_edges();
+                    if content_ids {
+                        assert_eq!(graph.assign_content_ids(), 0);
+                    }
+                    graph
+                })
+                .collect();
+            graphs
+        };
+
+        // Path-based IDs differ between checkouts, so merging duplicates
+        let calls = |graph: &CodeGraph| {
+            graph
+                .edges
+                .iter()
+                .filter(|e| e.edge_type == EdgeType::Calls)
+                .count()
+        };
+        let [mut a, b]: [CodeGraph; 2] = index(false).try_into().unwrap();
+        let single = (a.nodes.len(), calls(&a));
+        a.merge(b);
+        assert_eq!(a.nodes.len(), 2 * single.0);
+
+        // Import edges start at their (path-based) file and still add up
+        let [mut a, b]: [CodeGraph; 2] = index(true).try_into().unwrap();
+        assert!(a.diff(&b).added_nodes.is_empty());
+        assert!(a.nodes.iter().all(|n| !n.id.contains(':')));
+        a.merge_dedup(b);
+        assert_eq!((a.nodes.len(), calls(&a)), single);
+
+        // Edges follow the new IDs
+        let routes = a.get_nodes_by_name("Routes")[0];
+        let logging = a.get_nodes_by_name("Logging")[0];
+        let call = a
+            .get_outgoing_edges(&routes.id)
+            .into_iter()
+            .find(|e| e.to == "Logging")
+            .unwrap();
+        assert_eq!(call.to_id.as_deref(), Some(logging.id.as_str()));
+
+        // The hash is specified, so IDs match across builds and platforms
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        graph.add_node(Node::new(
+            "main.go:Run:1".to_string(),
+            "Run".to_string(),
+            NodeType::Function,
+            PathBuf::from("main.go"),
+            1,
+            3,
+            "main".to_string(),
+            "func Run()".to_string(),
+        ));
+        graph.assign_content_ids();
+        assert_eq!(graph.nodes[0].id, "9af5d9893de2549c");
+    }
+
+    #[test]
+    fn test_lossy_encoding_indexes_non_utf8_files() {
+        use crate::parser::{GoParser, ParseOptions};
+
+        let dir = tempfile::tempdir().unwrap();
+        // Latin-1 "é" (0xE9) in a comment is not valid UTF-8
+        let mut source = b"package legacy\n\n// caf".to_vec();
+        source.extend_from_slice(&[0xE9, b'\n']);
+        source.extend_from_slice(b"func Greet() {\n\tprintln(\"hi\")\n}\n");
+        std::fs::write(dir.path().join("legacy.go"), source).unwrap();
+
+        let index = |lossy_encoding| {
+            let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+            GoParser::new()
+                .unwrap()
+                .with_options(ParseOptions {
+                    lossy_encoding,
+                    ..Default::default()
+                })
+                .parse_directory(dir.path(), &mut graph)
+                .unwrap();
+            graph
+        };
+
+        assert!(index(false).get_nodes_by_name("Greet").is_empty());
+        let graph = index(true);
+        let greet = graph.get_nodes_by_name("Greet");
+        assert_eq!(greet.len(), 1);
+        assert_eq!(greet[0].package, "legacy");
+        assert_eq!(greet[0].line, 4);
+    }
+
+    #[test]
+    fn test_betweenness_centrality_peaks_at_diamond_center() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        let names = [
+            "top", "left", "right", "center", "out1", "out2", "bottom", "x", "y", "z",
+        ];
+        for name in names {
+            graph.add_node(Node::new(
+                format!("main.go:{}:1", name),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("main.go"),
+                1,
+                1,
+                "main".to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        // Two diamonds joined at `center`, plus a separate x -> y -> z chain
+        for (from, to) in [
+            ("top", "left"),
+            ("top", "right"),
+            ("left", "center"),
+            ("right", "center"),
+            ("center", "out1"),
+            ("center", "out2"),
+            ("out1", "bottom"),
+            ("out2", "bottom"),
+            ("x", "y"),
+            ("y", "z"),
+        ] {
+            graph.add_edge(Edge::new(
+                format!("main.go:{}:1", from),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("main.go"),
+                1,
+            ));
+        }
+        graph.ensure_indices();
+
+        let scores = graph.betweenness_centrality();
+        let score = |name: &str| {
+            scores
+                .iter()
+                .find(|(id, _)| *id == format!("main.go:{}:1", name))
+                .unwrap()
+                .1
+        };
+        assert_eq!(scores[0].0, "main.go:center:1");
+        // Every path from {top, left, right} to {out1, out2, bottom}
+        assert_eq!(score("center"), 9.0);
+        assert_eq!(score("left"), 2.0);
+        assert_eq!(score("y"), 1.0);
+        assert_eq!(score("top"), 0.0);
+
+        // Sampling every node is exact
+        assert_eq!(
+            graph.betweenness_centrality_sampled(Some(names.len())),
+            scores
+        );
+        assert!(graph.betweenness_centrality_sampled(Some(3))[0].1 > 0.0);
+    }
+
+    #[test]
+    fn test_parse_warnings_are_capped() {
+        use crate::parser::{GoParser, ParseOptions, WarningLog};
+
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..8 {
+            std::fs::write(
+                dir.path().join(format!("broken{}.go", i)),
+                b"package broken\n// \xff\n",
+            )
+            .unwrap();
+        }
+
+        let warnings = WarningLog::new(3);
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .with_options(ParseOptions {
+                // Parallel chunks share the one budget
+                parallel_threshold: Some(0),
+                warnings: warnings.clone(),
+                ..Default::default()
+            })
+            .parse_directory(dir.path(), &mut graph)
+            .unwrap();
+
+        assert_eq!(warnings.printed(), 3);
+        assert_eq!(warnings.suppressed(), 5);
+        assert_eq!(
+            warnings.summary().as_deref(),
+            Some("(5 more warnings suppressed)")
+        );
+        assert_eq!(WarningLog::new(3).summary(), None);
+    }
+
+    #[test]
+    fn test_dead_code_flags_orphans_not_main() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (line, name) in ["main", "run", "orphan", "helper", "recurse", "Serve"]
+            .iter()
+            .enumerate()
+        {
+            graph.add_node(Node::new(
+                format!("main.go:{}:{}", name, line),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("main.go"),
+                line,
+                line,
+                "main".to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        for (from, to) in [
+            ("main.go:main:0", "run"),
+            // orphan -> helper is dead code; recurse only calls itself
+            ("main.go:orphan:2", "helper"),
+            ("main.go:recurse:4", "recurse"),
+            ("main.go:Serve:5", "Println"),
+        ] {
+            graph.add_edge(Edge::new(
+                from.to_string(),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("main.go"),
+                1,
+            ));
+        }
+        graph.ensure_indices();
+
+        let reachable = graph.reachable_from(&["main.go:main:0".to_string()]);
+        assert_eq!(
+            reachable,
+            std::collections::HashSet::from([
+                "main.go:main:0".to_string(),
+                "main.go:run:1".to_string()
+            ])
+        );
+
+        let dead = |entrypoints: &[String]| {
+            graph
+                .find_dead_code(entrypoints)
+                .iter()
+                .map(|n| n.name.clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(dead(&[]), vec!["orphan", "helper", "recurse", "Serve"]);
+        assert_eq!(
+            dead(&["Serve".to_string(), "orphan".to_string()]),
+            vec!["recurse"]
+        );
+    }
+
+    #[test]
+    fn test_diff_detects_renamed_function() {
+        use crate::core::DiffReport;
+        use crate::parser::GoParser;
+
+        let index = |source: &str| {
+            let dir = tempfile::tempdir().unwrap();
+            let file = dir.path().join("users.go");
+            std::fs::write(&file, source).unwrap();
+            let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+            GoParser::new()
+                .unwrap()
+                .parse_file(&file, &mut graph)
+                .unwrap();
+            graph.relativize_paths(dir.path());
+            graph
+        };
+        let body = "(id int) error {\n\tuser := load(id)\n\treturn save(user)\n}\n";
+        let old = index(&format!(
+            "package users\n\nfunc UpdateUser{}\nfunc Ping() int {{ return 1 }}\n\nfunc Fact(n int) int {{ return n * Fact(n-1) }}\n\nfunc (a *Adder) Add(x int) int {{ return x + a.n }}\n\nfunc Inc(x int) int {{ return x + 1 }}\n",
+            body
+        ));
+        let new = index(&format!(
+            "package users\n\nfunc SyncUser{}\nfunc Pong() int {{ return 1 }}\n\nfunc Factorial(n int) int {{ return n * Factorial(n-1) }}\n\nfunc (a *Adder) Plus(x int) int {{ return x + a.n }}\n\nfunc Dec(x int) int {{ return x - 1 }}\n",
+            body
+        ));
+
+        let diff = old.diff(&new);
+        let renames: Vec<(&str, &str)> = diff
+            .renamed_nodes
+            .iter()
+            .map(|r| (r.old_name.as_str(), r.new_name.as_str()))
+            .collect();
+        // Leaf functions, recursive functions (whose body names themselves)
+        // and methods (whose signature names the receiver type) all match
+        assert_eq!(
+            renames,
+            vec![
+                ("Add", "Plus"),
+                ("Fact", "Factorial"),
+                ("Ping", "Pong"),
+                ("UpdateUser", "SyncUser")
+            ]
+        );
+        // Same shape and calls but a different body is not a rename
+        assert_eq!(diff.removed_nodes, vec!["users.go:Inc:14".to_string()]);
+        assert_eq!(diff.added_nodes, vec!["users.go:Dec:14".to_string()]);
+
+        let report = DiffReport::new(&old, &new, &diff, None);
+        assert_eq!(report.summary.renamed, 4);
+        let update = report
+            .renamed
+            .iter()
+            .find(|r| r.old.id == "users.go:UpdateUser:3")
+            .unwrap();
+        assert_eq!(update.new.id, "users.go:SyncUser:3");
+    }
+
+    #[test]
+    fn test_reindex_file_drops_stale_call_targets() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let a = dir.path().join("a.go");
+        let b = dir.path().join("b.go");
+        std::fs::write(&a, "package main\n\nfunc A() {\n\tB()\n}\n").unwrap();
+        std::fs::write(&b, "package main\n\nfunc B() {}\n").unwrap();
+
+        let mut graph = CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+        let mut parser = GoParser::new().unwrap();
+        parser.parse_directory(dir.path(), &mut graph).unwrap();
+        graph.resolve_edges();
+        let old_b = graph.get_nodes_by_name("B")[0].id.clone();
+        let call_target = |graph: &CodeGraph| graph.find_callers("B")[0].to_id.clone();
+        assert_eq!(call_target(&graph), Some(old_b.clone()));
+
+        // Moving B re-resolves A's call to the new node
+        std::fs::write(&b, "package main\n\n// B moved down\n\nfunc B() {}\n").unwrap();
+        graph
+            .reindex_file(&b, |graph| parser.parse_file(&b, graph))
+            .unwrap();
+        let moved_b = graph.get_nodes_by_name("B")[0].id.clone();
+        assert_ne!(moved_b, old_b);
+        assert_eq!(call_target(&graph), Some(moved_b.clone()));
+
+        // Renaming B leaves A's call (still in a.go's source) unresolved
+        // instead of pointing at the removed node
+        std::fs::write(&b, "package main\n\nfunc C() {}\n").unwrap();
+        graph
+            .reindex_file(&b, |graph| parser.parse_file(&b, graph))
+            .unwrap();
+        assert!(graph.get_nodes_by_name("B").is_empty());
+        assert!(graph.find_callers_of(&moved_b).is_empty());
+        assert_eq!(call_target(&graph), None);
+        let c = graph.get_nodes_by_name("C")[0].id.clone();
+        assert!(graph.find_callers_of(&c).is_empty());
+
+        // Removal alone also clears targets into the file
+        std::fs::write(&b, "package main\n\nfunc B() {}\n").unwrap();
+        graph
+            .reindex_file(&b, |graph| parser.parse_file(&b, graph))
+            .unwrap();
+        assert!(call_target(&graph).is_some());
+        graph.remove_nodes_from_file(&b.to_string_lossy());
+        assert_eq!(call_target(&graph), None);
+    }
+
+    #[test]
+    fn test_trace_max_branching_bounds_each_node() {
+        // hub -> c0..c5, each ci -> ci_0..ci_5
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        let mut function = |name: String| {
+            graph.add_node(Node::new(
+                format!("main.go:{}:1", name),
+                name.clone(),
+                NodeType::Function,
+                PathBuf::from("main.go"),
+                1,
+                1,
+                "main".to_string(),
+                format!("func {}()", name),
+            ));
+        };
+        function("hub".to_string());
+        for i in 0..6 {
+            function(format!("c{}", i));
+            for j in 0..6 {
+                function(format!("c{}_{}", i, j));
+            }
+        }
+        // Insert callees in reverse so ordering by name is observable
+        let mut call = |from: String, to: String| {
+            graph.add_edge(Edge::new(
+                format!("main.go:{}:1", from),
+                to.clone(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("main.go"),
+                1,
+            ));
+        };
+        for i in (0..6).rev() {
+            call("hub".to_string(), format!("c{}", i));
+            for j in (0..6).rev() {
+                call(format!("c{}", i), format!("c{}_{}", i, j));
+            }
+        }
+        graph.ensure_indices();
+
+        assert_eq!(graph.trace_dependencies("main.go:hub:1", 2).len(), 6 + 36);
+
+        let traces = graph.trace_dependencies_bounded("main.go:hub:1", 2, Some(2));
+        let names: Vec<&str> = traces.iter().map(|t| t.to_name.as_str()).collect();
+        assert_eq!(names, vec!["c0", "c0_0", "c0_1", "c1", "c1_0", "c1_1"]);
+        let mut children: std::collections::HashMap<&str, usize> = Default::default();
+        for trace in &traces {
+            *children.entry(trace.from_id.as_str()).or_default() += 1;
+        }
+        assert!(children.values().all(|&count| count <= 2));
+        // c0 and c1 each lost 4 callees; the leaves at max depth weren't expanded
+        for trace in &traces {
+            let expected = (trace.depth == 0).then_some(4);
+            assert_eq!(trace.omitted_callees, expected, "{}", trace.to_name);
+        }
+    }
+
+    #[test]
+    fn test_query_leaves() {
+        let mut graph = create_test_graph_with_calls();
+        // A call that resolves outside the graph doesn't stop funcD being a leaf
+        graph.add_edge(Edge::new(
+            "test:d:30".to_string(),
+            "fmt.Println".to_string(),
+            EdgeType::Calls,
+            "fmt.Println()".to_string(),
+            PathBuf::from("test.go"),
+            32,
+        ));
+
+        let leaves = graph.filter_leaves(graph.nodes.iter().collect());
+        let names: Vec<&str> = leaves.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["funcD"]);
+        assert!(!names.contains(&"funcA"));
+    }
+
+    #[test]
+    fn test_shard_by_package_and_load_one_shard() {
+        use crate::parser::GoParser;
+        use crate::serializer::shards::{self, ShardManifest, MANIFEST_FILE};
+
+        let repo = tempfile::tempdir().unwrap();
+        for (dir, source) in [
+            ("api", "package api\n\nfunc Serve() {\n\tLookup()\n}\n"),
+            ("store", "package store\n\nfunc Lookup() {}\n"),
+        ] {
+            std::fs::create_dir(repo.path().join(dir)).unwrap();
+            std::fs::write(repo.path().join(dir).join("main.go"), source).unwrap();
+        }
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_directory(repo.path(), &mut graph)
+            .unwrap();
+        graph.resolve_edges();
+
+        let out = tempfile::tempdir().unwrap();
+        let manifest = shards::save_shards(&graph, out.path()).unwrap();
+        let mut files: Vec<_> = std::fs::read_dir(out.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        assert_eq!(files, vec!["api.bin", MANIFEST_FILE, "store.bin"]);
+        assert_eq!(manifest.shards.len(), 2);
+
+        // The store shard alone answers "who calls Lookup?": the cross-package
+        // edge is recorded on both sides
+        let manifest = ShardManifest::load(out.path()).unwrap();
+        let store = manifest.shard_for_package("store").unwrap();
+        assert_eq!(manifest.shards_with_name("Lookup")[0].key, "store");
+        let loaded = shards::load_shards(out.path(), &manifest, |s| s.key == store.key).unwrap();
+        assert_eq!(loaded.nodes.len(), 1);
+        assert_eq!(loaded.get_nodes_by_name("Lookup").len(), 1);
+        assert!(loaded.get_nodes_by_name("Serve").is_empty());
+        assert_eq!(loaded.find_callers("Lookup").len(), 1);
+
+        // Loading both shards keeps the shared edge once
+        let all = shards::load_shards(out.path(), &manifest, |_| true).unwrap();
+        assert_eq!(all.nodes.len(), 2);
+        assert_eq!(all.find_callers("Lookup").len(), 1);
+    }
+
+    #[test]
+    fn test_package_coupling_counts_cross_package_edges() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for (id, name, package) in [
+            ("api.go:Serve:1", "Serve", "api"),
+            ("api.go:parse:9", "parse", "api"),
+            ("store.go:Lookup:1", "Lookup", "store"),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from(format!("{}.go", package)),
+                1,
+                5,
+                package.to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        // One call crosses into store; the api-internal and external calls don't count
+        for callee in ["Lookup", "parse", "fmt.Println"] {
+            graph.add_edge(Edge::new(
+                "api.go:Serve:1".to_string(),
+                callee.to_string(),
+                EdgeType::Calls,
+                format!("{}()", callee),
+                PathBuf::from("api.go"),
+                2,
+            ));
+        }
+        // `Close` exists in both packages, but this call resolves to api's own
+        for package in ["api", "store"] {
+            graph.add_node(Node::new(
+                format!("{}.go:Close:20", package),
+                "Close".to_string(),
+                NodeType::Method,
+                PathBuf::from(format!("{}.go", package)),
+                20,
+                22,
+                package.to_string(),
+                "func Close()".to_string(),
+            ));
+        }
+        let mut close = Edge::new(
+            "api.go:Serve:1".to_string(),
+            "Close".to_string(),
+            EdgeType::Calls,
+            "s.Close()".to_string(),
+            PathBuf::from("api.go"),
+            3,
+        );
+        close.to_id = Some("api.go:Close:20".to_string());
+        graph.add_edge(close);
+        // Type relationships aren't coupling through calls
+        graph.add_edge(Edge::new(
+            "store.go:Lookup:1".to_string(),
+            "Serve".to_string(),
+            EdgeType::Implements,
+            "Serve".to_string(),
+            PathBuf::from("store.go"),
+            1,
+        ));
+
+        let metrics = graph.package_coupling();
+        assert_eq!(metrics.len(), 2);
+        let api = metrics.iter().find(|m| m.package == "api").unwrap();
+        assert_eq!((api.afferent, api.efferent), (0, 1));
+        assert_eq!(api.instability, 1.0);
+        let store = metrics.iter().find(|m| m.package == "store").unwrap();
+        assert_eq!((store.afferent, store.efferent), (1, 0));
+        assert_eq!(store.instability, 0.0);
+        // Most unstable first
+        assert_eq!(metrics[0].package, "api");
+    }
+
+    fn package_graph(calls: &[(&str, &str)]) -> CodeGraph {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        let mut packages: Vec<&str> = calls.iter().flat_map(|&(a, b)| [a, b]).collect();
+        packages.sort();
+        packages.dedup();
+        for package in packages {
+            graph.add_node(Node::new(
+                format!("{}.go:{}Func:1", package, package),
+                format!("{}Func", package),
+                NodeType::Function,
+                PathBuf::from(format!("{}.go", package)),
+                1,
+                5,
+                package.to_string(),
+                format!("func {}Func()", package),
+            ));
+        }
+        for &(from, to) in calls {
+            graph.add_edge(Edge::new(
+                format!("{}.go:{}Func:1", from, from),
+                format!("{}Func", to),
+                EdgeType::Calls,
+                format!("{}Func()", to),
+                PathBuf::from(format!("{}.go", from)),
+                2,
+            ));
+        }
+        graph
+    }
+
+    #[test]
+    fn test_topological_order_of_package_dag() {
+        // api -> service -> store, api -> store, cli -> service
+        let graph = package_graph(&[
+            ("api", "service"),
+            ("service", "store"),
+            ("api", "store"),
+            ("cli", "service"),
+        ]);
+
+        let order = graph.topological_order().unwrap();
+        assert_eq!(order, vec!["store", "service", "api", "cli"]);
+        let position = |p: &str| order.iter().position(|o| o == p).unwrap();
+        for (dependent, dependency) in
+            [("api", "service"), ("service", "store"), ("cli", "service")]
+        {
+            assert!(position(dependency) < position(dependent));
+        }
+    }
+
+    #[test]
+    fn test_topological_order_reports_cycles() {
+        // a -> b -> c -> a, with d depending on the cycle
+        let graph = package_graph(&[("a", "b"), ("b", "c"), ("c", "a"), ("d", "a")]);
+
+        let cycles = graph.topological_order().unwrap_err();
+        assert_eq!(cycles, vec![vec!["a", "b", "c"]]);
+        assert_eq!(cycles, graph.find_package_cycles());
+    }
+
+    #[test]
+    fn test_query_isolated() {
+        let mut graph = create_test_graph_with_calls();
+        graph.add_node(Node::new(
+            "test:orphan:40".to_string(),
+            "orphan".to_string(),
+            NodeType::Function,
+            PathBuf::from("test.go"),
+            40,
+            42,
+            "main".to_string(),
+            "func orphan() {}".to_string(),
+        ));
+
+        // funcA has no callers and funcD no callees, but neither is isolated
+        let isolated = graph.filter_isolated(graph.nodes.iter().collect());
+        let names: Vec<&str> = isolated.iter().map(|n| n.name.as_str()).collect();
+        assert_eq!(names, vec!["orphan"]);
+    }
+
+    #[test]
+    fn test_go_structs_implement_interfaces_structurally() {
+        use crate::parser::GoParser;
+
+        let dir =
+            std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/go-interfaces");
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_directory(&dir, &mut graph)
+            .unwrap();
+
+        let shape = graph.get_nodes_by_name("Shape");
+        assert_eq!(shape.len(), 1);
+        assert_eq!(shape[0].node_type, NodeType::Class);
+        assert!(shape[0].is_abstract_type());
+        assert!(!graph.get_nodes_by_name("Circle")[0].is_abstract_type());
+
+        let implemented = |type_name: &str| -> Vec<String> {
+            let id = &graph.get_nodes_by_name(type_name)[0].id;
+            let mut names: Vec<String> = graph
+                .get_outgoing_edges(id)
+                .into_iter()
+                .filter(|e| e.edge_type == EdgeType::Implements)
+                .map(|e| e.to.clone())
+                .collect();
+            names.sort();
+            names
+        };
+        // Circle has Area, Scale (pointer receiver, differently spelled
+        // parameters) and Name, so it satisfies Shape and the embedding Named
+        assert_eq!(implemented("Circle"), vec!["Named", "Shape"]);
+        assert!(implemented("Square").is_empty());
+
+        let implementers: Vec<&str> = graph
+            .find_implementers("shapes.Shape")
+            .iter()
+            .map(|(node, _)| node.name.as_str())
+            .collect();
+        assert_eq!(implementers, vec!["Circle"]);
+    }
+
+    #[test]
+    fn test_humanize_duration_and_bytes() {
+        use crate::benchmark::{humanize_bytes, humanize_duration};
+        use std::time::Duration;
+
+        assert_eq!(humanize_duration(Duration::from_micros(850)), "850µs");
+        assert_eq!(humanize_duration(Duration::from_millis(450)), "450ms");
+        assert_eq!(humanize_duration(Duration::from_millis(1_234)), "1.2s");
+        assert_eq!(humanize_duration(Duration::from_secs(185)), "3m 05s");
+
+        assert_eq!(humanize_bytes(0), "0 B");
+        assert_eq!(humanize_bytes(512), "512 B");
+        assert_eq!(humanize_bytes(780 * 1024 + 300), "780 KB");
+        assert_eq!(humanize_bytes(3_565_158), "3.4 MB");
+        assert_eq!(humanize_bytes(5 * 1024 * 1024 * 1024 / 4), "1.2 GB");
+    }
+
+    #[test]
+    fn test_query_sort_topo() {
+        let mut graph = create_test_graph_with_calls();
+        let chain: Vec<&str> = graph
+            .sort_topologically(graph.nodes.iter().collect())
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(chain, vec!["funcD", "funcC", "funcB", "funcA"]);
+
+        // D -> A closes a cycle; its members fall back to name order
+        graph.add_edge(Edge::new(
+            "test:d:30".to_string(),
+            "funcA".to_string(),
+            EdgeType::Calls,
+            "funcA()".to_string(),
+            PathBuf::from("test.go"),
+            32,
+        ));
+        let cyclic: Vec<&str> = graph
+            .sort_topologically(graph.nodes.iter().collect())
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(cyclic, vec!["funcA", "funcB", "funcC", "funcD"]);
+
+        // a -> b, b <-> c: the cycle goes first, though a sorts before it
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for (name, line) in [("a", 1), ("b", 10), ("c", 20)] {
+            graph.add_node(Node::new(
+                format!("test:{}:{}", name, line),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("test.go"),
+                line,
+                line + 5,
+                "main".to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        for (from, to) in [("test:a:1", "b"), ("test:b:10", "c"), ("test:c:20", "b")] {
+            graph.add_edge(Edge::new(
+                from.to_string(),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("test.go"),
+                2,
+            ));
+        }
+        let sorted: Vec<&str> = graph
+            .sort_topologically(graph.nodes.iter().collect())
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(sorted, vec!["b", "c", "a"]);
+    }
+
+    #[test]
+    fn test_python_decorators_tag_and_classify_routes() {
+        use crate::parser::PythonParser;
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/python-decorators/app.py");
+        let mut graph = CodeGraph::new("test".to_string(), "python".to_string());
+        PythonParser::new()
+            .unwrap()
+            .parse_file(&fixture, &mut graph)
+            .unwrap();
+
+        let node = |name: &str| graph.get_nodes_by_name(name)[0];
+        assert_eq!(node("read_x").node_type, NodeType::HttpHandler);
+        assert_eq!(node("read_x").tags, vec!["get"]);
+        assert_eq!(node("create_item").node_type, NodeType::HttpHandler);
+        assert_eq!(node("create_item").tags, vec!["route"]);
+
+        // Non-route decorators only tag
+        assert_eq!(node("client").node_type, NodeType::Function);
+        assert_eq!(node("client").tags, vec!["fixture"]);
+        assert_eq!(node("default").node_type, NodeType::Method);
+        assert_eq!(node("default").tags, vec!["staticmethod"]);
+        assert!(node("load_x").tags.is_empty());
+
+        let tagged: Vec<&str> = graph
+            .get_nodes_by_tags(&["get"], crate::core::TagMode::And)
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(tagged, vec!["read_x"]);
+    }
+
+    #[test]
+    fn test_calls_marked_conditional_inside_branches() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("run.go");
+        std::fs::write(
+            &path,
+            "package main\n\nfunc Run(ok bool) {\n\tsetup()\n\tif check() {\n\t\tnotify()\n\t}\n\tok = ok && verify()\n\tif err := open(); err != nil {\n\t\tfail()\n\t}\n\tfor _, item := range items() {\n\t\tuse(item)\n\t}\n\tfor i := start(); i < limit(); i = next(i) {\n\t}\n}\n",
+        )
+        .unwrap();
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&path, &mut graph)
+            .unwrap();
+
+        let conditional = |callee: &str| {
+            graph
+                .edges
+                .iter()
+                .find(|e| e.to == callee)
+                .and_then(|e| e.is_conditional())
+        };
+        assert_eq!(conditional("setup"), Some(false));
+        assert_eq!(conditional("notify"), Some(true));
+        // An if condition always runs; the right side of && may not
+        assert_eq!(conditional("check"), Some(false));
+        assert_eq!(conditional("verify"), Some(true));
+        // Initializers and range expressions run once the statement is reached
+        assert_eq!(conditional("open"), Some(false));
+        assert_eq!(conditional("fail"), Some(true));
+        assert_eq!(conditional("items"), Some(false));
+        assert_eq!(conditional("use"), Some(true));
+        assert_eq!(conditional("start"), Some(false));
+        assert_eq!(conditional("limit"), Some(false));
+        assert_eq!(conditional("next"), Some(true));
+
+        let path = dir.path().join("run.py");
+        std::fs::write(&path, "def run():\n    for x in load():\n        save(x)\n").unwrap();
+        let mut graph = CodeGraph::new("test".to_string(), "python".to_string());
+        crate::parser::PythonParser::new()
+            .unwrap()
+            .parse_file(&path, &mut graph)
+            .unwrap();
+        let conditional = |callee: &str| {
+            graph
+                .edges
+                .iter()
+                .find(|e| e.to == callee)
+                .and_then(|e| e.is_conditional())
+        };
+        assert_eq!(conditional("load"), Some(false));
+        assert_eq!(conditional("save"), Some(true));
+    }
+
+    #[test]
+    fn test_query_tag_route_after_index_roundtrip() {
+        use crate::core::TagMode;
+        use crate::parser::PythonParser;
+        use crate::serializer::fast_compressed;
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/python-decorators");
+        let mut graph = CodeGraph::new("test".to_string(), "python".to_string());
+        PythonParser::new()
+            .unwrap()
+            .parse_directory(&fixture, &mut graph)
+            .unwrap();
+
+        // `query --tag route` against the saved index
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let path = file.path().to_string_lossy().to_string();
+        fast_compressed::save_to_file(&graph, &path).unwrap();
+        let loaded = fast_compressed::load_from_file(&path).unwrap();
+
+        let routes: Vec<&str> = loaded
+            .get_nodes_by_tags(&["route"], TagMode::And)
+            .iter()
+            .map(|n| n.name.as_str())
+            .collect();
+        assert_eq!(routes, vec!["create_item"]);
+    }
+
+    #[test]
+    fn test_trace_filter_by_package_keeps_paths_to_matches() {
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        for (name, package) in [
+            ("main", "main"),
+            ("Handle", "api"),
+            ("Charge", "billing"),
+            ("Log", "logging"),
+            ("Render", "api"),
+        ] {
+            graph.add_node(Node::new(
+                format!("{}.go:{}:1", package, name),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from(format!("{}.go", package)),
+                1,
+                5,
+                package.to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        for (from, to) in [
+            ("main.go:main:1", "Handle"),
+            ("main.go:main:1", "Log"),
+            ("api.go:Handle:1", "Render"),
+            ("api.go:Handle:1", "Charge"),
+        ] {
+            graph.add_edge(Edge::new(
+                from.to_string(),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("main.go"),
+                2,
+            ));
+        }
+
+        let traces = graph.trace_dependencies("main.go:main:1", 3);
+        assert_eq!(traces.len(), 4);
+
+        let names = |traces: &[crate::core::TraceResult]| -> Vec<String> {
+            traces.iter().map(|t| t.to_name.clone()).collect()
+        };
+        // Handle stays as the way to Charge; Render and Log are unrelated
+        let billing = graph.filter_trace(traces.clone(), "package:billing");
+        assert_eq!(names(&billing), vec!["Handle", "Charge"]);
+        assert_eq!(billing[1].depth, 1);
+
+        let render = graph.filter_trace(traces.clone(), "Rend");
+        assert_eq!(names(&render), vec!["Handle", "Render"]);
+        assert!(graph.filter_trace(traces, "package:none").is_empty());
+    }
+
+    #[test]
+    fn test_index_exclude_globs_and_include_tests() {
+        use crate::parser::{GoParser, ParseOptions};
+
+        let repo = tempfile::tempdir().unwrap();
+        for (file, source) in [
+            ("main.go", "package main\n\nfunc Run() {}\n"),
+            ("main_test.go", "package main\n\nfunc TestRun() {}\n"),
+            ("vendor/lib/lib.go", "package lib\n\nfunc Vendored() {}\n"),
+            ("api/api.pb.go", "package api\n\nfunc Generated() {}\n"),
+        ] {
+            let path = repo.path().join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, source).unwrap();
+        }
+        let index = |options: ParseOptions| {
+            let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+            GoParser::new()
+                .unwrap()
+                .with_options(options)
+                .parse_directory(repo.path(), &mut graph)
+                .unwrap();
+            let mut names: Vec<String> = graph.nodes.iter().map(|n| n.name.clone()).collect();
+            names.sort();
+            names
+        };
+
+        assert_eq!(
+            index(ParseOptions::default()),
+            vec!["Generated", "Run", "Vendored"]
+        );
+        assert_eq!(
+            index(ParseOptions {
+                exclude_globs: vec!["vendor".to_string(), "*.pb.go".to_string()],
+                ..Default::default()
+            }),
+            vec!["Run"]
+        );
+        assert_eq!(
+            index(ParseOptions {
+                include_tests: true,
+                ..Default::default()
+            }),
+            vec!["Generated", "Run", "TestRun", "Vendored"]
+        );
+    }
+
+    #[test]
+    fn test_graph_summary_counts() {
+        let mut graph = create_test_graph_with_calls();
+        let mut handler = Node::new(
+            "api.go:Serve:1".to_string(),
+            "Serve".to_string(),
+            NodeType::HttpHandler,
+            PathBuf::from("api.go"),
+            1,
+            4,
+            "api".to_string(),
+            "func Serve(w http.ResponseWriter, r *http.Request)".to_string(),
+        );
+        handler.tags.push("handler".to_string());
+        graph.add_node(handler);
+        graph.add_edge(Edge::new(
+            "api.go:Serve:1".to_string(),
+            "funcA".to_string(),
+            EdgeType::Calls,
+            "funcA()".to_string(),
+            PathBuf::from("api.go"),
+            2,
+        ));
+
+        let summary = graph.summary();
+        assert_eq!(summary.total_nodes, 5);
+        assert_eq!(summary.total_edges, 4);
+        assert_eq!(summary.files, 2);
+        assert_eq!(summary.nodes_by_type["Function"], 4);
+        assert_eq!(summary.nodes_by_type["HttpHandler"], 1);
+        assert_eq!(summary.edges_by_type["Calls"], 4);
+        assert_eq!(summary.packages[0].package, "main");
+        assert_eq!(summary.packages[0].nodes, 4);
+        assert_eq!(summary.packages[1].package, "api");
+        assert!((summary.avg_fan_out - 0.8).abs() < 1e-9);
+
+        // funcA..funcC each have one caller and one callee; funcA wins on name
+        let busiest = summary.most_connected.unwrap();
+        assert_eq!(busiest.name, "funcA");
+        assert_eq!((busiest.fan_in, busiest.fan_out), (1, 1));
+    }
+
+    #[test]
+    fn test_go_unused_params_reported() {
+        use crate::parser::GoParser;
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/go-unused-params/handlers.go");
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&fixture, &mut graph)
+            .unwrap();
+
+        let unused = graph.find_unused_params();
+        let names: Vec<(&str, Vec<&str>)> = unused
+            .iter()
+            .map(|(node, params)| (node.name.as_str(), params.clone()))
+            .collect();
+        // Put uses everything; Close's blank `_` parameter doesn't count
+        assert_eq!(names, vec![("Get", vec!["ctx"])]);
+        assert!(graph.get_nodes_by_name("Close")[0]
+            .unused_params()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_merge_all_skips_duplicate_node_ids() {
+        let node = |id: &str, name: &str| {
+            Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("shared.go"),
+                1,
+                3,
+                "shared".to_string(),
+                format!("func {}()", name),
+            )
+        };
+        let mut go = CodeGraph::new("/repo".to_string(), "go".to_string());
+        go.add_node(node("shared.go:Util:1", "Util"));
+        go.add_node(node("main.go:main:1", "main"));
+        let mut python = CodeGraph::new("/repo".to_string(), "python".to_string());
+        python.add_node(node("shared.go:Util:1", "Util"));
+        python.add_node(node("app.py:run:1", "run"));
+
+        let merged = CodeGraph::merge_all([go, python]).unwrap();
+        assert_eq!(merged.nodes.len(), 3);
+        assert_eq!(merged.get_nodes_by_name("Util").len(), 1);
+        assert_eq!(merged.get_node_by_id("app.py:run:1").unwrap().name, "run");
+        assert_eq!(merged.metadata.language, "go,python");
+        assert_eq!(merged.metadata.stats.total_nodes, 3);
+        assert!(CodeGraph::merge_all(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn test_merge_dedup_skips_existing_ids_and_their_edges() {
+        let node = |id: &str, name: &str| {
+            Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("shared.go"),
+                1,
+                3,
+                "shared".to_string(),
+                format!("func {}()", name),
+            )
+        };
+        let call = |from: &str, to: &str| {
+            Edge::new(
+                from.to_string(),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("shared.go"),
+                2,
+            )
+        };
+
+        let mut graph = CodeGraph::new("/repo".to_string(), "go".to_string());
+        graph.add_node(node("shared.go:Util:1", "Util"));
+        graph.add_edge(call("shared.go:Util:1", "log"));
+
+        let mut other = CodeGraph::new("/repo".to_string(), "go".to_string());
+        other.add_node(node("shared.go:Util:1", "Util"));
+        other.add_node(node("shared.go:Run:5", "Run"));
+        other.add_edge(call("shared.go:Util:1", "log"));
+        other.add_edge(call("shared.go:Run:5", "Util"));
+
+        graph.merge_dedup(other);
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.get_nodes_by_name("Util").len(), 1);
+        // The duplicate's edges are dropped, the new node's are kept
+        assert_eq!(graph.get_outgoing_edges("shared.go:Util:1").len(), 1);
+        assert_eq!(graph.get_outgoing_edges("shared.go:Run:5").len(), 1);
+        assert_eq!(graph.find_callers("Util").len(), 1);
+        assert_eq!(graph.edges.len(), 2);
+    }
+
+    #[test]
+    fn test_extract_subgraph_include_callers() {
+        use crate::core::TraversalDirection;
+
+        let graph = create_test_graph_with_calls();
+        let extract = |include_callers| {
+            let subgraph = graph.extract_subgraph_directed(
+                "funcC",
+                1,
+                TraversalDirection::Out,
+                include_callers,
+            );
+            let mut names: Vec<String> = subgraph.nodes.iter().map(|n| n.name.clone()).collect();
+            names.sort();
+            (names, subgraph.edges.len())
+        };
+
+        assert_eq!(extract(false), (vec!["funcC".into(), "funcD".into()], 1));
+        // funcB -> funcC joins, but funcB's own caller funcA does not
+        assert_eq!(
+            extract(true),
+            (vec!["funcB".into(), "funcC".into(), "funcD".into()], 2)
+        );
+    }
+
+    #[test]
+    fn test_trace_min_depth_skips_direct_callees() {
+        let graph = create_test_graph_with_calls();
+
+        let all = graph.trace_dependencies_from_depth("test:a:1", 0, 3, None);
+        assert_eq!(all.len(), 3);
+        assert_eq!(all[0].depth, 0);
+
+        // A -> B is depth 0; only B -> C and C -> D remain
+        let traces = graph.trace_dependencies_from_depth("test:a:1", 1, 3, None);
+        assert!(traces.iter().all(|trace| trace.depth >= 1));
+        let names: Vec<&str> = traces.iter().map(|t| t.to_name.as_str()).collect();
+        assert_eq!(names, vec!["funcC", "funcD"]);
+    }
+
+    #[test]
+    fn test_trace_callers_reverse_chain() {
+        let mut graph = create_test_graph_with_calls();
+        // D -> A closes a cycle: C shows up once as its own caller, then stops
+        graph.add_edge(Edge::new(
+            "test:d:30".to_string(),
+            "funcA".to_string(),
+            EdgeType::Calls,
+            "funcA()".to_string(),
+            PathBuf::from("test.go"),
+            31,
+        ));
+        // A same-named funcB elsewhere, whose caller must not leak in
+        graph.add_node(Node::new(
+            "other:b:5".to_string(),
+            "funcB".to_string(),
+            NodeType::Function,
+            PathBuf::from("other.go"),
+            5,
+            8,
+            "other".to_string(),
+            "func funcB()".to_string(),
+        ));
+        graph.add_node(Node::new(
+            "other:x:1".to_string(),
+            "funcX".to_string(),
+            NodeType::Function,
+            PathBuf::from("other.go"),
+            1,
+            3,
+            "other".to_string(),
+            "func funcX()".to_string(),
+        ));
+        let mut resolved = Edge::new(
+            "other:x:1".to_string(),
+            "funcB".to_string(),
+            EdgeType::Calls,
+            "funcB()".to_string(),
+            PathBuf::from("other.go"),
+            2,
+        );
+        resolved.to_id = Some("other:b:5".to_string());
+        graph.add_edge(resolved);
+
+        let traces = graph.trace_callers("test:c:20", 10);
+        let callers: Vec<(&str, usize)> = traces
+            .iter()
+            .map(|t| (t.from_id.as_str(), t.depth))
+            .collect();
+        assert_eq!(
+            callers,
+            vec![
+                ("test:b:10", 0),
+                ("test:a:1", 1),
+                ("test:d:30", 2),
+                ("test:c:20", 3)
+            ]
+        );
+
+        let direct = graph.trace_callers("test:c:20", 1);
+        assert_eq!(direct.len(), 1);
+        assert_eq!(direct[0].from_id, "test:b:10");
+    }
+
+    #[test]
+    fn test_typescript_class_implements_edges() {
+        use crate::parser::{Language, TypeScriptParser};
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/ts-classes/repository.ts");
+        let mut graph = CodeGraph::new("test".to_string(), "typescript".to_string());
+        TypeScriptParser::new(Language::TypeScript)
+            .unwrap()
+            .parse_file(&fixture, &mut graph)
+            .unwrap();
+
+        let class = graph.get_nodes_by_name("UserRepository")[0];
+        assert_eq!(class.node_type, NodeType::Class);
+
+        let edges = graph.get_outgoing_edges(&class.id);
+        let heritage = |edge_type: EdgeType| -> Vec<&str> {
+            edges
+                .iter()
+                .filter(|e| e.edge_type == edge_type)
+                .map(|e| e.to.as_str())
+                .collect()
+        };
+        assert_eq!(heritage(EdgeType::Extends), vec!["BaseRepository"]);
+        // Type arguments and namespace qualifiers are stripped from the name
+        assert_eq!(
+            heritage(EdgeType::Implements),
+            vec!["Repository", "Disposable"]
+        );
+
+        let implementers = graph.find_implementers("Repository");
+        assert_eq!(implementers.len(), 1);
+        assert_eq!(implementers[0].0.name, "UserRepository");
+        assert_eq!(graph.find_implementers("lifecycle.Disposable").len(), 1);
+    }
+
+    #[test]
+    fn test_typescript_callback_calls_attributed_to_enclosing_function() {
+        use crate::parser::{Language, TypeScriptParser};
+
+        let fixture = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("tests/fixtures/ts-callbacks/jobs.ts");
+        let mut graph = CodeGraph::new("test".to_string(), "typescript".to_string());
+        TypeScriptParser::new(Language::TypeScript)
+            .unwrap()
+            .parse_file(&fixture, &mut graph)
+            .unwrap();
+
+        let caller_names = |callee: &str| -> Vec<String> {
+            graph
+                .find_callers(callee)
+                .iter()
+                .map(|e| graph.get_node_by_id(&e.from).unwrap().name.clone())
+                .collect()
+        };
+        // Anonymous arrow and function-expression callbacks have no node of
+        // their own; their calls belong to the function passing them
+        assert_eq!(caller_names("doThing"), vec!["processAll", "report"]);
+        assert!(graph.get_nodes_by_name("anonymous").is_empty());
+
+        // A named arrow owns its calls; the enclosing function doesn't repeat them
+        assert_eq!(graph.get_nodes_by_name("format").len(), 1);
+        assert_eq!(caller_names("render"), vec!["format"]);
+    }
+
+    #[test]
+    fn test_package_cycles_skip_ambiguous_callee_names() {
+        // api -> store is real; store calling an unresolved `Close` that both
+        // packages define must not add store -> api
+        let mut graph = package_graph(&[("api", "store")]);
+        for package in ["api", "store"] {
+            graph.add_node(Node::new(
+                format!("{}.go:Close:10", package),
+                "Close".to_string(),
+                NodeType::Method,
+                PathBuf::from(format!("{}.go", package)),
+                10,
+                12,
+                package.to_string(),
+                "func Close()".to_string(),
+            ));
+        }
+        graph.add_edge(Edge::new(
+            "store.go:storeFunc:1".to_string(),
+            "Close".to_string(),
+            EdgeType::Calls,
+            "conn.Close()".to_string(),
+            PathBuf::from("store.go"),
+            3,
+        ));
+
+        assert!(graph.find_package_cycles().is_empty());
+        assert_eq!(graph.topological_order().unwrap(), vec!["store", "api"]);
+    }
+
+    #[test]
+    fn test_receiver_fallback_only_for_own_receiver() {
+        use crate::parser::{GoParser, RustParser};
+
+        let dir = tempfile::tempdir().unwrap();
+        let go_file = dir.path().join("server.go");
+        std::fs::write(
+            &go_file,
+            "package server\n\ntype Server struct{ listener *Listener }\ntype Listener struct{}\n\nfunc (l *Listener) Close() {}\n\nfunc (s *Server) Close() {\n\ts.listener.Close()\n\tconn.Close()\n}\n",
+        )
+        .unwrap();
+        let rust_file = dir.path().join("pool.rs");
+        std::fs::write(
+            &rust_file,
+            "struct Pool;\nstruct Conn;\n\nimpl Pool {\n    fn drain(&self) {\n        self.close();\n    }\n    fn close(&self) {}\n}\n\nimpl Conn {\n    fn close(&self) {}\n}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(&go_file, &mut graph)
+            .unwrap();
+        RustParser::new()
+            .unwrap()
+            .parse_file(&rust_file, &mut graph)
+            .unwrap();
+        graph.resolve_edges();
+
+        // Neither call is on the method's own receiver: no self-edge to Server.Close
+        let server_close = graph
+            .get_nodes_by_name("Close")
+            .into_iter()
+            .find(|n| n.receiver() == Some("Server"))
+            .unwrap();
+        let calls = graph.get_outgoing_edges(&server_close.id);
+        assert_eq!(calls.len(), 2);
+        assert!(calls
+            .iter()
+            .all(|e| e.to_id.as_deref() != Some(server_close.id.as_str())));
+
+        // `self.close()` resolves to the caller's own type
+        let drain = graph.get_nodes_by_name("drain")[0];
+        let close = graph.get_outgoing_edges(&drain.id)[0];
+        let target = graph.get_node_by_id(close.to_id.as_ref().unwrap()).unwrap();
+        assert_eq!(target.owner_type(), Some("Pool"));
+    }
+
+    #[test]
+    fn test_go_method_sets_are_per_directory_and_survive_reindex() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let api = dir.path().join("api");
+        let worker = dir.path().join("worker");
+        std::fs::create_dir_all(&api).unwrap();
+        std::fs::create_dir_all(&worker).unwrap();
+        std::fs::write(
+            api.join("runner.go"),
+            "package main\n\ntype Runner interface {\n\tRun() error\n}\n",
+        )
+        .unwrap();
+        let server = api.join("server.go");
+        std::fs::write(
+            &server,
+            "package main\n\ntype Server struct{}\n\nfunc (s *Server) Run() error { return nil }\n",
+        )
+        .unwrap();
+        // Same package name and type name, but no Run method
+        std::fs::write(
+            worker.join("server.go"),
+            "package main\n\ntype Server struct{}\n",
+        )
+        .unwrap();
+
+        let mut graph = CodeGraph::new("test".to_string(), "go".to_string());
+        let mut parser = GoParser::new().unwrap();
+        parser.parse_directory(dir.path(), &mut graph).unwrap();
+
+        let implementers = |graph: &CodeGraph| -> Vec<String> {
+            graph
+                .find_implementers("Runner")
+                .iter()
+                .map(|(node, _)| node.file_path.to_string_lossy().to_string())
+                .collect()
+        };
+        let expected = vec![server.to_string_lossy().to_string()];
+        assert_eq!(implementers(&graph), expected);
+
+        // Reindexing either side keeps the edge, pointing at the new IDs
+        std::fs::write(
+            api.join("runner.go"),
+            "package main\n\n// Runner runs\ntype Runner interface {\n\tRun() error\n}\n",
+        )
+        .unwrap();
+        for path in [server.clone(), api.join("runner.go")] {
+            graph
+                .reindex_file(&path, |g| parser.parse_file(&path, g))
+                .unwrap();
+            assert_eq!(implementers(&graph), expected);
+        }
+        let runner_id = &graph.get_nodes_by_name("Runner")[0].id;
+        let edges: Vec<&Edge> = graph
+            .edges
+            .iter()
+            .filter(|e| e.edge_type == EdgeType::Implements)
+            .collect();
+        assert_eq!(edges.len(), 1);
+        assert_eq!(edges[0].to_id.as_ref(), Some(runner_id));
+    }
+
+    #[test]
+    fn test_changed_since_catches_body_edits_and_removed_callees() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("billing.go");
+        std::fs::write(
+            dir.path().join("billing_test.go"),
+            "package billing\n\nfunc TestCharge() {\n\tCharge()\n}\n\nfunc TestRefund() {\n\tRefund()\n}\n\nfunc TestNothing() {}\n",
+        )
+        .unwrap();
+        let index = |body: &str| {
+            std::fs::write(&source, body).unwrap();
+            let mut graph =
+                CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+            let mut parser = GoParser::new().unwrap();
+            for file in ["billing.go", "billing_test.go"] {
+                parser
+                    .parse_file(&dir.path().join(file), &mut graph)
+                    .unwrap();
+            }
+            graph.tag_tests();
+            graph
+        };
+
+        let old = index(
+            "package billing\n\nfunc Charge() {\n\tround()\n}\n\nfunc audit() {}\n\nfunc Refund() {\n\taudit()\n}\n\nfunc round() {}\n",
+        );
+        // Refund grows without moving or changing its signature, and round
+        // is deleted while its caller Charge stays untouched
+        let new = index(
+            "package billing\n\nfunc Charge() {\n\tround()\n}\n\nfunc audit() {}\n\nfunc Refund() {\n\taudit()\n\taudit()\n}\n",
+        );
+        assert!(old.diff(&new).changed_nodes.is_empty());
+
+        let changed = new.changed_since(&old);
+        let names: Vec<String> = new
+            .affected_tests(&changed)
+            .iter()
+            .map(|t| t.name.clone())
+            .collect();
+        assert_eq!(names, vec!["TestCharge", "TestRefund"]);
+    }
+
+    #[test]
+    fn test_changed_since_catches_body_only_edits() {
+        use crate::parser::GoParser;
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("math_test.go"),
+            "package math\n\nfunc TestNext() {\n\tNext(1)\n}\n\nfunc TestDouble() {\n\tDouble(1)\n}\n",
+        )
+        .unwrap();
+        let index = |body: &str| {
+            std::fs::write(dir.path().join("math.go"), body).unwrap();
+            let mut graph =
+                CodeGraph::new(dir.path().to_string_lossy().to_string(), "go".to_string());
+            let mut parser = GoParser::new().unwrap();
+            for file in ["math.go", "math_test.go"] {
+                parser
+                    .parse_file(&dir.path().join(file), &mut graph)
+                    .unwrap();
+            }
+            graph.tag_tests();
+            graph
+        };
+
+        // Same lines, signature and calls; only an operator changes
+        let old = index("package math\n\nfunc Next(x int) int {\n\treturn x + 1\n}\n\nfunc Double(x int) int {\n\treturn x * 2\n}\n");
+        let new = index("package math\n\nfunc Next(x int) int {\n\treturn x - 1\n}\n\nfunc Double(x int) int {\n\treturn x * 2\n}\n");
+        assert!(old.diff(&new).changed_nodes.is_empty());
+
+        let changed = new.changed_since(&old);
+        let names: Vec<String> = new
+            .affected_tests(&changed)
+            .iter()
+            .map(|t| t.name.clone())
+            .collect();
+        assert_eq!(names, vec!["TestNext"]);
+    }
+
+    #[test]
+    fn test_parsers_extract_doc_comments() {
+        use crate::parser::{GoParser, Language, PythonParser, TypeScriptParser};
+
+        let fixture = |path: &str| std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(path);
+        let mut graph = CodeGraph::new("test".to_string(), "mixed".to_string());
+        GoParser::new()
+            .unwrap()
+            .parse_file(
+                &fixture("tests/fixtures/simple-go/calculator.go"),
+                &mut graph,
+            )
+            .unwrap();
+        PythonParser::new()
+            .unwrap()
+            .parse_file(
+                &fixture("tests/fixtures/simple-python/calculator.py"),
+                &mut graph,
+            )
+            .unwrap();
+        TypeScriptParser::new(Language::TypeScript)
+            .unwrap()
+            .parse_file(
+                &fixture("tests/fixtures/simple-ts/calculator.ts"),
+                &mut graph,
+            )
+            .unwrap();
+
+        let doc = |file: &str, name: &str| {
+            graph
+                .get_nodes_by_name(name)
+                .into_iter()
+                .find(|n| n.file_path.to_string_lossy().ends_with(file))
+                .and_then(|n| n.documentation.clone())
+        };
+        // Go line comments on functions, methods and types
+        assert_eq!(
+            doc("calculator.go", "NewCalculator").as_deref(),
+            Some("NewCalculator creates a new Calculator")
+        );
+        assert_eq!(
+            doc("calculator.go", "Add").as_deref(),
+            Some("Add adds two numbers (method)")
+        );
+        assert_eq!(
+            doc("calculator.go", "Calculator").as_deref(),
+            Some("Calculator is a simple calculator struct")
+        );
+        // Python docstrings
+        assert_eq!(
+            doc("calculator.py", "subtract").as_deref(),
+            Some("Subtract two numbers.")
+        );
+        assert_eq!(
+            doc("calculator.py", "Calculator").as_deref(),
+            Some("Calculator class with instance methods.")
+        );
+        // JSDoc above exported functions, arrow functions and classes; the
+        // file header comment is separated by a blank line
+        assert_eq!(
+            doc("calculator.ts", "add").as_deref(),
+            Some("Adds two numbers")
+        );
+        assert_eq!(
+            doc("calculator.ts", "power").as_deref(),
+            Some("Calculates power using repeated multiplication")
+        );
+        assert_eq!(
+            doc("calculator.ts", "Calculator").as_deref(),
+            Some("Calculator class")
+        );
+
+        let matches: Vec<&str> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.doc_contains("REPEATED"))
+            .map(|n| n.name.as_str())
+            .collect();
+        assert!(matches.contains(&"multiply") && matches.contains(&"power"));
+    }
 }
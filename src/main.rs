@@ -1,9 +1,26 @@
 use anyhow::Result;
 use clap::Parser;
-use code_navigator::benchmark::{BenchmarkMetrics, BenchmarkTimer};
-use code_navigator::core::{CodeGraph, NodeType};
-use code_navigator::parser::{GoParser, Language, PythonParser, TypeScriptParser};
-use code_navigator::serializer::{csv, dot, fast_compressed, graphml, json, jsonl};
+use code_navigator::archive;
+use code_navigator::benchmark::{humanize_duration, BenchmarkMetrics, BenchmarkTimer};
+use code_navigator::bisect::{self, BisectOutcome};
+use code_navigator::blame;
+use code_navigator::commands;
+use code_navigator::core::{
+    group_nodes, CodeGraph, DiffReport, GroupBy, NodeType, TagMode, TraversalDirection,
+    CUSTOM_NODE_TYPES, TEST_TAG,
+};
+use code_navigator::events::{Event, EventSink};
+use code_navigator::incremental;
+use code_navigator::manifest::IndexManifest;
+use code_navigator::parser::{
+    CppParser, GoParser, Language, ParseOptions, ParsedFiles, PythonParser, RustParser,
+    ScalaParser, TypeScriptParser, WarningLog,
+};
+use code_navigator::serializer::{
+    compressed, csv, cypher, cytoscape, dot, fast_compressed, graphml, json, jsonl, markdown,
+    mermaid, parquet, plantuml, schema, shards, shell, sqlite, stream,
+};
+use code_navigator::sidecar::SidecarMetadata;
 use colored::Colorize;
 
 mod cli;
@@ -14,30 +31,87 @@ use std::process::Command;
 
 /// Load graph from file, auto-detecting format from extension
 /// Phase 3 optimization: Try to load cached indices first
-fn load_graph(path: &Path) -> Result<CodeGraph> {
+/// With `validate_cache`, indices are always rebuilt and compared against the cache.
+/// With `explain_cache`, each cache decision is reported on stderr.
+fn load_graph(path: &Path, validate_cache: bool, explain_cache: bool) -> Result<CodeGraph> {
     use code_navigator::serializer::index_cache::SerializedIndices;
 
+    let explain = |message: String| {
+        if explain_cache {
+            eprintln!("{} {}", "cache:".dimmed(), message);
+        }
+    };
+
+    // `-` reads a piped graph; there is no index cache beside it
+    if stream::is_stdio(path) {
+        explain("graph read from stdin, no index cache; indices built".to_string());
+        return stream::load_from_reader(std::io::stdin().lock());
+    }
+
     let extension = path.extension().and_then(|s| s.to_str()).unwrap_or("bin");
 
     // Load the graph data - use optimized binary format with JSON fallback
-    let mut graph = match extension {
-        "json" => json::load_from_file(path)?, // Legacy JSON support
-        "jsonl" => jsonl::load_from_jsonl(&path.to_string_lossy())?, // Legacy JSONL support
-        _ => fast_compressed::load_from_file(&path.to_string_lossy())?, // Default: optimized binary (with JSON fallback)
+    let (mut graph, embedded_indices) = match extension {
+        "json" => (json::load_from_file(path)?, false), // Legacy JSON support
+        "jsonl" => (jsonl::load_from_jsonl(&path.to_string_lossy())?, false), // Legacy JSONL support
+        _ => fast_compressed::load_from_file_with_status(&path.to_string_lossy())?, // Default: optimized binary
     };
 
+    if validate_cache {
+        graph.build_indexes();
+        let fresh = graph.extract_indices();
+
+        let divergences = match SerializedIndices::load(path) {
+            Ok(cached) => cached.diff(&fresh),
+            Err(e) => {
+                eprintln!("{} No usable index cache ({})", "Note:".yellow(), e);
+                Vec::new()
+            }
+        };
+
+        // Always rewrite the cache from the freshly built indices
+        fresh.save(path)?;
+        explain("--validate-cache: indices rebuilt, compared and saved".to_string());
+
+        if !divergences.is_empty() {
+            anyhow::bail!(
+                "Index cache for {} diverged from the graph (cache rebuilt):\n  {}",
+                path.display(),
+                divergences.join("\n  ")
+            );
+        }
+
+        return Ok(graph);
+    }
+
+    // Indices embedded in the graph file were already validated and applied
+    if embedded_indices {
+        explain("hit: indices embedded in the graph file".to_string());
+        return Ok(graph);
+    }
+
     // Phase 3: Try to load cached indices
     let idx_path = path.with_extension("idx");
     if idx_path.exists() {
-        if let Ok(cached_indices) = SerializedIndices::load(path) {
-            let graph_hash = graph.compute_hash();
-            // Validate cache matches current graph
-            if cached_indices.validate(graph.nodes.len(), graph.edges.len(), &graph_hash) {
-                // Cache is valid - apply it
-                graph.apply_indices(cached_indices);
-                return Ok(graph);
+        explain(format!("{} found", idx_path.display()));
+        match SerializedIndices::load(path) {
+            Ok(cached_indices) => {
+                let graph_hash = graph.compute_hash();
+                // Validate cache matches current graph
+                match cached_indices.check(graph.nodes.len(), graph.edges.len(), &graph_hash) {
+                    Ok(()) => {
+                        // Cache is valid - apply it
+                        graph.apply_indices(cached_indices);
+                        explain("hit: validation passed, cached indices applied".to_string());
+                        return Ok(graph);
+                    }
+                    Err(reason) => explain(format!("miss: validation failed, {}", reason)),
+                }
             }
+            Err(e) => explain(format!("miss: unreadable ({})", e)),
         }
+    } else {
+        explain(format!("miss: {} not found", idx_path.display()));
     }
 
     // No cache or cache invalid - build indices and save cache
@@ -45,63 +119,59 @@ fn load_graph(path: &Path) -> Result<CodeGraph> {
 
     // Save cache for next time
     let indices = graph.extract_indices();
-    let _ = indices.save(path); // Ignore errors
+    match indices.save(path) {
+        Ok(()) => explain(format!(
+            "indices rebuilt and saved to {}",
+            idx_path.display()
+        )),
+        Err(e) => explain(format!("indices rebuilt; saving the cache failed ({})", e)),
+    }
 
     Ok(graph)
 }
 
-/// Detect changed files using git
-fn detect_changed_files_git(directory: &Path, file_extension: &str) -> Result<Vec<PathBuf>> {
-    // Get files changed compared to HEAD (includes both staged and unstaged)
-    let output = Command::new("git")
-        .arg("-C")
-        .arg(directory)
-        .arg("diff")
-        .arg("--name-only")
-        .arg("HEAD")
-        .output()?;
-
-    if !output.status.success() {
-        anyhow::bail!("Git command failed");
+/// Save a graph in the default binary format, to stdout when `path` is `-`.
+/// With `embed_indices`, the lookup indices are appended to the same file;
+/// with a `compression_level`, the graph is zstd-compressed at that level
+/// instead (the loaders detect either).
+fn save_graph(
+    graph: &CodeGraph,
+    path: &Path,
+    embed_indices: bool,
+    compression_level: Option<i32>,
+) -> Result<()> {
+    let bytes = match compression_level {
+        Some(level) => Some(compressed::to_bytes(graph, level)?),
+        None if embed_indices => Some(fast_compressed::to_bytes_with_indices(graph)?),
+        None => None,
+    };
+    if let Some(bytes) = bytes {
+        if stream::is_stdio(path) {
+            use std::io::Write;
+            let mut stdout = std::io::stdout().lock();
+            stdout.write_all(&bytes)?;
+            stdout.flush()?;
+        } else {
+            std::fs::write(path, bytes)?;
+        }
+        return Ok(());
     }
 
-    let stdout = String::from_utf8_lossy(&output.stdout);
-    let mut changed_files = Vec::new();
-
-    for line in stdout.lines() {
-        let path = directory.join(line);
-        if path.extension().and_then(|s| s.to_str()) == Some(file_extension) && path.exists() {
-            changed_files.push(path);
-        }
+    if stream::is_stdio(path) {
+        return stream::save_to_writer(graph, std::io::stdout().lock());
     }
+    fast_compressed::save_to_file(graph, &path.to_string_lossy())
+}
 
-    // Also check for untracked files
-    let output = Command::new("git")
-        .arg("-C")
-        .arg(directory)
-        .arg("ls-files")
-        .arg("--others")
-        .arg("--exclude-standard")
-        .output()?;
+/// Callers/callees listed per side by `query --relations`
+const RELATIONS_LIMIT: usize = 5;
 
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        for line in stdout.lines() {
-            let path = directory.join(line);
-            if path.extension().and_then(|s| s.to_str()) == Some(file_extension)
-                && path.exists()
-                && !changed_files.contains(&path)
-            {
-                changed_files.push(path);
-            }
-        }
+/// Get current git commit hash (`None` when git is disabled)
+fn get_git_commit_hash(directory: &Path, use_git: bool) -> Option<String> {
+    if !use_git {
+        return None;
     }
 
-    Ok(changed_files)
-}
-
-/// Get current git commit hash
-fn get_git_commit_hash(directory: &Path) -> Option<String> {
     let output = Command::new("git")
         .arg("-C")
         .arg(directory)
@@ -128,15 +198,20 @@ fn count_lines_of_code(path: &Path) -> Result<usize> {
     Ok(count)
 }
 
-/// Count total lines of code in all files with given extension
-fn count_total_loc(directory: &Path, file_ext: &str) -> Result<usize> {
+/// Count total lines of code in all files with the given extensions
+fn count_total_loc(directory: &Path, file_exts: &[&str]) -> Result<usize> {
     use walkdir::WalkDir;
 
     let mut total = 0;
     for entry in WalkDir::new(directory)
         .into_iter()
         .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some(file_ext))
+        .filter(|e| {
+            e.path()
+                .extension()
+                .and_then(|s| s.to_str())
+                .is_some_and(|ext| file_exts.contains(&ext))
+        })
     {
         if let Ok(loc) = count_lines_of_code(entry.path()) {
             total += loc;
@@ -145,87 +220,129 @@ fn count_total_loc(directory: &Path, file_ext: &str) -> Result<usize> {
     Ok(total)
 }
 
-/// Detect changed files using timestamps (fallback when git is not available)
-fn detect_changed_files_timestamp(
+/// Parse every source file of `lang` under `directory` into `graph`
+fn parse_directory(
+    lang: &str,
     directory: &Path,
-    existing_graph: &CodeGraph,
-    file_extension: &str,
-) -> Result<Vec<PathBuf>> {
-    use std::fs;
-    use walkdir::WalkDir;
-
-    let mut changed_files = Vec::new();
-
-    for entry in WalkDir::new(directory)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some(file_extension))
-    {
-        let path = entry.path();
-        let path_str = path.to_string_lossy().to_string();
-
-        // Check if file is new or modified
-        if let Some(file_meta) = existing_graph.metadata.file_metadata.get(&path_str) {
-            // File exists in graph, check if modified
-            if let Ok(metadata) = fs::metadata(path) {
-                if let Ok(modified) = metadata.modified() {
-                    let modified_str = format!("{:?}", modified);
-                    if modified_str != file_meta.last_modified {
-                        changed_files.push(path.to_path_buf());
-                    }
-                }
-            }
-        } else {
-            // New file
-            changed_files.push(path.to_path_buf());
-        }
-    }
-
-    Ok(changed_files)
-}
-
-/// Detect deleted files by comparing stored file metadata with current directory
-fn detect_deleted_files(directory: &Path, existing_graph: &CodeGraph) -> Vec<String> {
-    let mut deleted_files = Vec::new();
-
-    for file_path in existing_graph.metadata.file_metadata.keys() {
-        let full_path = if Path::new(file_path).is_absolute() {
-            PathBuf::from(file_path)
-        } else {
-            directory.join(file_path)
-        };
-
-        if !full_path.exists() {
-            deleted_files.push(file_path.clone());
-        }
+    options: &ParseOptions,
+    graph: &mut CodeGraph,
+) -> Result<()> {
+    match lang {
+        "go" => GoParser::new()?
+            .with_options(options.clone())
+            .parse_directory(directory, graph),
+        "typescript" | "ts" => TypeScriptParser::new(Language::TypeScript)?
+            .with_options(options.clone())
+            .parse_directory(directory, graph),
+        "javascript" | "js" => TypeScriptParser::new(Language::JavaScript)?
+            .with_options(options.clone())
+            .parse_directory(directory, graph),
+        "python" | "py" => PythonParser::new()?
+            .with_options(options.clone())
+            .parse_directory(directory, graph),
+        "cpp" | "c++" | "cc" | "cxx" | "hpp" => CppParser::new()?
+            .with_options(options.clone())
+            .parse_directory(directory, graph),
+        "scala" => ScalaParser::new()?
+            .with_options(options.clone())
+            .parse_directory(directory, graph),
+        "rust" | "rs" => RustParser::new()?
+            .with_options(options.clone())
+            .parse_directory(directory, graph),
+        _ => anyhow::bail!("Unsupported language: {}", lang),
     }
-
-    deleted_files
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    let events = cli.json_events.then(EventSink::stderr);
 
     match &cli.command {
         Commands::Index {
             directory,
+            from_archive,
             output,
             language,
-            exclude: _,
-            include_tests: _,
+            exclude,
+            include_tests,
             incremental,
             force,
+            dry_run,
+            metadata_file,
+            dedup_identical,
+            content_ids,
+            lossy_encoding,
+            max_warnings,
+            max_edges_per_node,
+            parallel_threshold,
+            blame: annotate_blame,
+            embed_indices,
+            compression_level,
+            precompute_degrees,
+            manifest,
+            shard_by,
+            shards_dir,
             benchmark,
             benchmark_json,
         } => {
+            if *annotate_blame && cli.no_git {
+                anyhow::bail!("--blame needs git; remove --no-git / CODENAV_NO_GIT");
+            }
+            if let Some(key) = shard_by.as_deref().filter(|key| *key != "package") {
+                anyhow::bail!("Unknown shard key: {}. Use: package", key);
+            }
+            let index_start = std::time::Instant::now();
+            if let Some(events) = &events {
+                events.emit(&Event::Start { command: "index" });
+            }
+
+            // Status output would corrupt a graph streamed to stdout
+            let quiet = cli.quiet || stream::is_stdio(output);
+            if *incremental && stream::is_stdio(output) {
+                anyhow::bail!("--incremental needs an existing graph file, not stdout");
+            }
+
+            // Archives are extracted to a temporary directory that is
+            // removed when `extracted` goes out of scope
+            let extracted = match from_archive {
+                Some(archive_path) => {
+                    if !quiet {
+                        println!(
+                            "{}",
+                            format!("Extracting {}...", archive_path.display()).dimmed()
+                        );
+                    }
+                    Some(archive::extract_archive(archive_path)?)
+                }
+                None => None,
+            };
+            let directory: &Path = match (&extracted, directory) {
+                (Some(extracted), _) => extracted.root(),
+                (None, Some(dir)) => dir,
+                (None, None) => anyhow::bail!("Either DIRECTORY or --from-archive is required"),
+            };
+
             let lang = language.as_deref().unwrap_or("go");
+            let parse_options = ParseOptions {
+                max_edges_per_node: *max_edges_per_node,
+                events: events.clone(),
+                parallel_threshold: *parallel_threshold,
+                lossy_encoding: *lossy_encoding,
+                warnings: WarningLog::new(*max_warnings),
+                exclude_globs: exclude.clone(),
+                include_tests: *include_tests,
+                parsed_files: ParsedFiles::default(),
+            };
 
-            // Determine file extension for the language
-            let file_ext = match lang {
-                "go" => "go",
-                "typescript" | "ts" => "ts",
-                "javascript" | "js" => "js",
-                "python" | "py" => "py",
+            // Determine file extensions for the language
+            let file_exts: &[&str] = match lang {
+                "go" => &["go"],
+                "typescript" | "ts" => &["ts"],
+                "javascript" | "js" => &["js"],
+                "python" | "py" => &["py"],
+                "cpp" | "c++" | "cc" | "cxx" | "hpp" => CppParser::EXTENSIONS,
+                "scala" => ScalaParser::EXTENSIONS,
+                "rust" | "rs" => RustParser::EXTENSIONS,
                 _ => anyhow::bail!("Unsupported language: {}", lang),
             };
 
@@ -238,11 +355,11 @@ fn main() -> Result<()> {
 
             // Count LOC if benchmarking
             let total_loc = if *benchmark {
-                if !cli.quiet {
+                if !quiet {
                     println!("{}", "Counting lines of code...".dimmed());
                 }
                 let discovery_start = std::time::Instant::now();
-                let loc = count_total_loc(directory, file_ext)?;
+                let loc = count_total_loc(directory, file_exts)?;
                 if let Some(ref mut timer) = bench_timer {
                     timer.discovery_duration = Some(discovery_start.elapsed());
                 }
@@ -254,59 +371,97 @@ fn main() -> Result<()> {
             // Check if incremental mode is requested
             let should_use_incremental = *incremental && !force && output.exists();
 
-            let graph = if should_use_incremental {
+            if *dry_run && !should_use_incremental {
+                println!(
+                    "{} No existing graph to update at {}; a full index would run",
+                    "Dry run:".bold(),
+                    output.display()
+                );
+                return Ok(());
+            }
+
+            let mut graph = if should_use_incremental {
                 // INCREMENTAL MODE
-                if !cli.quiet {
+                if !quiet {
                     println!("{}", "Incremental update mode...".green().bold());
                 }
 
                 // Load existing graph
-                let mut existing_graph = match load_graph(output) {
-                    Ok(g) => {
-                        if !cli.quiet {
-                            println!(
-                                "{} Loaded existing graph ({} nodes)",
-                                "✓".green().bold(),
-                                g.nodes.len().to_string().cyan()
-                            );
-                        }
-                        g
-                    }
-                    Err(e) => {
-                        if !cli.quiet {
-                            println!("{} Failed to load existing graph: {}", "⚠".yellow(), e);
-                            println!("{} Performing full generation...", "→".blue());
-                        }
-                        CodeGraph::new(directory.to_string_lossy().to_string(), lang.to_string())
-                    }
-                };
-
-                // Try git first, fallback to timestamps
-                let (changed_files, detection_method) =
-                    match detect_changed_files_git(directory, file_ext) {
-                        Ok(files) => (files, "git"),
-                        Err(_) => {
-                            if !cli.quiet {
+                let mut existing_graph =
+                    match load_graph(output, cli.validate_cache, cli.explain_cache) {
+                        Ok(g) => {
+                            if !quiet {
                                 println!(
-                                    "{} Git not available, using file timestamps",
-                                    "⚠".yellow()
+                                    "{} Loaded existing graph ({} nodes)",
+                                    "✓".green().bold(),
+                                    g.nodes.len().to_string().cyan()
                                 );
                             }
-                            (
-                                detect_changed_files_timestamp(
-                                    directory,
-                                    &existing_graph,
-                                    file_ext,
-                                )?,
-                                "timestamps",
+                            g
+                        }
+                        Err(e) => {
+                            if !quiet {
+                                println!("{} Failed to load existing graph: {}", "⚠".yellow(), e);
+                                println!("{} Performing full generation...", "→".blue());
+                            }
+                            CodeGraph::new(
+                                directory.to_string_lossy().to_string(),
+                                lang.to_string(),
                             )
                         }
                     };
 
-                // Detect deleted files
-                let deleted_files = detect_deleted_files(directory, &existing_graph);
+                // Try git first, fallback to timestamps
+                let changes = incremental::detect_changes(
+                    directory,
+                    &existing_graph,
+                    file_exts,
+                    !cli.no_git,
+                )?;
+                if changes.detection_method == "timestamps" && !quiet {
+                    println!("{} Git not available, using file timestamps", "⚠".yellow());
+                }
 
-                if !cli.quiet {
+                // Dry run: report what would change, then stop before reparsing or writing
+                if *dry_run {
+                    println!(
+                        "{} {} changed files via {}",
+                        "Dry run:".bold(),
+                        changes.changed_files.len().to_string().cyan(),
+                        changes.detection_method
+                    );
+                    for file in &changes.changed_files {
+                        println!("  {} {}", "~".yellow(), file.display());
+                    }
+                    println!(
+                        "{} {} deleted files",
+                        "Dry run:".bold(),
+                        changes.deleted_files.len().to_string().red()
+                    );
+                    for file in &changes.deleted_files {
+                        println!("  {} {}", "-".red(), file);
+                    }
+
+                    let removals = changes.nodes_to_remove(&existing_graph);
+                    let total: usize = removals.iter().map(|(_, count)| count).sum();
+                    println!(
+                        "{} {} nodes would be removed before reparsing",
+                        "Dry run:".bold(),
+                        total.to_string().yellow()
+                    );
+                    for (file, count) in removals.iter().filter(|(_, count)| *count > 0) {
+                        println!("  {} {} ({} nodes)", "→".blue(), file, count);
+                    }
+                    return Ok(());
+                }
+
+                let incremental::ChangeSet {
+                    changed_files,
+                    deleted_files,
+                    detection_method,
+                } = &changes;
+
+                if !quiet {
                     println!(
                         "{} Detected {} changed files via {}",
                         "✓".green().bold(),
@@ -322,39 +477,43 @@ fn main() -> Result<()> {
                     }
                 }
 
+                // Degrees stored on the existing graph go stale for nodes
+                // touched by the changed files (their own and their callees')
+                let had_degrees = existing_graph.has_precomputed_degrees();
+                let mut stale_degrees = HashSet::new();
+                if had_degrees {
+                    for file in deleted_files.iter().cloned().chain(
+                        changed_files
+                            .iter()
+                            .map(|f| f.to_string_lossy().to_string()),
+                    ) {
+                        stale_degrees.extend(existing_graph.degree_dependents(&file));
+                    }
+                }
+
                 // Remove deleted files
-                for deleted_file in &deleted_files {
+                for deleted_file in deleted_files {
                     existing_graph.remove_nodes_from_file(deleted_file);
                 }
 
-                // Remove and reparse changed files
+                // Reparse changed files, replacing their old nodes
                 let total_files_before = existing_graph.metadata.file_metadata.len();
-                for changed_file in &changed_files {
-                    let file_str = changed_file.to_string_lossy().to_string();
-                    existing_graph.remove_nodes_from_file(&file_str);
-                }
-
-                // Parse changed files
                 use std::fs;
 
-                let files_to_parse: HashSet<_> = changed_files.iter().collect();
+                let filter = parse_options.file_filter(directory)?;
+                let files_to_parse = changes.files_to_reparse(&filter, lang);
                 let mut files_parsed = 0;
 
                 // Create temporary parser based on language
                 match lang {
                     "go" => {
-                        let mut parser = GoParser::new()?;
+                        let mut parser = GoParser::new()?.with_options(parse_options.clone());
                         for file_path in &files_to_parse {
-                            if let Err(e) = parser.parse_file(file_path, &mut existing_graph) {
-                                if !cli.quiet {
-                                    println!(
-                                        "{} Failed to parse {}: {}",
-                                        "⚠".yellow(),
-                                        file_path.display(),
-                                        e
-                                    );
-                                }
-                            } else {
+                            let result = existing_graph.reindex_file(file_path, |graph| {
+                                parser.parse_file(file_path, graph)
+                            });
+                            parse_options.report_file(file_path, &result);
+                            if result.is_ok() {
                                 files_parsed += 1;
                                 // Track file metadata
                                 if let Ok(metadata) = fs::metadata(file_path) {
@@ -369,18 +528,14 @@ fn main() -> Result<()> {
                         }
                     }
                     "typescript" | "ts" => {
-                        let mut parser = TypeScriptParser::new(Language::TypeScript)?;
+                        let mut parser = TypeScriptParser::new(Language::TypeScript)?
+                            .with_options(parse_options.clone());
                         for file_path in &files_to_parse {
-                            if let Err(e) = parser.parse_file(file_path, &mut existing_graph) {
-                                if !cli.quiet {
-                                    println!(
-                                        "{} Failed to parse {}: {}",
-                                        "⚠".yellow(),
-                                        file_path.display(),
-                                        e
-                                    );
-                                }
-                            } else {
+                            let result = existing_graph.reindex_file(file_path, |graph| {
+                                parser.parse_file(file_path, graph)
+                            });
+                            parse_options.report_file(file_path, &result);
+                            if result.is_ok() {
                                 files_parsed += 1;
                                 if let Ok(metadata) = fs::metadata(file_path) {
                                     if let Ok(modified) = metadata.modified() {
@@ -394,18 +549,14 @@ fn main() -> Result<()> {
                         }
                     }
                     "javascript" | "js" => {
-                        let mut parser = TypeScriptParser::new(Language::JavaScript)?;
+                        let mut parser = TypeScriptParser::new(Language::JavaScript)?
+                            .with_options(parse_options.clone());
                         for file_path in &files_to_parse {
-                            if let Err(e) = parser.parse_file(file_path, &mut existing_graph) {
-                                if !cli.quiet {
-                                    println!(
-                                        "{} Failed to parse {}: {}",
-                                        "⚠".yellow(),
-                                        file_path.display(),
-                                        e
-                                    );
-                                }
-                            } else {
+                            let result = existing_graph.reindex_file(file_path, |graph| {
+                                parser.parse_file(file_path, graph)
+                            });
+                            parse_options.report_file(file_path, &result);
+                            if result.is_ok() {
                                 files_parsed += 1;
                                 if let Ok(metadata) = fs::metadata(file_path) {
                                     if let Ok(modified) = metadata.modified() {
@@ -419,18 +570,73 @@ fn main() -> Result<()> {
                         }
                     }
                     "python" | "py" => {
-                        let mut parser = PythonParser::new()?;
+                        let mut parser = PythonParser::new()?.with_options(parse_options.clone());
                         for file_path in &files_to_parse {
-                            if let Err(e) = parser.parse_file(file_path, &mut existing_graph) {
-                                if !cli.quiet {
-                                    println!(
-                                        "{} Failed to parse {}: {}",
-                                        "⚠".yellow(),
-                                        file_path.display(),
-                                        e
-                                    );
+                            let result = existing_graph.reindex_file(file_path, |graph| {
+                                parser.parse_file(file_path, graph)
+                            });
+                            parse_options.report_file(file_path, &result);
+                            if result.is_ok() {
+                                files_parsed += 1;
+                                if let Ok(metadata) = fs::metadata(file_path) {
+                                    if let Ok(modified) = metadata.modified() {
+                                        existing_graph.track_file_metadata(
+                                            file_path,
+                                            format!("{:?}", modified),
+                                        );
+                                    }
                                 }
-                            } else {
+                            }
+                        }
+                    }
+                    "cpp" | "c++" | "cc" | "cxx" | "hpp" => {
+                        let mut parser = CppParser::new()?.with_options(parse_options.clone());
+                        for file_path in &files_to_parse {
+                            let result = existing_graph.reindex_file(file_path, |graph| {
+                                parser.parse_file(file_path, graph)
+                            });
+                            parse_options.report_file(file_path, &result);
+                            if result.is_ok() {
+                                files_parsed += 1;
+                                if let Ok(metadata) = fs::metadata(file_path) {
+                                    if let Ok(modified) = metadata.modified() {
+                                        existing_graph.track_file_metadata(
+                                            file_path,
+                                            format!("{:?}", modified),
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    "scala" => {
+                        let mut parser = ScalaParser::new()?.with_options(parse_options.clone());
+                        for file_path in &files_to_parse {
+                            let result = existing_graph.reindex_file(file_path, |graph| {
+                                parser.parse_file(file_path, graph)
+                            });
+                            parse_options.report_file(file_path, &result);
+                            if result.is_ok() {
+                                files_parsed += 1;
+                                if let Ok(metadata) = fs::metadata(file_path) {
+                                    if let Ok(modified) = metadata.modified() {
+                                        existing_graph.track_file_metadata(
+                                            file_path,
+                                            format!("{:?}", modified),
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    "rust" | "rs" => {
+                        let mut parser = RustParser::new()?.with_options(parse_options.clone());
+                        for file_path in &files_to_parse {
+                            let result = existing_graph.reindex_file(file_path, |graph| {
+                                parser.parse_file(file_path, graph)
+                            });
+                            parse_options.report_file(file_path, &result);
+                            if result.is_ok() {
                                 files_parsed += 1;
                                 if let Ok(metadata) = fs::metadata(file_path) {
                                     if let Ok(modified) = metadata.modified() {
@@ -446,17 +652,29 @@ fn main() -> Result<()> {
                     _ => unreachable!(),
                 }
 
+                if had_degrees {
+                    for changed_file in changed_files {
+                        stale_degrees.extend(
+                            existing_graph.degree_dependents(&changed_file.to_string_lossy()),
+                        );
+                    }
+                    existing_graph.refresh_degrees(&stale_degrees);
+                } else if *precompute_degrees {
+                    existing_graph.precompute_degrees();
+                }
+
                 // Update metadata
                 existing_graph.metadata.generated_at = chrono::Utc::now().to_rfc3339();
                 existing_graph.metadata.stats.files_parsed = files_parsed;
                 existing_graph.metadata.stats.total_nodes = existing_graph.nodes.len();
                 existing_graph.metadata.stats.total_edges = existing_graph.edges.len();
-                existing_graph.metadata.git_commit_hash = get_git_commit_hash(directory);
+                existing_graph.metadata.git_commit_hash =
+                    get_git_commit_hash(directory, !cli.no_git);
 
                 let files_cached =
                     total_files_before - deleted_files.len() - changed_files.len() + files_parsed;
 
-                if !cli.quiet {
+                if !quiet {
                     let node_change = existing_graph.nodes.len() as i32 - total_files_before as i32;
                     let sign = if node_change >= 0 { "+" } else { "" };
                     println!(
@@ -482,7 +700,7 @@ fn main() -> Result<()> {
                 existing_graph
             } else {
                 // FULL GENERATION MODE
-                if !cli.quiet {
+                if !quiet {
                     println!("{}", "Indexing codebase...".green().bold());
                 }
 
@@ -496,53 +714,26 @@ fn main() -> Result<()> {
                     None
                 };
 
-                match lang {
-                    "go" => {
-                        let mut parser = GoParser::new()?;
-                        parser.parse_directory(directory, &mut new_graph)?;
-                    }
-                    "typescript" | "ts" => {
-                        let mut parser = TypeScriptParser::new(Language::TypeScript)?;
-                        parser.parse_directory(directory, &mut new_graph)?;
-                    }
-                    "javascript" | "js" => {
-                        let mut parser = TypeScriptParser::new(Language::JavaScript)?;
-                        parser.parse_directory(directory, &mut new_graph)?;
-                    }
-                    "python" | "py" => {
-                        let mut parser = PythonParser::new()?;
-                        parser.parse_directory(directory, &mut new_graph)?;
-                    }
-                    _ => unreachable!(),
-                }
+                parse_directory(lang, directory, &parse_options, &mut new_graph)?;
 
                 // Record parse duration
                 if let (Some(ref mut timer), Some(start)) = (&mut bench_timer, parse_start) {
                     timer.parsing_duration = Some(start.elapsed());
                 }
 
-                // Track all files in metadata
+                // Track the parsed files in metadata
                 use std::fs;
-                use walkdir::WalkDir;
-                for entry in WalkDir::new(directory)
-                    .into_iter()
-                    .filter_map(|e| e.ok())
-                    .filter(|e| e.path().extension().and_then(|s| s.to_str()) == Some(file_ext))
-                {
-                    let path = entry.path();
-                    if let Ok(metadata) = fs::metadata(path) {
+                for path in parse_options.parsed_files.paths() {
+                    if let Ok(metadata) = fs::metadata(&path) {
                         if let Ok(modified) = metadata.modified() {
-                            new_graph.track_file_metadata(
-                                &path.to_path_buf(),
-                                format!("{:?}", modified),
-                            );
+                            new_graph.track_file_metadata(&path, format!("{:?}", modified));
                         }
                     }
                 }
 
-                new_graph.metadata.git_commit_hash = get_git_commit_hash(directory);
+                new_graph.metadata.git_commit_hash = get_git_commit_hash(directory, !cli.no_git);
 
-                if !cli.quiet {
+                if !quiet {
                     println!(
                         "{} Indexed {} nodes and {} edges",
                         "✓".green().bold(),
@@ -558,6 +749,98 @@ fn main() -> Result<()> {
 
                 new_graph
             };
+            parse_options.warnings.finish();
+
+            // Keep paths meaningful once the temporary extraction is gone
+            if let (Some(extracted), Some(archive_path)) = (&extracted, from_archive) {
+                graph.relativize_paths(extracted.root());
+                graph.metadata.root_path = archive_path.to_string_lossy().to_string();
+            }
+
+            // Collapse vendored copies before metadata is attached to nodes
+            if *dedup_identical {
+                let removed = graph.dedup_identical_nodes();
+                if !quiet {
+                    println!(
+                        "  {} Duplicate nodes collapsed: {}",
+                        "→".blue(),
+                        removed.to_string().cyan()
+                    );
+                }
+            }
+
+            // A full index stores degrees once the node set is final; incremental
+            // updates refreshed only the nodes they touched, unless dedup
+            // rewrote edges across the whole graph
+            if (*precompute_degrees && !should_use_incremental)
+                || (*dedup_identical && graph.has_precomputed_degrees())
+            {
+                graph.precompute_degrees();
+            }
+
+            // Merge organizational metadata (owners, criticality, ...) from a sidecar file
+            if let Some(metadata_path) = metadata_file {
+                let sidecar = SidecarMetadata::load(metadata_path)?;
+                let enriched = sidecar.apply(&mut graph)?;
+                if !quiet {
+                    println!(
+                        "  {} Metadata applied to {} nodes",
+                        "→".blue(),
+                        enriched.to_string().cyan()
+                    );
+                }
+            }
+
+            // Annotate nodes with their most recent commit (opt-in: one blame per file)
+            if *annotate_blame {
+                let annotated = blame::annotate_graph(&mut graph)?;
+                if !quiet {
+                    println!(
+                        "  {} Blame recorded for {} nodes",
+                        "→".blue(),
+                        annotated.to_string().cyan()
+                    );
+                }
+            }
+
+            // Tag nodes from test files so analyses can tell test code apart
+            graph.tag_tests();
+
+            // Pin call edges to their target node where the callee name is
+            // unambiguous, so traversals can skip the by-name lookup
+            let resolved = graph.resolve_edges();
+            if !quiet {
+                println!(
+                    "  {} Call targets resolved: {}/{}",
+                    "→".blue(),
+                    resolved.to_string().cyan(),
+                    graph.edges.len()
+                );
+            }
+
+            // Location-independent IDs, once edges point at their final targets
+            if *content_ids {
+                let collisions = graph.assign_content_ids();
+                if !quiet {
+                    println!(
+                        "  {} Content IDs assigned ({} collisions suffixed)",
+                        "→".blue(),
+                        collisions.to_string().cyan()
+                    );
+                }
+            }
+
+            // Nodes without any edges hint at unresolved calls
+            graph.ensure_indices();
+            graph.metadata.stats.isolated_nodes =
+                graph.filter_isolated(graph.nodes.iter().collect()).len();
+            if !quiet {
+                println!(
+                    "  {} Isolated nodes (no callers or callees): {}",
+                    "→".blue(),
+                    graph.metadata.stats.isolated_nodes.to_string().cyan()
+                );
+            }
 
             // Save in binary format (compressed)
             let serialization_start = if bench_timer.is_some() {
@@ -566,23 +849,81 @@ fn main() -> Result<()> {
                 None
             };
 
-            fast_compressed::save_to_file(&graph, &output.to_string_lossy())?;
+            graph.ensure_indices();
+            let shard_count = match shards_dir {
+                Some(dir) => Some(shards::save_shards(&graph, dir)?.shards.len()),
+                None => {
+                    save_graph(&graph, output, *embed_indices, *compression_level)?;
+                    None
+                }
+            };
+
+            if let Some(manifest_path) = manifest {
+                // An incremental run parses only changed files; the rest are
+                // the ones earlier runs parsed
+                let files: Vec<PathBuf> = if should_use_incremental {
+                    graph
+                        .metadata
+                        .file_metadata
+                        .keys()
+                        .map(PathBuf::from)
+                        .collect()
+                } else {
+                    let parsed = parse_options.parsed_files.paths();
+                    match &extracted {
+                        Some(extracted) => parsed
+                            .iter()
+                            .map(|path| {
+                                path.strip_prefix(extracted.root())
+                                    .unwrap_or(path)
+                                    .to_path_buf()
+                            })
+                            .collect(),
+                        None => parsed,
+                    }
+                };
+                IndexManifest::from_graph(&graph, directory, &files)?.save(manifest_path)?;
+            }
 
             // Record serialization duration
             if let (Some(ref mut timer), Some(start)) = (&mut bench_timer, serialization_start) {
                 timer.serialization_duration = Some(start.elapsed());
             }
 
-            if !cli.quiet {
-                println!(
-                    "  {} Output: {}",
-                    "→".blue(),
-                    output.display().to_string().cyan()
-                );
+            if let Some(events) = &events {
+                events.emit(&Event::Done {
+                    nodes: graph.nodes.len(),
+                    edges: graph.edges.len(),
+                    results: None,
+                    duration_ms: index_start.elapsed().as_millis(),
+                });
             }
 
-            // Display benchmark results if enabled
-            if *benchmark {
+            if !quiet {
+                match (shards_dir, shard_count) {
+                    (Some(dir), Some(count)) => println!(
+                        "  {} Output: {} ({} shards)",
+                        "→".blue(),
+                        dir.display().to_string().cyan(),
+                        count
+                    ),
+                    _ => println!(
+                        "  {} Output: {}",
+                        "→".blue(),
+                        output.display().to_string().cyan()
+                    ),
+                }
+                if let Some(manifest_path) = manifest {
+                    println!(
+                        "  {} Manifest: {}",
+                        "→".blue(),
+                        manifest_path.display().to_string().cyan()
+                    );
+                }
+            }
+
+            // Display benchmark results if enabled
+            if *benchmark {
                 if let Some(timer) = bench_timer {
                     use std::fs;
                     let output_size = fs::metadata(output).map(|m| m.len()).unwrap_or(0);
@@ -609,7 +950,7 @@ fn main() -> Result<()> {
                                         "⚠".yellow(),
                                         e
                                     );
-                                } else if !cli.quiet {
+                                } else if !quiet {
                                     println!(
                                         "  {} Benchmark JSON: {}",
                                         "→".blue(),
@@ -633,19 +974,94 @@ fn main() -> Result<()> {
         Commands::Query {
             graph: graph_file,
             output,
+            shards_dir,
             count,
             limit,
             name,
+            regex,
             r#type,
             package,
             file,
-            tag: _,
+            tags,
+            tag_mode,
+            doc_contains,
+            min_complexity,
+            max_complexity,
+            leaves,
+            isolated,
+            sort,
+            exclude_generated_edges,
+            exclude_dunder,
+            relations,
+            group_by,
+            limit_per_group,
         } => {
             use std::time::Instant;
 
+            let group_by = match group_by.as_deref() {
+                None => None,
+                Some("package") => Some(GroupBy::Package),
+                Some("file") => Some(GroupBy::File),
+                Some(other) => anyhow::bail!("Unknown group: {}. Use: package, file", other),
+            };
+            let topo_sort = match sort.as_deref() {
+                None => false,
+                Some("topo") => true,
+                Some(other) => anyhow::bail!("Unknown sort: {}. Use: topo", other),
+            };
+
             let load_start = Instant::now();
-            let graph = load_graph(graph_file)?;
+            if let Some(events) = &events {
+                events.emit(&Event::Start { command: "query" });
+            }
+            let mut graph = match shards_dir {
+                Some(dir) => {
+                    // Read only the shard of --package, or those defining an
+                    // exact --name; anything else needs every shard
+                    let manifest = shards::ShardManifest::load(dir)?;
+                    let exact_name = name.as_deref().filter(|n| !*regex && !n.contains('*'));
+                    let selected: HashSet<&str> = match (package, exact_name) {
+                        (Some(package), _) => manifest
+                            .shard_for_package(package)
+                            .map(|shard| shard.file.as_str())
+                            .into_iter()
+                            .collect(),
+                        (None, Some(name)) => manifest
+                            .shards_with_name(name)
+                            .into_iter()
+                            .map(|shard| shard.file.as_str())
+                            .collect(),
+                        (None, None) => manifest.shards.iter().map(|s| s.file.as_str()).collect(),
+                    };
+                    if cli.verbose {
+                        eprintln!(
+                            "Loading {}/{} shards from {}",
+                            selected.len(),
+                            manifest.shards.len(),
+                            dir.display()
+                        );
+                    }
+                    shards::load_shards(dir, &manifest, |shard| {
+                        selected.contains(shard.file.as_str())
+                    })?
+                }
+                None => load_graph(graph_file, cli.validate_cache, cli.explain_cache)?,
+            };
             let load_time = load_start.elapsed();
+            if let Some(events) = &events {
+                events.emit(&Event::GraphLoaded {
+                    nodes: graph.nodes.len(),
+                    edges: graph.edges.len(),
+                });
+            }
+
+            if *exclude_generated_edges {
+                graph.remove_generated_edges();
+            }
+
+            if *exclude_dunder {
+                graph.remove_dunder();
+            }
 
             let query_start = Instant::now();
 
@@ -657,7 +1073,12 @@ fn main() -> Result<()> {
 
             // Priority 1: Exact name match (O(1) hash lookup)
             if let Some(name_filter) = name {
-                if !name_filter.contains('*') {
+                if *regex {
+                    let pattern = regex::Regex::new(name_filter)
+                        .map_err(|e| anyhow::anyhow!("Invalid --name regex: {}", e))?;
+                    nodes = graph.get_nodes_by_name_regex(&pattern);
+                    using_index = true;
+                } else if !name_filter.contains('*') {
                     // Exact match - use by_name index
                     nodes = graph.get_nodes_by_name(name_filter);
                     using_index = true;
@@ -674,13 +1095,7 @@ fn main() -> Result<()> {
 
             // Priority 2: Type filter (O(1) hash lookup)
             if let Some(type_filter) = r#type {
-                let node_type = match type_filter.as_str() {
-                    "function" => NodeType::Function,
-                    "method" => NodeType::Method,
-                    "handler" => NodeType::HttpHandler,
-                    "middleware" => NodeType::Middleware,
-                    _ => anyhow::bail!("Unknown node type: {}", type_filter),
-                };
+                let node_type = commands::parse_node_type(type_filter)?;
 
                 if !using_index {
                     // No previous filter - use type index directly
@@ -694,6 +1109,25 @@ fn main() -> Result<()> {
                 }
             }
 
+            // Priority 2b: Tag filter (tag index, set intersection/union)
+            if !tags.is_empty() {
+                let mode = match tag_mode.as_str() {
+                    "and" => TagMode::And,
+                    "or" => TagMode::Or,
+                    _ => anyhow::bail!("Unknown tag mode: {}. Use: and, or", tag_mode),
+                };
+                let tag_refs: Vec<&str> = tags.iter().map(String::as_str).collect();
+                let tag_nodes = graph.get_nodes_by_tags(&tag_refs, mode);
+
+                if !using_index {
+                    nodes = tag_nodes;
+                    using_index = true;
+                } else {
+                    let tag_set: HashSet<_> = tag_nodes.iter().map(|n| &n.id).collect();
+                    nodes.retain(|n| tag_set.contains(&n.id));
+                }
+            }
+
             // If no indexed filters applied yet, start with all nodes
             if !using_index {
                 nodes = graph.nodes.iter().collect();
@@ -709,18 +1143,52 @@ fn main() -> Result<()> {
                 nodes.retain(|n| n.file_path.to_string_lossy().contains(file_filter));
             }
 
+            // Priority 5: Documentation filter (O(n) scan on filtered results)
+            if let Some(doc_filter) = doc_contains {
+                nodes.retain(|n| n.doc_contains(doc_filter));
+            }
+
+            // Priority 6: Complexity range (O(n): fan-in/fan-out computed per remaining node)
+            if min_complexity.is_some() || max_complexity.is_some() {
+                nodes = graph.filter_by_complexity(nodes, *min_complexity, *max_complexity);
+            }
+
+            // Priority 7: Leaf functions (O(k) over each remaining node's outgoing edges)
+            if *leaves {
+                nodes = graph.filter_leaves(nodes);
+            }
+
+            // Priority 8: Isolated nodes (O(1) index membership per remaining node)
+            if *isolated {
+                nodes = graph.filter_isolated(nodes);
+            }
+
+            // Ordering before --limit, so the limit keeps the innermost callees
+            if topo_sort {
+                nodes = graph.sort_topologically(nodes);
+            }
+
             if let Some(limit_count) = limit {
                 nodes.truncate(*limit_count);
             }
 
             let query_time = query_start.elapsed();
 
+            if let Some(events) = &events {
+                events.emit(&Event::Done {
+                    nodes: graph.nodes.len(),
+                    edges: graph.edges.len(),
+                    results: Some(nodes.len()),
+                    duration_ms: load_start.elapsed().as_millis(),
+                });
+            }
+
             // Print timing info in verbose mode or as a comment
             if cli.verbose {
                 eprintln!(
-                    "⏱  Load time: {:.3}s | Query time: {:.3}s",
-                    load_time.as_secs_f64(),
-                    query_time.as_secs_f64()
+                    "⏱  Load time: {} | Query time: {}",
+                    humanize_duration(load_time),
+                    humanize_duration(query_time)
                 );
             }
 
@@ -729,54 +1197,102 @@ fn main() -> Result<()> {
                 return Ok(());
             }
 
+            // Relations are only shown for a single resolved node
+            let node_relations = match nodes.as_slice() {
+                [node] if *relations => graph.node_relations(&node.id, RELATIONS_LIMIT),
+                _ => None,
+            };
+
+            if let Some(group_by) = group_by {
+                let groups = group_nodes(&nodes, group_by, *limit_per_group);
+                match output.as_str() {
+                    "table" => {
+                        if groups.is_empty() {
+                            println!("{}", "No nodes found".yellow());
+                            return Ok(());
+                        }
+
+                        for group in &groups {
+                            println!(
+                                "{} ({})",
+                                group.group.bold(),
+                                group.nodes.len() + group.more
+                            );
+                            for node in &group.nodes {
+                                println!(
+                                    "  {:<40} {:<15} {:<10}",
+                                    node.display_name(),
+                                    node.node_type.to_string(),
+                                    node.line
+                                );
+                            }
+                            if let Some(note) = group.overflow_note() {
+                                println!("  {}", note.dimmed());
+                            }
+                            println!();
+                        }
+
+                        println!(
+                            "{} {} nodes found in {} groups",
+                            "→".blue(),
+                            nodes.len().to_string().cyan(),
+                            groups.len()
+                        );
+                    }
+                    "json" => {
+                        let json = serde_json::to_string_pretty(&groups)?;
+                        println!("{}", json);
+                    }
+                    _ => anyhow::bail!("--group-by supports table and json output"),
+                }
+                return Ok(());
+            }
+
             match output.as_str() {
                 "table" => {
+                    commands::print_node_table(&nodes);
                     if nodes.is_empty() {
-                        println!("{}", "No nodes found".yellow());
                         return Ok(());
                     }
 
-                    println!(
-                        "{:<40} {:<15} {:<30} {:<10}",
-                        "Name".bold(),
-                        "Type".bold(),
-                        "Package".bold(),
-                        "Line".bold()
-                    );
-                    println!("{}", "-".repeat(95));
-
-                    for node in &nodes {
-                        let type_str = match node.node_type {
-                            NodeType::Function => "Function".green(),
-                            NodeType::Method => "Method".blue(),
-                            NodeType::HttpHandler => "HTTP Handler".yellow(),
-                            NodeType::Middleware => "Middleware".magenta(),
-                        };
-
+                    if let Some(node_relations) = &node_relations {
+                        for (title, list) in [
+                            ("Top callers", &node_relations.callers),
+                            ("Top callees", &node_relations.callees),
+                        ] {
+                            println!();
+                            println!("{}", title.bold());
+                            if list.is_empty() {
+                                println!("  {}", "(none)".dimmed());
+                            }
+                            for relation in list {
+                                println!("  {:<40} {} calls", relation.name, relation.calls);
+                            }
+                        }
+                    } else if *relations {
                         println!(
-                            "{:<40} {:<15} {:<30} {:<10}",
-                            node.name,
-                            format!("{}", type_str),
-                            node.package,
-                            node.line
+                            "{} --relations needs exactly one matching node",
+                            "⚠".yellow()
                         );
                     }
-
-                    println!();
-                    println!(
-                        "{} {} nodes found",
-                        "→".blue(),
-                        nodes.len().to_string().cyan()
-                    );
                 }
                 "json" => {
-                    let json = serde_json::to_string_pretty(&nodes)?;
+                    let json = match (&node_relations, nodes.as_slice()) {
+                        (Some(node_relations), [node]) => serde_json::to_string_pretty(
+                            &serde_json::json!({ "node": node, "relations": node_relations }),
+                        )?,
+                        _ => serde_json::to_string_pretty(&nodes)?,
+                    };
                     println!("{}", json);
                 }
+                "shell" => {
+                    let names: Vec<&str> = nodes.iter().map(|n| n.name.as_str()).collect();
+                    println!("{}", shell::to_shell_array("CODENAV_RESULTS", &names));
+                }
                 "tree" => {
                     for node in &nodes {
                         println!("├─ {}", node.name.cyan().bold());
-                        println!("│  └─ Type: {:?}", node.node_type);
+                        println!("│  └─ Type: {}", node.node_type);
                         println!("│  └─ Package: {}", node.package);
                         println!("│  └─ File: {}", node.file_path.display());
                         println!("│  └─ Line: {}", node.line);
@@ -791,11 +1307,14 @@ fn main() -> Result<()> {
             graph: graph_file,
             from,
             depth,
+            min_depth,
+            max_branching,
             output,
             show_lines,
-            filter: _,
+            filter,
+            reverse,
         } => {
-            let graph = load_graph(graph_file)?;
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
 
             // Find the starting node
             let nodes = graph.get_nodes_by_name(from);
@@ -804,7 +1323,35 @@ fn main() -> Result<()> {
             }
 
             let start_node = nodes[0];
-            let traces = graph.trace_dependencies(&start_node.id, *depth);
+            if *reverse {
+                let mut traces = graph.trace_callers(&start_node.id, *depth);
+                traces.retain(|trace| trace.depth >= *min_depth);
+                if traces.is_empty() {
+                    if !cli.quiet {
+                        println!("{}", "No callers found".yellow());
+                    }
+                    return Ok(());
+                }
+                match output.as_str() {
+                    "tree" => commands::print_impact_tree(&graph, from, &traces, *show_lines),
+                    "json" => {
+                        let json = serde_json::to_string_pretty(&traces)?;
+                        println!("{}", json);
+                    }
+                    _ => anyhow::bail!("Unknown output format: {}", output),
+                }
+                return Ok(());
+            }
+
+            let mut traces = graph.trace_dependencies_from_depth(
+                &start_node.id,
+                *min_depth,
+                *depth,
+                *max_branching,
+            );
+            if let Some(pattern) = filter {
+                traces = graph.filter_trace(traces, pattern);
+            }
 
             if traces.is_empty() {
                 if !cli.quiet {
@@ -815,32 +1362,13 @@ fn main() -> Result<()> {
 
             match output.as_str() {
                 "tree" => {
-                    println!("{}", format!("Dependencies of {}", from).bold());
-                    println!();
-
-                    let mut current_depth = 0;
-                    for trace in &traces {
-                        if trace.depth > current_depth {
-                            current_depth = trace.depth;
-                        }
-
-                        let indent = "  ".repeat(trace.depth);
-                        let line_info = if *show_lines {
-                            format!(" ({}:{})", trace.file_path.display(), trace.line)
-                        } else {
-                            String::new()
-                        };
-
-                        println!(
-                            "{}├─ {}{}",
-                            indent,
-                            trace.to_name.cyan(),
-                            line_info.dimmed()
-                        );
-                    }
-
-                    println!();
-                    println!("{} {} dependencies found", "→".blue(), traces.len());
+                    let root_omitted = max_branching.map_or(0, |limit| {
+                        graph
+                            .get_outgoing_edges(&start_node.id)
+                            .len()
+                            .saturating_sub(limit)
+                    });
+                    commands::print_trace_tree(from, &traces, *show_lines, root_omitted)
                 }
                 "json" => {
                     let json = serde_json::to_string_pretty(&traces)?;
@@ -857,7 +1385,7 @@ fn main() -> Result<()> {
             output,
             show_lines,
         } => {
-            let graph = load_graph(graph_file)?;
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
             let callers = graph.find_callers(function);
 
             if *count {
@@ -873,63 +1401,189 @@ fn main() -> Result<()> {
             }
 
             match output.as_str() {
-                "tree" => {
-                    println!("{}", format!("Callers of {}", function).bold());
+                "tree" => commands::print_callers_tree(&graph, function, &callers, *show_lines),
+                "json" => {
+                    let json = serde_json::to_string_pretty(&callers)?;
+                    println!("{}", json);
+                }
+                "table" => commands::print_callers_table(&graph, &callers),
+                _ => anyhow::bail!("Unknown output format: {}", output),
+            }
+        }
+
+        Commands::Implementers {
+            graph: graph_file,
+            interface,
+            output,
+        } => {
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
+            let implementers = graph.find_implementers(interface);
+
+            if implementers.is_empty() {
+                if !cli.quiet {
+                    println!(
+                        "{}",
+                        format!("No implementers found for {}", interface).yellow()
+                    );
+                }
+                return Ok(());
+            }
+
+            match output.as_str() {
+                "table" => {
+                    println!(
+                        "{:<40} {:<30} {:<10}",
+                        "Implementer".bold(),
+                        "File".bold(),
+                        "Line".bold()
+                    );
+                    println!("{}", "-".repeat(80));
+
+                    for (node, _) in &implementers {
+                        println!(
+                            "{:<40} {:<30} {:<10}",
+                            node.name,
+                            node.file_path.display(),
+                            node.line
+                        );
+                    }
+
                     println!();
+                    println!("{} {} implementers found", "→".blue(), implementers.len());
+                }
+                "json" => {
+                    let nodes: Vec<_> = implementers.iter().map(|(node, _)| node).collect();
+                    let json = serde_json::to_string_pretty(&nodes)?;
+                    println!("{}", json);
+                }
+                _ => anyhow::bail!("Unknown output format: {}", output),
+            }
+        }
 
-                    for caller in &callers {
-                        let line_info = if *show_lines {
-                            format!(" ({}:{})", caller.file_path.display(), caller.line)
-                        } else {
-                            String::new()
-                        };
-
-                        // Try to get the calling function name from the node
-                        if let Some(node) = graph.get_node_by_id(&caller.from) {
-                            println!("├─ {}{}", node.name.cyan(), line_info.dimmed());
-                        } else {
-                            println!("├─ {}{}", caller.from.cyan(), line_info.dimmed());
-                        }
+        Commands::Xref {
+            graph: graph_file,
+            name,
+            output,
+        } => {
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
+            let xref = graph.xref(name);
+
+            if xref.definitions.is_empty() && xref.callers.is_empty() {
+                if !cli.quiet {
+                    println!("{}", format!("No references found for {}", name).yellow());
+                }
+                return Ok(());
+            }
+
+            match output.as_str() {
+                "table" => {
+                    let node_name = |id: &str| {
+                        graph
+                            .get_node_by_id(id)
+                            .map_or(id.to_string(), |n| n.name.clone())
+                    };
+
+                    println!("{}", format!("Definitions of {}", name).bold());
+                    println!("{}", "-".repeat(80));
+                    for node in &xref.definitions {
+                        println!(
+                            "{:<40} {}:{}",
+                            node.signature.trim(),
+                            node.file_path.display(),
+                            node.line
+                        );
+                    }
+
+                    println!();
+                    println!("{}", format!("Call sites ({})", xref.callers.len()).bold());
+                    println!("{}", "-".repeat(80));
+                    for edge in &xref.callers {
+                        println!(
+                            "{:<30} {:<40} {}",
+                            node_name(&edge.from).cyan(),
+                            format!("{}:{}", edge.file_path.display(), edge.line),
+                            edge.call_site.lines().next().unwrap_or("").dimmed()
+                        );
                     }
 
                     println!();
-                    println!("{} {} callers found", "→".blue(), callers.len());
+                    println!("{}", format!("Calls ({})", xref.callees.len()).bold());
+                    println!("{}", "-".repeat(80));
+                    for edge in &xref.callees {
+                        println!(
+                            "{:<30} {:<40} {}",
+                            edge.to.cyan(),
+                            format!("{}:{}", edge.file_path.display(), edge.line),
+                            edge.call_site.lines().next().unwrap_or("").dimmed()
+                        );
+                    }
                 }
                 "json" => {
-                    let json = serde_json::to_string_pretty(&callers)?;
+                    let json = serde_json::to_string_pretty(&xref)?;
                     println!("{}", json);
                 }
+                _ => anyhow::bail!("Unknown output format: {}", output),
+            }
+        }
+
+        Commands::AffectedTests {
+            graph: graph_file,
+            changed_functions,
+            since,
+            output,
+        } => {
+            if changed_functions.is_empty() && since.is_none() {
+                anyhow::bail!("Name changed functions or pass --since OLD_GRAPH");
+            }
+            let mut graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
+            graph.tag_tests();
+
+            let mut changed = changed_functions.clone();
+            if let Some(old_graph) = since {
+                let old = load_graph(old_graph, cli.validate_cache, cli.explain_cache)?;
+                changed.extend(graph.changed_since(&old));
+            }
+
+            let tests = graph.affected_tests(&changed);
+
+            match output.as_str() {
                 "table" => {
+                    if tests.is_empty() {
+                        if !cli.quiet {
+                            println!("{}", "No affected tests".yellow());
+                        }
+                        return Ok(());
+                    }
                     println!(
                         "{:<40} {:<30} {:<10}",
-                        "Caller".bold(),
+                        "Test".bold(),
                         "File".bold(),
                         "Line".bold()
                     );
                     println!("{}", "-".repeat(80));
-
-                    for caller in &callers {
-                        let caller_name = graph
-                            .get_node_by_id(&caller.from)
-                            .map(|n| n.name.as_str())
-                            .unwrap_or(&caller.from);
-
+                    for test in &tests {
                         println!(
                             "{:<40} {:<30} {:<10}",
-                            caller_name,
-                            caller
-                                .file_path
-                                .file_name()
-                                .and_then(|n| n.to_str())
-                                .unwrap_or(""),
-                            caller.line
+                            test.display_name().cyan(),
+                            test.file_path.display(),
+                            test.line
                         );
                     }
-
                     println!();
-                    println!("{} {} callers found", "→".blue(), callers.len());
+                    println!(
+                        "{} {} of {} tests affected",
+                        "→".blue(),
+                        tests.len(),
+                        graph.get_nodes_by_tags(&[TEST_TAG], TagMode::And).len()
+                    );
                 }
-                _ => anyhow::bail!("Unknown output format: {}", output),
+                "json" => println!("{}", serde_json::to_string_pretty(&tests)?),
+                "names" => {
+                    for test in &tests {
+                        println!("{}", test.name);
+                    }
+                }
+                _ => anyhow::bail!("Unknown output format: {}. Use: table, json, names", output),
             }
         }
 
@@ -942,7 +1596,7 @@ fn main() -> Result<()> {
             max_depth,
             output,
         } => {
-            let graph = load_graph(graph_file)?;
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
 
             // Find the starting node
             let from_nodes = graph.get_nodes_by_name(from);
@@ -952,26 +1606,31 @@ fn main() -> Result<()> {
 
             let from_node = from_nodes[0];
 
-            let paths = if let Some(n) = limit {
-                // Find N paths using DFS with early termination
-                let mut found_paths = graph.find_paths_limited(&from_node.id, to, *max_depth, *n);
-                found_paths.sort_by_key(|p| p.len());
-                found_paths
-            } else if *all {
-                // Find all paths (warning: may be very slow)
-                let mut found_paths =
-                    graph.find_paths_limited(&from_node.id, to, *max_depth, usize::MAX);
-                found_paths.sort_by_key(|p| p.len());
-                found_paths
-            } else {
-                // Default: Find shortest path using BFS (fastest)
-                if let Some(shortest_path) = graph.find_shortest_path(&from_node.id, to, *max_depth)
-                {
-                    vec![shortest_path]
-                } else {
-                    Vec::new()
+            // One `file:line` per step, suitable for `$EDITOR +line file` loops
+            if output == "jumplist" {
+                if limit.is_some() || *all {
+                    anyhow::bail!("jumplist output only supports the shortest path");
                 }
-            };
+
+                match graph.find_shortest_path_nodes(&from_node.id, to, *max_depth) {
+                    Some(steps) => {
+                        for step in steps {
+                            println!("{}", step.location());
+                        }
+                    }
+                    None => {
+                        if !cli.quiet {
+                            eprintln!(
+                                "{}",
+                                format!("No path found from {} to {}", from, to).yellow()
+                            );
+                        }
+                    }
+                }
+                return Ok(());
+            }
+
+            let paths = commands::find_paths(&graph, &from_node.id, to, *limit, *all, *max_depth);
 
             if paths.is_empty() {
                 if !cli.quiet {
@@ -984,25 +1643,7 @@ fn main() -> Result<()> {
             }
 
             match output.as_str() {
-                "tree" => {
-                    println!("{}", format!("Paths from {} to {}", from, to).bold());
-                    println!();
-
-                    for (idx, path) in paths.iter().enumerate() {
-                        println!("{} Path {} (length: {})", "→".blue(), idx + 1, path.len());
-                        for (i, step) in path.iter().enumerate() {
-                            let prefix = if i == path.len() - 1 {
-                                "└─"
-                            } else {
-                                "├─"
-                            };
-                            println!("  {} {}", prefix, step.cyan());
-                        }
-                        println!();
-                    }
-
-                    println!("{} {} paths found", "→".blue(), paths.len());
-                }
+                "tree" => commands::print_paths_tree(from, to, &paths),
                 "json" => {
                     let json = serde_json::to_string_pretty(&paths)?;
                     println!("{}", json);
@@ -1011,149 +1652,754 @@ fn main() -> Result<()> {
             }
         }
 
-        Commands::Analyze {
+        Commands::Shell { graph: graph_file } => {
+            use std::io::IsTerminal;
+
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
+            let stdin = std::io::stdin();
+            let interactive = stdin.is_terminal();
+            if interactive && !cli.quiet {
+                println!(
+                    "Loaded {} nodes and {} edges. Type `help` for commands, `quit` to leave.",
+                    graph.nodes.len().to_string().cyan(),
+                    graph.edges.len().to_string().cyan()
+                );
+            }
+            commands::run_shell(&graph, stdin.lock(), interactive)?;
+        }
+
+        Commands::Explain {
             graph: graph_file,
-            analysis_type,
-            threshold,
-            limit,
+            from,
+            to,
+            max_depth,
             output,
         } => {
-            let graph = load_graph(graph_file)?;
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
 
-            match analysis_type.as_str() {
-                "complexity" => {
-                    let mut results: Vec<_> = graph
-                        .nodes
-                        .iter()
-                        .map(|node| {
-                            let metrics = graph.get_complexity(&node.id);
-                            (node, metrics)
-                        })
-                        .collect();
+            let from_nodes = graph.get_nodes_by_name(from);
+            if from_nodes.is_empty() {
+                anyhow::bail!("Starting function not found: {}", from);
+            }
+
+            let Some(hops) = graph.explain_path(&from_nodes[0].id, to, *max_depth) else {
+                if !cli.quiet {
+                    println!(
+                        "{}",
+                        format!("No path found from {} to {}", from, to).yellow()
+                    );
+                }
+                return Ok(());
+            };
+
+            match output.as_str() {
+                "text" => {
+                    println!("{}", format!("How {} reaches {}", from, to).bold());
+                    println!();
+                    for (idx, hop) in hops.iter().enumerate() {
+                        println!("{}. {}", idx + 1, hop.describe());
+                    }
+
+                    println!();
+                    println!("{}", "Signatures:".bold());
+                    let mut seen = HashSet::new();
+                    for (name, signature) in hops.iter().flat_map(|hop| {
+                        [
+                            (&hop.caller, &hop.caller_signature),
+                            (&hop.callee, &hop.callee_signature),
+                        ]
+                    }) {
+                        if seen.insert(name) {
+                            let signature =
+                                signature.as_deref().unwrap_or("(not defined in graph)");
+                            println!("  {:<30} {}", name.cyan(), signature.dimmed());
+                        }
+                    }
+                }
+                "json" => {
+                    let json = serde_json::to_string_pretty(&hops)?;
+                    println!("{}", json);
+                }
+                _ => anyhow::bail!("Unknown output format: {}. Use: text, json", output),
+            }
+        }
+
+        Commands::Stats {
+            graph: graph_file,
+            top,
+            output,
+        } => {
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
+            let mut summary = graph.summary();
+            let total_packages = summary.packages.len();
+            summary.packages.truncate(*top);
+
+            match output.as_str() {
+                "table" => {
+                    println!("{}", "Graph summary".bold());
+                    println!("{}", "-".repeat(50));
+                    println!("{:<30} {}", "Nodes", summary.total_nodes.to_string().cyan());
+                    println!("{:<30} {}", "Edges", summary.total_edges.to_string().cyan());
+                    println!("{:<30} {}", "Files", summary.files.to_string().cyan());
+                    println!("{:<30} {}", "Packages", total_packages.to_string().cyan());
+                    println!("{:<30} {:.2}", "Average fan-in", summary.avg_fan_in);
+                    println!("{:<30} {:.2}", "Average fan-out", summary.avg_fan_out);
+                    if let Some(node) = &summary.most_connected {
+                        println!(
+                            "{:<30} {} (fan-in {}, fan-out {})",
+                            "Most connected",
+                            node.name.cyan(),
+                            node.fan_in,
+                            node.fan_out
+                        );
+                    }
+
+                    for (title, counts) in [
+                        ("Node type", &summary.nodes_by_type),
+                        ("Edge type", &summary.edges_by_type),
+                    ] {
+                        println!();
+                        println!("{:<30} {}", title.bold(), "Count".bold());
+                        println!("{}", "-".repeat(50));
+                        for (kind, count) in counts {
+                            println!("{:<30} {}", kind, count);
+                        }
+                    }
+
+                    println!();
+                    println!("{:<30} {}", "Package".bold(), "Nodes".bold());
+                    println!("{}", "-".repeat(50));
+                    for package in &summary.packages {
+                        println!("{:<30} {}", package.package, package.nodes);
+                    }
+                    if total_packages > summary.packages.len() {
+                        println!(
+                            "{}",
+                            format!("(+{} more)", total_packages - summary.packages.len()).dimmed()
+                        );
+                    }
+                }
+                "json" => {
+                    let json = serde_json::to_string_pretty(&summary)?;
+                    println!("{}", json);
+                }
+                _ => anyhow::bail!("Unknown output format: {}", output),
+            }
+        }
+
+        Commands::Analyze {
+            graph: graph_file,
+            analysis_type,
+            file,
+            threshold,
+            scope_from,
+            scope_depth,
+            exclude_test_callers,
+            samples,
+            entrypoints,
+            limit,
+            output,
+        } => {
+            if *exclude_test_callers && analysis_type != "hotspots" {
+                anyhow::bail!("--exclude-test-callers is only supported with hotspots");
+            }
+            if samples.is_some() && analysis_type != "centrality" {
+                anyhow::bail!("--samples is only supported with centrality");
+            }
+            if !entrypoints.is_empty() && analysis_type != "dead-code" {
+                anyhow::bail!("--entrypoint is only supported with dead-code");
+            }
+
+            let mut graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
+
+            // Narrow the analysis to the subgraph around one function
+            if let Some(scope) = scope_from {
+                graph = graph.extract_subgraph(scope, *scope_depth);
+                if graph.nodes.is_empty() {
+                    anyhow::bail!("No nodes found starting from '{}'", scope);
+                }
+            }
+
+            match analysis_type.as_str() {
+                "complexity" => {
+                    let mut results: Vec<_> = graph
+                        .nodes
+                        .iter()
+                        .map(|node| {
+                            let metrics = graph.get_complexity(&node.id);
+                            (node, metrics)
+                        })
+                        .collect();
+
+                    // Sort by combined complexity
+                    results.sort_by(|a, b| {
+                        b.1.combined().cmp(&a.1.combined())
+                    });
+
+                    if let Some(limit_count) = limit {
+                        results.truncate(*limit_count);
+                    }
+
+                    match output.as_str() {
+                        "table" => {
+                            println!(
+                                "{:<40} {:<10} {:<10} {:<10}",
+                                "Function".bold(),
+                                "Fan-In".bold(),
+                                "Fan-Out".bold(),
+                                "Cyclomatic".bold()
+                            );
+                            println!("{}", "-".repeat(70));
+
+                            for (node, metrics) in &results {
+                                println!(
+                                    "{:<40} {:<10} {:<10} {:<10}",
+                                    node.name, metrics.fan_in, metrics.fan_out, metrics.cyclomatic
+                                );
+                            }
+
+                            println!();
+                            println!("{} {} nodes analyzed", "→".blue(), results.len());
+                        }
+                        "json" => {
+                            let json_results: Vec<_> = results
+                                .iter()
+                                .map(|(node, metrics)| {
+                                    serde_json::json!({
+                                        "name": node.name,
+                                        "fan_in": metrics.fan_in,
+                                        "fan_out": metrics.fan_out,
+                                        "cyclomatic": metrics.cyclomatic
+                                    })
+                                })
+                                .collect();
+                            let json = serde_json::to_string_pretty(&json_results)?;
+                            println!("{}", json);
+                        }
+                        _ => anyhow::bail!("Unknown output format: {}", output),
+                    }
+                }
+
+                "hotspots" => {
+                    let limit_count = limit.unwrap_or(20);
+                    if *exclude_test_callers {
+                        // Graphs indexed before test tagging existed
+                        graph.tag_tests();
+                    }
+                    let hotspots = graph.find_hotspots(limit_count, *exclude_test_callers);
+
+                    if hotspots.is_empty() {
+                        println!("{}", "No hotspots found".yellow());
+                        return Ok(());
+                    }
+
+                    match output.as_str() {
+                        "table" => {
+                            println!("{:<50} {:<15}", "Function".bold(), "Call Count".bold());
+                            println!("{}", "-".repeat(65));
+
+                            for hotspot in &hotspots {
+                                println!("{:<50} {:<15}", hotspot.name, hotspot.call_count);
+                            }
+
+                            println!();
+                            println!("{} {} hotspots found", "→".blue(), hotspots.len());
+                        }
+                        "json" => {
+                            let json = serde_json::to_string_pretty(&hotspots)?;
+                            println!("{}", json);
+                        }
+                        _ => anyhow::bail!("Unknown output format: {}", output),
+                    }
+                }
+
+                "centrality" => {
+                    let mut results = graph.betweenness_centrality_sampled(*samples);
+                    results.retain(|(_, score)| *score > 0.0);
+                    results.truncate(limit.unwrap_or(20));
+
+                    if results.is_empty() {
+                        println!("{}", "No functions lie between others".yellow());
+                        return Ok(());
+                    }
+
+                    match output.as_str() {
+                        "table" => {
+                            println!(
+                                "{:<40} {:>12}  {}",
+                                "Function".bold(),
+                                "Betweenness".bold(),
+                                "Location".bold()
+                            );
+                            println!("{}", "-".repeat(80));
+
+                            for (id, score) in &results {
+                                let node = graph.get_node_by_id(id);
+                                println!(
+                                    "{:<40} {:>12.2}  {}",
+                                    node.map_or(id.as_str(), |n| n.name.as_str()),
+                                    score,
+                                    node.map(|n| n.location()).unwrap_or_default()
+                                );
+                            }
+
+                            println!();
+                            println!("{} {} bottlenecks found", "→".blue(), results.len());
+                        }
+                        "json" => {
+                            let rows: Vec<_> = results
+                                .iter()
+                                .map(|(id, score)| {
+                                    serde_json::json!({
+                                        "id": id,
+                                        "name": graph.get_node_by_id(id).map(|n| &n.name),
+                                        "betweenness": score,
+                                    })
+                                })
+                                .collect();
+                            println!("{}", serde_json::to_string_pretty(&rows)?);
+                        }
+                        _ => anyhow::bail!("Unknown output format: {}", output),
+                    }
+                }
+
+                "dead-code" => {
+                    // Graphs indexed before test tagging existed
+                    graph.tag_tests();
+                    let mut dead = graph.find_dead_code(entrypoints);
+                    if let Some(limit_count) = limit {
+                        dead.truncate(*limit_count);
+                    }
+
+                    if dead.is_empty() {
+                        println!("{}", "No unreachable functions found".green());
+                        return Ok(());
+                    }
+
+                    match output.as_str() {
+                        "table" => {
+                            println!(
+                                "{:<40} {:<50} {}",
+                                "Function".bold(),
+                                "Location".bold(),
+                                "Reason".bold()
+                            );
+                            println!("{}", "-".repeat(110));
+
+                            for node in &dead {
+                                let reason = if graph.find_callers_of(&node.id).is_empty() {
+                                    "never called"
+                                } else {
+                                    "only called from dead code"
+                                };
+                                println!(
+                                    "{:<40} {:<50} {}",
+                                    node.display_name(),
+                                    node.location(),
+                                    reason.dimmed()
+                                );
+                            }
+
+                            println!();
+                            println!(
+                                "{} {} functions unreachable from entrypoints",
+                                "→".blue(),
+                                dead.len()
+                            );
+                        }
+                        "json" => {
+                            let json = serde_json::to_string_pretty(&dead)?;
+                            println!("{}", json);
+                        }
+                        _ => anyhow::bail!("Unknown output format: {}", output),
+                    }
+                }
+
+                "nesting" => {
+                    let threshold_val = threshold.unwrap_or(3);
+                    let mut results = graph.find_deeply_nested(threshold_val);
+
+                    if let Some(limit_count) = limit {
+                        results.truncate(*limit_count);
+                    }
+
+                    match output.as_str() {
+                        "table" => {
+                            if results.is_empty() {
+                                println!(
+                                    "{}",
+                                    format!("No functions nested deeper than {}", threshold_val)
+                                        .yellow()
+                                );
+                                return Ok(());
+                            }
+
+                            println!(
+                                "{:<40} {:<8} {:<50}",
+                                "Function".bold(),
+                                "Depth".bold(),
+                                "Location".bold()
+                            );
+                            println!("{}", "-".repeat(98));
+
+                            for (node, depth) in &results {
+                                println!("{:<40} {:<8} {:<50}", node.name, depth, node.location());
+                            }
+
+                            println!();
+                            println!(
+                                "{} {} functions nested deeper than {}",
+                                "→".blue(),
+                                results.len(),
+                                threshold_val
+                            );
+                        }
+                        "json" => {
+                            let json_results: Vec<_> = results
+                                .iter()
+                                .map(|(node, depth)| {
+                                    serde_json::json!({
+                                        "name": node.name,
+                                        "file": node.file_path,
+                                        "line": node.line,
+                                        "max_nesting": depth
+                                    })
+                                })
+                                .collect();
+                            let json = serde_json::to_string_pretty(&json_results)?;
+                            println!("{}", json);
+                        }
+                        _ => anyhow::bail!("Unknown output format: {}", output),
+                    }
+                }
+
+                "unused-params" => {
+                    let mut results = graph.find_unused_params();
+
+                    if let Some(limit_count) = limit {
+                        results.truncate(*limit_count);
+                    }
+
+                    match output.as_str() {
+                        "table" => {
+                            if results.is_empty() {
+                                println!("{}", "No unused parameters found".yellow());
+                                return Ok(());
+                            }
+
+                            println!(
+                                "{:<40} {:<30} {:<50}",
+                                "Function".bold(),
+                                "Unused".bold(),
+                                "Location".bold()
+                            );
+                            println!("{}", "-".repeat(120));
+
+                            for (node, params) in &results {
+                                println!(
+                                    "{:<40} {:<30} {:<50}",
+                                    node.display_name(),
+                                    params.join(", "),
+                                    node.location()
+                                );
+                            }
+
+                            println!();
+                            println!(
+                                "{} {} functions with unused parameters",
+                                "→".blue(),
+                                results.len()
+                            );
+                        }
+                        "json" => {
+                            let json_results: Vec<_> = results
+                                .iter()
+                                .map(|(node, params)| {
+                                    serde_json::json!({
+                                        "name": node.name,
+                                        "file": node.file_path,
+                                        "line": node.line,
+                                        "unused_params": params
+                                    })
+                                })
+                                .collect();
+                            let json = serde_json::to_string_pretty(&json_results)?;
+                            println!("{}", json);
+                        }
+                        _ => anyhow::bail!("Unknown output format: {}", output),
+                    }
+                }
+
+                "coupling" => {
+                    let threshold_val = threshold.unwrap_or(5);
+                    let mut coupling_data: std::collections::HashMap<String, usize> =
+                        std::collections::HashMap::new();
+
+                    // Prefer the distinct packages each package imports; graphs
+                    // without import edges fall back to counting call edges
+                    let package_imports = graph.package_imports();
+                    if !package_imports.is_empty() {
+                        for (package, imported) in package_imports {
+                            coupling_data.insert(package, imported.len());
+                        }
+                    } else {
+                        for edge in &graph.edges {
+                            // Extract package from node ID or edge
+                            if let Some(from_node) = graph.get_node_by_id(&edge.from) {
+                                let package = from_node.package.clone();
+                                *coupling_data.entry(package).or_insert(0) += 1;
+                            }
+                        }
+                    }
+
+                    let mut results: Vec<_> = coupling_data
+                        .into_iter()
+                        .filter(|(_, count)| *count >= threshold_val)
+                        .collect();
+
+                    results.sort_by_key(|r| std::cmp::Reverse(r.1));
+
+                    if let Some(limit_count) = limit {
+                        results.truncate(*limit_count);
+                    }
+
+                    println!("{:<40} {:<15}", "Package".bold(), "Dependencies".bold());
+                    println!("{}", "-".repeat(55));
+
+                    for (package, count) in &results {
+                        println!("{:<40} {:<15}", package, count);
+                    }
+
+                    println!();
+                    println!("{} {} packages above threshold", "→".blue(), results.len());
+                }
+
+                "circular" => {
+                    let mut cycles = graph.find_package_cycles();
+
+                    if let Some(limit_count) = limit {
+                        cycles.truncate(*limit_count);
+                    }
+
+                    match output.as_str() {
+                        "table" => {
+                            if cycles.is_empty() {
+                                println!("{}", "No circular package dependencies found".green());
+                            } else {
+                                println!("{}", "Circular Package Dependencies:".bold());
+                                println!("{}", "-".repeat(80));
+                                for (i, cycle) in cycles.iter().enumerate() {
+                                    println!("{}. {}", i + 1, cycle.join(" ↔ "));
+                                }
+                                println!();
+                                println!("{} {} cycles found", "→".blue(), cycles.len());
+                            }
+                        }
+                        "json" => {
+                            let json = serde_json::to_string_pretty(&cycles)?;
+                            println!("{}", json);
+                        }
+                        _ => anyhow::bail!("Unknown output format: {}", output),
+                    }
+                }
+
+                "topo-order" => match graph.topological_order() {
+                    Ok(order) => match output.as_str() {
+                        "table" => {
+                            println!("{:<6} {}", "#".bold(), "Package".bold());
+                            println!("{}", "-".repeat(46));
+                            for (i, package) in order.iter().enumerate() {
+                                println!("{:<6} {}", i + 1, package);
+                            }
+                            println!();
+                            println!(
+                                "{} {} packages, dependencies first",
+                                "→".blue(),
+                                order.len()
+                            );
+                        }
+                        "json" => {
+                            let json = serde_json::to_string_pretty(
+                                &serde_json::json!({ "order": order }),
+                            )?;
+                            println!("{}", json);
+                        }
+                        _ => anyhow::bail!("Unknown output format: {}", output),
+                    },
+                    Err(cycles) => {
+                        match output.as_str() {
+                            "table" => {
+                                println!("{}", "Circular Package Dependencies:".bold());
+                                println!("{}", "-".repeat(80));
+                                for (i, cycle) in cycles.iter().enumerate() {
+                                    println!("{}. {}", i + 1, cycle.join(" ↔ "));
+                                }
+                                println!();
+                            }
+                            "json" => {
+                                let json = serde_json::to_string_pretty(
+                                    &serde_json::json!({ "cycles": cycles }),
+                                )?;
+                                println!("{}", json);
+                            }
+                            _ => anyhow::bail!("Unknown output format: {}", output),
+                        }
+                        anyhow::bail!(
+                            "No topological order: {} package cycles (see analyze circular)",
+                            cycles.len()
+                        );
+                    }
+                },
 
-                    // Sort by combined complexity
-                    results.sort_by(|a, b| {
-                        (b.1.fan_in + b.1.fan_out).cmp(&(a.1.fan_in + a.1.fan_out))
-                    });
+                "file-layout" => {
+                    let file = file
+                        .as_deref()
+                        .ok_or_else(|| anyhow::anyhow!("file-layout requires --file <PATH>"))?;
 
-                    if let Some(limit_count) = limit {
-                        results.truncate(*limit_count);
-                    }
+                    let path = graph
+                        .find_file(file)?
+                        .ok_or_else(|| anyhow::anyhow!("No functions found in file: {}", file))?;
+
+                    // Count lines from disk when the source is still available
+                    let total_lines = std::fs::read_to_string(path)
+                        .ok()
+                        .map(|source| source.lines().count());
+
+                    let layout = graph
+                        .file_layout(path, total_lines)
+                        .ok_or_else(|| anyhow::anyhow!("No functions found in file: {}", file))?;
 
                     match output.as_str() {
                         "table" => {
+                            println!("{}", format!("Layout of {}", layout.file).bold());
+                            println!();
                             println!(
                                 "{:<40} {:<10} {:<10} {:<10}",
                                 "Function".bold(),
-                                "Fan-In".bold(),
-                                "Fan-Out".bold(),
-                                "Cyclomatic".bold()
+                                "Line".bold(),
+                                "End Line".bold(),
+                                "Gap Before".bold()
                             );
                             println!("{}", "-".repeat(70));
 
-                            for (node, metrics) in &results {
+                            for span in &layout.functions {
                                 println!(
                                     "{:<40} {:<10} {:<10} {:<10}",
-                                    node.name, metrics.fan_in, metrics.fan_out, metrics.cyclomatic
+                                    span.name, span.line, span.end_line, span.gap_before
                                 );
                             }
 
                             println!();
-                            println!("{} {} nodes analyzed", "→".blue(), results.len());
+                            println!(
+                                "{} {} of {} lines in functions ({:.1}%)",
+                                "→".blue(),
+                                layout.function_lines,
+                                layout.total_lines,
+                                layout.coverage_percent
+                            );
                         }
                         "json" => {
-                            let json_results: Vec<_> = results
-                                .iter()
-                                .map(|(node, metrics)| {
-                                    serde_json::json!({
-                                        "name": node.name,
-                                        "fan_in": metrics.fan_in,
-                                        "fan_out": metrics.fan_out,
-                                        "cyclomatic": metrics.cyclomatic
-                                    })
-                                })
-                                .collect();
-                            let json = serde_json::to_string_pretty(&json_results)?;
+                            let json = serde_json::to_string_pretty(&layout)?;
                             println!("{}", json);
                         }
                         _ => anyhow::bail!("Unknown output format: {}", output),
                     }
                 }
 
-                "hotspots" => {
-                    let limit_count = limit.unwrap_or(20);
-                    let hotspots = graph.find_hotspots(limit_count);
+                "package-metrics" => {
+                    let mut results = graph.package_coupling();
 
-                    if hotspots.is_empty() {
-                        println!("{}", "No hotspots found".yellow());
-                        return Ok(());
+                    if let Some(limit_count) = limit {
+                        results.truncate(*limit_count);
                     }
 
                     match output.as_str() {
                         "table" => {
-                            println!("{:<50} {:<15}", "Function".bold(), "Call Count".bold());
-                            println!("{}", "-".repeat(65));
+                            println!(
+                                "{:<40} {:>6} {:>6} {:>6}",
+                                "Package".bold(),
+                                "Ca".bold(),
+                                "Ce".bold(),
+                                "I".bold()
+                            );
+                            println!("{}", "-".repeat(61));
 
-                            for hotspot in &hotspots {
-                                println!("{:<50} {:<15}", hotspot.name, hotspot.call_count);
+                            for m in &results {
+                                println!(
+                                    "{:<40} {:>6} {:>6} {:>6.2}",
+                                    m.package, m.afferent, m.efferent, m.instability
+                                );
                             }
 
                             println!();
-                            println!("{} {} hotspots found", "→".blue(), hotspots.len());
+                            println!(
+                                "{} {} packages (Ca/Ce count edges crossing package boundaries)",
+                                "→".blue(),
+                                results.len()
+                            );
                         }
                         "json" => {
-                            let json = serde_json::to_string_pretty(&hotspots)?;
+                            let json = serde_json::to_string_pretty(&results)?;
                             println!("{}", json);
                         }
                         _ => anyhow::bail!("Unknown output format: {}", output),
                     }
                 }
 
-                "coupling" => {
-                    let threshold_val = threshold.unwrap_or(5);
-                    let mut coupling_data: std::collections::HashMap<String, usize> =
-                        std::collections::HashMap::new();
-
-                    for edge in &graph.edges {
-                        // Extract package from node ID or edge
-                        if let Some(from_node) = graph.get_node_by_id(&edge.from) {
-                            let package = from_node.package.clone();
-                            *coupling_data.entry(package).or_insert(0) += 1;
-                        }
-                    }
-
-                    let mut results: Vec<_> = coupling_data
-                        .into_iter()
-                        .filter(|(_, count)| *count >= threshold_val)
-                        .collect();
-
-                    results.sort_by(|a, b| b.1.cmp(&a.1));
+                "abstractness" => {
+                    let mut results = graph.package_abstractness();
 
                     if let Some(limit_count) = limit {
                         results.truncate(*limit_count);
                     }
 
-                    println!("{:<40} {:<15}", "Package".bold(), "Dependencies".bold());
-                    println!("{}", "-".repeat(55));
-
-                    for (package, count) in &results {
-                        println!("{:<40} {:<15}", package, count);
-                    }
+                    match output.as_str() {
+                        "table" => {
+                            println!(
+                                "{:<40} {:>6} {:>6} {:>6} {:>8}  {}",
+                                "Package".bold(),
+                                "A".bold(),
+                                "I".bold(),
+                                "D".bold(),
+                                "Types".bold(),
+                                "Zone".bold()
+                            );
+                            println!("{}", "-".repeat(80));
 
-                    println!();
-                    println!("{} {} packages above threshold", "→".blue(), results.len());
-                }
+                            for m in &results {
+                                let zone = if m.in_zone_of_pain() {
+                                    "pain".red().to_string()
+                                } else {
+                                    String::new()
+                                };
+                                println!(
+                                    "{:<40} {:>6.2} {:>6.2} {:>6.2} {:>8}  {}",
+                                    m.package,
+                                    m.abstractness,
+                                    m.instability,
+                                    m.distance,
+                                    m.abstract_types + m.concrete_types,
+                                    zone
+                                );
+                            }
 
-                "circular" => {
-                    println!(
-                        "{}",
-                        "Circular dependency detection not yet implemented".yellow()
-                    );
-                    println!("Coming soon!");
+                            println!();
+                            println!(
+                                "{} {} packages in the zone of pain (concrete and stable)",
+                                "→".blue(),
+                                results.iter().filter(|m| m.in_zone_of_pain()).count()
+                            );
+                        }
+                        "json" => {
+                            let json = serde_json::to_string_pretty(&results)?;
+                            println!("{}", json);
+                        }
+                        _ => anyhow::bail!("Unknown output format: {}", output),
+                    }
                 }
 
                 _ => anyhow::bail!(
-                    "Unknown analysis type: {}. Use: complexity, hotspots, coupling, circular",
+                    "Unknown analysis type: {}. Use: complexity, hotspots, centrality, dead-code, coupling, circular, file-layout, abstractness, package-metrics, topo-order, nesting, unused-params",
                     analysis_type
                 ),
             }
@@ -1165,11 +2411,38 @@ fn main() -> Result<()> {
             format,
             filter,
             exclude_tests,
+            drop_dangling_edges,
+            exclude_generated_edges,
+            exclude_dunder,
+            only_public_edges,
+            compact,
+            with_legend,
         } => {
-            let mut graph = load_graph(graph_file)?;
+            if *compact && format != "dot" {
+                anyhow::bail!("--compact is only supported with --format dot");
+            }
+            if *with_legend && !matches!(format.as_str(), "dot" | "graphml" | "mermaid") {
+                anyhow::bail!(
+                    "--with-legend is only supported with --format dot, graphml or mermaid"
+                );
+            }
+
+            let mut graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
+
+            if *exclude_generated_edges {
+                graph.remove_generated_edges();
+            }
+
+            if *exclude_dunder {
+                graph.remove_dunder();
+            }
+
+            if *only_public_edges {
+                graph.retain_public_edges();
+            }
 
             // Apply filters if specified
-            if filter.is_some() || *exclude_tests {
+            if filter.is_some() || *exclude_tests || *drop_dangling_edges {
                 let mut package_filter = None;
                 let mut type_filter = None;
 
@@ -1184,6 +2457,10 @@ fn main() -> Result<()> {
                                     "method" => Some(NodeType::Method),
                                     "handler" => Some(NodeType::HttpHandler),
                                     "middleware" => Some(NodeType::Middleware),
+                                    "class" => Some(NodeType::Class),
+                                    custom if CUSTOM_NODE_TYPES.contains(&custom) => {
+                                        Some(NodeType::Custom(custom.to_string()))
+                                    }
                                     _ => anyhow::bail!("Unknown node type: {}", parts[1]),
                                 };
                             }
@@ -1197,9 +2474,14 @@ fn main() -> Result<()> {
                     }
                 }
 
-                graph = graph.filter(package_filter, type_filter.as_ref(), *exclude_tests);
+                graph = graph.filter(
+                    package_filter,
+                    type_filter.as_ref(),
+                    *exclude_tests,
+                    *drop_dangling_edges,
+                );
 
-                if !cli.quiet && (filter.is_some() || *exclude_tests) {
+                if !cli.quiet {
                     println!(
                         "{} Filtered to {} nodes and {} edges",
                         "→".blue(),
@@ -1218,7 +2500,7 @@ fn main() -> Result<()> {
 
             match format.as_str() {
                 "graphml" => {
-                    graphml::save_to_file(&graph, output)?;
+                    graphml::save_to_file(&graph, output, *with_legend)?;
                     if !cli.quiet {
                         println!(
                             "{} Exported to GraphML: {}",
@@ -1228,7 +2510,11 @@ fn main() -> Result<()> {
                     }
                 }
                 "dot" => {
-                    dot::save_to_file(&graph, output)?;
+                    if *compact {
+                        dot::save_to_file_compact(&graph, output)?;
+                    } else {
+                        dot::save_to_file(&graph, output, *with_legend)?;
+                    }
                     if !cli.quiet {
                         println!(
                             "{} Exported to DOT: {}",
@@ -1243,7 +2529,76 @@ fn main() -> Result<()> {
                         println!("{} Exported to CSV files", "✓".green().bold());
                     }
                 }
-                _ => anyhow::bail!("Unknown export format: {}. Use: graphml, dot, csv", format),
+                "parquet" => {
+                    parquet::save_to_files(&graph, output)?;
+                    if !cli.quiet {
+                        println!("{} Exported to Parquet files", "✓".green().bold());
+                    }
+                }
+                "markdown" | "md" => {
+                    markdown::save_to_file(&graph, output)?;
+                    if !cli.quiet {
+                        println!(
+                            "{} Exported to Markdown: {}",
+                            "✓".green().bold(),
+                            output.display()
+                        );
+                    }
+                }
+                "mermaid" => {
+                    mermaid::save_to_file(&graph, output, *with_legend)?;
+                    if !cli.quiet {
+                        println!(
+                            "{} Exported to Mermaid: {}",
+                            "✓".green().bold(),
+                            output.display()
+                        );
+                    }
+                }
+                "plantuml" => {
+                    plantuml::save_to_file(&graph, output)?;
+                    if !cli.quiet {
+                        println!(
+                            "{} Exported to PlantUML: {}",
+                            "✓".green().bold(),
+                            output.display()
+                        );
+                    }
+                }
+                "cypher" => {
+                    cypher::save_to_file(&graph, output)?;
+                    if !cli.quiet {
+                        println!(
+                            "{} Exported to Cypher: {}",
+                            "✓".green().bold(),
+                            output.display()
+                        );
+                    }
+                }
+                "cytoscape" => {
+                    cytoscape::save_to_file(&graph, output)?;
+                    if !cli.quiet {
+                        println!(
+                            "{} Exported to Cytoscape.js JSON: {}",
+                            "✓".green().bold(),
+                            output.display()
+                        );
+                    }
+                }
+                "sqlite" => {
+                    sqlite::save_to_file(&graph, output)?;
+                    if !cli.quiet {
+                        println!(
+                            "{} Exported to SQLite: {}",
+                            "✓".green().bold(),
+                            output.display()
+                        );
+                    }
+                }
+                _ => anyhow::bail!(
+                    "Unknown export format: {}. Use: graphml, dot, csv, parquet, markdown, mermaid, plantuml, cypher, cytoscape, sqlite",
+                    format
+                ),
             }
         }
 
@@ -1251,11 +2606,22 @@ fn main() -> Result<()> {
             graph: graph_file,
             from,
             depth,
+            direction,
+            include_callers,
             output,
+            compression_level,
         } => {
-            let graph = load_graph(graph_file)?;
+            let direction = match direction.as_str() {
+                "out" => TraversalDirection::Out,
+                "in" => TraversalDirection::In,
+                "both" => TraversalDirection::Both,
+                _ => anyhow::bail!("Unknown direction: {}. Use: out, in, both", direction),
+            };
 
-            if !cli.quiet {
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
+            let quiet = cli.quiet || stream::is_stdio(output);
+
+            if !quiet {
                 println!(
                     "{}",
                     format!(
@@ -1268,16 +2634,17 @@ fn main() -> Result<()> {
             }
 
             // Extract subgraph
-            let subgraph = graph.extract_subgraph(from, *depth);
+            let subgraph =
+                graph.extract_subgraph_directed(from, *depth, direction, *include_callers);
 
             if subgraph.nodes.is_empty() {
                 anyhow::bail!("No nodes found starting from '{}'", from);
             }
 
             // Save in binary format (compressed)
-            fast_compressed::save_to_file(&subgraph, &output.to_string_lossy())?;
+            save_graph(&subgraph, output, false, *compression_level)?;
 
-            if !cli.quiet {
+            if !quiet {
                 println!(
                     "{} Extracted subgraph with {} nodes and {} edges",
                     "✓".green().bold(),
@@ -1292,6 +2659,110 @@ fn main() -> Result<()> {
             }
         }
 
+        Commands::Similar {
+            graph: graph_file,
+            function,
+            threshold,
+            limit,
+            output,
+        } => {
+            let graph = load_graph(graph_file, cli.validate_cache, cli.explain_cache)?;
+            let targets = graph.get_nodes_by_name(function);
+
+            if targets.is_empty() {
+                anyhow::bail!("Function not found: {}", function);
+            }
+
+            let mut results = Vec::new();
+            for target in &targets {
+                let mut matches = graph.find_similar_signatures(&target.id, *threshold);
+                if let Some(lim) = limit {
+                    matches.truncate(*lim);
+                }
+                results.push((*target, matches));
+            }
+
+            match output.as_str() {
+                "json" => {
+                    let json_results: Vec<_> = results
+                        .iter()
+                        .map(|(target, matches)| {
+                            serde_json::json!({
+                                "function": target.id,
+                                "similar": matches
+                                    .iter()
+                                    .map(|(node, score)| serde_json::json!({
+                                        "id": node.id,
+                                        "name": node.name,
+                                        "signature": node.signature,
+                                        "score": score,
+                                    }))
+                                    .collect::<Vec<_>>(),
+                            })
+                        })
+                        .collect();
+                    println!("{}", serde_json::to_string_pretty(&json_results)?);
+                }
+                "table" => {
+                    for (target, matches) in &results {
+                        println!(
+                            "{}",
+                            format!("Functions similar to {} ({})", target.name, target.id).bold()
+                        );
+                        println!();
+
+                        if matches.is_empty() {
+                            println!("{}", "No similar functions found".yellow());
+                            println!();
+                            continue;
+                        }
+
+                        println!(
+                            "{:<8} {:<30} {:<60}",
+                            "Score".bold(),
+                            "Function".bold(),
+                            "Signature".bold()
+                        );
+                        println!("{}", "-".repeat(100));
+
+                        for (node, score) in matches {
+                            println!("{:<8.2} {:<30} {:<60}", score, node.name, node.signature);
+                        }
+
+                        println!();
+                        println!("{} {} similar functions found", "→".blue(), matches.len());
+                        println!();
+                    }
+                }
+                _ => anyhow::bail!("Unknown output format: {}. Use: table, json", output),
+            }
+        }
+
+        Commands::Merge { inputs, output } => {
+            let mut graphs = Vec::with_capacity(inputs.len());
+            let mut input_nodes = 0;
+            for input in inputs {
+                let graph = load_graph(input, cli.validate_cache, cli.explain_cache)?;
+                input_nodes += graph.nodes.len();
+                graphs.push(graph);
+            }
+            let merged = CodeGraph::merge_all(graphs)
+                .ok_or_else(|| anyhow::anyhow!("No graphs to merge"))?;
+            save_graph(&merged, output, false, None)?;
+
+            if !cli.quiet && !stream::is_stdio(output) {
+                println!(
+                    "{} Merged {} graphs into {} ({} nodes, {} edges, {} duplicate nodes skipped)",
+                    "✓".green().bold(),
+                    inputs.len(),
+                    output.display(),
+                    merged.nodes.len().to_string().cyan(),
+                    merged.edges.len().to_string().cyan(),
+                    input_nodes - merged.nodes.len()
+                );
+            }
+        }
+
         Commands::Diff {
             old_graph,
             new_graph,
@@ -1299,10 +2770,15 @@ fn main() -> Result<()> {
             show_removed,
             show_changed,
             complexity_threshold,
+            fail_on_complexity_increase,
             output,
         } => {
-            let old = load_graph(old_graph)?;
-            let new = load_graph(new_graph)?;
+            if stream::is_stdio(old_graph) && stream::is_stdio(new_graph) {
+                anyhow::bail!("Only one of the graphs can be read from stdin");
+            }
+
+            let old = load_graph(old_graph, cli.validate_cache, cli.explain_cache)?;
+            let new = load_graph(new_graph, cli.validate_cache, cli.explain_cache)?;
 
             if !cli.quiet {
                 println!("{}", "Comparing graphs...".green().bold());
@@ -1312,7 +2788,9 @@ fn main() -> Result<()> {
 
             match output.as_str() {
                 "json" => {
-                    let json = serde_json::to_string_pretty(&diff)?;
+                    // Emit the versioned report rather than the internal GraphDiff
+                    let report = DiffReport::new(&old, &new, &diff, *complexity_threshold);
+                    let json = serde_json::to_string_pretty(&report)?;
                     println!("{}", json);
                 }
                 "table" => {
@@ -1330,6 +2808,10 @@ fn main() -> Result<()> {
                         "Changed nodes: {}",
                         diff.changed_nodes.len().to_string().yellow()
                     );
+                    println!(
+                        "Renamed nodes: {}",
+                        diff.renamed_nodes.len().to_string().yellow()
+                    );
                     println!(
                         "Edge changes:  {} added, {} removed",
                         diff.added_edges_count.to_string().green(),
@@ -1376,6 +2858,21 @@ fn main() -> Result<()> {
                         }
                     }
 
+                    // Renames are changes that kept the body
+                    if (*show_changed || (!show_added && !show_removed && !show_changed))
+                        && !diff.renamed_nodes.is_empty()
+                    {
+                        println!("\n{}", "=== RENAMED NODES ===".yellow().bold());
+                        for rename in &diff.renamed_nodes {
+                            println!(
+                                "  {} {} → {}",
+                                "~".yellow(),
+                                rename.old_name,
+                                rename.new_name
+                            );
+                        }
+                    }
+
                     // Show complexity changes if threshold specified
                     if let Some(threshold) = complexity_threshold {
                         let significant_changes: Vec<_> = diff
@@ -1412,6 +2909,99 @@ fn main() -> Result<()> {
                 }
                 _ => anyhow::bail!("Unknown output format: {}. Use: table, json", output),
             }
+
+            // CI gate: stderr keeps JSON output on stdout parseable
+            if let Some(threshold) = fail_on_complexity_increase {
+                let increases = diff.complexity_increases(*threshold);
+                if !increases.is_empty() {
+                    eprintln!(
+                        "{}",
+                        format!("Complexity increased by ≥{}:", threshold)
+                            .red()
+                            .bold()
+                    );
+                    for change in &increases {
+                        eprintln!(
+                            "  {} {} ({:+}; fan-in {} → {}, fan-out {} → {})",
+                            "↑".red(),
+                            change.node_name,
+                            change.change,
+                            change.old_fan_in,
+                            change.new_fan_in,
+                            change.old_fan_out,
+                            change.new_fan_out
+                        );
+                    }
+                    anyhow::bail!(
+                        "{} function(s) exceeded --fail-on-complexity-increase {}",
+                        increases.len(),
+                        threshold
+                    );
+                }
+            }
+        }
+
+        Commands::BisectCycle {
+            directory,
+            language,
+            good,
+            bad,
+        } => {
+            if cli.no_git {
+                anyhow::bail!("bisect-cycle needs git; remove --no-git / CODENAV_NO_GIT");
+            }
+            let lang = language.as_deref().unwrap_or("go");
+            let parse_options = ParseOptions::default();
+
+            if !cli.quiet {
+                println!(
+                    "{}",
+                    format!("Bisecting {}..{} for package cycles...", good, bad).dimmed()
+                );
+            }
+
+            let outcome = bisect::bisect_cycle(directory, good, bad, |checkout| {
+                let mut graph =
+                    CodeGraph::new(checkout.to_string_lossy().to_string(), lang.to_string());
+                parse_directory(lang, checkout, &parse_options, &mut graph)?;
+                Ok(graph)
+            })?;
+
+            match outcome {
+                BisectOutcome::AlreadyPresent { cycles } => {
+                    println!(
+                        "{} {} already has {} package cycles",
+                        "⚠".yellow(),
+                        good,
+                        cycles.len()
+                    );
+                    for cycle in &cycles {
+                        println!("  {}", cycle.join(" ↔ "));
+                    }
+                }
+                BisectOutcome::NotIntroduced => {
+                    println!("{} No package cycles at {}", "✓".green().bold(), bad);
+                }
+                BisectOutcome::Introduced {
+                    commit,
+                    summary,
+                    cycles,
+                } => {
+                    println!(
+                        "{} First commit with a package cycle: {} {}",
+                        "→".blue(),
+                        commit.cyan(),
+                        summary
+                    );
+                    for cycle in &cycles {
+                        println!("  {}", cycle.join(" ↔ "));
+                    }
+                }
+            }
+        }
+
+        Commands::Schema => {
+            println!("{}", serde_json::to_string_pretty(&schema::json_schema())?);
         }
     }
 
@@ -0,0 +1,91 @@
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+use crate::core::CodeGraph;
+
+/// Provenance record of an index run, written by `index --manifest`.
+///
+/// File hashes and the graph fingerprint are SHA-256, so they can be checked
+/// with standard tools (`sha256sum`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IndexManifest {
+    pub tool_version: String,
+    pub generated_at: String,
+    pub languages: Vec<String>,
+    pub root_path: String,
+    pub git_commit: Option<String>,
+    pub file_count: usize,
+    pub node_count: usize,
+    pub edge_count: usize,
+    /// Indexed files, sorted by path
+    pub files: Vec<ManifestFile>,
+    /// Hash over every node and edge, see [`CodeGraph::fingerprint`]
+    pub graph_fingerprint: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestFile {
+    pub path: String,
+    pub size: u64,
+    /// SHA-256 of the file contents, hex-encoded
+    pub hash: String,
+}
+
+impl IndexManifest {
+    /// Build the manifest for `graph`, hashing `files`: the files the run
+    /// indexed, e.g. [`ParsedFiles::paths`]. Relative paths (e.g. from an
+    /// archive) are read below `root`.
+    ///
+    /// [`ParsedFiles::paths`]: crate::parser::ParsedFiles::paths
+    pub fn from_graph(graph: &CodeGraph, root: &Path, files: &[PathBuf]) -> Result<Self> {
+        let mut paths: Vec<&PathBuf> = files.iter().collect();
+        paths.sort();
+        paths.dedup();
+
+        let files = paths
+            .into_iter()
+            .map(|path| {
+                let on_disk = if path.is_relative() && !path.exists() {
+                    root.join(path)
+                } else {
+                    path.clone()
+                };
+                let contents = std::fs::read(&on_disk)
+                    .with_context(|| format!("Failed to hash {}", on_disk.display()))?;
+                Ok(ManifestFile {
+                    path: path.to_string_lossy().to_string(),
+                    size: contents.len() as u64,
+                    hash: hash_bytes(&contents),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            tool_version: env!("CARGO_PKG_VERSION").to_string(),
+            generated_at: graph.metadata.generated_at.clone(),
+            languages: vec![graph.metadata.language.clone()],
+            root_path: graph.metadata.root_path.clone(),
+            git_commit: graph.metadata.git_commit_hash.clone(),
+            file_count: files.len(),
+            node_count: graph.nodes.len(),
+            edge_count: graph.edges.len(),
+            files,
+            graph_fingerprint: graph.fingerprint(),
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_string_pretty(self)?;
+        std::fs::write(path, json)
+            .with_context(|| format!("Failed to write manifest {}", path.display()))
+    }
+}
+
+fn hash_bytes(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
@@ -0,0 +1,517 @@
+use super::generated;
+use super::options::{CallBudget, ParseOptions, TRUNCATED_EDGES_TAG};
+use super::traversal;
+use crate::core::{CodeGraph, Edge, EdgeType, Node, NodeType, Parameter};
+use anyhow::{Context, Result};
+use std::collections::HashSet;
+use std::path::Path;
+use tree_sitter::Parser;
+
+/// Control-flow statements counted for `max_nesting` metadata
+const NESTING_KINDS: &[&str] = &[
+    "if_statement",
+    "for_statement",
+    "for_range_loop",
+    "while_statement",
+    "do_statement",
+    "switch_statement",
+    "try_statement",
+];
+
+/// Branching constructs counted as decision points (cyclomatic complexity).
+/// `case_statement` also covers `default:`, which is excluded where counted.
+const DECISION_KINDS: &[&str] = &[
+    "if_statement",
+    "for_statement",
+    "for_range_loop",
+    "while_statement",
+    "do_statement",
+    "case_statement",
+    "catch_clause",
+    "conditional_expression",
+    "&&",
+    "||",
+    "and",
+    "or",
+];
+
+/// Package recorded for definitions outside any namespace
+const GLOBAL_NAMESPACE: &str = "global";
+
+pub struct CppParser {
+    parser: Parser,
+    options: ParseOptions,
+}
+
+impl CppParser {
+    /// File extensions treated as C++ sources (`.h` headers included)
+    pub const EXTENSIONS: &'static [&'static str] = &["cpp", "cc", "cxx", "hpp", "h"];
+
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_cpp::LANGUAGE.into())
+            .context("Failed to set C++ language")?;
+        Ok(Self {
+            parser,
+            options: ParseOptions::default(),
+        })
+    }
+
+    /// Apply parse options (e.g. edge caps) to this parser
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn parse_directory(&mut self, dir: &Path, graph: &mut CodeGraph) -> Result<()> {
+        use rayon::prelude::*;
+
+        let filter = self.options.file_filter(dir)?;
+        let file_paths: Vec<_> = jwalk::WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                let path = e.path();
+                path.extension()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|ext| Self::EXTENSIONS.contains(&ext))
+                    // No test-file convention to skip here
+                    && !filter.skips(&path, false)
+            })
+            .map(|e| e.path())
+            .collect();
+
+        let dir_str = dir.to_string_lossy().to_string();
+        let options = self.options.clone();
+
+        let files_parsed = file_paths.len();
+        if options.parse_serially(files_parsed) {
+            // Reuse this parser; no worker threads or chunk graphs
+            for path in &file_paths {
+                let result = self.parse_file(path, graph);
+                options.report_file(path, &result);
+            }
+        } else {
+            let chunk_size = 100.min(file_paths.len().max(1));
+            let results: Vec<CodeGraph> = file_paths
+                .par_chunks(chunk_size)
+                .map(|chunk| {
+                    let mut chunk_graph = CodeGraph::new_with_capacity(
+                        dir_str.clone(),
+                        "cpp".to_string(),
+                        chunk.len() * 20,
+                        chunk.len() * 80,
+                    );
+
+                    for path in chunk {
+                        let mut parser = match Self::new() {
+                            Ok(p) => p.with_options(options.clone()),
+                            Err(_) => continue,
+                        };
+
+                        let result = parser.parse_file(path, &mut chunk_graph);
+                        options.report_file(path, &result);
+                    }
+
+                    chunk_graph
+                })
+                .collect();
+
+            for chunk_graph in results {
+                graph.merge(chunk_graph);
+            }
+        }
+
+        graph.metadata.stats.files_parsed = files_parsed;
+        graph.metadata.stats.total_nodes = graph.nodes.len();
+        graph.metadata.stats.total_edges = graph.edges.len();
+        Ok(())
+    }
+
+    pub fn parse_file(&mut self, file_path: &Path, graph: &mut CodeGraph) -> Result<()> {
+        let source = self
+            .options
+            .read_source(file_path)
+            .context(format!("Failed to read file: {}", file_path.display()))?;
+
+        if generated::is_generated(file_path, &source) {
+            graph.mark_generated_file(file_path);
+        }
+
+        let tree = self
+            .parser
+            .parse(&source, None)
+            .context("Failed to parse C++ file")?;
+
+        let root = tree.root_node();
+        let namespaces = self.collect_namespaces(root, &source)?;
+
+        traversal::walk_preorder(root, |node| {
+            if node.kind() == "function_definition" {
+                self.extract_function(node, &source, file_path, &namespaces, graph)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Names of all namespaces declared in the file. Used to tell
+    /// `ns::free_function` apart from `Class::method` in out-of-line definitions.
+    fn collect_namespaces(&self, root: tree_sitter::Node, source: &str) -> Result<HashSet<String>> {
+        let mut namespaces = HashSet::new();
+        traversal::walk_preorder(root, |node| {
+            if node.kind() == "namespace_definition" {
+                if let Some(name) = node.child_by_field_name("name") {
+                    for segment in source[name.byte_range()].split("::") {
+                        namespaces.insert(segment.trim().to_string());
+                    }
+                }
+            }
+            Ok(())
+        })?;
+        Ok(namespaces)
+    }
+
+    fn extract_function(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        namespaces: &HashSet<String>,
+        graph: &mut CodeGraph,
+    ) -> Result<()> {
+        let Some(declarator) = node.child_by_field_name("declarator") else {
+            return Ok(());
+        };
+
+        // Pointer/reference return types wrap the function declarator
+        let mut return_suffix = String::new();
+        let mut func_declarator = declarator;
+        while func_declarator.kind() != "function_declarator" {
+            match func_declarator.kind() {
+                "pointer_declarator" => return_suffix.push('*'),
+                "reference_declarator" => return_suffix.push('&'),
+                _ => {}
+            }
+            match func_declarator
+                .child_by_field_name("declarator")
+                .or_else(|| func_declarator.named_child(0))
+            {
+                Some(inner) => func_declarator = inner,
+                None => return Ok(()),
+            }
+        }
+
+        let Some(name_node) = func_declarator.child_by_field_name("declarator") else {
+            return Ok(());
+        };
+
+        // Enclosing namespaces and classes, outermost first
+        let (mut package_path, mut class_path) = self.enclosing_scopes(node, source);
+
+        // Out-of-line definitions: `ns::Class::method`
+        let mut name_node = name_node;
+        while name_node.kind() == "qualified_identifier" {
+            if let Some(scope) = name_node.child_by_field_name("scope") {
+                let segment_node = if scope.kind() == "template_type" {
+                    scope.child_by_field_name("name").unwrap_or(scope)
+                } else {
+                    scope
+                };
+                let segment = source[segment_node.byte_range()].to_string();
+
+                if class_path.is_empty()
+                    && scope.kind() == "namespace_identifier"
+                    && namespaces.contains(&segment)
+                {
+                    package_path.push(segment);
+                } else {
+                    class_path.push(segment);
+                }
+            }
+            match name_node.child_by_field_name("name") {
+                Some(inner) => name_node = inner,
+                None => return Ok(()),
+            }
+        }
+
+        let func_name = match name_node.kind() {
+            "identifier" | "field_identifier" | "destructor_name" | "operator_name" => {
+                source[name_node.byte_range()].to_string()
+            }
+            "template_function" => match name_node.child_by_field_name("name") {
+                Some(name) => source[name.byte_range()].to_string(),
+                None => return Ok(()),
+            },
+            _ => return Ok(()),
+        };
+
+        let node_type = if class_path.is_empty() {
+            NodeType::Function
+        } else {
+            NodeType::Method
+        };
+        let package = if package_path.is_empty() {
+            GLOBAL_NAMESPACE.to_string()
+        } else {
+            package_path.join("::")
+        };
+
+        let line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        let signature = source[node.byte_range()]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let id = format!("{}:{}:{}", file_path.display(), func_name, line);
+
+        let mut node_obj = Node::new(
+            id.clone(),
+            func_name,
+            node_type,
+            file_path.to_path_buf(),
+            line,
+            end_line,
+            package,
+            signature,
+        );
+
+        if let Some(parameters) = func_declarator.child_by_field_name("parameters") {
+            node_obj.parameters = self.extract_parameters(parameters, source);
+        }
+        if let Some(return_type) = node.child_by_field_name("type") {
+            node_obj.returns.push(format!(
+                "{}{}",
+                &source[return_type.byte_range()],
+                return_suffix
+            ));
+        }
+        if !class_path.is_empty() {
+            node_obj
+                .metadata
+                .insert("class".to_string(), class_path.join("::"));
+        }
+        let template_params = self.template_params(node, source);
+        if !template_params.is_empty() {
+            node_obj
+                .metadata
+                .insert("template_params".to_string(), template_params.join(", "));
+        }
+
+        node_obj.metadata.insert(
+            "max_nesting".to_string(),
+            traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+        );
+
+        traversal::mark_unused_parameters(&mut node_obj, node, source);
+
+        traversal::record_body_hash(&mut node_obj, node, source);
+        node_obj.decision_points = traversal::count_decision_points(node, |n| {
+            DECISION_KINDS.contains(&n.kind())
+                && (n.kind() != "case_statement" || n.child_by_field_name("value").is_some())
+        });
+
+        graph.add_node(node_obj);
+
+        if let Some(body) = node.child_by_field_name("body") {
+            self.extract_calls_in_node(body, source, file_path, &id, graph)?;
+        }
+
+        Ok(())
+    }
+
+    /// Namespace and class names enclosing `node`, each outermost first
+    fn enclosing_scopes(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+    ) -> (Vec<String>, Vec<String>) {
+        let mut namespaces = Vec::new();
+        let mut classes = Vec::new();
+
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            match parent.kind() {
+                "namespace_definition" => {
+                    // Anonymous namespaces have no name and don't qualify anything
+                    if let Some(name) = parent.child_by_field_name("name") {
+                        namespaces.push(source[name.byte_range()].to_string());
+                    }
+                }
+                "class_specifier" | "struct_specifier" | "union_specifier" => {
+                    if let Some(name) = parent.child_by_field_name("name") {
+                        classes.push(source[name.byte_range()].to_string());
+                    }
+                }
+                _ => {}
+            }
+            current = parent.parent();
+        }
+
+        namespaces.reverse();
+        classes.reverse();
+        (namespaces, classes)
+    }
+
+    /// Template parameter lists of the function and any enclosing class
+    /// templates (best-effort; specializations are recorded verbatim)
+    fn template_params(&self, node: tree_sitter::Node, source: &str) -> Vec<String> {
+        let mut params = Vec::new();
+
+        let mut current = node.parent();
+        while let Some(parent) = current {
+            if parent.kind() == "template_declaration" {
+                if let Some(list) = parent.child_by_field_name("parameters") {
+                    let text = &source[list.byte_range()];
+                    let text = text
+                        .strip_prefix('<')
+                        .and_then(|t| t.strip_suffix('>'))
+                        .unwrap_or(text)
+                        .trim();
+                    if !text.is_empty() {
+                        params.push(text.to_string());
+                    }
+                }
+            }
+            current = parent.parent();
+        }
+
+        params.reverse();
+        params
+    }
+
+    fn extract_parameters(&self, node: tree_sitter::Node, source: &str) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let mut cursor = node.walk();
+
+        for child in node.named_children(&mut cursor) {
+            if !matches!(
+                child.kind(),
+                "parameter_declaration"
+                    | "optional_parameter_declaration"
+                    | "variadic_parameter_declaration"
+            ) {
+                continue;
+            }
+
+            // Innermost identifier of the declarator, e.g. `s` in `const std::string& s`
+            let mut name_node = child.child_by_field_name("declarator");
+            while let Some(n) = name_node {
+                if n.kind() == "identifier" {
+                    break;
+                }
+                name_node = n
+                    .child_by_field_name("declarator")
+                    .or_else(|| n.named_child(0));
+            }
+
+            let (name, param_type) = match name_node {
+                Some(name) => (
+                    source[name.byte_range()].to_string(),
+                    source[child.start_byte()..name.start_byte()]
+                        .trim()
+                        .to_string(),
+                ),
+                None => (
+                    "_".to_string(),
+                    child
+                        .child_by_field_name("type")
+                        .map(|t| source[t.byte_range()].to_string())
+                        .unwrap_or_default(),
+                ),
+            };
+
+            parameters.push(Parameter { name, param_type });
+        }
+
+        parameters
+    }
+
+    fn extract_calls_in_node(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        from_id: &str,
+        graph: &mut CodeGraph,
+    ) -> Result<()> {
+        let mut budget = CallBudget::new(&self.options);
+        traversal::walk_preorder(node, |child| {
+            self.record_call(child, node, source, file_path, from_id, graph, &mut budget);
+            Ok(())
+        })?;
+
+        if budget.truncated() {
+            graph.add_tag(from_id, TRUNCATED_EDGES_TAG);
+        }
+
+        Ok(())
+    }
+
+    /// Record a call edge if `node` is a call expression and the budget allows it
+    #[allow(clippy::too_many_arguments)]
+    fn record_call(
+        &self,
+        node: tree_sitter::Node,
+        root: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        from_id: &str,
+        graph: &mut CodeGraph,
+        budget: &mut CallBudget,
+    ) {
+        if node.kind() != "call_expression" {
+            return;
+        }
+
+        // Methods of local classes are functions of their own; lambdas are not
+        // and their calls stay with the function that defines them
+        let owner = std::iter::successors(node.parent(), |n| n.parent())
+            .find(|n| n.kind() == "function_definition");
+        if owner != root.parent() {
+            return;
+        }
+
+        // Unwrap `ns::f`, `obj.f`, `ptr->f` and `f<T>` down to the callee name
+        let mut callee = node.child_by_field_name("function");
+        while let Some(n) = callee {
+            callee = match n.kind() {
+                "qualified_identifier" | "template_function" | "template_method" => {
+                    n.child_by_field_name("name")
+                }
+                "field_expression" => n.child_by_field_name("field"),
+                _ => break,
+            };
+        }
+
+        let called_func = match callee {
+            Some(n) if matches!(n.kind(), "identifier" | "field_identifier") => {
+                source[n.byte_range()].to_string()
+            }
+            _ => return,
+        };
+
+        if !budget.admit(&called_func) {
+            return;
+        }
+
+        let line = node.start_position().row + 1;
+        let call_site = source[node.byte_range()].to_string();
+
+        let mut edge = Edge::new(
+            from_id.to_string(),
+            called_func,
+            EdgeType::Calls,
+            call_site,
+            file_path.to_path_buf(),
+            line,
+        );
+        edge.metadata.insert(
+            "conditional".to_string(),
+            traversal::is_conditional(node, root, |n| DECISION_KINDS.contains(&n.kind()))
+                .to_string(),
+        );
+        graph.add_edge(edge);
+    }
+}
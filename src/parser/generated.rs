@@ -0,0 +1,52 @@
+use std::path::Path;
+
+/// File name suffixes commonly produced by code generators
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    "_generated.go",
+    ".gen.go",
+    "_gen.go",
+    "_pb2.py",
+    "_pb2_grpc.py",
+    ".generated.ts",
+    ".gen.ts",
+    ".generated.js",
+    ".gen.js",
+];
+
+/// Header markers emitted by non-Go code generators (checked near the top of
+/// the file)
+const GENERATED_MARKERS: &[&str] = &["@generated", "<auto-generated", "AUTO-GENERATED"];
+
+/// Go's generated-code comment, `^// Code generated .* DO NOT EDIT\.$`
+const GO_MARKER_PREFIX: &str = "// Code generated ";
+const GO_MARKER_SUFFIX: &str = " DO NOT EDIT.";
+
+/// Number of leading lines inspected for generator markers
+const HEADER_LINES: usize = 10;
+
+/// Detect whether a source file was produced by a code generator,
+/// using well-known file name suffixes and header comments
+/// (e.g. Go's `// Code generated ... DO NOT EDIT.` convention)
+pub fn is_generated(file_path: &Path, source: &str) -> bool {
+    let file_name = file_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+
+    if GENERATED_SUFFIXES
+        .iter()
+        .any(|suffix| file_name.ends_with(suffix))
+    {
+        return true;
+    }
+
+    source.lines().take(HEADER_LINES).any(|line| {
+        is_go_marker(line) || GENERATED_MARKERS.iter().any(|marker| line.contains(marker))
+    })
+}
+
+/// A whole line matching Go's convention; prose that merely mentions
+/// "DO NOT EDIT" or "Code generated" does not count
+fn is_go_marker(line: &str) -> bool {
+    line.len() >= GO_MARKER_PREFIX.len() + GO_MARKER_SUFFIX.len()
+        && line.starts_with(GO_MARKER_PREFIX)
+        && line.ends_with(GO_MARKER_SUFFIX)
+}
@@ -1,11 +1,69 @@
-use crate::core::{CodeGraph, Edge, EdgeType, Node, NodeType, Parameter};
+use super::generated;
+use super::options::{CallBudget, ParseOptions, TRUNCATED_EDGES_TAG};
+use super::traversal;
+use crate::core::{CodeGraph, Edge, EdgeType, Node, NodeType, Parameter, ABSTRACT_TAG};
 use anyhow::{Context, Result};
-use std::fs;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use tree_sitter::Parser;
 
+/// Control-flow statements counted for `max_nesting` metadata
+const NESTING_KINDS: &[&str] = &[
+    "if_statement",
+    "for_statement",
+    "expression_switch_statement",
+    "type_switch_statement",
+    "select_statement",
+];
+
+/// Method key of a method with a receiver (see [`GoParser::method_key`])
+const METHOD_KEY: &str = "method_key";
+/// Space-separated method keys declared by an interface
+const METHOD_SET: &str = "method_set";
+/// Space-separated interfaces embedded by an interface
+const EMBEDS: &str = "embeds";
+
+/// Branching constructs counted as decision points (cyclomatic complexity).
+/// `default` cases don't branch, so only `case` clauses are listed.
+const DECISION_KINDS: &[&str] = &[
+    "if_statement",
+    "for_statement",
+    "expression_case",
+    "type_case",
+    "communication_case",
+    "&&",
+    "||",
+];
+
 pub struct GoParser {
     parser: Parser,
+    options: ParseOptions,
+}
+
+/// Directory and package name of a declaration. Go packages are per
+/// directory, so two `package main` directories are different scopes.
+type Scope = (PathBuf, String);
+
+/// Method sets of the Go types in a graph. Go types implement interfaces
+/// implicitly, so `Implements` edges are only added once every file is
+/// parsed, see [`MethodSets::link`]. The sets are read back from node
+/// metadata, so an incremental reparse can relink the whole graph.
+#[derive(Default)]
+struct MethodSets {
+    interfaces: Vec<InterfaceDecl>,
+    /// (node ID, scope, name) of each struct
+    structs: Vec<(String, Scope, String)>,
+    /// Method keys (see [`GoParser::method_key`]) by scope and receiver type
+    methods: HashMap<(Scope, String), HashSet<String>>,
+}
+
+struct InterfaceDecl {
+    id: String,
+    scope: Scope,
+    name: String,
+    methods: HashSet<String>,
+    /// Embedded interfaces, as spelled (`Reader`, `io.Reader`)
+    embeds: Vec<String>,
 }
 
 impl GoParser {
@@ -14,57 +72,82 @@ impl GoParser {
         parser
             .set_language(&tree_sitter_go::LANGUAGE.into())
             .context("Failed to set Go language")?;
-        Ok(Self { parser })
+        Ok(Self {
+            parser,
+            options: ParseOptions::default(),
+        })
+    }
+
+    /// Apply parse options (e.g. edge caps) to this parser
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Go's test-file convention (`_test.go`), skipped unless tests are included
+    pub fn is_test_file(path: &Path) -> bool {
+        path.to_string_lossy().contains("_test.go")
     }
 
     pub fn parse_directory(&mut self, dir: &Path, graph: &mut CodeGraph) -> Result<()> {
         use rayon::prelude::*;
 
         // Phase 3: Parallel file discovery with jwalk
+        let filter = self.options.file_filter(dir)?;
         let file_paths: Vec<_> = jwalk::WalkDir::new(dir)
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| {
-                e.path().extension().and_then(|s| s.to_str()) == Some("go")
-                    && !e.path().to_string_lossy().contains("_test.go")
+                let path = e.path();
+                path.extension().and_then(|s| s.to_str()) == Some("go")
+                    && !filter.skips(&path, Self::is_test_file(&path))
             })
             .map(|e| e.path())
             .collect();
 
         let dir_str = dir.to_string_lossy().to_string();
+        let options = self.options.clone();
 
-        // Phase 3: Batched parallel processing for better CPU utilization
-        let chunk_size = 100.min(file_paths.len().max(1));
-        let results: Vec<CodeGraph> = file_paths
-            .par_chunks(chunk_size)
-            .map(|chunk| {
-                let mut chunk_graph = CodeGraph::new_with_capacity(
-                    dir_str.clone(),
-                    "go".to_string(),
-                    chunk.len() * 20, // Estimate ~20 nodes per file
-                    chunk.len() * 80, // Estimate ~80 edges per file
-                );
+        let files_parsed = file_paths.len();
+        if options.parse_serially(files_parsed) {
+            // Reuse this parser; no worker threads or chunk graphs
+            for path in &file_paths {
+                let result = self.parse_source_file(path, graph);
+                options.report_file(path, &result);
+            }
+        } else {
+            // Phase 3: Batched parallel processing for better CPU utilization
+            let chunk_size = 100.min(file_paths.len().max(1));
+            let results: Vec<CodeGraph> = file_paths
+                .par_chunks(chunk_size)
+                .map(|chunk| {
+                    let mut chunk_graph = CodeGraph::new_with_capacity(
+                        dir_str.clone(),
+                        "go".to_string(),
+                        chunk.len() * 20, // Estimate ~20 nodes per file
+                        chunk.len() * 80, // Estimate ~80 edges per file
+                    );
 
-                for path in chunk {
-                    let mut parser = match Self::new() {
-                        Ok(p) => p,
-                        Err(_) => continue,
-                    };
+                    for path in chunk {
+                        let mut parser = match Self::new() {
+                            Ok(p) => p.with_options(options.clone()),
+                            Err(_) => continue,
+                        };
 
-                    if let Err(e) = parser.parse_file(path, &mut chunk_graph) {
-                        eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+                        let result = parser.parse_source_file(path, &mut chunk_graph);
+                        options.report_file(path, &result);
                     }
-                }
 
-                chunk_graph
-            })
-            .collect();
+                    chunk_graph
+                })
+                .collect();
 
-        // Merge all chunk results - uses incremental index updates
-        let files_parsed = file_paths.len();
-        for chunk_graph in results {
-            graph.merge(chunk_graph);
+            // Merge all chunk results - uses incremental index updates
+            for chunk_graph in results {
+                graph.merge(chunk_graph);
+            }
         }
+        Self::link_implementations(graph);
 
         graph.metadata.stats.files_parsed = files_parsed;
         graph.metadata.stats.total_nodes = graph.nodes.len();
@@ -72,10 +155,34 @@ impl GoParser {
         Ok(())
     }
 
+    /// Parse one file and relink the graph's `Implements` edges, so types in
+    /// other files pick up interfaces the file declares or satisfies
     pub fn parse_file(&mut self, file_path: &Path, graph: &mut CodeGraph) -> Result<()> {
-        let source = fs::read_to_string(file_path)
+        let result = self.parse_source_file(file_path, graph);
+        Self::link_implementations(graph);
+        result
+    }
+
+    /// Replace the `Implements` edges of every Go struct in the graph with
+    /// the interfaces it satisfies now
+    pub fn link_implementations(graph: &mut CodeGraph) {
+        graph
+            .edges
+            .retain(|e| e.edge_type != EdgeType::Implements || !is_go_file(&e.file_path));
+        graph.build_indexes();
+        MethodSets::from_graph(graph).link(graph);
+    }
+
+    fn parse_source_file(&mut self, file_path: &Path, graph: &mut CodeGraph) -> Result<()> {
+        let source = self
+            .options
+            .read_source(file_path)
             .context(format!("Failed to read file: {}", file_path.display()))?;
 
+        if generated::is_generated(file_path, &source) {
+            graph.mark_generated_file(file_path);
+        }
+
         let tree = self
             .parser
             .parse(&source, None)
@@ -113,19 +220,154 @@ impl GoParser {
         package_name: &str,
         graph: &mut CodeGraph,
     ) -> Result<()> {
-        if node.kind() == "function_declaration" {
-            self.extract_function(node, source, file_path, package_name, graph)?;
-        } else if node.kind() == "method_declaration" {
-            self.extract_method(node, source, file_path, package_name, graph)?;
-        }
+        traversal::walk_preorder(node, |node| {
+            if node.kind() == "function_declaration" {
+                self.extract_function(node, source, file_path, package_name, graph)?;
+            } else if node.kind() == "method_declaration" {
+                self.extract_method(node, source, file_path, package_name, graph)?;
+            } else if node.kind() == "import_spec" {
+                self.extract_import(node, source, file_path, package_name, graph);
+            } else if node.kind() == "type_spec" {
+                self.extract_type(node, source, file_path, package_name, graph);
+            }
 
-        // Recurse into children
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            self.walk_tree(child, source, file_path, package_name, graph)?;
+            Ok(())
+        })
+    }
+
+    /// Record an `Imports` edge for one spec of an `import` declaration
+    /// (`"fmt"`, `log "github.com/x/log"`, ...), targeting the import path
+    fn extract_import(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) {
+        let Some(path) = node.child_by_field_name("path") else {
+            return;
+        };
+        let target = source[path.byte_range()]
+            .trim_matches(['"', '`'])
+            .to_string();
+
+        graph.add_import(
+            file_path,
+            package_name,
+            target,
+            source[node.byte_range()].to_string(),
+            node.start_position().row + 1,
+        );
+    }
+
+    /// Add a `Class` node for a struct or interface declaration (tagged
+    /// abstract for interfaces) and record its method set. Other named
+    /// types (`type ID int`) are skipped.
+    fn extract_type(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) {
+        let (Some(name_node), Some(type_node)) = (
+            node.child_by_field_name("name"),
+            node.child_by_field_name("type"),
+        ) else {
+            return;
+        };
+        let is_interface = match type_node.kind() {
+            "interface_type" => true,
+            "struct_type" => false,
+            _ => return,
+        };
+
+        let name = source[name_node.byte_range()].to_string();
+        let line = node.start_position().row + 1;
+        let signature = format!(
+            "type {}",
+            source[node.byte_range()].lines().next().unwrap_or("")
+        );
+        let id = format!("{}:{}:{}", file_path.display(), name, line);
+
+        let mut type_obj = Node::new(
+            id.clone(),
+            name.clone(),
+            NodeType::Class,
+            file_path.to_path_buf(),
+            line,
+            node.end_position().row + 1,
+            package_name.to_string(),
+            signature,
+        );
+        // `// Doc` sits above `type`, which may group several specs
+        type_obj.documentation = traversal::leading_comments(node, source).or_else(|| {
+            node.parent()
+                .filter(|parent| parent.kind() == "type_declaration")
+                .and_then(|parent| traversal::leading_comments(parent, source))
+        });
+        if is_interface {
+            type_obj.tags.push(ABSTRACT_TAG.to_string());
+
+            let mut methods = Vec::new();
+            let mut embeds = Vec::new();
+            let mut cursor = type_node.walk();
+            for element in type_node.named_children(&mut cursor) {
+                match element.kind() {
+                    "method_elem" => methods.extend(self.method_key(element, source)),
+                    "type_elem" => embeds.push(normalize_type(&source[element.byte_range()])),
+                    _ => {}
+                }
+            }
+            // Keys and spellings have no whitespace, so a space separates them
+            type_obj
+                .metadata
+                .insert(METHOD_SET.to_string(), methods.join(" "));
+            type_obj
+                .metadata
+                .insert(EMBEDS.to_string(), embeds.join(" "));
         }
+        graph.add_node(type_obj);
+    }
 
-        Ok(())
+    /// `Name(T1,T2)(R1,R2)` for a method declaration or interface method:
+    /// parameter and result types with names and whitespace dropped, so
+    /// `Area(a, b int) float64` and `Area(int, int) float64` match
+    fn method_key(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        let name = &source[node.child_by_field_name("name")?.byte_range()];
+        let types = |field: &str| -> Vec<String> {
+            let Some(list) = node.child_by_field_name(field) else {
+                return Vec::new();
+            };
+            if list.kind() != "parameter_list" {
+                return vec![normalize_type(&source[list.byte_range()])];
+            }
+            let mut types = Vec::new();
+            let mut cursor = list.walk();
+            for declaration in list.named_children(&mut cursor) {
+                let Some(type_node) = declaration.child_by_field_name("type") else {
+                    continue;
+                };
+                let mut spelled = normalize_type(&source[type_node.byte_range()]);
+                if declaration.kind() == "variadic_parameter_declaration" {
+                    spelled.insert_str(0, "...");
+                }
+                let mut name_cursor = declaration.walk();
+                let names = declaration
+                    .children_by_field_name("name", &mut name_cursor)
+                    .count();
+                types.extend(std::iter::repeat_n(spelled, names.max(1)));
+            }
+            types
+        };
+        Some(format!(
+            "{}({})({})",
+            name,
+            types("parameters").join(","),
+            types("result").join(",")
+        ))
     }
 
     fn extract_function(
@@ -165,7 +407,7 @@ impl GoParser {
             let mut node_obj = Node::new(
                 id,
                 func_name.clone(),
-                NodeType::Function,
+                self.classify(node, source, NodeType::Function),
                 file_path.to_path_buf(),
                 line,
                 end_line,
@@ -173,6 +415,15 @@ impl GoParser {
                 signature,
             );
             node_obj.parameters = parameters;
+            node_obj.documentation = traversal::leading_comments(node, source);
+            node_obj.metadata.insert(
+                "max_nesting".to_string(),
+                traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+            );
+            traversal::mark_unused_parameters(&mut node_obj, node, source);
+            traversal::record_body_hash(&mut node_obj, node, source);
+            node_obj.decision_points =
+                traversal::count_decision_points(node, |n| DECISION_KINDS.contains(&n.kind()));
             graph.add_node(node_obj);
 
             // Extract calls within this function
@@ -190,51 +441,131 @@ impl GoParser {
         package_name: &str,
         graph: &mut CodeGraph,
     ) -> Result<()> {
-        let mut method_name = String::new();
-        let mut parameters = Vec::new();
-
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            match child.kind() {
-                "field_identifier" if method_name.is_empty() => {
-                    method_name = source[child.byte_range()].to_string();
-                }
-                "parameter_list" if !parameters.is_empty() => {
-                    // Second parameter_list is the method parameters (first is receiver)
-                    parameters = self.extract_parameters(child, source);
-                }
-                _ => {}
+        let method_name = match node.child_by_field_name("name") {
+            Some(name_node) => source[name_node.byte_range()].to_string(),
+            None => return Ok(()),
+        };
+        let receiver = node
+            .child_by_field_name("receiver")
+            .and_then(|list| self.receiver_type(list, source));
+        let parameters = node
+            .child_by_field_name("parameters")
+            .map(|list| self.extract_parameters(list, source))
+            .unwrap_or_default();
+
+        // Same-named methods on different receivers get distinct IDs
+        let qualified_name = match &receiver {
+            Some(receiver) => format!("{}.{}", receiver, method_name),
+            None => method_name.clone(),
+        };
+
+        let line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        let signature = source[node.byte_range()]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let id = format!("{}:{}:{}", file_path.display(), qualified_name, line);
+
+        let mut node_obj = Node::new(
+            id,
+            method_name,
+            self.classify(node, source, NodeType::Method),
+            file_path.to_path_buf(),
+            line,
+            end_line,
+            package_name.to_string(),
+            signature,
+        );
+        node_obj.parameters = parameters;
+        node_obj.documentation = traversal::leading_comments(node, source);
+        if let Some(receiver) = receiver {
+            if let Some(key) = self.method_key(node, source) {
+                node_obj.metadata.insert(METHOD_KEY.to_string(), key);
             }
+            node_obj.metadata.insert("receiver".to_string(), receiver);
         }
+        node_obj.metadata.insert(
+            "max_nesting".to_string(),
+            traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+        );
+        traversal::mark_unused_parameters(&mut node_obj, node, source);
+        traversal::record_body_hash(&mut node_obj, node, source);
+        node_obj.decision_points =
+            traversal::count_decision_points(node, |n| DECISION_KINDS.contains(&n.kind()));
+        graph.add_node(node_obj);
+
+        // Extract calls within this method
+        self.extract_calls_in_node(node, source, file_path, &qualified_name, line, graph)?;
 
-        if !method_name.is_empty() {
-            let line = node.start_position().row + 1;
-            let end_line = node.end_position().row + 1;
-            let signature = source[node.byte_range()]
-                .lines()
-                .next()
-                .unwrap_or("")
-                .to_string();
-            let id = format!("{}:{}:{}", file_path.display(), method_name, line);
+        Ok(())
+    }
 
-            let mut node_obj = Node::new(
-                id,
-                method_name.clone(),
-                NodeType::Method,
-                file_path.to_path_buf(),
-                line,
-                end_line,
-                package_name.to_string(),
-                signature,
-            );
-            node_obj.parameters = parameters;
-            graph.add_node(node_obj);
+    /// `HttpHandler` for `net/http` handler signatures (`http.ResponseWriter`
+    /// and `*http.Request` parameters), `Middleware` for functions returning
+    /// an `http.Handler` (e.g. `func(next http.Handler) http.Handler`),
+    /// otherwise `default`
+    fn classify(&self, node: tree_sitter::Node, source: &str, default: NodeType) -> NodeType {
+        let text = |field: &str| {
+            node.child_by_field_name(field)
+                .map_or("", |n| &source[n.byte_range()])
+        };
+
+        let parameters = text("parameters");
+        if parameters.contains("http.ResponseWriter") && parameters.contains("*http.Request") {
+            NodeType::HttpHandler
+        } else if text("result").contains("http.Handler") {
+            NodeType::Middleware
+        } else {
+            default
+        }
+    }
+
+    /// Type name of a receiver list: `Calculator` for `(c *Calculator)` or
+    /// `(s Stack[T])`
+    fn receiver_type(&self, receiver: tree_sitter::Node, source: &str) -> Option<String> {
+        let mut cursor = receiver.walk();
+        let declaration = receiver
+            .named_children(&mut cursor)
+            .find(|c| c.kind() == "parameter_declaration")?;
+        self.named_type(declaration.child_by_field_name("type")?, source)
+    }
 
-            // Extract calls within this method
-            self.extract_calls_in_node(node, source, file_path, &method_name, line, graph)?;
+    /// Local type name behind pointers and type arguments (`*T`, `T[K]`);
+    /// `None` for qualified, composite or builtin-shaped types
+    fn named_type(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "type_identifier" => Some(source[node.byte_range()].to_string()),
+            "pointer_type" => self.named_type(node.named_child(0)?, source),
+            "generic_type" => self.named_type(node.child_by_field_name("type")?, source),
+            _ => None,
         }
+    }
 
-        Ok(())
+    /// Types of the receiver and parameters of a function or method, by
+    /// variable name, for resolving `v.Method()` calls
+    fn typed_variables(&self, node: tree_sitter::Node, source: &str) -> HashMap<String, String> {
+        let mut variables = HashMap::new();
+        for field in ["receiver", "parameters"] {
+            let Some(list) = node.child_by_field_name(field) else {
+                continue;
+            };
+            let mut cursor = list.walk();
+            for declaration in list.named_children(&mut cursor) {
+                let Some(type_name) = declaration
+                    .child_by_field_name("type")
+                    .and_then(|t| self.named_type(t, source))
+                else {
+                    continue;
+                };
+                let mut name_cursor = declaration.walk();
+                for name in declaration.children_by_field_name("name", &mut name_cursor) {
+                    variables.insert(source[name.byte_range()].to_string(), type_name.clone());
+                }
+            }
+        }
+        variables
     }
 
     fn extract_parameters(&self, node: tree_sitter::Node, source: &str) -> Vec<Parameter> {
@@ -285,21 +616,48 @@ impl GoParser {
         func_line: usize,
         graph: &mut CodeGraph,
     ) -> Result<()> {
-        self.find_calls(node, source, file_path, func_name, func_line, graph);
+        let from_id = format!("{}:{}:{}", file_path.display(), func_name, func_line);
+        let variables = self.typed_variables(node, source);
+        let mut budget = CallBudget::new(&self.options);
+        traversal::walk_preorder(node, |child| {
+            self.record_call(
+                child,
+                node,
+                source,
+                file_path,
+                &from_id,
+                &variables,
+                graph,
+                &mut budget,
+            );
+            Ok(())
+        })?;
+
+        if budget.truncated() {
+            graph.add_tag(&from_id, TRUNCATED_EDGES_TAG);
+        }
+
         Ok(())
     }
 
-    fn find_calls(
+    /// Record a call edge if `node` is a call expression and the budget allows it.
+    /// `v.Method()` on a receiver or parameter of known type records that type
+    /// as the edge's `receiver` metadata.
+    #[allow(clippy::too_many_arguments)]
+    fn record_call(
         &self,
         node: tree_sitter::Node,
+        root: tree_sitter::Node,
         source: &str,
         file_path: &Path,
-        func_name: &str,
-        func_line: usize,
+        from_id: &str,
+        variables: &HashMap<String, String>,
         graph: &mut CodeGraph,
+        budget: &mut CallBudget,
     ) {
         if node.kind() == "call_expression" {
             let mut called_func = String::new();
+            let mut receiver = None;
             let mut cursor = node.walk();
 
             for child in node.children(&mut cursor) {
@@ -315,6 +673,10 @@ impl GoParser {
                                 called_func = source[sel_child.byte_range()].to_string();
                             }
                         }
+                        receiver = child
+                            .child_by_field_name("operand")
+                            .filter(|operand| operand.kind() == "identifier")
+                            .and_then(|operand| variables.get(&source[operand.byte_range()]));
                     }
                     _ => {}
                 }
@@ -323,26 +685,148 @@ impl GoParser {
             if !called_func.is_empty() {
                 let line = node.start_position().row + 1;
                 let call_site = source[node.byte_range()].to_string();
-                let from_id = format!("{}:{}:{}", file_path.display(), func_name, func_line);
 
-                if graph.get_node_by_id(&from_id).is_some() {
-                    let edge = Edge::new(
-                        from_id,
+                if graph.get_node_by_id(from_id).is_some() && budget.admit(&called_func) {
+                    let mut edge = Edge::new(
+                        from_id.to_string(),
                         called_func,
                         EdgeType::Calls,
                         call_site,
                         file_path.to_path_buf(),
                         line,
                     );
+                    edge.metadata.insert(
+                        "conditional".to_string(),
+                        traversal::is_conditional(node, root, |n| {
+                            DECISION_KINDS.contains(&n.kind())
+                        })
+                        .to_string(),
+                    );
+                    if let Some(receiver) = receiver {
+                        edge.metadata
+                            .insert("receiver".to_string(), receiver.clone());
+                    }
                     graph.add_edge(edge);
                 }
             }
         }
+    }
+}
 
-        // Recurse into children
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            self.find_calls(child, source, file_path, func_name, func_line, graph);
+impl MethodSets {
+    /// Collect the structs, interfaces and receiver methods of Go files
+    fn from_graph(graph: &CodeGraph) -> Self {
+        let mut sets = MethodSets::default();
+        for node in graph.nodes.iter().filter(|n| is_go_file(&n.file_path)) {
+            let scope = (
+                node.file_path
+                    .parent()
+                    .unwrap_or(Path::new(""))
+                    .to_path_buf(),
+                node.package.clone(),
+            );
+            match node.node_type {
+                NodeType::Class if node.is_abstract_type() => {
+                    let words = |key: &str| -> Vec<String> {
+                        node.metadata
+                            .get(key)
+                            .map(|v| v.split_whitespace().map(str::to_string).collect())
+                            .unwrap_or_default()
+                    };
+                    sets.interfaces.push(InterfaceDecl {
+                        id: node.id.clone(),
+                        scope,
+                        name: node.name.clone(),
+                        methods: words(METHOD_SET).into_iter().collect(),
+                        embeds: words(EMBEDS),
+                    });
+                }
+                NodeType::Class => sets
+                    .structs
+                    .push((node.id.clone(), scope, node.name.clone())),
+                _ => {
+                    if let (Some(receiver), Some(key)) =
+                        (node.metadata.get("receiver"), node.metadata.get(METHOD_KEY))
+                    {
+                        sets.methods
+                            .entry((scope, receiver.clone()))
+                            .or_default()
+                            .insert(key.clone());
+                    }
+                }
+            }
+        }
+        sets
+    }
+
+    /// Add an `Implements` edge from each struct to every non-empty
+    /// interface whose methods it all has (value or pointer receivers).
+    /// Interfaces embedding ones that aren't in the graph (`io.Reader`) are
+    /// skipped, as are methods promoted from embedded structs.
+    fn link(self, graph: &mut CodeGraph) {
+        let interfaces: Vec<(&InterfaceDecl, HashSet<&str>)> = self
+            .interfaces
+            .iter()
+            .filter_map(|interface| Some((interface, self.full_method_set(interface)?)))
+            .filter(|(_, methods)| !methods.is_empty())
+            .collect();
+
+        for (id, scope, name) in &self.structs {
+            let Some(methods) = self.methods.get(&(scope.clone(), name.clone())) else {
+                continue;
+            };
+            let Some((file_path, line)) = graph
+                .get_node_by_id(id)
+                .map(|n| (n.file_path.clone(), n.line))
+            else {
+                continue;
+            };
+            for (interface, required) in &interfaces {
+                if !required.iter().all(|key| methods.contains(*key)) {
+                    continue;
+                }
+                let mut edge = Edge::new(
+                    id.clone(),
+                    interface.name.clone(),
+                    EdgeType::Implements,
+                    format!("{}.{}", interface.scope.1, interface.name),
+                    file_path.clone(),
+                    line,
+                );
+                edge.to_id = Some(interface.id.clone());
+                graph.add_edge(edge);
+            }
+        }
+    }
+
+    /// Own and embedded methods of an interface; `None` when an embedded
+    /// interface isn't declared in the same package directory
+    fn full_method_set<'a>(&'a self, interface: &'a InterfaceDecl) -> Option<HashSet<&'a str>> {
+        let mut methods = HashSet::new();
+        let mut pending = vec![interface];
+        let mut seen = HashSet::new();
+        while let Some(current) = pending.pop() {
+            if !seen.insert(current.id.as_str()) {
+                continue;
+            }
+            methods.extend(current.methods.iter().map(String::as_str));
+            for embed in &current.embeds {
+                let embedded = self
+                    .interfaces
+                    .iter()
+                    .find(|i| i.scope == current.scope && i.name == *embed)?;
+                pending.push(embedded);
+            }
         }
+        Some(methods)
     }
 }
+
+fn is_go_file(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "go")
+}
+
+/// Type text without whitespace, for comparing spellings
+fn normalize_type(text: &str) -> String {
+    text.split_whitespace().collect()
+}
@@ -1,7 +1,20 @@
+pub mod cpp;
+pub mod generated;
 pub mod go;
+pub mod options;
 pub mod python;
+pub mod rust;
+pub mod scala;
+pub mod traversal;
 pub mod typescript;
 
+pub use cpp::CppParser;
 pub use go::GoParser;
+pub use options::{
+    FileFilter, ParseOptions, ParsedFiles, WarningLog, DEFAULT_MAX_WARNINGS,
+    DEFAULT_PARALLEL_THRESHOLD, TRUNCATED_EDGES_TAG,
+};
 pub use python::PythonParser;
+pub use rust::RustParser;
+pub use scala::ScalaParser;
 pub use typescript::{Language, TypeScriptParser};
@@ -0,0 +1,260 @@
+use crate::events::{Event, EventSink};
+use anyhow::Context;
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// Tag added to functions whose outgoing call edges were capped
+pub const TRUNCATED_EDGES_TAG: &str = "truncated_edges";
+
+/// Directories with fewer source files than this are parsed serially on the
+/// calling thread. Parsers split work into chunks of 100 files, so smaller
+/// directories are a single chunk anyway and the parallel path only adds
+/// thread-pool startup and a fresh parser (~0.5-1.5ms on 1-64 file repos).
+pub const DEFAULT_PARALLEL_THRESHOLD: usize = 100;
+
+/// Per-file warnings printed during indexing before the rest are suppressed
+pub const DEFAULT_MAX_WARNINGS: usize = 50;
+
+/// Prints per-file warnings to stderr up to a cap and counts the rest.
+/// Clones share one count, so parser threads draw from the same budget.
+#[derive(Debug, Clone)]
+pub struct WarningLog {
+    max: usize,
+    counts: Arc<Mutex<WarningCounts>>,
+}
+
+#[derive(Debug, Default)]
+struct WarningCounts {
+    printed: usize,
+    suppressed: usize,
+}
+
+impl Default for WarningLog {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_WARNINGS)
+    }
+}
+
+impl WarningLog {
+    pub fn new(max: usize) -> Self {
+        Self {
+            max,
+            counts: Arc::default(),
+        }
+    }
+
+    /// Print `Warning: <message>`, or count it once the cap is reached
+    pub fn warn(&self, message: &str) {
+        let mut counts = self.counts.lock().unwrap();
+        if counts.printed < self.max {
+            counts.printed += 1;
+            eprintln!("Warning: {}", message);
+        } else {
+            counts.suppressed += 1;
+        }
+    }
+
+    /// Number of warnings printed so far
+    pub fn printed(&self) -> usize {
+        self.counts.lock().unwrap().printed
+    }
+
+    /// Number of warnings dropped over the cap
+    pub fn suppressed(&self) -> usize {
+        self.counts.lock().unwrap().suppressed
+    }
+
+    /// The closing `(N more warnings suppressed)` line, if any were
+    pub fn summary(&self) -> Option<String> {
+        match self.suppressed() {
+            0 => None,
+            1 => Some("(1 more warning suppressed)".to_string()),
+            n => Some(format!("({} more warnings suppressed)", n)),
+        }
+    }
+
+    /// Print the summary line to stderr, if any warnings were suppressed
+    pub fn finish(&self) {
+        if let Some(summary) = self.summary() {
+            eprintln!("Warning: {}", summary);
+        }
+    }
+}
+
+/// Paths of the files parsed successfully. Clones share one list, like
+/// [`WarningLog`], so parser threads record into the same run.
+#[derive(Debug, Clone, Default)]
+pub struct ParsedFiles(Arc<Mutex<Vec<PathBuf>>>);
+
+impl ParsedFiles {
+    pub fn record(&self, path: &Path) {
+        self.0.lock().unwrap().push(path.to_path_buf());
+    }
+
+    /// Recorded paths, sorted, each once
+    pub fn paths(&self) -> Vec<PathBuf> {
+        let mut paths = self.0.lock().unwrap().clone();
+        paths.sort();
+        paths.dedup();
+        paths
+    }
+}
+
+/// Options shared by all language parsers
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Stop recording call edges from a function once it has this many
+    /// distinct callees (bounds fan-out of generated/macro-heavy code)
+    pub max_edges_per_node: Option<usize>,
+
+    /// Receives a `file_parsed`/`file_failed` event for every file
+    pub events: Option<EventSink>,
+
+    /// Parse serially below this many files
+    /// (default: [`DEFAULT_PARALLEL_THRESHOLD`]; 0 always parses in parallel)
+    pub parallel_threshold: Option<usize>,
+
+    /// Decode files that aren't valid UTF-8 lossily (invalid bytes become
+    /// U+FFFD) instead of failing them
+    pub lossy_encoding: bool,
+
+    /// Where per-file parse failures are reported (capped, see [`WarningLog`])
+    pub warnings: WarningLog,
+
+    /// Skip files matching any of these globs (`vendor/**`, `*.pb.go`),
+    /// see [`FileFilter`]
+    pub exclude_globs: Vec<String>,
+
+    /// Parse test files (`foo_test.go`, `test_foo.py`, `foo.spec.ts`) too
+    pub include_tests: bool,
+
+    /// Collects every file [`report_file`](ParseOptions::report_file) sees
+    /// parse successfully
+    pub parsed_files: ParsedFiles,
+}
+
+impl ParseOptions {
+    /// Whether a directory of `file_count` files should skip the parallel path
+    pub fn parse_serially(&self, file_count: usize) -> bool {
+        file_count
+            < self
+                .parallel_threshold
+                .unwrap_or(DEFAULT_PARALLEL_THRESHOLD)
+    }
+
+    /// Read a source file as text, honoring `lossy_encoding`
+    pub fn read_source(&self, path: &Path) -> anyhow::Result<String> {
+        let bytes = std::fs::read(path)?;
+        match String::from_utf8(bytes) {
+            Ok(source) => Ok(source),
+            Err(e) if self.lossy_encoding => Ok(String::from_utf8_lossy(e.as_bytes()).into_owned()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Compile `exclude_globs` for discovering files under `root`
+    pub fn file_filter(&self, root: &Path) -> anyhow::Result<FileFilter> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in &self.exclude_globs {
+            builder.add(
+                Glob::new(pattern)
+                    .with_context(|| format!("Invalid --exclude glob: {}", pattern))?,
+            );
+        }
+        Ok(FileFilter {
+            root: root.to_path_buf(),
+            excludes: builder.build()?,
+            include_tests: self.include_tests,
+        })
+    }
+
+    /// Report the outcome of parsing one file to the event sink, if any,
+    /// and log a warning for a failure
+    pub fn report_file(&self, path: &Path, result: &anyhow::Result<()>) {
+        match result {
+            Ok(()) => self.parsed_files.record(path),
+            Err(e) => self
+                .warnings
+                .warn(&format!("Failed to parse {}: {}", path.display(), e)),
+        }
+        let Some(events) = &self.events else {
+            return;
+        };
+        match result {
+            Ok(()) => events.emit(&Event::FileParsed { path }),
+            Err(e) => events.emit(&Event::FileFailed {
+                path,
+                error: e.to_string(),
+            }),
+        }
+    }
+}
+
+/// Which discovered files a parser skips: exclude-glob matches, and test
+/// files unless `include_tests` is set
+#[derive(Debug, Clone)]
+pub struct FileFilter {
+    root: PathBuf,
+    excludes: GlobSet,
+    include_tests: bool,
+}
+
+impl FileFilter {
+    /// Whether to skip `path`; `is_test` is the parser's own test-file check.
+    /// Globs match the path relative to the root or any of its parent
+    /// directories, so `vendor` and `vendor/**` both exclude `vendor/a/b.go`.
+    pub fn skips(&self, path: &Path, is_test: bool) -> bool {
+        if is_test && !self.include_tests {
+            return true;
+        }
+        if self.excludes.is_empty() {
+            return false;
+        }
+        let relative = path.strip_prefix(&self.root).unwrap_or(path);
+        self.excludes.is_match(path)
+            || relative
+                .ancestors()
+                .filter(|p| !p.as_os_str().is_empty())
+                .any(|p| self.excludes.is_match(p))
+    }
+}
+
+/// Enforces `max_edges_per_node` while collecting calls from one function body
+pub(crate) struct CallBudget {
+    limit: Option<usize>,
+    callees: HashSet<String>,
+    truncated: bool,
+}
+
+impl CallBudget {
+    pub(crate) fn new(options: &ParseOptions) -> Self {
+        Self {
+            limit: options.max_edges_per_node,
+            callees: HashSet::new(),
+            truncated: false,
+        }
+    }
+
+    /// Whether an edge to `callee` may still be recorded. Once the limit of
+    /// distinct callees is reached, every further call is rejected.
+    pub(crate) fn admit(&mut self, callee: &str) -> bool {
+        let Some(limit) = self.limit else {
+            return true;
+        };
+
+        if self.callees.len() >= limit {
+            self.truncated = true;
+            return false;
+        }
+
+        self.callees.insert(callee.to_string());
+        true
+    }
+
+    /// Whether any call was dropped
+    pub(crate) fn truncated(&self) -> bool {
+        self.truncated
+    }
+}
@@ -1,11 +1,55 @@
-use crate::core::{CodeGraph, Edge, EdgeType, Node, NodeType, Parameter};
+use super::generated;
+use super::options::{CallBudget, ParseOptions, TRUNCATED_EDGES_TAG};
+use super::traversal;
+use crate::core::{is_dunder, CodeGraph, Edge, EdgeType, Node, NodeType, Parameter, DUNDER_TAG};
 use anyhow::{Context, Result};
-use std::fs;
 use std::path::Path;
 use tree_sitter::Parser;
 
+/// Control-flow statements counted for `max_nesting` metadata
+const NESTING_KINDS: &[&str] = &[
+    "if_statement",
+    "for_statement",
+    "while_statement",
+    "try_statement",
+    "with_statement",
+    "match_statement",
+];
+
+/// Branching constructs counted as decision points (cyclomatic complexity),
+/// including comprehension `for`/`if` clauses and boolean operators
+const DECISION_KINDS: &[&str] = &[
+    "if_statement",
+    "elif_clause",
+    "for_statement",
+    "while_statement",
+    "except_clause",
+    "case_clause",
+    "conditional_expression",
+    "for_in_clause",
+    "if_clause",
+    "and",
+    "or",
+];
+
+/// Decorator methods that register a Flask/FastAPI route (`@app.route`,
+/// `@router.get`)
+const ROUTE_DECORATORS: &[&str] = &[
+    "route",
+    "api_route",
+    "get",
+    "post",
+    "put",
+    "patch",
+    "delete",
+    "head",
+    "options",
+    "websocket",
+];
+
 pub struct PythonParser {
     parser: Parser,
+    options: ParseOptions,
 }
 
 impl PythonParser {
@@ -14,57 +58,82 @@ impl PythonParser {
         parser
             .set_language(&tree_sitter_python::LANGUAGE.into())
             .context("Failed to set Python language")?;
-        Ok(Self { parser })
+        Ok(Self {
+            parser,
+            options: ParseOptions::default(),
+        })
+    }
+
+    /// Apply parse options (e.g. edge caps) to this parser
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Python's test-file conventions (`*_test.py`, `test_*.py`), skipped
+    /// unless tests are included
+    pub fn is_test_file(path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        path_str.contains("_test.py") || path_str.contains("test_")
     }
 
     pub fn parse_directory(&mut self, dir: &Path, graph: &mut CodeGraph) -> Result<()> {
         use rayon::prelude::*;
 
         // Phase 3: Parallel file discovery with jwalk
+        let filter = self.options.file_filter(dir)?;
         let file_paths: Vec<_> = jwalk::WalkDir::new(dir)
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| {
-                e.path().extension().and_then(|s| s.to_str()) == Some("py")
-                    && !e.path().to_string_lossy().contains("_test.py")
-                    && !e.path().to_string_lossy().contains("test_")
+                let path = e.path();
+                path.extension().and_then(|s| s.to_str()) == Some("py")
+                    && !filter.skips(&path, Self::is_test_file(&path))
             })
             .map(|e| e.path())
             .collect();
 
         let dir_str = dir.to_string_lossy().to_string();
+        let options = self.options.clone();
+
+        let files_parsed = file_paths.len();
+        if options.parse_serially(files_parsed) {
+            // Reuse this parser; no worker threads or chunk graphs
+            for path in &file_paths {
+                let result = self.parse_file(path, graph);
+                options.report_file(path, &result);
+            }
+        } else {
+            // Phase 3: Batched parallel processing for better CPU utilization
+            let chunk_size = 100.min(file_paths.len().max(1));
+            let results: Vec<CodeGraph> = file_paths
+                .par_chunks(chunk_size)
+                .map(|chunk| {
+                    let mut chunk_graph = CodeGraph::new_with_capacity(
+                        dir_str.clone(),
+                        "python".to_string(),
+                        chunk.len() * 20, // Estimate ~20 nodes per file
+                        chunk.len() * 80, // Estimate ~80 edges per file
+                    );
+
+                    for path in chunk {
+                        let mut parser = match Self::new() {
+                            Ok(p) => p.with_options(options.clone()),
+                            Err(_) => continue,
+                        };
 
-        // Phase 3: Batched parallel processing for better CPU utilization
-        let chunk_size = 100.min(file_paths.len().max(1));
-        let results: Vec<CodeGraph> = file_paths
-            .par_chunks(chunk_size)
-            .map(|chunk| {
-                let mut chunk_graph = CodeGraph::new_with_capacity(
-                    dir_str.clone(),
-                    "python".to_string(),
-                    chunk.len() * 20, // Estimate ~20 nodes per file
-                    chunk.len() * 80, // Estimate ~80 edges per file
-                );
-
-                for path in chunk {
-                    let mut parser = match Self::new() {
-                        Ok(p) => p,
-                        Err(_) => continue,
-                    };
-
-                    if let Err(e) = parser.parse_file(path, &mut chunk_graph) {
-                        eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+                        let result = parser.parse_file(path, &mut chunk_graph);
+                        options.report_file(path, &result);
                     }
-                }
 
-                chunk_graph
-            })
-            .collect();
+                    chunk_graph
+                })
+                .collect();
 
-        // Merge all chunk results - uses incremental index updates
-        let files_parsed = file_paths.len();
-        for chunk_graph in results {
-            graph.merge(chunk_graph);
+            // Merge all chunk results - uses incremental index updates
+            for chunk_graph in results {
+                graph.merge(chunk_graph);
+            }
         }
 
         graph.metadata.stats.files_parsed = files_parsed;
@@ -74,9 +143,15 @@ impl PythonParser {
     }
 
     pub fn parse_file(&mut self, file_path: &Path, graph: &mut CodeGraph) -> Result<()> {
-        let source = fs::read_to_string(file_path)
+        let source = self
+            .options
+            .read_source(file_path)
             .context(format!("Failed to read file: {}", file_path.display()))?;
 
+        if generated::is_generated(file_path, &source) {
+            graph.mark_generated_file(file_path);
+        }
+
         let tree = self
             .parser
             .parse(&source, None)
@@ -106,22 +181,65 @@ impl PythonParser {
         package_name: &str,
         graph: &mut CodeGraph,
     ) -> Result<()> {
-        if node.kind() == "function_definition" {
-            // Check if it's inside a class (method) or standalone (function)
-            if self.is_inside_class(node) {
-                self.extract_method(node, source, file_path, package_name, graph)?;
-            } else {
-                self.extract_function(node, source, file_path, package_name, graph)?;
+        traversal::walk_preorder(node, |node| {
+            match node.kind() {
+                "function_definition" => {
+                    // Check if it's inside a class (method) or standalone (function)
+                    if self.is_inside_class(node) {
+                        self.extract_method(node, source, file_path, package_name, graph)?;
+                    } else {
+                        self.extract_function(node, source, file_path, package_name, graph)?;
+                    }
+                }
+                "class_definition" => {
+                    self.extract_class(node, source, file_path, package_name, graph)?;
+                }
+                "import_statement" | "import_from_statement" => {
+                    self.extract_imports(node, source, file_path, package_name, graph);
+                }
+                _ => {}
             }
-        }
 
-        // Recurse into children
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            self.walk_tree(child, source, file_path, package_name, graph)?;
-        }
+            Ok(())
+        })
+    }
 
-        Ok(())
+    /// Record `Imports` edges targeting the imported modules: each module
+    /// of `import a.b, c as d`, or the (possibly relative) module of
+    /// `from .a import b`
+    fn extract_imports(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) {
+        let modules: Vec<tree_sitter::Node> = if node.kind() == "import_from_statement" {
+            node.child_by_field_name("module_name")
+                .into_iter()
+                .collect()
+        } else {
+            let mut cursor = node.walk();
+            node.children_by_field_name("name", &mut cursor)
+                .filter_map(|name| match name.kind() {
+                    "aliased_import" => name.child_by_field_name("name"),
+                    _ => Some(name),
+                })
+                .collect()
+        };
+
+        let import_text = source[node.byte_range()].to_string();
+        let line = node.start_position().row + 1;
+        for module in modules {
+            graph.add_import(
+                file_path,
+                package_name,
+                source[module.byte_range()].to_string(),
+                import_text.clone(),
+                line,
+            );
+        }
     }
 
     fn is_inside_class(&self, node: tree_sitter::Node) -> bool {
@@ -135,6 +253,68 @@ impl PythonParser {
         false
     }
 
+    fn extract_class(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) -> Result<()> {
+        let class_name = match node.child_by_field_name("name") {
+            Some(name_node) => source[name_node.byte_range()].to_string(),
+            None => return Ok(()),
+        };
+
+        let line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        let signature = source[node.byte_range()]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let id = format!("{}:{}:{}", file_path.display(), class_name, line);
+
+        let mut class_node = Node::new(
+            id.clone(),
+            class_name,
+            NodeType::Class,
+            file_path.to_path_buf(),
+            line,
+            end_line,
+            package_name.to_string(),
+            signature,
+        );
+        class_node.documentation = self.docstring(node, source);
+        graph.add_node(class_node);
+
+        // Base classes: class B(A, mod.C, metaclass=Meta)
+        if let Some(superclasses) = node.child_by_field_name("superclasses") {
+            let mut cursor = superclasses.walk();
+            for base in superclasses.named_children(&mut cursor) {
+                let base_name = match base.kind() {
+                    "identifier" => source[base.byte_range()].to_string(),
+                    "attribute" => match base.child_by_field_name("attribute") {
+                        Some(attr) => source[attr.byte_range()].to_string(),
+                        None => continue,
+                    },
+                    _ => continue, // keyword arguments such as metaclass=
+                };
+
+                graph.add_edge(Edge::new(
+                    id.clone(),
+                    base_name,
+                    EdgeType::Extends,
+                    source[base.byte_range()].to_string(),
+                    file_path.to_path_buf(),
+                    base.start_position().row + 1,
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
     fn extract_function(
         &self,
         node: tree_sitter::Node,
@@ -169,10 +349,15 @@ impl PythonParser {
                 .to_string();
             let id = format!("{}:{}:{}", file_path.display(), func_name, line);
 
+            let (tags, is_route) = self.decorator_tags(node, source);
             let mut node_obj = Node::new(
                 id,
                 func_name.clone(),
-                NodeType::Function,
+                if is_route {
+                    NodeType::HttpHandler
+                } else {
+                    NodeType::Function
+                },
                 file_path.to_path_buf(),
                 line,
                 end_line,
@@ -180,6 +365,16 @@ impl PythonParser {
                 signature,
             );
             node_obj.parameters = parameters;
+            node_obj.tags = tags;
+            node_obj.documentation = self.docstring(node, source);
+            node_obj.metadata.insert(
+                "max_nesting".to_string(),
+                traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+            );
+            traversal::mark_unused_parameters(&mut node_obj, node, source);
+            traversal::record_body_hash(&mut node_obj, node, source);
+            node_obj.decision_points =
+                traversal::count_decision_points(node, |n| DECISION_KINDS.contains(&n.kind()));
             graph.add_node(node_obj);
 
             // Extract calls within this function
@@ -223,10 +418,15 @@ impl PythonParser {
                 .to_string();
             let id = format!("{}:{}:{}", file_path.display(), method_name, line);
 
+            let (tags, is_route) = self.decorator_tags(node, source);
             let mut node_obj = Node::new(
                 id,
                 method_name.clone(),
-                NodeType::Method,
+                if is_route {
+                    NodeType::HttpHandler
+                } else {
+                    NodeType::Method
+                },
                 file_path.to_path_buf(),
                 line,
                 end_line,
@@ -234,6 +434,19 @@ impl PythonParser {
                 signature,
             );
             node_obj.parameters = parameters;
+            node_obj.tags = tags;
+            node_obj.documentation = self.docstring(node, source);
+            if is_dunder(&method_name) {
+                node_obj.tags.push(DUNDER_TAG.to_string());
+            }
+            node_obj.metadata.insert(
+                "max_nesting".to_string(),
+                traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+            );
+            traversal::mark_unused_parameters(&mut node_obj, node, source);
+            traversal::record_body_hash(&mut node_obj, node, source);
+            node_obj.decision_points =
+                traversal::count_decision_points(node, |n| DECISION_KINDS.contains(&n.kind()));
             graph.add_node(node_obj);
 
             // Extract calls within this method
@@ -243,6 +456,68 @@ impl PythonParser {
         Ok(())
     }
 
+    /// Docstring of a function or class: the string literal opening its
+    /// body, quotes and indentation stripped
+    fn docstring(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        let statement = node.child_by_field_name("body")?.named_child(0)?;
+        if statement.kind() != "expression_statement" {
+            return None;
+        }
+        let string = statement.named_child(0).filter(|s| s.kind() == "string")?;
+        let text = source[string.byte_range()].trim_start_matches(|c: char| c.is_alphabetic());
+        let text = ["\"\"\"", "'''", "\"", "'"]
+            .iter()
+            .find_map(|quote| text.strip_prefix(quote)?.strip_suffix(quote))?;
+        let lines: Vec<&str> = text.lines().map(str::trim).collect();
+        traversal::doc_text(&lines)
+    }
+
+    /// Names of the decorators on a function (`route` for
+    /// `@app.route("/x")`, `staticmethod` for `@staticmethod`), and whether
+    /// one of them registers an HTTP route
+    fn decorator_tags(&self, node: tree_sitter::Node, source: &str) -> (Vec<String>, bool) {
+        let mut tags = Vec::new();
+        let mut is_route = false;
+        let Some(parent) = node.parent().filter(|p| p.kind() == "decorated_definition") else {
+            return (tags, is_route);
+        };
+
+        let mut cursor = parent.walk();
+        for decorator in parent.named_children(&mut cursor) {
+            if decorator.kind() != "decorator" {
+                continue;
+            }
+            let Some(mut expr) = decorator.named_child(0) else {
+                continue;
+            };
+            let is_call = expr.kind() == "call";
+            if is_call {
+                match expr.child_by_field_name("function") {
+                    Some(function) => expr = function,
+                    None => continue,
+                }
+            }
+            let name = match expr.kind() {
+                "identifier" => &source[expr.byte_range()],
+                "attribute" => match expr.child_by_field_name("attribute") {
+                    Some(attr) => &source[attr.byte_range()],
+                    None => continue,
+                },
+                _ => continue,
+            };
+
+            // Routes are registered on an app or router: @app.get("/x")
+            if is_call && expr.kind() == "attribute" && ROUTE_DECORATORS.contains(&name) {
+                is_route = true;
+            }
+            if !tags.iter().any(|t| t == name) {
+                tags.push(name.to_string());
+            }
+        }
+
+        (tags, is_route)
+    }
+
     fn extract_parameters(&self, node: tree_sitter::Node, source: &str) -> Vec<Parameter> {
         let mut parameters = Vec::new();
         let mut cursor = node.walk();
@@ -303,18 +578,31 @@ impl PythonParser {
         func_line: usize,
         graph: &mut CodeGraph,
     ) -> Result<()> {
-        self.find_calls(node, source, file_path, func_name, func_line, graph);
+        let from_id = format!("{}:{}:{}", file_path.display(), func_name, func_line);
+        let mut budget = CallBudget::new(&self.options);
+        traversal::walk_preorder(node, |child| {
+            self.record_call(child, node, source, file_path, &from_id, graph, &mut budget);
+            Ok(())
+        })?;
+
+        if budget.truncated() {
+            graph.add_tag(&from_id, TRUNCATED_EDGES_TAG);
+        }
+
         Ok(())
     }
 
-    fn find_calls(
+    /// Record a call edge if `node` is a call expression and the budget allows it
+    #[allow(clippy::too_many_arguments)]
+    fn record_call(
         &self,
         node: tree_sitter::Node,
+        root: tree_sitter::Node,
         source: &str,
         file_path: &Path,
-        func_name: &str,
-        func_line: usize,
+        from_id: &str,
         graph: &mut CodeGraph,
+        budget: &mut CallBudget,
     ) {
         if node.kind() == "call" {
             let mut called_func = String::new();
@@ -342,26 +630,26 @@ impl PythonParser {
             if !called_func.is_empty() {
                 let line = node.start_position().row + 1;
                 let call_site = source[node.byte_range()].to_string();
-                let from_id = format!("{}:{}:{}", file_path.display(), func_name, func_line);
 
-                if graph.get_node_by_id(&from_id).is_some() {
-                    let edge = Edge::new(
-                        from_id,
+                if graph.get_node_by_id(from_id).is_some() && budget.admit(&called_func) {
+                    let mut edge = Edge::new(
+                        from_id.to_string(),
                         called_func,
                         EdgeType::Calls,
                         call_site,
                         file_path.to_path_buf(),
                         line,
                     );
+                    edge.metadata.insert(
+                        "conditional".to_string(),
+                        traversal::is_conditional(node, root, |n| {
+                            DECISION_KINDS.contains(&n.kind())
+                        })
+                        .to_string(),
+                    );
                     graph.add_edge(edge);
                 }
             }
         }
-
-        // Recurse into children
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            self.find_calls(child, source, file_path, func_name, func_line, graph);
-        }
     }
 }
@@ -0,0 +1,414 @@
+use super::generated;
+use super::options::{CallBudget, ParseOptions, TRUNCATED_EDGES_TAG};
+use super::traversal;
+use crate::core::{CodeGraph, Edge, EdgeType, Node, NodeType, Parameter};
+use anyhow::{Context, Result};
+use std::path::{Component, Path};
+use tree_sitter::Parser;
+
+/// Control-flow expressions counted for `max_nesting` metadata
+const NESTING_KINDS: &[&str] = &[
+    "if_expression",
+    "match_expression",
+    "for_expression",
+    "while_expression",
+    "loop_expression",
+];
+
+/// Branching constructs counted as decision points (cyclomatic complexity).
+/// Each match arm counts except the catch-all `_ =>`; `&&`/`||` are matched
+/// through the operator of a `binary_expression`.
+const DECISION_KINDS: &[&str] = &[
+    "if_expression",
+    "for_expression",
+    "while_expression",
+    "match_arm",
+];
+
+/// Blocks whose `fn` items are methods of the named type or trait
+const IMPL_KINDS: &[&str] = &["impl_item", "trait_item"];
+
+pub struct RustParser {
+    parser: Parser,
+    options: ParseOptions,
+}
+
+impl RustParser {
+    /// File extensions treated as Rust sources
+    pub const EXTENSIONS: &'static [&'static str] = &["rs"];
+
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_rust::LANGUAGE.into())
+            .context("Failed to set Rust language")?;
+        Ok(Self {
+            parser,
+            options: ParseOptions::default(),
+        })
+    }
+
+    /// Apply parse options (e.g. edge caps) to this parser
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn parse_directory(&mut self, dir: &Path, graph: &mut CodeGraph) -> Result<()> {
+        use rayon::prelude::*;
+
+        let filter = self.options.file_filter(dir)?;
+        let file_paths: Vec<_> = jwalk::WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                let path = e.path();
+                path.extension()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|ext| Self::EXTENSIONS.contains(&ext))
+                    // No test-file convention to skip here
+                    && !filter.skips(&path, false)
+            })
+            .map(|e| e.path())
+            .collect();
+
+        let dir_str = dir.to_string_lossy().to_string();
+        let options = self.options.clone();
+
+        let files_parsed = file_paths.len();
+        if options.parse_serially(files_parsed) {
+            // Reuse this parser; no worker threads or chunk graphs
+            for path in &file_paths {
+                let result = self.parse_file(path, graph);
+                options.report_file(path, &result);
+            }
+        } else {
+            let chunk_size = 100.min(file_paths.len().max(1));
+            let results: Vec<CodeGraph> = file_paths
+                .par_chunks(chunk_size)
+                .map(|chunk| {
+                    let mut chunk_graph = CodeGraph::new_with_capacity(
+                        dir_str.clone(),
+                        "rust".to_string(),
+                        chunk.len() * 20,
+                        chunk.len() * 80,
+                    );
+
+                    for path in chunk {
+                        let mut parser = match Self::new() {
+                            Ok(p) => p.with_options(options.clone()),
+                            Err(_) => continue,
+                        };
+
+                        let result = parser.parse_file(path, &mut chunk_graph);
+                        options.report_file(path, &result);
+                    }
+
+                    chunk_graph
+                })
+                .collect();
+
+            for chunk_graph in results {
+                graph.merge(chunk_graph);
+            }
+        }
+
+        graph.metadata.stats.files_parsed = files_parsed;
+        graph.metadata.stats.total_nodes = graph.nodes.len();
+        graph.metadata.stats.total_edges = graph.edges.len();
+        Ok(())
+    }
+
+    pub fn parse_file(&mut self, file_path: &Path, graph: &mut CodeGraph) -> Result<()> {
+        let source = self
+            .options
+            .read_source(file_path)
+            .context(format!("Failed to read file: {}", file_path.display()))?;
+
+        if generated::is_generated(file_path, &source) {
+            graph.mark_generated_file(file_path);
+        }
+
+        let tree = self
+            .parser
+            .parse(&source, None)
+            .context("Failed to parse Rust file")?;
+
+        let root = tree.root_node();
+        let file_module = Self::module_path(file_path);
+
+        traversal::walk_preorder(root, |node| {
+            if node.kind() == "function_item" {
+                let package_name = self.package_name(node, &source, &file_module);
+                self.extract_function(node, &source, file_path, &package_name, graph)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Module path of a file relative to its crate's `src` directory:
+    /// `src/core/graph.rs` is `crate::core::graph`, while `lib.rs`, `main.rs`
+    /// and `mod.rs` name their directory. Files outside a `src` directory
+    /// are named by their file stem.
+    fn module_path(file_path: &Path) -> String {
+        let components: Vec<&str> = file_path
+            .components()
+            .filter_map(|c| match c {
+                Component::Normal(s) => s.to_str(),
+                _ => None,
+            })
+            .collect();
+        let relative = match components.iter().rposition(|c| *c == "src") {
+            Some(src) => &components[src + 1..],
+            None => &components[components.len().saturating_sub(1)..],
+        };
+
+        let mut segments = vec!["crate".to_string()];
+        for (i, component) in relative.iter().enumerate() {
+            if i + 1 < relative.len() {
+                segments.push(component.to_string());
+                continue;
+            }
+            let stem = component.strip_suffix(".rs").unwrap_or(component);
+            if !matches!(stem, "lib" | "main" | "mod") {
+                segments.push(stem.to_string());
+            }
+        }
+        segments.join("::")
+    }
+
+    /// The file's module path extended by every inline `mod` enclosing `node`
+    fn package_name(&self, node: tree_sitter::Node, source: &str, file_module: &str) -> String {
+        let mut inline_mods = Vec::new();
+        let mut ancestor = node.parent();
+        while let Some(n) = ancestor {
+            if n.kind() == "mod_item" {
+                if let Some(name) = n.child_by_field_name("name") {
+                    inline_mods.push(&source[name.byte_range()]);
+                }
+            }
+            ancestor = n.parent();
+        }
+
+        let mut package = file_module.to_string();
+        for name in inline_mods.into_iter().rev() {
+            package.push_str("::");
+            package.push_str(name);
+        }
+        package
+    }
+
+    fn extract_function(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) -> Result<()> {
+        let func_name = match node.child_by_field_name("name") {
+            Some(name_node) => source[name_node.byte_range()].to_string(),
+            None => return Ok(()),
+        };
+
+        let owner = node
+            .parent()
+            .filter(|p| p.kind() == "declaration_list")
+            .and_then(|list| list.parent())
+            .filter(|p| IMPL_KINDS.contains(&p.kind()));
+        let node_type = if owner.is_some() {
+            NodeType::Method
+        } else {
+            NodeType::Function
+        };
+
+        let line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        let signature = source[node.byte_range()]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let id = format!("{}:{}:{}", file_path.display(), func_name, line);
+
+        let mut node_obj = Node::new(
+            id.clone(),
+            func_name,
+            node_type,
+            file_path.to_path_buf(),
+            line,
+            end_line,
+            package_name.to_string(),
+            signature,
+        );
+
+        if let Some(params) = node.child_by_field_name("parameters") {
+            node_obj.parameters = self.extract_parameters(params, source);
+        }
+        if let Some(return_type) = node.child_by_field_name("return_type") {
+            node_obj
+                .returns
+                .push(source[return_type.byte_range()].to_string());
+        }
+        if let Some(owner) = owner {
+            // `impl Trait for Type` methods belong to `Type`; trait default
+            // methods belong to the trait
+            let (type_field, trait_field) = match owner.kind() {
+                "impl_item" => ("type", owner.child_by_field_name("trait")),
+                _ => ("name", None),
+            };
+            if let Some(type_name) = owner
+                .child_by_field_name(type_field)
+                .and_then(|t| self.type_name(t, source))
+            {
+                node_obj.metadata.insert("class".to_string(), type_name);
+            }
+            if let Some(trait_name) = trait_field.and_then(|t| self.type_name(t, source)) {
+                node_obj.metadata.insert("trait".to_string(), trait_name);
+            }
+        }
+        node_obj.metadata.insert(
+            "max_nesting".to_string(),
+            traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+        );
+        traversal::mark_unused_parameters(&mut node_obj, node, source);
+        traversal::record_body_hash(&mut node_obj, node, source);
+        node_obj.decision_points = traversal::count_decision_points(node, |n| match n.kind() {
+            "match_arm" => n
+                .child_by_field_name("pattern")
+                .is_none_or(|p| &source[p.byte_range()] != "_"),
+            "binary_expression" => n
+                .child_by_field_name("operator")
+                .is_some_and(|op| matches!(op.kind(), "&&" | "||")),
+            kind => DECISION_KINDS.contains(&kind),
+        });
+
+        graph.add_node(node_obj);
+
+        if let Some(body) = node.child_by_field_name("body") {
+            self.extract_calls_in_node(body, source, file_path, &id, graph)?;
+        }
+
+        Ok(())
+    }
+
+    /// Simple name of a type reference: `Foo` for `Foo`, `a::Foo` or `Foo<T>`
+    fn type_name(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "type_identifier" => Some(source[node.byte_range()].to_string()),
+            "scoped_type_identifier" => node
+                .child_by_field_name("name")
+                .map(|n| source[n.byte_range()].to_string()),
+            "generic_type" => self.type_name(node.child_by_field_name("type")?, source),
+            _ => None,
+        }
+    }
+
+    fn extract_parameters(&self, node: tree_sitter::Node, source: &str) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let mut cursor = node.walk();
+
+        for child in node.named_children(&mut cursor) {
+            match child.kind() {
+                "self_parameter" => parameters.push(Parameter {
+                    name: "self".to_string(),
+                    param_type: source[child.byte_range()].to_string(),
+                }),
+                "parameter" => {
+                    let name = child
+                        .child_by_field_name("pattern")
+                        .map(|p| source[p.byte_range()].to_string())
+                        .unwrap_or_else(|| "_".to_string());
+                    let param_type = child
+                        .child_by_field_name("type")
+                        .map(|t| source[t.byte_range()].to_string())
+                        .unwrap_or_default();
+                    parameters.push(Parameter { name, param_type });
+                }
+                _ => {}
+            }
+        }
+
+        parameters
+    }
+
+    fn extract_calls_in_node(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        from_id: &str,
+        graph: &mut CodeGraph,
+    ) -> Result<()> {
+        let mut budget = CallBudget::new(&self.options);
+        traversal::walk_preorder(node, |child| {
+            self.record_call(child, node, source, file_path, from_id, graph, &mut budget);
+            Ok(())
+        })?;
+
+        if budget.truncated() {
+            graph.add_tag(from_id, TRUNCATED_EDGES_TAG);
+        }
+
+        Ok(())
+    }
+
+    /// Record a call edge if `node` is a call and the budget allows it.
+    /// Method calls (`x.f()`) are call expressions over a field expression;
+    /// macro invocations are not recorded.
+    #[allow(clippy::too_many_arguments)]
+    fn record_call(
+        &self,
+        node: tree_sitter::Node,
+        root: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        from_id: &str,
+        graph: &mut CodeGraph,
+        budget: &mut CallBudget,
+    ) {
+        if node.kind() != "call_expression" {
+            return;
+        }
+
+        // Unwrap `x.f(..)`, `Type::f(..)` and `f::<T>(..)` down to the callee name
+        let mut callee = node.child_by_field_name("function");
+        while let Some(n) = callee {
+            callee = match n.kind() {
+                "field_expression" => n.child_by_field_name("field"),
+                "scoped_identifier" => n.child_by_field_name("name"),
+                "generic_function" => n.child_by_field_name("function"),
+                _ => break,
+            };
+        }
+
+        let called_func = match callee {
+            Some(n) if matches!(n.kind(), "identifier" | "field_identifier") => {
+                source[n.byte_range()].to_string()
+            }
+            _ => return,
+        };
+
+        if !budget.admit(&called_func) {
+            return;
+        }
+
+        let line = node.start_position().row + 1;
+        let call_site = source[node.byte_range()].to_string();
+
+        let mut edge = Edge::new(
+            from_id.to_string(),
+            called_func,
+            EdgeType::Calls,
+            call_site,
+            file_path.to_path_buf(),
+            line,
+        );
+        edge.metadata.insert(
+            "conditional".to_string(),
+            traversal::is_conditional(node, root, |n| DECISION_KINDS.contains(&n.kind()))
+                .to_string(),
+        );
+        graph.add_edge(edge);
+    }
+}
@@ -0,0 +1,464 @@
+use super::generated;
+use super::options::{CallBudget, ParseOptions, TRUNCATED_EDGES_TAG};
+use super::traversal;
+use crate::core::{CodeGraph, Edge, EdgeType, Node, NodeType, Parameter, ABSTRACT_TAG};
+use anyhow::{Context, Result};
+use std::path::Path;
+use tree_sitter::Parser;
+
+/// Control-flow expressions counted for `max_nesting` metadata
+const NESTING_KINDS: &[&str] = &[
+    "if_expression",
+    "match_expression",
+    "for_expression",
+    "while_expression",
+    "do_while_expression",
+    "try_expression",
+];
+
+/// Branching constructs counted as decision points (cyclomatic complexity).
+/// Each `case` of a match or catch counts except the catch-all `case _`;
+/// `&&`/`||` are operator identifiers in Scala and are matched by text.
+const DECISION_KINDS: &[&str] = &[
+    "if_expression",
+    "for_expression",
+    "while_expression",
+    "do_while_expression",
+    "case_clause",
+];
+
+/// Type definitions recorded as `Class` nodes; `def`s inside them are methods
+const TYPE_KINDS: &[&str] = &["class_definition", "object_definition", "trait_definition"];
+
+pub struct ScalaParser {
+    parser: Parser,
+    options: ParseOptions,
+}
+
+impl ScalaParser {
+    /// File extensions treated as Scala sources
+    pub const EXTENSIONS: &'static [&'static str] = &["scala"];
+
+    pub fn new() -> Result<Self> {
+        let mut parser = Parser::new();
+        parser
+            .set_language(&tree_sitter_scala::LANGUAGE.into())
+            .context("Failed to set Scala language")?;
+        Ok(Self {
+            parser,
+            options: ParseOptions::default(),
+        })
+    }
+
+    /// Apply parse options (e.g. edge caps) to this parser
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn parse_directory(&mut self, dir: &Path, graph: &mut CodeGraph) -> Result<()> {
+        use rayon::prelude::*;
+
+        let filter = self.options.file_filter(dir)?;
+        let file_paths: Vec<_> = jwalk::WalkDir::new(dir)
+            .into_iter()
+            .filter_map(|e| e.ok())
+            .filter(|e| {
+                let path = e.path();
+                path.extension()
+                    .and_then(|s| s.to_str())
+                    .is_some_and(|ext| Self::EXTENSIONS.contains(&ext))
+                    // No test-file convention to skip here
+                    && !filter.skips(&path, false)
+            })
+            .map(|e| e.path())
+            .collect();
+
+        let dir_str = dir.to_string_lossy().to_string();
+        let options = self.options.clone();
+
+        let files_parsed = file_paths.len();
+        if options.parse_serially(files_parsed) {
+            // Reuse this parser; no worker threads or chunk graphs
+            for path in &file_paths {
+                let result = self.parse_file(path, graph);
+                options.report_file(path, &result);
+            }
+        } else {
+            let chunk_size = 100.min(file_paths.len().max(1));
+            let results: Vec<CodeGraph> = file_paths
+                .par_chunks(chunk_size)
+                .map(|chunk| {
+                    let mut chunk_graph = CodeGraph::new_with_capacity(
+                        dir_str.clone(),
+                        "scala".to_string(),
+                        chunk.len() * 20,
+                        chunk.len() * 80,
+                    );
+
+                    for path in chunk {
+                        let mut parser = match Self::new() {
+                            Ok(p) => p.with_options(options.clone()),
+                            Err(_) => continue,
+                        };
+
+                        let result = parser.parse_file(path, &mut chunk_graph);
+                        options.report_file(path, &result);
+                    }
+
+                    chunk_graph
+                })
+                .collect();
+
+            for chunk_graph in results {
+                graph.merge(chunk_graph);
+            }
+        }
+
+        graph.metadata.stats.files_parsed = files_parsed;
+        graph.metadata.stats.total_nodes = graph.nodes.len();
+        graph.metadata.stats.total_edges = graph.edges.len();
+        Ok(())
+    }
+
+    pub fn parse_file(&mut self, file_path: &Path, graph: &mut CodeGraph) -> Result<()> {
+        let source = self
+            .options
+            .read_source(file_path)
+            .context(format!("Failed to read file: {}", file_path.display()))?;
+
+        if generated::is_generated(file_path, &source) {
+            graph.mark_generated_file(file_path);
+        }
+
+        let tree = self
+            .parser
+            .parse(&source, None)
+            .context("Failed to parse Scala file")?;
+
+        let root = tree.root_node();
+        let package_name = self.package_name(root, &source);
+
+        traversal::walk_preorder(root, |node| {
+            match node.kind() {
+                "function_definition" => {
+                    self.extract_function(node, &source, file_path, &package_name, graph)?;
+                }
+                kind if TYPE_KINDS.contains(&kind) => {
+                    // Record the type itself; traversal continues to find methods
+                    self.extract_type(node, &source, file_path, &package_name, graph)?;
+                }
+                _ => {}
+            }
+            Ok(())
+        })
+    }
+
+    /// Dotted name from the file's `package` clauses (`package a.b` then
+    /// `package c` yields `a.b.c`), or `default` without any
+    fn package_name(&self, root: tree_sitter::Node, source: &str) -> String {
+        let mut segments = Vec::new();
+        let mut cursor = root.walk();
+        for child in root.children(&mut cursor) {
+            if child.kind() == "package_clause" {
+                if let Some(name) = child.child_by_field_name("name") {
+                    segments.push(source[name.byte_range()].to_string());
+                }
+            }
+        }
+
+        if segments.is_empty() {
+            "default".to_string()
+        } else {
+            segments.join(".")
+        }
+    }
+
+    fn extract_type(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) -> Result<()> {
+        let type_name = match node.child_by_field_name("name") {
+            Some(name_node) => source[name_node.byte_range()].to_string(),
+            None => return Ok(()),
+        };
+
+        let line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        let signature = source[node.byte_range()]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let id = format!("{}:{}:{}", file_path.display(), type_name, line);
+
+        let mut type_node = Node::new(
+            id.clone(),
+            type_name,
+            NodeType::Class,
+            file_path.to_path_buf(),
+            line,
+            end_line,
+            package_name.to_string(),
+            signature,
+        );
+        if node.kind() == "trait_definition" || self.has_modifier(node, source, "abstract") {
+            type_node.tags.push(ABSTRACT_TAG.to_string());
+        }
+        graph.add_node(type_node);
+
+        // `extends Base(args) with Mixin1 with Mixin2`: the first parent is
+        // extended, mixed-in traits are implemented
+        let Some(clause) = node.child_by_field_name("extend") else {
+            return Ok(());
+        };
+        let mut cursor = clause.walk();
+        for (i, parent) in clause
+            .children_by_field_name("type", &mut cursor)
+            .enumerate()
+        {
+            let Some(parent_name) = self.type_name(parent, source) else {
+                continue;
+            };
+            let edge_type = if i == 0 {
+                EdgeType::Extends
+            } else {
+                EdgeType::Implements
+            };
+
+            graph.add_edge(Edge::new(
+                id.clone(),
+                parent_name,
+                edge_type,
+                source[parent.byte_range()].to_string(),
+                file_path.to_path_buf(),
+                parent.start_position().row + 1,
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Simple name of a type reference: `Base` for `Base`, `a.b.Base` or `Base[T]`
+    fn type_name(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "type_identifier" => Some(source[node.byte_range()].to_string()),
+            "stable_type_identifier" => node
+                .named_child(node.named_child_count().checked_sub(1)?)
+                .map(|n| source[n.byte_range()].to_string()),
+            "generic_type" => self.type_name(node.child_by_field_name("type")?, source),
+            _ => None,
+        }
+    }
+
+    fn has_modifier(&self, node: tree_sitter::Node, source: &str, modifier: &str) -> bool {
+        let mut cursor = node.walk();
+        let has_modifier = node.children(&mut cursor).any(|child| {
+            child.kind() == "modifiers"
+                && source[child.byte_range()]
+                    .split_whitespace()
+                    .any(|m| m == modifier)
+        });
+        has_modifier
+    }
+
+    fn extract_function(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) -> Result<()> {
+        let func_name = match node.child_by_field_name("name") {
+            Some(name_node) => source[name_node.byte_range()].to_string(),
+            None => return Ok(()),
+        };
+
+        let enclosing_type = self.enclosing_type(node, source);
+        let node_type = if enclosing_type.is_some() {
+            NodeType::Method
+        } else {
+            NodeType::Function
+        };
+
+        let line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        let signature = source[node.byte_range()]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let id = format!("{}:{}:{}", file_path.display(), func_name, line);
+
+        let mut node_obj = Node::new(
+            id.clone(),
+            func_name,
+            node_type,
+            file_path.to_path_buf(),
+            line,
+            end_line,
+            package_name.to_string(),
+            signature,
+        );
+
+        // Both type and value parameter lists use the `parameters` field
+        let mut cursor = node.walk();
+        for list in node.children_by_field_name("parameters", &mut cursor) {
+            if list.kind() == "parameters" {
+                node_obj
+                    .parameters
+                    .extend(self.extract_parameters(list, source));
+            }
+        }
+        if let Some(return_type) = node.child_by_field_name("return_type") {
+            node_obj
+                .returns
+                .push(source[return_type.byte_range()].to_string());
+        }
+        if let Some(type_name) = enclosing_type {
+            node_obj.metadata.insert("class".to_string(), type_name);
+        }
+        node_obj.metadata.insert(
+            "max_nesting".to_string(),
+            traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+        );
+        traversal::mark_unused_parameters(&mut node_obj, node, source);
+        traversal::record_body_hash(&mut node_obj, node, source);
+        node_obj.decision_points = traversal::count_decision_points(node, |n| {
+            (DECISION_KINDS.contains(&n.kind())
+                && n.child_by_field_name("pattern")
+                    .is_none_or(|p| p.kind() != "wildcard"))
+                || (n.kind() == "operator_identifier"
+                    && matches!(&source[n.byte_range()], "&&" | "||"))
+        });
+
+        graph.add_node(node_obj);
+
+        if let Some(body) = node.child_by_field_name("body") {
+            self.extract_calls_in_node(body, source, file_path, &id, graph)?;
+        }
+
+        Ok(())
+    }
+
+    /// Name of the nearest class/object/trait whose body directly holds `node`.
+    /// Local `def`s nested in another `def` are functions, not methods.
+    fn enclosing_type(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        let body = node.parent().filter(|p| p.kind() == "template_body")?;
+        let owner = body.parent().filter(|p| TYPE_KINDS.contains(&p.kind()))?;
+        owner
+            .child_by_field_name("name")
+            .map(|name| source[name.byte_range()].to_string())
+    }
+
+    fn extract_parameters(&self, node: tree_sitter::Node, source: &str) -> Vec<Parameter> {
+        let mut parameters = Vec::new();
+        let mut cursor = node.walk();
+
+        for child in node.named_children(&mut cursor) {
+            if child.kind() != "parameter" {
+                continue;
+            }
+
+            let name = child
+                .child_by_field_name("name")
+                .map(|n| source[n.byte_range()].to_string())
+                .unwrap_or_else(|| "_".to_string());
+            let param_type = child
+                .child_by_field_name("type")
+                .map(|t| source[t.byte_range()].to_string())
+                .unwrap_or_default();
+
+            parameters.push(Parameter { name, param_type });
+        }
+
+        parameters
+    }
+
+    fn extract_calls_in_node(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        from_id: &str,
+        graph: &mut CodeGraph,
+    ) -> Result<()> {
+        let mut budget = CallBudget::new(&self.options);
+        traversal::walk_preorder(node, |child| {
+            self.record_call(child, node, source, file_path, from_id, graph, &mut budget);
+            Ok(())
+        })?;
+
+        if budget.truncated() {
+            graph.add_tag(from_id, TRUNCATED_EDGES_TAG);
+        }
+
+        Ok(())
+    }
+
+    /// Record a call edge if `node` is a call or a named infix application
+    /// (`xs map f`) and the budget allows it
+    #[allow(clippy::too_many_arguments)]
+    fn record_call(
+        &self,
+        node: tree_sitter::Node,
+        root: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        from_id: &str,
+        graph: &mut CodeGraph,
+        budget: &mut CallBudget,
+    ) {
+        let callee = match node.kind() {
+            "call_expression" => {
+                // Unwrap `obj.f(...)` and `f[T](...)` down to the callee name
+                let mut callee = node.child_by_field_name("function");
+                while let Some(n) = callee {
+                    callee = match n.kind() {
+                        "field_expression" => n.child_by_field_name("field"),
+                        "generic_function" => n.child_by_field_name("function"),
+                        _ => break,
+                    };
+                }
+                callee
+            }
+            // Symbolic operators (`a + b`) are not method calls worth recording
+            "infix_expression" => node
+                .child_by_field_name("operator")
+                .filter(|op| op.kind() == "identifier"),
+            _ => return,
+        };
+
+        let called_func = match callee {
+            Some(n) if n.kind() == "identifier" => source[n.byte_range()].to_string(),
+            _ => return,
+        };
+
+        if !budget.admit(&called_func) {
+            return;
+        }
+
+        let line = node.start_position().row + 1;
+        let call_site = source[node.byte_range()].to_string();
+
+        let mut edge = Edge::new(
+            from_id.to_string(),
+            called_func,
+            EdgeType::Calls,
+            call_site,
+            file_path.to_path_buf(),
+            line,
+        );
+        edge.metadata.insert(
+            "conditional".to_string(),
+            traversal::is_conditional(node, root, |n| DECISION_KINDS.contains(&n.kind()))
+                .to_string(),
+        );
+        graph.add_edge(edge);
+    }
+}
@@ -0,0 +1,249 @@
+use anyhow::Result;
+use sha2::{Digest, Sha256};
+use std::collections::HashSet;
+use tree_sitter::Node;
+
+/// Visit `root` and all of its descendants in pre-order (document order).
+///
+/// Uses a tree cursor instead of native recursion, so deeply nested sources
+/// (e.g. generated code or long chained expressions) can't overflow the stack.
+pub fn walk_preorder<'tree, F>(root: Node<'tree>, mut visit: F) -> Result<()>
+where
+    F: FnMut(Node<'tree>) -> Result<()>,
+{
+    let mut cursor = root.walk();
+
+    loop {
+        visit(cursor.node())?;
+
+        if cursor.goto_first_child() {
+            continue;
+        }
+
+        // No children: move to the next sibling, climbing up as needed.
+        // The cursor never moves above `root`, so this terminates there.
+        loop {
+            if cursor.goto_next_sibling() {
+                break;
+            }
+            if !cursor.goto_parent() {
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Deepest nesting of `nesting_kinds` nodes (if/for/while/...) below `root`.
+/// `else if` chains count as one level, like their source indentation.
+pub fn max_nesting_depth(root: Node, nesting_kinds: &[&str]) -> usize {
+    let is_else_if = |node: Node| {
+        node.parent().is_some_and(|p| {
+            p.kind() == "else_clause" || p.child_by_field_name("alternative") == Some(node)
+        })
+    };
+
+    let mut max_depth = 0;
+    // Explicit stack for the same reason as `walk_preorder`
+    let mut stack = vec![(root, 0)];
+    while let Some((node, depth)) = stack.pop() {
+        let depth = if node.id() != root.id()
+            && nesting_kinds.contains(&node.kind())
+            && !is_else_if(node)
+        {
+            depth + 1
+        } else {
+            depth
+        };
+        max_depth = max_depth.max(depth);
+
+        let mut cursor = node.walk();
+        stack.extend(node.children(&mut cursor).map(|child| (child, depth)));
+    }
+
+    max_depth
+}
+
+/// Number of nodes below `root` that branch control flow, as judged by
+/// `is_decision` (if/loop/case/catch statements, `&&`/`||` tokens, ...).
+/// Used for cyclomatic complexity: decision points + 1.
+pub fn count_decision_points<F>(root: Node, is_decision: F) -> usize
+where
+    F: Fn(Node) -> bool,
+{
+    let mut count = 0;
+    // walk_preorder's visitor never fails here
+    let _ = walk_preorder(root, |node| {
+        if node.id() != root.id() && is_decision(node) {
+            count += 1;
+        }
+        Ok(())
+    });
+    count
+}
+
+/// Whether `node` only runs on some paths through `root`: some ancestor up
+/// to `root` is a decision point (per `is_decision`) and `node` isn't in a
+/// part of it that always runs (see [`runs_on_entry`]), or `node` is the
+/// right operand of a short-circuit operator.
+pub fn is_conditional<F>(node: Node, root: Node, is_decision: F) -> bool
+where
+    F: Fn(Node) -> bool,
+{
+    let mut grandchild = None;
+    let mut child = node;
+    while child.id() != root.id() {
+        let Some(parent) = child.parent() else {
+            break;
+        };
+        if is_decision(parent) && !runs_on_entry(parent, child, grandchild) {
+            return true;
+        }
+        if parent.child_by_field_name("right") == Some(child)
+            && parent
+                .child_by_field_name("operator")
+                .is_some_and(&is_decision)
+        {
+            return true;
+        }
+        grandchild = Some(child);
+        child = parent;
+    }
+    false
+}
+
+/// Whether `child` of the decision point `parent` runs whenever `parent`
+/// is reached: its condition, an initializer (Go `if v := f(); ...`, C
+/// `for (init; ...)`), or the iterable of a for-each loop (`for x in f()`,
+/// Go `range f()`). `grandchild` is the node `child` was reached from, for
+/// the clauses Go nests the loop header in.
+fn runs_on_entry(parent: Node, child: Node, grandchild: Option<Node>) -> bool {
+    let is_field = |node: Node, field: &str| node.child_by_field_name(field) == Some(child);
+    if is_field(parent, "condition") || is_field(parent, "initializer") {
+        return true;
+    }
+    if parent.kind().contains("for") && (is_field(parent, "right") || is_field(parent, "value")) {
+        return true;
+    }
+    let Some(grandchild) = grandchild else {
+        return false;
+    };
+    let fields: &[&str] = match child.kind() {
+        "for_clause" => &["initializer", "condition"],
+        "range_clause" => &["right"],
+        _ => &[],
+    };
+    fields
+        .iter()
+        .any(|field| child.child_by_field_name(field) == Some(grandchild))
+}
+
+/// Doc comment of a declaration: the comments directly above `node`, with
+/// no blank line or code in between, markers (`//`, `/**`, `*`) stripped.
+/// `None` when there are none.
+pub fn leading_comments(node: Node, source: &str) -> Option<String> {
+    let mut comments = Vec::new();
+    let mut next_row = node.start_position().row;
+    let mut sibling = node.prev_sibling();
+    while let Some(comment) = sibling.filter(|s| s.kind() == "comment") {
+        let before = comment.prev_sibling();
+        let trailing = before.is_some_and(|b| b.end_position().row == comment.start_position().row);
+        if comment.end_position().row + 1 < next_row || trailing {
+            break;
+        }
+        comments.push(&source[comment.byte_range()]);
+        next_row = comment.start_position().row;
+        sibling = before;
+    }
+    comments.reverse();
+
+    let lines: Vec<&str> = comments
+        .iter()
+        .flat_map(|comment| comment.lines())
+        .map(|line| {
+            let line = line.trim();
+            let line = line.strip_suffix("*/").unwrap_or(line);
+            let line = ["///", "//", "/**", "/*", "*"]
+                .iter()
+                .find_map(|marker| line.strip_prefix(marker))
+                .unwrap_or(line);
+            line.trim()
+        })
+        .collect();
+    doc_text(&lines)
+}
+
+/// Lines of documentation joined, without leading or trailing blank lines
+pub fn doc_text(lines: &[&str]) -> Option<String> {
+    let doc = lines.join("\n").trim().to_string();
+    (!doc.is_empty()).then_some(doc)
+}
+
+/// Record the parameters of `node` never referenced in the body of
+/// `function` as comma-separated `unused_params` metadata. A reference is
+/// any identifier token with the parameter's name. `_`-prefixed names,
+/// receivers (`self`, `this`) and destructuring patterns are skipped, as
+/// are functions without a body.
+pub fn mark_unused_parameters(node: &mut crate::core::Node, function: Node, source: &str) {
+    let Some(body) = function.child_by_field_name("body") else {
+        return;
+    };
+
+    let mut referenced = HashSet::new();
+    // walk_preorder's visitor never fails here
+    let _ = walk_preorder(body, |n| {
+        if n.child_count() == 0 && n.kind().ends_with("identifier") {
+            referenced.insert(&source[n.byte_range()]);
+        }
+        Ok(())
+    });
+
+    let unused: Vec<&str> = node
+        .parameters
+        .iter()
+        .map(|p| p.name.trim_start_matches("mut "))
+        .filter(|name| {
+            !name.is_empty()
+                && !name.starts_with('_')
+                && !matches!(*name, "self" | "this")
+                && name
+                    .chars()
+                    .all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+                && !referenced.contains(name)
+        })
+        .collect();
+    if !unused.is_empty() {
+        node.metadata
+            .insert("unused_params".to_string(), unused.join(","));
+    }
+}
+
+/// Record a hash of the source bytes of the body of `function` as
+/// `body_hash` metadata (first 8 bytes of a SHA-256, hex). Identifiers naming
+/// the function itself hash as a placeholder, so a recursive function keeps
+/// its hash when renamed. Functions without a body get none.
+pub fn record_body_hash(node: &mut crate::core::Node, function: Node, source: &str) {
+    let Some(body) = function.child_by_field_name("body") else {
+        return;
+    };
+
+    let mut hasher = Sha256::new();
+    let mut start = body.start_byte();
+    // walk_preorder's visitor never fails here
+    let _ = walk_preorder(body, |n| {
+        if n.child_count() == 0
+            && n.kind().ends_with("identifier")
+            && source[n.byte_range()] == node.name
+        {
+            hasher.update(&source.as_bytes()[start..n.start_byte()]);
+            hasher.update([0]);
+            start = n.end_byte();
+        }
+        Ok(())
+    });
+    hasher.update(&source.as_bytes()[start..body.end_byte()]);
+    let hash: String = hasher.finalize()[..8]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    node.metadata.insert("body_hash".to_string(), hash);
+}
@@ -1,12 +1,42 @@
-use crate::core::{CodeGraph, Edge, EdgeType, Node, NodeType, Parameter};
+use super::generated;
+use super::options::{CallBudget, ParseOptions, TRUNCATED_EDGES_TAG};
+use super::traversal;
+use crate::core::{CodeGraph, Edge, EdgeType, Node, NodeType, Parameter, ABSTRACT_TAG};
 use anyhow::{Context, Result};
-use std::fs;
 use std::path::Path;
 use tree_sitter::Parser;
 
+/// Control-flow statements counted for `max_nesting` metadata
+const NESTING_KINDS: &[&str] = &[
+    "if_statement",
+    "for_statement",
+    "for_in_statement",
+    "while_statement",
+    "do_statement",
+    "switch_statement",
+    "try_statement",
+];
+
+/// Branching constructs counted as decision points (cyclomatic complexity).
+/// `default` cases don't branch, so only `case` clauses are listed.
+const DECISION_KINDS: &[&str] = &[
+    "if_statement",
+    "for_statement",
+    "for_in_statement",
+    "while_statement",
+    "do_statement",
+    "switch_case",
+    "catch_clause",
+    "ternary_expression",
+    "&&",
+    "||",
+    "??",
+];
+
 pub struct TypeScriptParser {
     parser: Parser,
     language: Language,
+    options: ParseOptions,
 }
 
 #[derive(Clone, Copy)]
@@ -26,7 +56,24 @@ impl TypeScriptParser {
         parser
             .set_language(&ts_language.into())
             .context("Failed to set TypeScript language")?;
-        Ok(Self { parser, language })
+        Ok(Self {
+            parser,
+            language,
+            options: ParseOptions::default(),
+        })
+    }
+
+    /// Apply parse options (e.g. edge caps) to this parser
+    pub fn with_options(mut self, options: ParseOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// TypeScript/JavaScript test-file conventions (`*.test.*`, `*.spec.*`),
+    /// skipped unless tests are included
+    pub fn is_test_file(path: &Path) -> bool {
+        let path_str = path.to_string_lossy();
+        path_str.contains(".test.") || path_str.contains(".spec.")
     }
 
     pub fn parse_directory(&mut self, dir: &Path, graph: &mut CodeGraph) -> Result<()> {
@@ -38,14 +85,14 @@ impl TypeScriptParser {
         };
 
         // Phase 3: Parallel file discovery with jwalk
+        let filter = self.options.file_filter(dir)?;
         let file_paths: Vec<_> = jwalk::WalkDir::new(dir)
             .into_iter()
             .filter_map(|e| e.ok())
             .filter(|e| {
-                if let Some(ext) = e.path().extension().and_then(|s| s.to_str()) {
-                    extensions.contains(&ext)
-                        && !e.path().to_string_lossy().contains(".test.")
-                        && !e.path().to_string_lossy().contains(".spec.")
+                let path = e.path();
+                if let Some(ext) = path.extension().and_then(|s| s.to_str()) {
+                    extensions.contains(&ext) && !filter.skips(&path, Self::is_test_file(&path))
                 } else {
                     false
                 }
@@ -55,43 +102,51 @@ impl TypeScriptParser {
 
         let language = self.language;
         let dir_str = dir.to_string_lossy().to_string();
+        let options = self.options.clone();
         let lang_str = match language {
             Language::TypeScript => "typescript".to_string(),
             Language::JavaScript => "javascript".to_string(),
         };
 
-        // Phase 3: Batched parallel processing for better CPU utilization
-        // Process in chunks of 100 files to reduce merge overhead
-        let chunk_size = 100.min(file_paths.len().max(1));
-        let results: Vec<CodeGraph> = file_paths
-            .par_chunks(chunk_size)
-            .map(|chunk| {
-                let mut chunk_graph = CodeGraph::new_with_capacity(
-                    dir_str.clone(),
-                    lang_str.clone(),
-                    chunk.len() * 20, // Estimate ~20 nodes per file
-                    chunk.len() * 80, // Estimate ~80 edges per file
-                );
-
-                for path in chunk {
-                    let mut parser = match Self::new(language) {
-                        Ok(p) => p,
-                        Err(_) => continue,
-                    };
-
-                    if let Err(e) = parser.parse_file(path, &mut chunk_graph) {
-                        eprintln!("Warning: Failed to parse {}: {}", path.display(), e);
+        let files_parsed = file_paths.len();
+        if options.parse_serially(files_parsed) {
+            // Reuse this parser; no worker threads or chunk graphs
+            for path in &file_paths {
+                let result = self.parse_file(path, graph);
+                options.report_file(path, &result);
+            }
+        } else {
+            // Phase 3: Batched parallel processing for better CPU utilization
+            // Process in chunks of 100 files to reduce merge overhead
+            let chunk_size = 100.min(file_paths.len().max(1));
+            let results: Vec<CodeGraph> = file_paths
+                .par_chunks(chunk_size)
+                .map(|chunk| {
+                    let mut chunk_graph = CodeGraph::new_with_capacity(
+                        dir_str.clone(),
+                        lang_str.clone(),
+                        chunk.len() * 20, // Estimate ~20 nodes per file
+                        chunk.len() * 80, // Estimate ~80 edges per file
+                    );
+
+                    for path in chunk {
+                        let mut parser = match Self::new(language) {
+                            Ok(p) => p.with_options(options.clone()),
+                            Err(_) => continue,
+                        };
+
+                        let result = parser.parse_file(path, &mut chunk_graph);
+                        options.report_file(path, &result);
                     }
-                }
 
-                chunk_graph
-            })
-            .collect();
+                    chunk_graph
+                })
+                .collect();
 
-        // Merge all chunk results - uses incremental index updates (Phase 1 optimization)
-        let files_parsed = file_paths.len();
-        for chunk_graph in results {
-            graph.merge(chunk_graph);
+            // Merge all chunk results - uses incremental index updates (Phase 1 optimization)
+            for chunk_graph in results {
+                graph.merge(chunk_graph);
+            }
         }
 
         graph.metadata.stats.files_parsed = files_parsed;
@@ -101,9 +156,15 @@ impl TypeScriptParser {
     }
 
     pub fn parse_file(&mut self, file_path: &Path, graph: &mut CodeGraph) -> Result<()> {
-        let source = fs::read_to_string(file_path)
+        let source = self
+            .options
+            .read_source(file_path)
             .context(format!("Failed to read file: {}", file_path.display()))?;
 
+        if generated::is_generated(file_path, &source) {
+            graph.mark_generated_file(file_path);
+        }
+
         let tree = self
             .parser
             .parse(&source, None)
@@ -133,31 +194,292 @@ impl TypeScriptParser {
         package_name: &str,
         graph: &mut CodeGraph,
     ) -> Result<()> {
-        match node.kind() {
-            "function_declaration" | "function" => {
-                self.extract_function(node, source, file_path, package_name, graph)?;
-            }
-            "method_definition" => {
-                self.extract_method(node, source, file_path, package_name, graph)?;
-            }
-            "arrow_function" => {
-                self.extract_arrow_function(node, source, file_path, package_name, graph)?;
+        // Functions around the current node, innermost last. Calls belong to
+        // the innermost one, so calls inside anonymous callbacks
+        // (`items.map(x => save(x))`) are attributed to the function passing
+        // them, and calls inside a nested function only to that function.
+        let mut enclosing: Vec<Enclosing> = Vec::new();
+        traversal::walk_preorder(node, |node| {
+            while enclosing
+                .last()
+                .is_some_and(|function| node.start_byte() >= function.node.end_byte())
+            {
+                if let Some(function) = enclosing.pop() {
+                    function.finish(graph);
+                }
             }
-            "class_declaration" => {
-                // For classes, we still want to traverse to find methods
+
+            let owner = match node.kind() {
+                "function_declaration" => {
+                    Some(self.extract_function(node, source, file_path, package_name, graph))
+                }
+                "method_definition" => {
+                    self.extract_method(node, source, file_path, package_name, graph)
+                }
+                "arrow_function" | "function_expression" => {
+                    self.extract_arrow_function(node, source, file_path, package_name, graph)
+                }
+                "call_expression" => {
+                    if let Some(function) = enclosing.last_mut() {
+                        self.record_call(
+                            node,
+                            function.node,
+                            source,
+                            file_path,
+                            &function.id,
+                            graph,
+                            &mut function.budget,
+                        );
+                    }
+                    None
+                }
+                "class_declaration" | "abstract_class_declaration" => {
+                    // Record the class itself; traversal continues to find methods
+                    self.extract_class(node, source, file_path, package_name, graph)?;
+                    None
+                }
+                "import_statement" => {
+                    self.extract_import(node, source, file_path, package_name, graph);
+                    None
+                }
+                "enum_declaration" => {
+                    self.extract_declaration(node, "enum", source, file_path, package_name, graph);
+                    None
+                }
+                "type_alias_declaration" => {
+                    self.extract_declaration(
+                        node,
+                        "type_alias",
+                        source,
+                        file_path,
+                        package_name,
+                        graph,
+                    );
+                    None
+                }
+                "internal_module" | "module" => {
+                    // Functions inside the namespace are still found by the traversal
+                    self.extract_declaration(
+                        node,
+                        "namespace",
+                        source,
+                        file_path,
+                        package_name,
+                        graph,
+                    );
+                    None
+                }
+                _ => None,
+            };
+            if let Some(id) = owner {
+                enclosing.push(Enclosing {
+                    node,
+                    id,
+                    budget: CallBudget::new(&self.options),
+                });
             }
-            _ => {}
+
+            Ok(())
+        })?;
+
+        for function in enclosing {
+            function.finish(graph);
         }
+        Ok(())
+    }
+
+    /// Record an `Imports` edge targeting the module specifier of
+    /// `import ... from "mod"`, `import "mod"` or `import x = require("mod")`
+    fn extract_import(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) {
+        let specifier = node.child_by_field_name("source").or_else(|| {
+            let mut cursor = node.walk();
+            let require = node
+                .named_children(&mut cursor)
+                .find(|c| c.kind() == "import_require_clause");
+            require.and_then(|r| r.child_by_field_name("source"))
+        });
+        let Some(specifier) = specifier else {
+            return;
+        };
+
+        graph.add_import(
+            file_path,
+            package_name,
+            source[specifier.byte_range()]
+                .trim_matches(['"', '\'', '`'])
+                .to_string(),
+            source[node.byte_range()].to_string(),
+            node.start_position().row + 1,
+        );
+    }
+
+    /// Record an enum, type alias or namespace as a [`NodeType::Custom`]
+    /// node of subtype `kind`. These aren't callable, so no edges are added.
+    fn extract_declaration(
+        &self,
+        node: tree_sitter::Node,
+        kind: &str,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) {
+        // `namespace A.B` keeps its dotted name; `declare module "x"` its module name
+        let name = match node.child_by_field_name("name") {
+            Some(name_node) => source[name_node.byte_range()]
+                .trim_matches(['"', '\''])
+                .to_string(),
+            None => return,
+        };
 
-        // Recurse into children
+        let line = node.start_position().row + 1;
+        let signature = source[node.byte_range()]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+
+        graph.add_node(Node::new(
+            format!("{}:{}:{}", file_path.display(), name, line),
+            name,
+            NodeType::Custom(kind.to_string()),
+            file_path.to_path_buf(),
+            line,
+            node.end_position().row + 1,
+            package_name.to_string(),
+            signature,
+        ));
+    }
+
+    fn extract_class(
+        &self,
+        node: tree_sitter::Node,
+        source: &str,
+        file_path: &Path,
+        package_name: &str,
+        graph: &mut CodeGraph,
+    ) -> Result<()> {
+        let class_name = match node.child_by_field_name("name") {
+            Some(name_node) => source[name_node.byte_range()].to_string(),
+            None => return Ok(()),
+        };
+
+        let line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        let signature = source[node.byte_range()]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let id = format!("{}:{}:{}", file_path.display(), class_name, line);
+
+        let mut class_node = Node::new(
+            id.clone(),
+            class_name,
+            NodeType::Class,
+            file_path.to_path_buf(),
+            line,
+            end_line,
+            package_name.to_string(),
+            signature,
+        );
+        if node.kind() == "abstract_class_declaration" {
+            class_node.tags.push(ABSTRACT_TAG.to_string());
+        }
+        class_node.documentation = self.documentation(node, source);
+        graph.add_node(class_node);
+
+        // class B extends A / class B extends ns.A<T> implements C, ns.D<T>
         let mut cursor = node.walk();
         for child in node.children(&mut cursor) {
-            self.walk_tree(child, source, file_path, package_name, graph)?;
+            if child.kind() != "class_heritage" {
+                continue;
+            }
+
+            let mut heritage_cursor = child.walk();
+            for clause in child.children(&mut heritage_cursor) {
+                match clause.kind() {
+                    "extends_clause" => {
+                        if let Some(base) = clause.child_by_field_name("value") {
+                            let base_name = match base.kind() {
+                                "member_expression" => base
+                                    .child_by_field_name("property")
+                                    .map(|p| source[p.byte_range()].to_string()),
+                                _ => Some(source[base.byte_range()].to_string()),
+                            };
+
+                            if let Some(base_name) = base_name {
+                                graph.add_edge(Edge::new(
+                                    id.clone(),
+                                    base_name,
+                                    EdgeType::Extends,
+                                    source[clause.byte_range()].to_string(),
+                                    file_path.to_path_buf(),
+                                    clause.start_position().row + 1,
+                                ));
+                            }
+                        }
+                    }
+                    "implements_clause" => {
+                        let mut type_cursor = clause.walk();
+                        for interface in clause.named_children(&mut type_cursor) {
+                            if let Some(interface_name) = self.type_name(interface, source) {
+                                graph.add_edge(Edge::new(
+                                    id.clone(),
+                                    interface_name,
+                                    EdgeType::Implements,
+                                    source[interface.byte_range()].to_string(),
+                                    file_path.to_path_buf(),
+                                    interface.start_position().row + 1,
+                                ));
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+            }
         }
 
         Ok(())
     }
 
+    /// JSDoc or line comments above a declaration, looking past the
+    /// `export` and `const f =` wrappers around it
+    fn documentation(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        let mut declaration = node;
+        while let Some(parent) = declaration.parent().filter(|parent| {
+            matches!(
+                parent.kind(),
+                "export_statement"
+                    | "variable_declarator"
+                    | "lexical_declaration"
+                    | "variable_declaration"
+            )
+        }) {
+            declaration = parent;
+        }
+        traversal::leading_comments(declaration, source)
+    }
+
+    /// Simple name of a type reference: `Base` for `Base`, `ns.Base` or `Base<T>`
+    fn type_name(&self, node: tree_sitter::Node, source: &str) -> Option<String> {
+        match node.kind() {
+            "type_identifier" | "identifier" => Some(source[node.byte_range()].to_string()),
+            "nested_type_identifier" | "generic_type" => node
+                .child_by_field_name("name")
+                .and_then(|name| self.type_name(name, source)),
+            _ => None,
+        }
+    }
+
+    /// Record a function declaration; returns its node ID
     fn extract_function(
         &self,
         node: tree_sitter::Node,
@@ -165,7 +487,7 @@ impl TypeScriptParser {
         file_path: &Path,
         package_name: &str,
         graph: &mut CodeGraph,
-    ) -> Result<()> {
+    ) -> String {
         let mut func_name = String::new();
         let mut parameters = Vec::new();
 
@@ -196,8 +518,8 @@ impl TypeScriptParser {
         let id = format!("{}:{}:{}", file_path.display(), func_name, line);
 
         let mut node_obj = Node::new(
-            id,
-            func_name.clone(),
+            id.clone(),
+            func_name,
             NodeType::Function,
             file_path.to_path_buf(),
             line,
@@ -206,14 +528,20 @@ impl TypeScriptParser {
             signature,
         );
         node_obj.parameters = parameters;
+        node_obj.documentation = self.documentation(node, source);
+        node_obj.metadata.insert(
+            "max_nesting".to_string(),
+            traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+        );
+        traversal::mark_unused_parameters(&mut node_obj, node, source);
+        traversal::record_body_hash(&mut node_obj, node, source);
+        node_obj.decision_points =
+            traversal::count_decision_points(node, |n| DECISION_KINDS.contains(&n.kind()));
         graph.add_node(node_obj);
-
-        // Extract calls within this function
-        self.extract_calls_in_node(node, source, file_path, &func_name, line, graph)?;
-
-        Ok(())
+        id
     }
 
+    /// Record a class method; returns its node ID, or `None` if it has no name
     fn extract_method(
         &self,
         node: tree_sitter::Node,
@@ -221,7 +549,7 @@ impl TypeScriptParser {
         file_path: &Path,
         package_name: &str,
         graph: &mut CodeGraph,
-    ) -> Result<()> {
+    ) -> Option<String> {
         let mut method_name = String::new();
         let mut parameters = Vec::new();
 
@@ -238,36 +566,48 @@ impl TypeScriptParser {
             }
         }
 
-        if !method_name.is_empty() {
-            let line = node.start_position().row + 1;
-            let end_line = node.end_position().row + 1;
-            let signature = source[node.byte_range()]
-                .lines()
-                .next()
-                .unwrap_or("")
-                .to_string();
-            let id = format!("{}:{}:{}", file_path.display(), method_name, line);
-
-            let mut node_obj = Node::new(
-                id,
-                method_name.clone(),
-                NodeType::Method,
-                file_path.to_path_buf(),
-                line,
-                end_line,
-                package_name.to_string(),
-                signature,
-            );
-            node_obj.parameters = parameters;
-            graph.add_node(node_obj);
-
-            // Extract calls within this method
-            self.extract_calls_in_node(node, source, file_path, &method_name, line, graph)?;
+        if method_name.is_empty() {
+            return None;
         }
 
-        Ok(())
+        let line = node.start_position().row + 1;
+        let end_line = node.end_position().row + 1;
+        let signature = source[node.byte_range()]
+            .lines()
+            .next()
+            .unwrap_or("")
+            .to_string();
+        let id = format!("{}:{}:{}", file_path.display(), method_name, line);
+
+        let mut node_obj = Node::new(
+            id.clone(),
+            method_name,
+            NodeType::Method,
+            file_path.to_path_buf(),
+            line,
+            end_line,
+            package_name.to_string(),
+            signature,
+        );
+        node_obj.parameters = parameters;
+        node_obj.documentation = self.documentation(node, source);
+        node_obj.metadata.insert(
+            "max_nesting".to_string(),
+            traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+        );
+        traversal::mark_unused_parameters(&mut node_obj, node, source);
+        traversal::record_body_hash(&mut node_obj, node, source);
+        node_obj.decision_points =
+            traversal::count_decision_points(node, |n| DECISION_KINDS.contains(&n.kind()));
+        graph.add_node(node_obj);
+        Some(id)
     }
 
+    /// Record an arrow function or function expression named by its
+    /// variable (`const f = () => ...`) or, for `function f() {}`
+    /// expressions, by itself; returns its node ID. Anonymous ones
+    /// (callbacks, IIFEs) get no node and `None`, leaving their calls to the
+    /// enclosing function.
     fn extract_arrow_function(
         &self,
         node: tree_sitter::Node,
@@ -275,27 +615,14 @@ impl TypeScriptParser {
         file_path: &Path,
         package_name: &str,
         graph: &mut CodeGraph,
-    ) -> Result<()> {
-        // Try to find if this arrow function is assigned to a variable
-        let parent = node.parent();
-        let mut func_name = String::new();
-
-        if let Some(parent_node) = parent {
-            if parent_node.kind() == "variable_declarator" {
-                let mut cursor = parent_node.walk();
-                for child in parent_node.children(&mut cursor) {
-                    if child.kind() == "identifier" {
-                        func_name = source[child.byte_range()].to_string();
-                        break;
-                    }
-                }
-            }
-        }
-
-        if func_name.is_empty() {
-            // Skip anonymous arrow functions that aren't assigned
-            return Ok(());
-        }
+    ) -> Option<String> {
+        let name_node = node.child_by_field_name("name").or_else(|| {
+            node.parent()
+                .filter(|parent| parent.kind() == "variable_declarator")
+                .and_then(|parent| parent.child_by_field_name("name"))
+                .filter(|name| name.kind() == "identifier")
+        })?;
+        let func_name = source[name_node.byte_range()].to_string();
 
         let mut parameters = Vec::new();
         let mut cursor = node.walk();
@@ -315,8 +642,8 @@ impl TypeScriptParser {
         let id = format!("{}:{}:{}", file_path.display(), func_name, line);
 
         let mut node_obj = Node::new(
-            id,
-            func_name.clone(),
+            id.clone(),
+            func_name,
             NodeType::Function,
             file_path.to_path_buf(),
             line,
@@ -325,12 +652,17 @@ impl TypeScriptParser {
             signature,
         );
         node_obj.parameters = parameters;
+        node_obj.documentation = self.documentation(node, source);
+        node_obj.metadata.insert(
+            "max_nesting".to_string(),
+            traversal::max_nesting_depth(node, NESTING_KINDS).to_string(),
+        );
+        traversal::mark_unused_parameters(&mut node_obj, node, source);
+        traversal::record_body_hash(&mut node_obj, node, source);
+        node_obj.decision_points =
+            traversal::count_decision_points(node, |n| DECISION_KINDS.contains(&n.kind()));
         graph.add_node(node_obj);
-
-        // Extract calls within this arrow function
-        self.extract_calls_in_node(node, source, file_path, &func_name, line, graph)?;
-
-        Ok(())
+        Some(id)
     }
 
     fn extract_parameters(&self, node: tree_sitter::Node, source: &str) -> Vec<Parameter> {
@@ -380,27 +712,17 @@ impl TypeScriptParser {
         parameters
     }
 
-    fn extract_calls_in_node(
+    /// Record a call edge if `node` is a call expression and the budget allows it
+    #[allow(clippy::too_many_arguments)]
+    fn record_call(
         &self,
         node: tree_sitter::Node,
+        root: tree_sitter::Node,
         source: &str,
         file_path: &Path,
-        func_name: &str,
-        func_line: usize,
-        graph: &mut CodeGraph,
-    ) -> Result<()> {
-        self.find_calls(node, source, file_path, func_name, func_line, graph);
-        Ok(())
-    }
-
-    fn find_calls(
-        &self,
-        node: tree_sitter::Node,
-        source: &str,
-        file_path: &Path,
-        func_name: &str,
-        func_line: usize,
+        from_id: &str,
         graph: &mut CodeGraph,
+        budget: &mut CallBudget,
     ) {
         if node.kind() == "call_expression" {
             let mut called_func = String::new();
@@ -427,26 +749,42 @@ impl TypeScriptParser {
             if !called_func.is_empty() {
                 let line = node.start_position().row + 1;
                 let call_site = source[node.byte_range()].to_string();
-                let from_id = format!("{}:{}:{}", file_path.display(), func_name, func_line);
 
-                if graph.get_node_by_id(&from_id).is_some() {
-                    let edge = Edge::new(
-                        from_id,
+                if graph.get_node_by_id(from_id).is_some() && budget.admit(&called_func) {
+                    let mut edge = Edge::new(
+                        from_id.to_string(),
                         called_func,
                         EdgeType::Calls,
                         call_site,
                         file_path.to_path_buf(),
                         line,
                     );
+                    edge.metadata.insert(
+                        "conditional".to_string(),
+                        traversal::is_conditional(node, root, |n| {
+                            DECISION_KINDS.contains(&n.kind())
+                        })
+                        .to_string(),
+                    );
                     graph.add_edge(edge);
                 }
             }
         }
+    }
+}
 
-        // Recurse into children
-        let mut cursor = node.walk();
-        for child in node.children(&mut cursor) {
-            self.find_calls(child, source, file_path, func_name, func_line, graph);
+/// A function being walked, collecting the calls in its body
+struct Enclosing<'tree> {
+    node: tree_sitter::Node<'tree>,
+    id: String,
+    budget: CallBudget,
+}
+
+impl Enclosing<'_> {
+    /// Tag the function if its call budget cut edges
+    fn finish(self, graph: &mut CodeGraph) {
+        if self.budget.truncated() {
+            graph.add_tag(&self.id, TRUNCATED_EDGES_TAG);
         }
     }
 }
@@ -5,7 +5,11 @@ use anyhow::Result;
 /// Much faster than plain JSON and produces smaller files
 pub fn save_to_file(graph: &CodeGraph, path: &str) -> Result<()> {
     // Use compressed JSON for stability (bincode has issues with serde(skip) fields)
-    crate::serializer::compressed::save_to_file(graph, path)
+    crate::serializer::compressed::save_to_file(
+        graph,
+        path,
+        crate::serializer::DEFAULT_COMPRESSION_LEVEL,
+    )
 }
 
 /// Load graph from binary format
@@ -37,6 +41,7 @@ mod tests {
             documentation: None,
             tags: vec![],
             metadata: Default::default(),
+            decision_points: 0,
         };
         graph.add_node(node);
 
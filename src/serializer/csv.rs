@@ -1,9 +1,49 @@
 use crate::core::CodeGraph;
 use anyhow::Result;
-use std::fs::File;
-use std::io::Write;
+use serde::Serialize;
 use std::path::Path;
 
+/// Header of `<prefix>_nodes.csv`, matching [`NodeRecord`]'s fields
+const NODE_HEADERS: [&str; 8] = [
+    "id",
+    "name",
+    "type",
+    "file_path",
+    "line",
+    "end_line",
+    "package",
+    "signature",
+];
+
+/// Header of `<prefix>_edges.csv`, matching [`EdgeRecord`]'s fields
+const EDGE_HEADERS: [&str; 6] = ["from", "to", "type", "call_site", "file_path", "line"];
+
+/// One row of `<prefix>_nodes.csv`
+#[derive(Serialize)]
+struct NodeRecord<'a> {
+    id: &'a str,
+    name: &'a str,
+    #[serde(rename = "type")]
+    node_type: String,
+    file_path: String,
+    line: usize,
+    end_line: usize,
+    package: &'a str,
+    signature: &'a str,
+}
+
+/// One row of `<prefix>_edges.csv`
+#[derive(Serialize)]
+struct EdgeRecord<'a> {
+    from: &'a str,
+    to: &'a str,
+    #[serde(rename = "type")]
+    edge_type: String,
+    call_site: &'a str,
+    file_path: String,
+    line: usize,
+}
+
 pub fn save_to_files(graph: &CodeGraph, output_prefix: &Path) -> Result<()> {
     // Generate nodes.csv and edges.csv files
     let nodes_path = output_prefix.with_file_name(format!(
@@ -15,46 +55,43 @@ pub fn save_to_files(graph: &CodeGraph, output_prefix: &Path) -> Result<()> {
         output_prefix.file_stem().unwrap().to_string_lossy()
     ));
 
-    // Write nodes CSV
-    let mut nodes_file = File::create(&nodes_path)?;
-    writeln!(
-        nodes_file,
-        "id,name,type,file_path,line,end_line,package,signature"
-    )?;
-
+    // Rows are streamed through csv::Writer, which quotes fields containing
+    // commas, quotes or newlines as needed. Headers are written explicitly:
+    // serde would only emit them with the first row, leaving empty graphs
+    // without one.
+    let mut nodes_writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_path(&nodes_path)?;
+    nodes_writer.write_record(NODE_HEADERS)?;
     for node in &graph.nodes {
-        let node_type = format!("{:?}", node.node_type);
-        writeln!(
-            nodes_file,
-            "\"{}\",\"{}\",\"{}\",\"{}\",{},{},\"{}\",\"{}\"",
-            escape_csv(&node.id),
-            escape_csv(&node.name),
-            node_type,
-            escape_csv(&node.file_path.display().to_string()),
-            node.line,
-            node.end_line,
-            escape_csv(&node.package),
-            escape_csv(&node.signature)
-        )?;
+        nodes_writer.serialize(NodeRecord {
+            id: &node.id,
+            name: &node.name,
+            node_type: node.node_type.to_string(),
+            file_path: node.file_path.display().to_string(),
+            line: node.line,
+            end_line: node.end_line,
+            package: &node.package,
+            signature: &node.signature,
+        })?;
     }
+    nodes_writer.flush()?;
 
-    // Write edges CSV
-    let mut edges_file = File::create(&edges_path)?;
-    writeln!(edges_file, "from,to,type,call_site,file_path,line")?;
-
+    let mut edges_writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_path(&edges_path)?;
+    edges_writer.write_record(EDGE_HEADERS)?;
     for edge in &graph.edges {
-        let edge_type = format!("{:?}", edge.edge_type);
-        writeln!(
-            edges_file,
-            "\"{}\",\"{}\",\"{}\",\"{}\",\"{}\",{}",
-            escape_csv(&edge.from),
-            escape_csv(&edge.to),
-            edge_type,
-            escape_csv(&edge.call_site),
-            escape_csv(&edge.file_path.display().to_string()),
-            edge.line
-        )?;
+        edges_writer.serialize(EdgeRecord {
+            from: &edge.from,
+            to: &edge.to,
+            edge_type: format!("{:?}", edge.edge_type),
+            call_site: &edge.call_site,
+            file_path: edge.file_path.display().to_string(),
+            line: edge.line,
+        })?;
     }
+    edges_writer.flush()?;
 
     println!("Nodes written to: {}", nodes_path.display());
     println!("Edges written to: {}", edges_path.display());
@@ -62,6 +99,65 @@ pub fn save_to_files(graph: &CodeGraph, output_prefix: &Path) -> Result<()> {
     Ok(())
 }
 
-fn escape_csv(s: &str) -> String {
-    s.replace('"', "\"\"")
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Edge, EdgeType, Node, NodeType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_csv_quotes_commas_and_newlines() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        graph.add_node(Node::new(
+            "main.go:main:1".to_string(),
+            "main".to_string(),
+            NodeType::Function,
+            PathBuf::from("main.go"),
+            1,
+            6,
+            "main".to_string(),
+            "func main() {".to_string(),
+        ));
+        let call_site = "fmt.Printf(\"%s, %s\",\n\ta, b)";
+        graph.add_edge(Edge::new(
+            "main.go:main:1".to_string(),
+            "fmt.Printf".to_string(),
+            EdgeType::Calls,
+            call_site.to_string(),
+            PathBuf::from("main.go"),
+            2,
+        ));
+
+        let dir = tempfile::tempdir().unwrap();
+        save_to_files(&graph, &dir.path().join("graph.csv")).unwrap();
+
+        let mut reader = csv::Reader::from_path(dir.path().join("graph_edges.csv")).unwrap();
+        assert_eq!(reader.headers().unwrap(), EDGE_HEADERS.as_slice());
+        let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].len(), 6);
+        assert_eq!(&rows[0][3], call_site);
+        assert_eq!(&rows[0][5], "2");
+
+        let mut reader = csv::Reader::from_path(dir.path().join("graph_nodes.csv")).unwrap();
+        let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(&rows[0][2], "Function");
+        assert_eq!(&rows[0][7], "func main() {");
+    }
+
+    #[test]
+    fn test_csv_empty_graph_keeps_headers() {
+        let graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        let dir = tempfile::tempdir().unwrap();
+        save_to_files(&graph, &dir.path().join("graph.csv")).unwrap();
+
+        for (file, headers) in [
+            ("graph_nodes.csv", NODE_HEADERS.as_slice()),
+            ("graph_edges.csv", EDGE_HEADERS.as_slice()),
+        ] {
+            let mut reader = csv::Reader::from_path(dir.path().join(file)).unwrap();
+            assert_eq!(reader.headers().unwrap(), headers);
+            assert_eq!(reader.records().count(), 0);
+        }
+    }
 }
@@ -0,0 +1,169 @@
+use crate::core::{CodeGraph, EdgeType, Node, NodeType};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Rows per `UNWIND` statement; keeps each transaction small enough for
+/// cypher-shell on large graphs
+const BATCH_SIZE: usize = 1000;
+
+/// Save the graph as a Neo4j Cypher script, e.g. for
+/// `cypher-shell -f graph.cypher`
+pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
+    let mut file = File::create(output_path)?;
+    file.write_all(to_cypher_string(graph).as_bytes())?;
+    Ok(())
+}
+
+/// Cypher script creating every node as `(:CodeNode:<Type> {id, name,
+/// package, file, line, signature})`, then `CALLS`, `IMPLEMENTS` and
+/// `EXTENDS` relationships between them. Nodes and relationships are
+/// created in `UNWIND` batches; an index on `CodeNode(id)` keeps the
+/// relationship `MATCH`es fast. Imports and calls to functions outside the
+/// graph are left out.
+pub fn to_cypher_string(graph: &CodeGraph) -> String {
+    let mut out =
+        String::from("CREATE INDEX code_node_id IF NOT EXISTS FOR (n:CodeNode) ON (n.id);\n");
+
+    let mut by_label: BTreeMap<String, Vec<&Node>> = BTreeMap::new();
+    for node in &graph.nodes {
+        by_label
+            .entry(label(&node.node_type))
+            .or_default()
+            .push(node);
+    }
+    for (label, nodes) in &by_label {
+        for batch in nodes.chunks(BATCH_SIZE) {
+            let rows: Vec<String> = batch
+                .iter()
+                .map(|node| {
+                    format!(
+                        "{{id: {}, name: {}, package: {}, file: {}, line: {}, signature: {}}}",
+                        quote(&node.id),
+                        quote(&node.name),
+                        quote(&node.package),
+                        quote(&node.file_path.to_string_lossy()),
+                        node.line,
+                        quote(&node.signature)
+                    )
+                })
+                .collect();
+            out.push_str(&format!(
+                "UNWIND [{}] AS row\nCREATE (:CodeNode:{} {{id: row.id, name: row.name, package: row.package, file: row.file, line: row.line, signature: row.signature}});\n",
+                rows.join(", "),
+                label
+            ));
+        }
+    }
+
+    let mut by_type: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+    let mut seen = HashSet::new();
+    for edge in &graph.edges {
+        let rel_type = match edge.edge_type {
+            EdgeType::Calls => "CALLS",
+            EdgeType::Implements => "IMPLEMENTS",
+            EdgeType::Extends => "EXTENDS",
+            EdgeType::Imports => continue,
+        };
+        let Some(to) = graph.edge_target_id(edge) else {
+            continue;
+        };
+        if graph.get_node_by_id(&edge.from).is_none()
+            || !seen.insert((edge.from.as_str(), to, rel_type, edge.line))
+        {
+            continue;
+        }
+        by_type.entry(rel_type).or_default().push(format!(
+            "{{from: {}, to: {}, line: {}}}",
+            quote(&edge.from),
+            quote(to),
+            edge.line
+        ));
+    }
+    for (rel_type, rows) in &by_type {
+        for batch in rows.chunks(BATCH_SIZE) {
+            out.push_str(&format!(
+                "UNWIND [{}] AS row\nMATCH (a:CodeNode {{id: row.from}}), (b:CodeNode {{id: row.to}})\nCREATE (a)-[:{} {{line: row.line}}]->(b);\n",
+                batch.join(", "),
+                rel_type
+            ));
+        }
+    }
+
+    out
+}
+
+/// Node label: the variant name (`HttpHandler`, `TypeAlias`)
+fn label(node_type: &NodeType) -> String {
+    node_type.to_string()
+}
+
+/// Single-quoted Cypher string literal
+fn quote(s: &str) -> String {
+    let escaped = s
+        .replace('\\', "\\\\")
+        .replace('\'', "\\'")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r");
+    format!("'{}'", escaped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::Edge;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_cypher_nodes_calls_and_escaping() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name, node_type, signature) in [
+            ("main.go:main:1", "main", NodeType::Function, "func main()"),
+            (
+                "main.go:quote:5",
+                "quote",
+                NodeType::Function,
+                "func quote(s string) rune { return '\\'' }",
+            ),
+            (
+                "main.go:Kind:9",
+                "Kind",
+                NodeType::Custom("type_alias".to_string()),
+                "type Kind = string",
+            ),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                node_type,
+                PathBuf::from("main.go"),
+                1,
+                3,
+                "main".to_string(),
+                signature.to_string(),
+            ));
+        }
+        for callee in ["quote", "fmt.Println"] {
+            graph.add_edge(Edge::new(
+                "main.go:main:1".to_string(),
+                callee.to_string(),
+                EdgeType::Calls,
+                format!("{}()", callee),
+                PathBuf::from("main.go"),
+                2,
+            ));
+        }
+
+        let cypher = to_cypher_string(&graph);
+        assert!(cypher.contains("CREATE (:CodeNode:Function {id: row.id"));
+        assert!(cypher.contains("CREATE (:CodeNode:TypeAlias {id: row.id"));
+        assert!(cypher.contains("{id: 'main.go:main:1', name: 'main', package: 'main'"));
+        assert!(cypher.contains("signature: 'func quote(s string) rune { return \\'\\\\\\'\\' }'"));
+        assert!(cypher.contains("CREATE (a)-[:CALLS {line: row.line}]->(b);"));
+        assert!(cypher.contains("{from: 'main.go:main:1', to: 'main.go:quote:5', line: 2}"));
+        // External callees have no node to attach to
+        assert!(!cypher.contains("Println"));
+    }
+}
@@ -0,0 +1,153 @@
+use crate::core::{CodeGraph, NodeType};
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Save the graph as Cytoscape.js elements JSON, ready for
+/// `cytoscape({ elements: data.elements })`
+pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
+    let json = serde_json::to_string_pretty(&to_cytoscape_json(graph))?;
+    let mut file = File::create(output_path)?;
+    file.write_all(json.as_bytes())?;
+    Ok(())
+}
+
+/// `{"elements": {"nodes": [...], "edges": [...]}}`. Nodes carry
+/// `data: {id, label, type}` and the type as `classes` for stylesheet
+/// selectors (`.http-handler`); edges carry `data: {id, source, target,
+/// label}`. Callees and import sources outside the graph become nodes of
+/// type `external`, since Cytoscape rejects edges to missing nodes.
+pub fn to_cytoscape_json(graph: &CodeGraph) -> Value {
+    let mut nodes: Vec<Value> = graph
+        .nodes
+        .iter()
+        .map(|node| {
+            let class = css_class(&node.node_type);
+            json!({
+                "data": {
+                    "id": node.id,
+                    "label": node.display_name(),
+                    "type": class,
+                },
+                "classes": class,
+            })
+        })
+        .collect();
+
+    let mut externals = HashSet::new();
+    let mut external = |name: &str, nodes: &mut Vec<Value>| {
+        let id = format!("external:{}", name);
+        if externals.insert(id.clone()) {
+            nodes.push(json!({
+                "data": { "id": id, "label": name, "type": "external" },
+                "classes": "external",
+            }));
+        }
+        id
+    };
+
+    let mut edges = Vec::with_capacity(graph.edges.len());
+    for (idx, edge) in graph.edges.iter().enumerate() {
+        let source = match graph.get_node_by_id(&edge.from) {
+            Some(node) => node.id.clone(),
+            None => external(&edge.from, &mut nodes),
+        };
+        let target = match graph.edge_target_id(edge) {
+            Some(id) => id.to_string(),
+            None => external(&edge.to, &mut nodes),
+        };
+        edges.push(json!({
+            "data": {
+                "id": format!("e{}", idx),
+                "source": source,
+                "target": target,
+                "label": format!("{:?}", edge.edge_type).to_lowercase(),
+            }
+        }));
+    }
+
+    json!({ "elements": { "nodes": nodes, "edges": edges } })
+}
+
+/// Node type as a lowercase, hyphenated class name (`HttpHandler` ->
+/// `http-handler`, `type_alias` -> `type-alias`)
+fn css_class(node_type: &NodeType) -> String {
+    let mut class = String::new();
+    for (i, c) in node_type.to_string().chars().enumerate() {
+        if c == '_' {
+            class.push('-');
+        } else if c.is_ascii_uppercase() {
+            if i > 0 {
+                class.push('-');
+            }
+            class.push(c.to_ascii_lowercase());
+        } else {
+            class.push(c);
+        }
+    }
+    class
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Edge, EdgeType, Node};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_cytoscape_elements() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name, node_type) in [
+            ("main.go:main:1", "main", NodeType::Function),
+            ("main.go:Health:5", "Health", NodeType::HttpHandler),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                node_type,
+                PathBuf::from("main.go"),
+                1,
+                3,
+                "main".to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        for callee in ["Health", "fmt.Println"] {
+            graph.add_edge(Edge::new(
+                "main.go:main:1".to_string(),
+                callee.to_string(),
+                EdgeType::Calls,
+                format!("{}()", callee),
+                PathBuf::from("main.go"),
+                2,
+            ));
+        }
+
+        let json = to_cytoscape_json(&graph);
+        let nodes = json["elements"]["nodes"].as_array().unwrap();
+        let edges = json["elements"]["edges"].as_array().unwrap();
+        // Two graph nodes plus the external fmt.Println
+        assert_eq!(nodes.len(), 3);
+        assert_eq!(edges.len(), 2);
+
+        assert_eq!(nodes[1]["data"]["id"], "main.go:Health:5");
+        assert_eq!(nodes[1]["data"]["label"], "Health");
+        assert_eq!(nodes[1]["data"]["type"], "http-handler");
+        assert_eq!(nodes[1]["classes"], "http-handler");
+        assert_eq!(nodes[2]["data"]["type"], "external");
+
+        assert_eq!(edges[0]["data"]["id"], "e0");
+        assert_eq!(edges[0]["data"]["source"], "main.go:main:1");
+        assert_eq!(edges[0]["data"]["target"], "main.go:Health:5");
+        assert_eq!(edges[0]["data"]["label"], "calls");
+        assert_eq!(edges[1]["data"]["target"], "external:fmt.Println");
+
+        assert_eq!(
+            css_class(&NodeType::Custom("type_alias".to_string())),
+            "type-alias"
+        );
+    }
+}
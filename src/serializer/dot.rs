@@ -1,12 +1,26 @@
-use crate::core::CodeGraph;
+use super::legend;
+use crate::core::{CodeGraph, NodeType};
 use anyhow::Result;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
-pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
+/// Save the graph as styled DOT. `with_legend` adds a `cluster_legend`
+/// subgraph explaining the node colors and edge labels.
+pub fn save_to_file(graph: &CodeGraph, output_path: &Path, with_legend: bool) -> Result<()> {
     let mut file = File::create(output_path)?;
+    write_dot(graph, &mut file, with_legend)
+}
+
+/// Save the graph as single-line DOT without styling or labels, for
+/// embedding small graphs in tooltips and other inline renderers
+pub fn save_to_file_compact(graph: &CodeGraph, output_path: &Path) -> Result<()> {
+    let mut file = File::create(output_path)?;
+    writeln!(file, "{}", to_compact_string(graph))?;
+    Ok(())
+}
 
+fn write_dot(graph: &CodeGraph, file: &mut impl Write, with_legend: bool) -> Result<()> {
     // Write DOT header
     writeln!(file, "digraph CodeGraph {{")?;
     writeln!(file, "  rankdir=LR;")?;
@@ -15,60 +29,202 @@ pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
 
     // Write nodes
     for node in &graph.nodes {
-        let node_type = format!("{:?}", node.node_type);
+        let node_type = node.node_type.to_string();
         let label = format!(
             "{}\\n{}\\n{}:{}",
             node.name, node_type, node.package, node.line
         );
 
-        // Color nodes by type
-        let color = match node.node_type {
-            crate::core::NodeType::Function => "lightblue",
-            crate::core::NodeType::Method => "lightgreen",
-            crate::core::NodeType::HttpHandler => "yellow",
-            crate::core::NodeType::Middleware => "pink",
-        };
-
         writeln!(
             file,
             "  \"{}\" [label=\"{}\", fillcolor={}, style=filled];",
             escape_dot(&node.id),
             escape_dot(&label),
-            color
+            node_color(&node.node_type)
         )?;
     }
 
     writeln!(file)?;
 
-    // Write edges
+    // Write edges to the node the callee resolves to, or to the bare callee
+    // name for external and ambiguous callees
     for edge in &graph.edges {
         let edge_type = format!("{:?}", edge.edge_type);
 
-        // Try to find the target node to link to its ID
-        // If not found, just use the function name
-        let target = if let Some(_target_node) = graph.get_nodes_by_name(&edge.to).first() {
-            format!("{}:{}", edge.to, edge.line)
-        } else {
-            edge.to.clone()
-        };
-
         writeln!(
             file,
             "  \"{}\" -> \"{}\" [label=\"{}\"];",
             escape_dot(&edge.from),
-            escape_dot(&target),
+            escape_dot(graph.edge_target_id(edge).unwrap_or(&edge.to)),
             escape_dot(&edge_type)
         )?;
     }
 
+    if with_legend {
+        writeln!(file)?;
+        write_legend(file)?;
+    }
+
     // Close digraph
     writeln!(file, "}}")?;
 
     Ok(())
 }
 
+/// Fill color of nodes by type
+fn node_color(node_type: &NodeType) -> &'static str {
+    match node_type {
+        NodeType::Function => "lightblue",
+        NodeType::Method => "lightgreen",
+        NodeType::HttpHandler => "yellow",
+        NodeType::Middleware => "pink",
+        NodeType::Class => "orange",
+        NodeType::Custom(_) => "lightgrey",
+    }
+}
+
+/// One sample node per node type and a note listing the edge labels
+fn write_legend(file: &mut impl Write) -> Result<()> {
+    writeln!(file, "  subgraph cluster_legend {{")?;
+    writeln!(file, "    label=\"Legend\";")?;
+    writeln!(file, "    style=dashed;")?;
+    for (node_type, description) in legend::NODE_TYPES {
+        writeln!(
+            file,
+            "    \"legend:{}\" [label=\"{}\\n{}\", fillcolor={}, style=filled];",
+            legend::type_name(node_type),
+            legend::type_name(node_type),
+            escape_dot(description),
+            node_color(node_type)
+        )?;
+    }
+    let edge_labels: Vec<String> = legend::EDGE_TYPES
+        .iter()
+        .map(|(edge_type, description)| format!("{:?}: {}", edge_type, description))
+        .collect();
+    writeln!(
+        file,
+        "    \"legend:edges\" [shape=note, label=\"Edge labels\\l{}\\l\"];",
+        escape_dot(&edge_labels.join("\\l"))
+    )?;
+    writeln!(file, "  }}")?;
+    Ok(())
+}
+
+/// The whole graph as one line: bare node and edge statements only
+pub fn to_compact_string(graph: &CodeGraph) -> String {
+    let mut out = String::from("digraph CodeGraph{");
+
+    for node in &graph.nodes {
+        out.push_str(&format!("\"{}\";", escape_dot(&node.id)));
+    }
+    for edge in &graph.edges {
+        out.push_str(&format!(
+            "\"{}\"->\"{}\";",
+            escape_dot(&edge.from),
+            escape_dot(graph.edge_target_id(edge).unwrap_or(&edge.to))
+        ));
+    }
+
+    out.push('}');
+    out
+}
+
 fn escape_dot(s: &str) -> String {
     s.replace('"', "\\\"")
         .replace('\n', "\\n")
         .replace('\r', "\\r")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Edge, EdgeType, Node, NodeType};
+    use std::path::PathBuf;
+
+    /// Count (node statements, edge statements) in DOT text
+    fn count_statements(dot: &str) -> (usize, usize) {
+        let body = &dot[dot.find('{').unwrap() + 1..dot.rfind('}').unwrap()];
+        let statements: Vec<&str> = body
+            .split(';')
+            .map(str::trim)
+            .filter(|s| s.starts_with('"'))
+            .collect();
+        let edges = statements.iter().filter(|s| s.contains("->")).count();
+        (statements.len() - edges, edges)
+    }
+
+    #[test]
+    fn test_compact_dot_matches_full_dot() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name) in [("main.go:main:1", "main"), ("main.go:run:5", "run")] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("main.go"),
+                1,
+                3,
+                "main".to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        for callee in ["run", "fmt.Println"] {
+            graph.add_edge(Edge::new(
+                "main.go:main:1".to_string(),
+                callee.to_string(),
+                EdgeType::Calls,
+                format!("{}()", callee),
+                PathBuf::from("main.go"),
+                2,
+            ));
+        }
+
+        let mut full = Vec::new();
+        write_dot(&graph, &mut full, false).unwrap();
+        let full = String::from_utf8(full).unwrap();
+        let compact = to_compact_string(&graph);
+
+        assert_eq!(compact.lines().count(), 1);
+        assert!(!compact.contains("fillcolor"));
+        assert_eq!(count_statements(&compact), (2, 2));
+        assert_eq!(count_statements(&compact), count_statements(&full));
+        // Calls into the graph land on the callee's node, external ones on the name
+        assert!(compact.contains("\"main.go:main:1\"->\"main.go:run:5\";"));
+        assert!(compact.contains("\"main.go:main:1\"->\"fmt.Println\";"));
+    }
+
+    #[test]
+    fn test_dot_legend_cluster() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        graph.add_node(Node::new(
+            "main.go:main:1".to_string(),
+            "main".to_string(),
+            NodeType::Function,
+            PathBuf::from("main.go"),
+            1,
+            3,
+            "main".to_string(),
+            "func main()".to_string(),
+        ));
+
+        let render = |with_legend| {
+            let mut out = Vec::new();
+            write_dot(&graph, &mut out, with_legend).unwrap();
+            String::from_utf8(out).unwrap()
+        };
+
+        let plain = render(false);
+        assert!(!plain.contains("cluster_legend"));
+
+        let with_legend = render(true);
+        assert!(with_legend.contains("subgraph cluster_legend {"));
+        assert!(with_legend
+            .contains("\"legend:HttpHandler\" [label=\"HttpHandler\\nHTTP request handler\", fillcolor=yellow"));
+        assert!(with_legend.contains("\"legend:Custom\" [label=\"Custom\\nenum, type alias"));
+        assert!(with_legend.contains("fillcolor=lightgrey"));
+        assert!(with_legend.contains("Implements: type -> interface it implements"));
+        // The legend is inside the digraph
+        assert!(with_legend.find("cluster_legend").unwrap() < with_legend.rfind('}').unwrap());
+    }
+}
@@ -1,34 +1,114 @@
+use super::index_cache::SerializedIndices;
 use crate::core::CodeGraph;
 use anyhow::Result;
 
+/// Magic bytes closing a graph file that carries its own indices.
+/// Layout: `[graph][indices][indices length: u64 LE][magic]`.
+const EMBEDDED_INDICES_MAGIC: &[u8; 8] = b"CNAVIDX1";
+
 /// Save graph to JSON format with LZ4 compression
 /// LZ4 is 3-4x faster to decompress than zstd, with slightly larger files
 pub fn save_to_file(graph: &CodeGraph, path: &str) -> Result<()> {
-    // Serialize to JSON (respects serde attributes)
-    let json = serde_json::to_vec(graph)?;
-
-    // Compress with LZ4 (much faster decompression than zstd)
-    let compressed = lz4_flex::compress_prepend_size(&json);
-
     // Write directly to file
-    std::fs::write(path, compressed)?;
+    std::fs::write(path, to_bytes(graph)?)?;
 
     Ok(())
 }
 
+/// Save graph with its indices embedded in a trailer, so a single file
+/// loads without rebuilding them (no `.idx` companion needed)
+pub fn save_to_file_with_indices(graph: &CodeGraph, path: &str) -> Result<()> {
+    std::fs::write(path, to_bytes_with_indices(graph)?)?;
+    Ok(())
+}
+
 /// Load graph from JSON+LZ4 format
 pub fn load_from_file(path: &str) -> Result<CodeGraph> {
+    Ok(load_from_file_with_status(path)?.0)
+}
+
+/// Load graph from JSON+LZ4 format, also returning whether its indexes
+/// came from an embedded trailer (`false` means they were rebuilt)
+pub fn load_from_file_with_status(path: &str) -> Result<(CodeGraph, bool)> {
     // Read compressed data from file
     let compressed = std::fs::read(path)?;
+    decode(&compressed)
+}
+
+/// Encode a graph as JSON+LZ4 bytes (the contents of a `.bin` graph file)
+pub fn to_bytes(graph: &CodeGraph) -> Result<Vec<u8>> {
+    // Serialize to JSON (respects serde attributes)
+    let json = serde_json::to_vec(graph)?;
+
+    // Compress with LZ4 (much faster decompression than zstd)
+    Ok(lz4_flex::compress_prepend_size(&json))
+}
+
+/// Encode a graph followed by a trailer holding its serialized indices
+pub fn to_bytes_with_indices(graph: &CodeGraph) -> Result<Vec<u8>> {
+    let mut bytes = to_bytes(graph)?;
+    let indices = graph.extract_indices().to_bytes()?;
+
+    bytes.extend_from_slice(&indices);
+    bytes.extend_from_slice(&(indices.len() as u64).to_le_bytes());
+    bytes.extend_from_slice(EMBEDDED_INDICES_MAGIC);
+    Ok(bytes)
+}
+
+/// Decode JSON+LZ4 bytes and build (or restore embedded) graph indexes
+pub fn from_bytes(compressed: &[u8]) -> Result<CodeGraph> {
+    Ok(decode(compressed)?.0)
+}
+
+fn decode(bytes: &[u8]) -> Result<(CodeGraph, bool)> {
+    // Written with `--compression-level`: JSON+Zstd, never with embedded indices
+    if super::compressed::is_compressed(bytes) {
+        return Ok((super::compressed::from_bytes(bytes)?, false));
+    }
+
+    let (compressed, embedded) = split_embedded_indices(bytes);
 
     // Decompress with LZ4 (very fast)
-    let decompressed = lz4_flex::decompress_size_prepended(&compressed)
+    let decompressed = lz4_flex::decompress_size_prepended(compressed)
         .map_err(|e| anyhow::anyhow!("Failed to decompress: {}", e))?;
 
     // Deserialize from JSON
     let mut graph: CodeGraph = serde_json::from_slice(&decompressed)?;
+
+    // Embedded indices are only trusted if they still describe this graph
+    if let Some(indices) = embedded.and_then(|b| SerializedIndices::from_bytes(b).ok()) {
+        if indices.validate(graph.nodes.len(), graph.edges.len(), &graph.compute_hash()) {
+            graph.apply_indices(indices);
+            return Ok((graph, true));
+        }
+    }
+
     graph.build_indexes();
-    Ok(graph)
+    Ok((graph, false))
+}
+
+/// Split a graph file into the compressed graph and, if present, the
+/// embedded index bytes
+fn split_embedded_indices(bytes: &[u8]) -> (&[u8], Option<&[u8]>) {
+    let footer_len = EMBEDDED_INDICES_MAGIC.len() + 8;
+    let Some(footer_start) = bytes.len().checked_sub(footer_len) else {
+        return (bytes, None);
+    };
+    if &bytes[bytes.len() - EMBEDDED_INDICES_MAGIC.len()..] != EMBEDDED_INDICES_MAGIC {
+        return (bytes, None);
+    }
+
+    let mut len_bytes = [0u8; 8];
+    len_bytes.copy_from_slice(&bytes[footer_start..footer_start + 8]);
+    let indices_len = u64::from_le_bytes(len_bytes) as usize;
+
+    match footer_start.checked_sub(indices_len) {
+        Some(indices_start) => (
+            &bytes[..indices_start],
+            Some(&bytes[indices_start..footer_start]),
+        ),
+        None => (bytes, None),
+    }
 }
 
 #[cfg(test)]
@@ -55,6 +135,7 @@ mod tests {
             documentation: None,
             tags: vec![],
             metadata: Default::default(),
+            decision_points: 0,
         };
         graph.add_node(node);
 
@@ -68,4 +149,44 @@ mod tests {
         assert_eq!(loaded.nodes.len(), 1);
         assert_eq!(loaded.nodes[0].name, "testFunc");
     }
+
+    #[test]
+    fn test_embedded_indices_load_without_rebuild() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for name in ["main", "run"] {
+            graph.add_node(crate::core::Node::new(
+                format!("main.go:{}:1", name),
+                name.to_string(),
+                crate::core::NodeType::Function,
+                std::path::PathBuf::from("main.go"),
+                1,
+                3,
+                "main".to_string(),
+                String::new(),
+            ));
+        }
+        graph.add_edge(crate::core::Edge::new(
+            "main.go:main:1".to_string(),
+            "run".to_string(),
+            crate::core::EdgeType::Calls,
+            "run()".to_string(),
+            std::path::PathBuf::from("main.go"),
+            2,
+        ));
+
+        let temp_file = NamedTempFile::new().unwrap();
+        let path = temp_file.path().to_str().unwrap();
+
+        save_to_file_with_indices(&graph, path).unwrap();
+        let (loaded, embedded) = load_from_file_with_status(path).unwrap();
+        assert!(embedded);
+        assert_eq!(loaded.get_nodes_by_name("run").len(), 1);
+        assert_eq!(loaded.get_outgoing_edges("main.go:main:1").len(), 1);
+
+        // Plain files still load, with indexes rebuilt
+        save_to_file(&graph, path).unwrap();
+        let (loaded, embedded) = load_from_file_with_status(path).unwrap();
+        assert!(!embedded);
+        assert_eq!(loaded.get_nodes_by_name("run").len(), 1);
+    }
 }
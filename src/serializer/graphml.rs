@@ -1,10 +1,18 @@
-use crate::core::CodeGraph;
+use super::legend;
+use crate::core::{CodeGraph, Edge, EdgeType};
 use anyhow::Result;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
 
-pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
+/// Save the graph as GraphML. `with_legend` adds a `<desc>` to the graph
+/// explaining the node and edge `type` values. Nodes carry a numeric
+/// `complexity` (fan-in + fan-out). Parallel edges (the same call made
+/// several times) are written once, with the first call site and a `weight`
+/// counting them, for tools that scale node size and edge thickness by an
+/// attribute.
+pub fn save_to_file(graph: &CodeGraph, output_path: &Path, with_legend: bool) -> Result<()> {
     let mut file = File::create(output_path)?;
 
     // Write GraphML header
@@ -56,14 +64,25 @@ pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
         file,
         "  <key id=\"d6\" for=\"edge\" attr.name=\"call_site\" attr.type=\"string\"/>"
     )?;
+    writeln!(
+        file,
+        "  <key id=\"d7\" for=\"node\" attr.name=\"complexity\" attr.type=\"int\"/>"
+    )?;
+    writeln!(
+        file,
+        "  <key id=\"d8\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>"
+    )?;
     writeln!(file)?;
 
     // Start graph
     writeln!(file, "  <graph id=\"G\" edgedefault=\"directed\">")?;
+    if with_legend {
+        writeln!(file, "    <desc>{}</desc>", escape_xml(&legend_text()))?;
+    }
 
     // Write nodes
     for node in &graph.nodes {
-        let node_type = format!("{:?}", node.node_type);
+        let node_type = node.node_type.to_string();
         let file_path = node.file_path.display().to_string();
 
         writeln!(file, "    <node id=\"{}\">", escape_xml(&node.id))?;
@@ -88,11 +107,31 @@ pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
             "      <data key=\"d4\">{}</data>",
             escape_xml(&node.package)
         )?;
+        writeln!(
+            file,
+            "      <data key=\"d7\">{}</data>",
+            graph.get_complexity(&node.id).combined()
+        )?;
         writeln!(file, "    </node>")?;
     }
 
+    // Parallel edges (the same call made several times) become one
+    // weighted edge, in order of first appearance
+    let mut groups: Vec<(&Edge, usize)> = Vec::new();
+    let mut group_of: HashMap<(&str, &str, &EdgeType), usize> = HashMap::new();
+    for edge in &graph.edges {
+        let key = (edge.from.as_str(), edge.to.as_str(), &edge.edge_type);
+        match group_of.get(&key) {
+            Some(&idx) => groups[idx].1 += 1,
+            None => {
+                group_of.insert(key, groups.len());
+                groups.push((edge, 1));
+            }
+        }
+    }
+
     // Write edges
-    for (idx, edge) in graph.edges.iter().enumerate() {
+    for (idx, (edge, weight)) in groups.iter().enumerate() {
         let edge_type = format!("{:?}", edge.edge_type);
 
         writeln!(
@@ -112,6 +151,7 @@ pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
             "      <data key=\"d6\">{}</data>",
             escape_xml(&edge.call_site)
         )?;
+        writeln!(file, "      <data key=\"d8\">{}</data>", weight)?;
         writeln!(file, "    </edge>")?;
     }
 
@@ -122,6 +162,24 @@ pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Legend of the `type` values (node key d1, edge key d5)
+fn legend_text() -> String {
+    let mut text = String::from("Legend. Node type (d1):");
+    for (node_type, description) in legend::NODE_TYPES {
+        text.push_str(&format!(
+            " {} = {};",
+            legend::type_name(node_type),
+            description
+        ));
+    }
+    text.push_str(" Edge type (d5):");
+    for (edge_type, description) in legend::EDGE_TYPES {
+        text.push_str(&format!(" {:?} = {};", edge_type, description));
+    }
+    text.pop();
+    text
+}
+
 fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
@@ -129,3 +187,56 @@ fn escape_xml(s: &str) -> String {
         .replace('"', "&quot;")
         .replace('\'', "&apos;")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Node, NodeType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_graphml_complexity_and_edge_weight() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name) in [("a.go:main:1", "main"), ("a.go:run:5", "run")] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("a.go"),
+                1,
+                3,
+                "main".to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        for (callee, line) in [("run", 2), ("run", 3), ("fmt.Println", 4)] {
+            graph.add_edge(Edge::new(
+                "a.go:main:1".to_string(),
+                callee.to_string(),
+                EdgeType::Calls,
+                format!("{}()", callee),
+                PathBuf::from("a.go"),
+                line,
+            ));
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.graphml");
+        save_to_file(&graph, &path, false).unwrap();
+        let xml = std::fs::read_to_string(&path).unwrap();
+
+        assert!(xml
+            .contains("<key id=\"d7\" for=\"node\" attr.name=\"complexity\" attr.type=\"int\"/>"));
+        assert!(
+            xml.contains("<key id=\"d8\" for=\"edge\" attr.name=\"weight\" attr.type=\"int\"/>")
+        );
+        // main: fan-out 3, fan-in 0
+        assert!(xml.contains(
+            "<data key=\"d4\">main</data>\n      <data key=\"d7\">3</data>\n    </node>"
+        ));
+        // The two main -> run calls are one edge of weight 2
+        assert_eq!(xml.matches("<edge ").count(), 2);
+        assert_eq!(xml.matches("<data key=\"d8\">2</data>").count(), 1);
+        assert_eq!(xml.matches("<data key=\"d8\">1</data>").count(), 1);
+    }
+}
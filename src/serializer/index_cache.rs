@@ -1,14 +1,24 @@
 use anyhow::Result;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::hash::Hash;
 use std::path::Path;
 
 use crate::core::NodeType;
 
+/// Layout of the encoded indices; bump whenever a field is added, removed or
+/// changes type, since bincode can't skip unknown fields.
+/// 2: added `by_tag`.
+pub const FORMAT_VERSION: u32 = 2;
+
 /// Serialized indices for fast loading
 /// Stored as a companion .idx file alongside the graph binary
 #[derive(Serialize, Deserialize)]
 pub struct SerializedIndices {
+    /// Layout version ([`FORMAT_VERSION`]); encoded first so older layouts
+    /// are recognized before decoding the rest
+    pub format: u32,
+
     /// Version string for compatibility checking
     pub version: String,
 
@@ -33,8 +43,11 @@ pub struct SerializedIndices {
     /// Edge source node ID → edge indices
     pub outgoing: HashMap<String, Vec<usize>>,
 
-    /// Edge target node ID → edge indices
+    /// Edge target name → edge indices (`Imports` edges excluded)
     pub incoming: HashMap<String, Vec<usize>>,
+
+    /// Node tag → node indices
+    pub by_tag: HashMap<String, Vec<usize>>,
 }
 
 impl SerializedIndices {
@@ -47,10 +60,12 @@ impl SerializedIndices {
         node_by_id: &HashMap<String, usize>,
         by_name: &HashMap<String, Vec<usize>>,
         by_type: &HashMap<NodeType, Vec<usize>>,
+        by_tag: &HashMap<String, Vec<usize>>,
         outgoing: &HashMap<String, Vec<usize>>,
         incoming: &HashMap<String, Vec<usize>>,
     ) -> Self {
         Self {
+            format: FORMAT_VERSION,
             version: env!("CARGO_PKG_VERSION").to_string(),
             graph_hash,
             node_count,
@@ -60,20 +75,14 @@ impl SerializedIndices {
             by_type: by_type.clone(),
             outgoing: outgoing.clone(),
             incoming: incoming.clone(),
+            by_tag: by_tag.clone(),
         }
     }
 
     /// Save serialized indices to disk with compression
     pub fn save(&self, graph_path: &Path) -> Result<()> {
         let idx_path = graph_path.with_extension("idx");
-
-        // Serialize with bincode (faster than JSON)
-        let data = bincode::serialize(self)?;
-
-        // Compress with zstd (fast compression level)
-        let compressed = zstd::encode_all(&data[..], 1)?;
-
-        std::fs::write(idx_path, compressed)?;
+        std::fs::write(idx_path, self.to_bytes()?)?;
         Ok(())
     }
 
@@ -85,18 +94,258 @@ impl SerializedIndices {
             anyhow::bail!("Index cache file not found");
         }
 
-        let compressed = std::fs::read(idx_path)?;
-        let data = zstd::decode_all(&compressed[..])?;
-        let indices: Self = bincode::deserialize(&data)?;
+        Self::from_bytes(&std::fs::read(idx_path)?)
+    }
+
+    /// Encode as compressed bytes (the contents of a `.idx` file)
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        // Serialize with bincode (faster than JSON)
+        let data = bincode::serialize(self)?;
+
+        // Compress with zstd (fast compression level)
+        Ok(zstd::encode_all(&data[..], 1)?)
+    }
 
-        Ok(indices)
+    /// Decode bytes produced by [`SerializedIndices::to_bytes`]. Bytes in
+    /// another layout fail with "format changed" rather than a decode error.
+    pub fn from_bytes(compressed: &[u8]) -> Result<Self> {
+        let data = zstd::decode_all(compressed)?;
+        // The leading field alone; bincode ignores the bytes after it
+        let format: u32 = bincode::deserialize(&data)?;
+        if format != FORMAT_VERSION {
+            anyhow::bail!(format_changed(format));
+        }
+        Ok(bincode::deserialize(&data)?)
     }
 
     /// Validate that cached indices match the current graph
     pub fn validate(&self, node_count: usize, edge_count: usize, graph_hash: &str) -> bool {
-        self.node_count == node_count
-            && self.edge_count == edge_count
-            && self.graph_hash == graph_hash
-            && self.version == env!("CARGO_PKG_VERSION")
+        self.check(node_count, edge_count, graph_hash).is_ok()
+    }
+
+    /// Like [`validate`](Self::validate), but describes the first check that
+    /// failed (format, version, node count, edge count, then hash)
+    pub fn check(
+        &self,
+        node_count: usize,
+        edge_count: usize,
+        graph_hash: &str,
+    ) -> std::result::Result<(), String> {
+        if self.format != FORMAT_VERSION {
+            return Err(format_changed(self.format));
+        }
+        let version = env!("CARGO_PKG_VERSION");
+        if self.version != version {
+            return Err(format!(
+                "version mismatch (cache {}, codenav {})",
+                self.version, version
+            ));
+        }
+        if self.node_count != node_count {
+            return Err(format!(
+                "node count mismatch (cache {}, graph {})",
+                self.node_count, node_count
+            ));
+        }
+        if self.edge_count != edge_count {
+            return Err(format!(
+                "edge count mismatch (cache {}, graph {})",
+                self.edge_count, edge_count
+            ));
+        }
+        if self.graph_hash != graph_hash {
+            return Err(format!(
+                "hash mismatch (cache {}, graph {})",
+                self.graph_hash, graph_hash
+            ));
+        }
+        Ok(())
+    }
+
+    /// Compare these (cached) indices against freshly built ones and
+    /// describe every divergence. An empty result means they are identical.
+    pub fn diff(&self, expected: &SerializedIndices) -> Vec<String> {
+        let mut divergences = Vec::new();
+
+        if self.node_count != expected.node_count {
+            divergences.push(format!(
+                "node_count: cached {} vs actual {}",
+                self.node_count, expected.node_count
+            ));
+        }
+        if self.edge_count != expected.edge_count {
+            divergences.push(format!(
+                "edge_count: cached {} vs actual {}",
+                self.edge_count, expected.edge_count
+            ));
+        }
+        if self.graph_hash != expected.graph_hash {
+            divergences.push(format!(
+                "graph_hash: cached {} vs actual {}",
+                self.graph_hash, expected.graph_hash
+            ));
+        }
+
+        diff_index(
+            "node_by_id",
+            &self.node_by_id,
+            &expected.node_by_id,
+            &mut divergences,
+        );
+        diff_index(
+            "by_name",
+            &self.by_name,
+            &expected.by_name,
+            &mut divergences,
+        );
+        diff_index(
+            "by_type",
+            &self.by_type,
+            &expected.by_type,
+            &mut divergences,
+        );
+        diff_index("by_tag", &self.by_tag, &expected.by_tag, &mut divergences);
+        diff_index(
+            "outgoing",
+            &self.outgoing,
+            &expected.outgoing,
+            &mut divergences,
+        );
+        diff_index(
+            "incoming",
+            &self.incoming,
+            &expected.incoming,
+            &mut divergences,
+        );
+
+        divergences
+    }
+}
+
+/// Reason given for indices encoded in another layout
+fn format_changed(format: u32) -> String {
+    format!(
+        "format changed (cache format {}, codenav {})",
+        format, FORMAT_VERSION
+    )
+}
+
+/// Report keys that are missing, extra, or mapped to different values
+fn diff_index<K, V>(
+    name: &str,
+    cached: &HashMap<K, V>,
+    expected: &HashMap<K, V>,
+    divergences: &mut Vec<String>,
+) where
+    K: Eq + Hash + std::fmt::Debug,
+    V: PartialEq,
+{
+    let mut missing = 0;
+    let mut mismatched = 0;
+
+    for (key, value) in expected {
+        match cached.get(key) {
+            None => missing += 1,
+            Some(cached_value) if cached_value != value => mismatched += 1,
+            _ => {}
+        }
+    }
+    let extra = cached.keys().filter(|k| !expected.contains_key(k)).count();
+
+    if missing + mismatched + extra > 0 {
+        divergences.push(format!(
+            "{}: {} missing, {} extra, {} mismatched entries",
+            name, missing, extra, mismatched
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{CodeGraph, Edge, EdgeType, Node};
+    use std::path::PathBuf;
+
+    fn sample_graph() -> CodeGraph {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name, line) in [("test.go:a:1", "a", 1), ("test.go:b:10", "b", 10)] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("test.go"),
+                line,
+                line + 5,
+                "main".to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        graph.add_edge(Edge::new(
+            "test.go:a:1".to_string(),
+            "b".to_string(),
+            EdgeType::Calls,
+            "b()".to_string(),
+            PathBuf::from("test.go"),
+            2,
+        ));
+        graph
+    }
+
+    #[test]
+    fn test_cache_diff_detects_corruption() {
+        let dir = tempfile::tempdir().unwrap();
+        let graph_path = dir.path().join("graph.bin");
+        let graph = sample_graph();
+
+        graph.extract_indices().save(&graph_path).unwrap();
+        let cached = SerializedIndices::load(&graph_path).unwrap();
+        assert!(cached.diff(&graph.extract_indices()).is_empty());
+
+        // Corrupt the cache while keeping counts and hash intact, so the
+        // cheap validate() check still accepts it
+        let mut corrupted = cached;
+        corrupted.node_by_id.insert("test.go:a:1".to_string(), 1);
+        corrupted.incoming.remove("b");
+        corrupted.save(&graph_path).unwrap();
+
+        let reloaded = SerializedIndices::load(&graph_path).unwrap();
+        assert!(reloaded.validate(2, 1, &graph.compute_hash()));
+
+        let divergences = reloaded.diff(&graph.extract_indices());
+        assert_eq!(divergences.len(), 2);
+        assert!(divergences[0].starts_with("node_by_id"));
+        assert!(divergences[1].starts_with("incoming"));
+    }
+
+    #[test]
+    fn test_cache_check_explains_hash_mismatch() {
+        let mut graph = sample_graph();
+        let cached = graph.extract_indices();
+        assert_eq!(cached.check(2, 1, &graph.compute_hash()), Ok(()));
+
+        // Same counts, different content: only the hash catches it
+        graph.nodes[0].id = "test.go:a:2".to_string();
+        let reason = cached.check(2, 1, &graph.compute_hash()).unwrap_err();
+        assert!(reason.starts_with("hash mismatch"), "{}", reason);
+        assert!(!cached.validate(2, 1, &graph.compute_hash()));
+
+        let reason = cached.check(3, 1, &graph.compute_hash()).unwrap_err();
+        assert!(reason.starts_with("node count mismatch (cache 2, graph 3)"));
+    }
+
+    #[test]
+    fn test_cache_reports_changed_format() {
+        let graph = sample_graph();
+        let mut cached = graph.extract_indices();
+        cached.format = FORMAT_VERSION - 1;
+
+        let reason = cached.check(2, 1, &graph.compute_hash()).unwrap_err();
+        assert!(reason.starts_with("format changed"), "{}", reason);
+
+        // Another layout is reported as such instead of failing to decode
+        let err = SerializedIndices::from_bytes(&cached.to_bytes().unwrap())
+            .err()
+            .unwrap();
+        assert!(err.to_string().starts_with("format changed"), "{}", err);
     }
 }
@@ -1,9 +1,13 @@
-use crate::core::CodeGraph;
+use crate::core::{
+    CodeGraph, Edge, EdgeType, GraphMetadata, GraphStats, Node, NodeType, Parameter,
+    CUSTOM_NODE_TYPES,
+};
 use anyhow::Result;
 use serde_json;
 use std::collections::HashMap;
 use std::fs::File;
-use std::io::{BufWriter, Write};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
 
 /// Export graph to JSONL (JSON Lines) format
 /// Each line is a separate JSON object for streaming processing
@@ -19,10 +23,12 @@ pub fn export_jsonl(graph: &CodeGraph, output_path: &str) -> Result<()> {
         "generator": graph.metadata.generator,
         "language": graph.metadata.language,
         "root_path": graph.metadata.root_path,
+        "generated_files": graph.metadata.generated_files,
         "stats": {
             "total_nodes": graph.metadata.stats.total_nodes,
             "total_edges": graph.metadata.stats.total_edges,
             "files_parsed": graph.metadata.stats.files_parsed,
+            "isolated_nodes": graph.metadata.stats.isolated_nodes,
         }
     });
     writeln!(writer, "{}", serde_json::to_string(&metadata_line)?)?;
@@ -33,7 +39,7 @@ pub fn export_jsonl(graph: &CodeGraph, output_path: &str) -> Result<()> {
             "type": "node",
             "id": node.id,
             "name": node.name,
-            "node_type": format!("{:?}", node.node_type),
+            "node_type": node.node_type.to_string(),
             "file_path": node.file_path.display().to_string(),
             "line": node.line,
             "end_line": node.end_line,
@@ -44,6 +50,7 @@ pub fn export_jsonl(graph: &CodeGraph, output_path: &str) -> Result<()> {
             "documentation": node.documentation,
             "tags": node.tags,
             "metadata": node.metadata,
+            "decision_points": node.decision_points,
         });
         writeln!(writer, "{}", serde_json::to_string(&node_line)?)?;
     }
@@ -54,6 +61,7 @@ pub fn export_jsonl(graph: &CodeGraph, output_path: &str) -> Result<()> {
             "type": "edge",
             "from": edge.from,
             "to": edge.to,
+            "to_id": edge.to_id,
             "edge_type": format!("{:?}", edge.edge_type),
             "call_site": edge.call_site,
             "file_path": edge.file_path.display().to_string(),
@@ -69,144 +77,48 @@ pub fn export_jsonl(graph: &CodeGraph, output_path: &str) -> Result<()> {
 
 /// Load graph from JSONL format
 pub fn load_from_jsonl(input_path: &str) -> Result<CodeGraph> {
-    use crate::core::{Edge, EdgeType, GraphMetadata, GraphStats, Node, NodeType, Parameter};
-    use std::io::{BufRead, BufReader};
-    use std::path::PathBuf;
-
     let file = File::open(input_path)?;
-    let reader = BufReader::new(file);
+    load_from_jsonl_reader(BufReader::new(file))
+}
 
-    let mut metadata: Option<GraphMetadata> = None;
-    let mut nodes = Vec::new();
-    let mut edges = Vec::new();
+/// One line of a JSONL export
+#[derive(Debug, Clone)]
+pub enum JsonlRecord {
+    Metadata(GraphMetadata),
+    Node(Node),
+    Edge(Edge),
+}
+
+/// Stream the records of a JSONL export to `f` one line at a time, without
+/// building a graph. Lines of unknown type are skipped.
+pub fn for_each_record<F: FnMut(JsonlRecord)>(input_path: &str, f: F) -> Result<()> {
+    let file = File::open(input_path)?;
+    for_each_record_reader(BufReader::new(file), f)
+}
 
+/// [`for_each_record`] over any buffered reader (e.g. stdin)
+pub fn for_each_record_reader<F: FnMut(JsonlRecord)>(reader: impl BufRead, mut f: F) -> Result<()> {
     for line in reader.lines() {
         let line = line?;
         let value: serde_json::Value = serde_json::from_str(&line)?;
-
-        match value["type"].as_str() {
-            Some("metadata") => {
-                metadata = Some(GraphMetadata {
-                    version: value["version"].as_str().unwrap_or("1.0.0").to_string(),
-                    generated_at: value["generated_at"].as_str().unwrap_or("").to_string(),
-                    generator: value["generator"]
-                        .as_str()
-                        .unwrap_or("code-navigator")
-                        .to_string(),
-                    language: value["language"].as_str().unwrap_or("").to_string(),
-                    root_path: value["root_path"].as_str().unwrap_or("").to_string(),
-                    stats: GraphStats {
-                        total_nodes: value["stats"]["total_nodes"].as_u64().unwrap_or(0) as usize,
-                        total_edges: value["stats"]["total_edges"].as_u64().unwrap_or(0) as usize,
-                        files_parsed: value["stats"]["files_parsed"].as_u64().unwrap_or(0) as usize,
-                    },
-                    file_metadata: HashMap::new(),
-                    git_commit_hash: None,
-                });
-            }
-            Some("node") => {
-                let node_type = match value["node_type"].as_str().unwrap_or("Function") {
-                    "Function" => NodeType::Function,
-                    "Method" => NodeType::Method,
-                    "HttpHandler" => NodeType::HttpHandler,
-                    "Middleware" => NodeType::Middleware,
-                    _ => NodeType::Function,
-                };
-
-                let parameters: Vec<Parameter> =
-                    if let Some(params_array) = value["parameters"].as_array() {
-                        params_array
-                            .iter()
-                            .filter_map(|p| {
-                                Some(Parameter {
-                                    name: p["name"].as_str()?.to_string(),
-                                    param_type: p["param_type"].as_str()?.to_string(),
-                                })
-                            })
-                            .collect()
-                    } else {
-                        Vec::new()
-                    };
-
-                let returns: Vec<String> = if let Some(ret_array) = value["returns"].as_array() {
-                    ret_array
-                        .iter()
-                        .filter_map(|r| r.as_str().map(|s| s.to_string()))
-                        .collect()
-                } else {
-                    Vec::new()
-                };
-
-                let tags: Vec<String> = if let Some(tag_array) = value["tags"].as_array() {
-                    tag_array
-                        .iter()
-                        .filter_map(|t| t.as_str().map(|s| s.to_string()))
-                        .collect()
-                } else {
-                    Vec::new()
-                };
-
-                let metadata_map: std::collections::HashMap<String, String> =
-                    if let Some(meta_obj) = value["metadata"].as_object() {
-                        meta_obj
-                            .iter()
-                            .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
-                            .collect()
-                    } else {
-                        std::collections::HashMap::new()
-                    };
-
-                let node = Node {
-                    id: value["id"].as_str().unwrap_or("").to_string(),
-                    name: value["name"].as_str().unwrap_or("").to_string(),
-                    node_type,
-                    file_path: PathBuf::from(value["file_path"].as_str().unwrap_or("")),
-                    line: value["line"].as_u64().unwrap_or(0) as usize,
-                    end_line: value["end_line"].as_u64().unwrap_or(0) as usize,
-                    package: value["package"].as_str().unwrap_or("").to_string(),
-                    signature: value["signature"].as_str().unwrap_or("").to_string(),
-                    parameters,
-                    returns,
-                    documentation: value["documentation"].as_str().map(|s| s.to_string()),
-                    tags,
-                    metadata: metadata_map,
-                };
-                nodes.push(node);
-            }
-            Some("edge") => {
-                let edge_type = match value["edge_type"].as_str().unwrap_or("Calls") {
-                    "Calls" => EdgeType::Calls,
-                    "Imports" => EdgeType::Imports,
-                    "Implements" => EdgeType::Implements,
-                    _ => EdgeType::Calls,
-                };
-
-                let metadata_map: std::collections::HashMap<String, String> =
-                    if let Some(meta_obj) = value["metadata"].as_object() {
-                        meta_obj
-                            .iter()
-                            .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
-                            .collect()
-                    } else {
-                        std::collections::HashMap::new()
-                    };
-
-                let edge = Edge {
-                    from: value["from"].as_str().unwrap_or("").to_string(),
-                    to: value["to"].as_str().unwrap_or("").to_string(),
-                    edge_type,
-                    call_site: value["call_site"].as_str().unwrap_or("").to_string(),
-                    file_path: PathBuf::from(value["file_path"].as_str().unwrap_or("")),
-                    line: value["line"].as_u64().unwrap_or(0) as usize,
-                    metadata: metadata_map,
-                };
-                edges.push(edge);
-            }
-            _ => {
-                // Unknown type, skip
-            }
+        if let Some(record) = parse_record(&value) {
+            f(record);
         }
     }
+    Ok(())
+}
+
+/// Load graph from JSONL lines read from any buffered reader (e.g. stdin)
+pub fn load_from_jsonl_reader(reader: impl BufRead) -> Result<CodeGraph> {
+    let mut metadata: Option<GraphMetadata> = None;
+    let mut nodes = Vec::new();
+    let mut edges = Vec::new();
+
+    for_each_record_reader(reader, |record| match record {
+        JsonlRecord::Metadata(m) => metadata = Some(m),
+        JsonlRecord::Node(node) => nodes.push(node),
+        JsonlRecord::Edge(edge) => edges.push(edge),
+    })?;
 
     let metadata = metadata.unwrap_or_else(|| GraphMetadata {
         version: "1.0.0".to_string(),
@@ -218,9 +130,11 @@ pub fn load_from_jsonl(input_path: &str) -> Result<CodeGraph> {
             total_nodes: nodes.len(),
             total_edges: edges.len(),
             files_parsed: 0,
+            isolated_nodes: 0,
         },
         file_metadata: HashMap::new(),
         git_commit_hash: None,
+        generated_files: Default::default(),
     });
 
     let mut graph = CodeGraph {
@@ -232,6 +146,7 @@ pub fn load_from_jsonl(input_path: &str) -> Result<CodeGraph> {
         incoming: Default::default(),
         by_name: Default::default(),
         by_type: Default::default(),
+        by_tag: Default::default(),
         indices_dirty: true,
     };
 
@@ -239,6 +154,150 @@ pub fn load_from_jsonl(input_path: &str) -> Result<CodeGraph> {
     Ok(graph)
 }
 
+/// The typed record on one JSONL line, or `None` for an unknown type
+fn parse_record(value: &serde_json::Value) -> Option<JsonlRecord> {
+    match value["type"].as_str() {
+        Some("metadata") => Some(JsonlRecord::Metadata(GraphMetadata {
+            version: value["version"].as_str().unwrap_or("1.0.0").to_string(),
+            generated_at: value["generated_at"].as_str().unwrap_or("").to_string(),
+            generator: value["generator"]
+                .as_str()
+                .unwrap_or("code-navigator")
+                .to_string(),
+            language: value["language"].as_str().unwrap_or("").to_string(),
+            root_path: value["root_path"].as_str().unwrap_or("").to_string(),
+            stats: GraphStats {
+                total_nodes: value["stats"]["total_nodes"].as_u64().unwrap_or(0) as usize,
+                total_edges: value["stats"]["total_edges"].as_u64().unwrap_or(0) as usize,
+                files_parsed: value["stats"]["files_parsed"].as_u64().unwrap_or(0) as usize,
+                isolated_nodes: value["stats"]["isolated_nodes"].as_u64().unwrap_or(0) as usize,
+            },
+            file_metadata: HashMap::new(),
+            git_commit_hash: None,
+            generated_files: value["generated_files"]
+                .as_array()
+                .map(|files| {
+                    files
+                        .iter()
+                        .filter_map(|f| f.as_str().map(|s| s.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+        })),
+        Some("node") => {
+            let node_type = match value["node_type"].as_str().unwrap_or("Function") {
+                "Function" => NodeType::Function,
+                "Method" => NodeType::Method,
+                "HttpHandler" => NodeType::HttpHandler,
+                "Middleware" => NodeType::Middleware,
+                "Class" => NodeType::Class,
+                // `TypeAlias`; older files wrote the raw subtype (`type_alias`)
+                other => CUSTOM_NODE_TYPES
+                    .iter()
+                    .map(|kind| NodeType::Custom(kind.to_string()))
+                    .find(|custom| {
+                        custom.to_string() == other
+                            || matches!(custom, NodeType::Custom(kind) if kind == other)
+                    })
+                    .unwrap_or(NodeType::Function),
+            };
+
+            let parameters: Vec<Parameter> =
+                if let Some(params_array) = value["parameters"].as_array() {
+                    params_array
+                        .iter()
+                        .filter_map(|p| {
+                            Some(Parameter {
+                                name: p["name"].as_str()?.to_string(),
+                                param_type: p["param_type"].as_str()?.to_string(),
+                            })
+                        })
+                        .collect()
+                } else {
+                    Vec::new()
+                };
+
+            let returns: Vec<String> = if let Some(ret_array) = value["returns"].as_array() {
+                ret_array
+                    .iter()
+                    .filter_map(|r| r.as_str().map(|s| s.to_string()))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            let tags: Vec<String> = if let Some(tag_array) = value["tags"].as_array() {
+                tag_array
+                    .iter()
+                    .filter_map(|t| t.as_str().map(|s| s.to_string()))
+                    .collect()
+            } else {
+                Vec::new()
+            };
+
+            let metadata_map: std::collections::HashMap<String, String> =
+                if let Some(meta_obj) = value["metadata"].as_object() {
+                    meta_obj
+                        .iter()
+                        .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                        .collect()
+                } else {
+                    std::collections::HashMap::new()
+                };
+
+            let node = Node {
+                id: value["id"].as_str().unwrap_or("").to_string(),
+                name: value["name"].as_str().unwrap_or("").to_string(),
+                node_type,
+                file_path: PathBuf::from(value["file_path"].as_str().unwrap_or("")),
+                line: value["line"].as_u64().unwrap_or(0) as usize,
+                end_line: value["end_line"].as_u64().unwrap_or(0) as usize,
+                package: value["package"].as_str().unwrap_or("").to_string(),
+                signature: value["signature"].as_str().unwrap_or("").to_string(),
+                parameters,
+                returns,
+                documentation: value["documentation"].as_str().map(|s| s.to_string()),
+                tags,
+                metadata: metadata_map,
+                decision_points: value["decision_points"].as_u64().unwrap_or(0) as usize,
+            };
+            Some(JsonlRecord::Node(node))
+        }
+        Some("edge") => {
+            let edge_type = match value["edge_type"].as_str().unwrap_or("Calls") {
+                "Calls" => EdgeType::Calls,
+                "Imports" => EdgeType::Imports,
+                "Implements" => EdgeType::Implements,
+                "Extends" => EdgeType::Extends,
+                _ => EdgeType::Calls,
+            };
+
+            let metadata_map: std::collections::HashMap<String, String> =
+                if let Some(meta_obj) = value["metadata"].as_object() {
+                    meta_obj
+                        .iter()
+                        .filter_map(|(k, v)| Some((k.clone(), v.as_str()?.to_string())))
+                        .collect()
+                } else {
+                    std::collections::HashMap::new()
+                };
+
+            let edge = Edge {
+                from: value["from"].as_str().unwrap_or("").to_string(),
+                to: value["to"].as_str().unwrap_or("").to_string(),
+                to_id: value["to_id"].as_str().map(|s| s.to_string()),
+                edge_type,
+                call_site: value["call_site"].as_str().unwrap_or("").to_string(),
+                file_path: PathBuf::from(value["file_path"].as_str().unwrap_or("")),
+                line: value["line"].as_u64().unwrap_or(0) as usize,
+                metadata: metadata_map,
+            };
+            Some(JsonlRecord::Edge(edge))
+        }
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -258,9 +317,11 @@ mod tests {
                     total_nodes: 1,
                     total_edges: 1,
                     files_parsed: 1,
+                    isolated_nodes: 0,
                 },
                 file_metadata: HashMap::new(),
                 git_commit_hash: None,
+                generated_files: Default::default(),
             },
             nodes: vec![Node {
                 id: "test:func1:10".to_string(),
@@ -276,10 +337,12 @@ mod tests {
                 documentation: None,
                 tags: vec![],
                 metadata: Default::default(),
+                decision_points: 0,
             }],
             edges: vec![Edge {
                 from: "test:func1:10".to_string(),
                 to: "func2".to_string(),
+                to_id: None,
                 edge_type: EdgeType::Calls,
                 call_site: "func2()".to_string(),
                 file_path: PathBuf::from("test.go"),
@@ -291,6 +354,7 @@ mod tests {
             incoming: Default::default(),
             by_name: Default::default(),
             by_type: Default::default(),
+            by_tag: Default::default(),
             indices_dirty: true,
         };
 
@@ -309,4 +373,31 @@ mod tests {
         assert_eq!(loaded_graph.nodes[0].name, "func1");
         assert_eq!(loaded_graph.edges[0].to, "func2");
     }
+
+    #[test]
+    fn test_for_each_record_streams_without_graph() {
+        let lines = [
+            r#"{"type":"metadata","language":"go","root_path":"/test"}"#,
+            r#"{"type":"node","id":"a.go:A:1","name":"A","node_type":"Function"}"#,
+            r#"{"type":"node","id":"a.go:B:5","name":"B","node_type":"Method"}"#,
+            r#"{"type":"edge","from":"a.go:A:1","to":"B","edge_type":"Calls"}"#,
+            r#"{"type":"comment"}"#,
+        ];
+        let temp_file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(temp_file.path(), lines.join("\n")).unwrap();
+
+        let mut nodes = 0;
+        let mut edges = Vec::new();
+        let mut language = None;
+        for_each_record(temp_file.path().to_str().unwrap(), |record| match record {
+            JsonlRecord::Metadata(metadata) => language = Some(metadata.language),
+            JsonlRecord::Node(_) => nodes += 1,
+            JsonlRecord::Edge(edge) => edges.push(edge.to),
+        })
+        .unwrap();
+
+        assert_eq!(nodes, 2);
+        assert_eq!(edges, vec!["B"]);
+        assert_eq!(language.as_deref(), Some("go"));
+    }
 }
@@ -0,0 +1,30 @@
+use crate::core::{EdgeType, NodeType};
+
+/// What each node type stands for, in legend order
+pub const NODE_TYPES: &[(NodeType, &str)] = &[
+    (NodeType::Function, "free function"),
+    (NodeType::Method, "method of a type"),
+    (NodeType::HttpHandler, "HTTP request handler"),
+    (NodeType::Middleware, "HTTP middleware (wraps a handler)"),
+    (NodeType::Class, "class, struct or interface"),
+    (
+        NodeType::Custom(String::new()),
+        "enum, type alias or namespace (typed by subtype, e.g. TypeAlias)",
+    ),
+];
+
+/// Legend name of a node type; custom nodes share one `Custom` entry
+pub fn type_name(node_type: &NodeType) -> String {
+    match node_type {
+        NodeType::Custom(_) => "Custom".to_string(),
+        other => other.to_string(),
+    }
+}
+
+/// What each edge type connects, in legend order
+pub const EDGE_TYPES: &[(EdgeType, &str)] = &[
+    (EdgeType::Calls, "caller -> callee"),
+    (EdgeType::Imports, "file -> imported module"),
+    (EdgeType::Implements, "type -> interface it implements"),
+    (EdgeType::Extends, "subclass -> base class"),
+];
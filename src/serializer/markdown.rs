@@ -0,0 +1,141 @@
+use crate::core::CodeGraph;
+use anyhow::Result;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Export the graph as a GitHub-Flavored-Markdown document (summary, nodes
+/// table, edges table) for pasting into wikis and pull requests
+pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
+    let mut file = File::create(output_path)?;
+    write_markdown(graph, &mut file)
+}
+
+fn write_markdown(graph: &CodeGraph, out: &mut impl Write) -> Result<()> {
+    writeln!(out, "# Code Graph")?;
+    writeln!(out)?;
+    writeln!(
+        out,
+        "- **Language:** {}",
+        escape_cell(&graph.metadata.language)
+    )?;
+    writeln!(
+        out,
+        "- **Root:** {}",
+        escape_cell(&graph.metadata.root_path)
+    )?;
+    writeln!(out, "- **Nodes:** {}", graph.nodes.len())?;
+    writeln!(out, "- **Edges:** {}", graph.edges.len())?;
+    writeln!(out)?;
+
+    writeln!(out, "## Nodes")?;
+    writeln!(out)?;
+    writeln!(out, "| Name | Type | Package | Location | Signature |")?;
+    writeln!(out, "| --- | --- | --- | --- | --- |")?;
+    for node in &graph.nodes {
+        writeln!(
+            out,
+            "| {} | {} | {} | {}:{} | {} |",
+            escape_cell(&node.name),
+            node.node_type,
+            escape_cell(&node.package),
+            escape_cell(&node.file_path.display().to_string()),
+            node.line,
+            escape_cell(&node.signature)
+        )?;
+    }
+    writeln!(out)?;
+
+    writeln!(out, "## Edges")?;
+    writeln!(out)?;
+    writeln!(out, "| From | To | Type | Location |")?;
+    writeln!(out, "| --- | --- | --- | --- |")?;
+    for edge in &graph.edges {
+        // Callers are shown by name; fall back to the raw ID if unknown
+        let from = graph
+            .get_node_by_id(&edge.from)
+            .map_or(edge.from.as_str(), |n| n.name.as_str());
+        writeln!(
+            out,
+            "| {} | {} | {:?} | {}:{} |",
+            escape_cell(from),
+            escape_cell(&edge.to),
+            edge.edge_type,
+            escape_cell(&edge.file_path.display().to_string()),
+            edge.line
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Keep a value inside its table cell: pipes would split the cell and
+/// newlines would end the row
+fn escape_cell(s: &str) -> String {
+    s.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Edge, EdgeType, Node, NodeType};
+    use std::path::PathBuf;
+
+    /// Count the data rows of the table under a `## heading`
+    fn table_rows(markdown: &str, heading: &str) -> usize {
+        markdown
+            .split(heading)
+            .nth(1)
+            .unwrap()
+            .lines()
+            .skip_while(|l| !l.starts_with('|'))
+            .take_while(|l| l.starts_with('|'))
+            .skip(2)
+            .count()
+    }
+
+    #[test]
+    fn test_markdown_tables() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name, signature) in [
+            ("main.go:main:1", "main", "func main()"),
+            ("main.go:pick:5", "pick", "func pick(a int | string)"),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("main.go"),
+                1,
+                3,
+                "main".to_string(),
+                signature.to_string(),
+            ));
+        }
+        graph.add_edge(Edge::new(
+            "main.go:main:1".to_string(),
+            "pick".to_string(),
+            EdgeType::Calls,
+            "pick(1)".to_string(),
+            PathBuf::from("main.go"),
+            2,
+        ));
+
+        let mut out = Vec::new();
+        write_markdown(&graph, &mut out).unwrap();
+        let markdown = String::from_utf8(out).unwrap();
+
+        assert!(markdown.contains("- **Nodes:** 2"));
+        assert!(markdown.contains("- **Edges:** 1"));
+        assert!(markdown.contains("| --- | --- | --- | --- | --- |"));
+        assert!(markdown.contains("| --- | --- | --- | --- |\n"));
+        assert_eq!(table_rows(&markdown, "## Nodes"), 2);
+        assert_eq!(table_rows(&markdown, "## Edges"), 1);
+
+        // The escaped pipe keeps the signature row at five cells
+        assert!(markdown.contains("func pick(a int \\| string)"));
+        let row = markdown.lines().find(|l| l.contains("pick(a int")).unwrap();
+        assert_eq!(row.replace("\\|", "").matches('|').count(), 6);
+        assert!(markdown.contains("| main | pick | Calls | main.go:2 |"));
+    }
+}
@@ -0,0 +1,206 @@
+use super::legend;
+use crate::core::{CodeGraph, EdgeType, NodeType};
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Save the graph as a Mermaid flowchart. `with_legend` adds `%%` comment
+/// lines explaining the node shapes and arrows.
+pub fn save_to_file(graph: &CodeGraph, output_path: &Path, with_legend: bool) -> Result<()> {
+    let mut file = File::create(output_path)?;
+    file.write_all(to_mermaid_string(graph, with_legend).as_bytes())?;
+    Ok(())
+}
+
+/// The graph as a `flowchart LR` Mermaid diagram. Node shapes follow the
+/// node type; callees that aren't in the graph (stdlib, third party) and
+/// import sources are drawn as flags.
+pub fn to_mermaid_string(graph: &CodeGraph, with_legend: bool) -> String {
+    let mut ids = MermaidIds::default();
+    let mut out = String::from("flowchart LR\n");
+    if with_legend {
+        write_legend(&mut out);
+    }
+
+    for node in &graph.nodes {
+        let id = ids.assign(&node.id);
+        out.push_str(&format!(
+            "    {}{}\n",
+            id,
+            node_shape(&node.node_type, &escape_label(&node.display_name()))
+        ));
+    }
+
+    let mut edge_lines = Vec::with_capacity(graph.edges.len());
+    for edge in &graph.edges {
+        let from = match graph.get_node_by_id(&edge.from) {
+            Some(node) => ids.get(&node.id),
+            None => ids.external(&edge.from, &mut out),
+        };
+        let to = match graph.edge_target_id(edge) {
+            Some(target_id) => ids.get(target_id),
+            None => ids.external(&edge.to, &mut out),
+        };
+        edge_lines.push(format!("    {} {} {}\n", from, arrow(&edge.edge_type), to));
+    }
+
+    for line in edge_lines {
+        out.push_str(&line);
+    }
+    out
+}
+
+/// Node declaration suffix: rounded functions, stadium methods, hexagon
+/// handlers, subroutine middleware and rectangle classes
+fn node_shape(node_type: &NodeType, label: &str) -> String {
+    match node_type {
+        NodeType::Function => format!("(\"{}\")", label),
+        NodeType::Method => format!("([\"{}\"])", label),
+        NodeType::HttpHandler => format!("{{{{\"{}\"}}}}", label),
+        NodeType::Middleware => format!("[[\"{}\"]]", label),
+        NodeType::Class => format!("[\"{}\"]", label),
+        NodeType::Custom(_) => format!("[/\"{}\"/]", label),
+    }
+}
+
+fn arrow(edge_type: &EdgeType) -> &'static str {
+    match edge_type {
+        EdgeType::Calls => "-->",
+        EdgeType::Imports => "-.->|imports|",
+        EdgeType::Implements => "-->|implements|",
+        EdgeType::Extends => "-->|extends|",
+    }
+}
+
+/// `%%` comment lines pairing each shape and arrow with its meaning
+fn write_legend(out: &mut String) {
+    out.push_str("    %% Legend\n");
+    for (node_type, description) in legend::NODE_TYPES {
+        out.push_str(&format!(
+            "    %% {} {}: {}\n",
+            node_shape(node_type, "..."),
+            legend::type_name(node_type),
+            description
+        ));
+    }
+    out.push_str("    %% >\"...\"] external: callee or module outside the graph\n");
+    for (edge_type, description) in legend::EDGE_TYPES {
+        out.push_str(&format!(
+            "    %% {} {:?}: {}\n",
+            arrow(edge_type),
+            edge_type,
+            description
+        ));
+    }
+}
+
+/// Maps node IDs and external names to unique Mermaid identifiers
+#[derive(Default)]
+struct MermaidIds {
+    ids: HashMap<String, String>,
+    used: HashSet<String>,
+}
+
+impl MermaidIds {
+    /// Allocate the identifier for `key`: non-alphanumeric characters become
+    /// `_`, and collisions after sanitizing get a numeric suffix
+    fn assign(&mut self, key: &str) -> String {
+        let mut base: String = key
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        // Identifiers must not start with a digit or collide with keywords
+        // such as `end`
+        base.insert_str(0, "n_");
+
+        let mut id = base.clone();
+        let mut suffix = 2;
+        while self.used.contains(&id) {
+            id = format!("{}_{}", base, suffix);
+            suffix += 1;
+        }
+        self.used.insert(id.clone());
+        self.ids.insert(key.to_string(), id.clone());
+        id
+    }
+
+    fn get(&self, key: &str) -> String {
+        self.ids[key].clone()
+    }
+
+    /// Identifier of a name outside the graph, declaring it on first use
+    fn external(&mut self, name: &str, out: &mut String) -> String {
+        let key = format!("external:{}", name);
+        if let Some(id) = self.ids.get(&key) {
+            return id.clone();
+        }
+        let id = self.assign(&key);
+        out.push_str(&format!("    {}>\"{}\"]\n", id, escape_label(name)));
+        id
+    }
+}
+
+/// Quote-safe label text (Mermaid entity codes)
+fn escape_label(s: &str) -> String {
+    s.replace('"', "#quot;").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Edge, Node};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_mermaid_flowchart_with_sanitized_ids() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name, node_type) in [
+            ("main.go:main:1", "main", NodeType::Function),
+            ("main.go:Server.Run:5", "Run", NodeType::Method),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                node_type,
+                PathBuf::from("main.go"),
+                1,
+                3,
+                "main".to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        for callee in ["Run", "fmt.Println"] {
+            graph.add_edge(Edge::new(
+                "main.go:main:1".to_string(),
+                callee.to_string(),
+                EdgeType::Calls,
+                format!("{}()", callee),
+                PathBuf::from("main.go"),
+                2,
+            ));
+        }
+
+        let mermaid = to_mermaid_string(&graph, false);
+        assert!(mermaid.starts_with("flowchart LR\n"));
+        assert!(mermaid.contains("    n_main_go_main_1(\"main\")\n"));
+        assert!(mermaid.contains("    n_main_go_Server_Run_5([\"Run\"])\n"));
+        assert!(mermaid.contains("    n_main_go_main_1 --> n_main_go_Server_Run_5\n"));
+        // Unknown callees become flag-shaped external nodes
+        assert!(mermaid.contains("    n_external_fmt_Println>\"fmt.Println\"]\n"));
+        assert!(mermaid.contains("    n_main_go_main_1 --> n_external_fmt_Println\n"));
+    }
+
+    #[test]
+    fn test_mermaid_ids_stay_unique_after_sanitizing() {
+        let mut ids = MermaidIds::default();
+        let a = ids.assign("a.go:f:1");
+        let b = ids.assign("a_go:f:1");
+        let c = ids.assign("a.go:f:1:2");
+        assert_ne!(a, b);
+        assert_ne!(b, c);
+        assert_eq!(ids.get("a.go:f:1"), a);
+        assert_eq!(ids.get("a_go:f:1"), b);
+    }
+}
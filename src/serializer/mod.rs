@@ -1,10 +1,26 @@
 pub mod binary;
 pub mod compressed;
 pub mod csv;
+pub mod cypher;
+pub mod cytoscape;
 pub mod dot;
 pub mod fast_compressed;
 pub mod graphml;
 pub mod index_cache;
 pub mod json;
 pub mod jsonl;
+pub mod legend;
+pub mod markdown;
+pub mod mermaid;
 pub mod optimized_binary;
+pub mod parquet;
+pub mod plantuml;
+pub mod schema;
+pub mod shards;
+pub mod shell;
+pub mod sqlite;
+pub mod stream;
+
+/// zstd level for graph files when none is given (`--compression-level`).
+/// zstd accepts 1 (fastest) to 22 (smallest).
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 3;
@@ -8,8 +8,8 @@ const MAGIC_BYTES: &[u8; 8] = b"CODENAV\x01";
 
 /// Save graph in optimized binary format
 /// Uses MessagePack (faster than JSON, serde-compatible) + zstd compression
-/// This is 2-3x faster to load than JSON deserialization
-pub fn save_to_file(graph: &CodeGraph, path: &str) -> Result<()> {
+/// at `level` (1-22). This is 2-3x faster to load than JSON deserialization
+pub fn save_to_file(graph: &CodeGraph, path: &str, level: i32) -> Result<()> {
     // Serialize graph with MessagePack (faster than JSON, handles serde attributes)
     let serialized = rmp_serde::to_vec(graph)
         .map_err(|e| anyhow::anyhow!("Failed to serialize graph with MessagePack: {}", e))?;
@@ -20,8 +20,7 @@ pub fn save_to_file(graph: &CodeGraph, path: &str) -> Result<()> {
     buffer.write_all(MAGIC_BYTES)?;
     buffer.write_all(&FORMAT_VERSION.to_le_bytes())?;
 
-    // Compress with zstd (level 3 for speed/compression balance)
-    let compressed = zstd::encode_all(&serialized[..], 3)?;
+    let compressed = zstd::encode_all(&serialized[..], level)?;
 
     // Write compressed data
     buffer.write_all(&compressed)?;
@@ -106,6 +105,7 @@ mod tests {
             documentation: None,
             tags: vec![],
             metadata: Default::default(),
+            decision_points: 0,
         };
         graph.add_node(node);
 
@@ -122,7 +122,7 @@ mod tests {
         let temp_file = NamedTempFile::new().unwrap();
         let path = temp_file.path().to_str().unwrap();
 
-        save_to_file(&graph, path).unwrap();
+        save_to_file(&graph, path, crate::serializer::DEFAULT_COMPRESSION_LEVEL).unwrap();
         eprintln!("Saved to file");
 
         let loaded = load_from_file(path).unwrap();
@@ -0,0 +1,261 @@
+use crate::core::CodeGraph;
+use anyhow::Result;
+use arrow::array::{
+    ArrayRef, DictionaryArray, Int64Array, ListBuilder, StringArray, StringBuilder,
+};
+use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+use arrow::record_batch::RecordBatch;
+use std::fs::File;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Save graph as `<prefix>_nodes.parquet` and `<prefix>_edges.parquet`
+/// (same naming as the CSV export). Node and edge types are stored as
+/// dictionary-encoded string columns, line numbers as int64 (what pandas
+/// and Polars default to) and node parameters as a JSON array string.
+pub fn save_to_files(graph: &CodeGraph, output_prefix: &Path) -> Result<()> {
+    let (nodes_path, edges_path) = output_paths(output_prefix);
+
+    write_batch(&nodes_path, nodes_batch(graph)?)?;
+    write_batch(&edges_path, edges_batch(graph)?)?;
+
+    Ok(())
+}
+
+/// Paths of the nodes and edges files written for an output prefix
+pub fn output_paths(output_prefix: &Path) -> (PathBuf, PathBuf) {
+    let stem = output_prefix
+        .file_stem()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| "graph".to_string());
+
+    (
+        output_prefix.with_file_name(format!("{}_nodes.parquet", stem)),
+        output_prefix.with_file_name(format!("{}_edges.parquet", stem)),
+    )
+}
+
+fn nodes_batch(graph: &CodeGraph) -> Result<RecordBatch> {
+    let nodes = &graph.nodes;
+
+    let mut tags = ListBuilder::new(StringBuilder::new());
+    for node in nodes {
+        for tag in &node.tags {
+            tags.values().append_value(tag);
+        }
+        tags.append(true);
+    }
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(nodes.iter().map(|n| &n.id))),
+        Arc::new(StringArray::from_iter_values(nodes.iter().map(|n| &n.name))),
+        Arc::new(dictionary_column(
+            nodes.iter().map(|n| n.node_type.to_string()),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            nodes.iter().map(|n| n.file_path.to_string_lossy()),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            nodes.iter().map(|n| n.line as i64),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            nodes.iter().map(|n| n.end_line as i64),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            nodes.iter().map(|n| &n.package),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            nodes.iter().map(|n| &n.signature),
+        )),
+        Arc::new(StringArray::from_iter(
+            nodes.iter().map(|n| n.documentation.as_deref()),
+        )),
+        Arc::new(tags.finish()),
+        Arc::new(StringArray::from_iter_values(
+            nodes
+                .iter()
+                .map(|n| serde_json::to_string(&n.parameters))
+                .collect::<serde_json::Result<Vec<_>>>()?,
+        )),
+    ];
+
+    Ok(RecordBatch::try_new(nodes_schema(), columns)?)
+}
+
+fn edges_batch(graph: &CodeGraph) -> Result<RecordBatch> {
+    let edges = &graph.edges;
+
+    let columns: Vec<ArrayRef> = vec![
+        Arc::new(StringArray::from_iter_values(edges.iter().map(|e| &e.from))),
+        Arc::new(StringArray::from_iter_values(edges.iter().map(|e| &e.to))),
+        Arc::new(dictionary_column(
+            edges.iter().map(|e| format!("{:?}", e.edge_type)),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            edges.iter().map(|e| &e.call_site),
+        )),
+        Arc::new(StringArray::from_iter_values(
+            edges.iter().map(|e| e.file_path.to_string_lossy()),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            edges.iter().map(|e| e.line as i64),
+        )),
+    ];
+
+    Ok(RecordBatch::try_new(edges_schema(), columns)?)
+}
+
+/// Dictionary-encode a low-cardinality string column (e.g. node/edge types)
+fn dictionary_column(values: impl Iterator<Item = String>) -> DictionaryArray<Int32Type> {
+    let values: Vec<String> = values.collect();
+    values.iter().map(|v| v.as_str()).collect()
+}
+
+fn type_dictionary() -> DataType {
+    DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8))
+}
+
+fn nodes_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("name", DataType::Utf8, false),
+        Field::new("type", type_dictionary(), false),
+        Field::new("file_path", DataType::Utf8, false),
+        Field::new("line", DataType::Int64, false),
+        Field::new("end_line", DataType::Int64, false),
+        Field::new("package", DataType::Utf8, false),
+        Field::new("signature", DataType::Utf8, false),
+        Field::new("documentation", DataType::Utf8, true),
+        Field::new(
+            "tags",
+            DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))),
+            false,
+        ),
+        Field::new("parameters", DataType::Utf8, false),
+    ]))
+}
+
+fn edges_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("from", DataType::Utf8, false),
+        Field::new("to", DataType::Utf8, false),
+        Field::new("type", type_dictionary(), false),
+        Field::new("call_site", DataType::Utf8, false),
+        Field::new("file_path", DataType::Utf8, false),
+        Field::new("line", DataType::Int64, false),
+    ]))
+}
+
+fn write_batch(path: &Path, batch: RecordBatch) -> Result<()> {
+    use ::parquet::arrow::ArrowWriter;
+    use ::parquet::basic::{Compression, ZstdLevel};
+    use ::parquet::file::properties::WriterProperties;
+
+    let props = WriterProperties::builder()
+        .set_compression(Compression::ZSTD(ZstdLevel::default()))
+        .build();
+
+    let file = File::create(path)?;
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Edge, EdgeType, Node, NodeType, Parameter};
+    use ::parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+    use arrow::array::Array;
+
+    fn read_rows(path: &Path) -> (usize, Arc<Schema>) {
+        let builder = ParquetRecordBatchReaderBuilder::try_new(File::open(path).unwrap()).unwrap();
+        let schema = builder.schema().clone();
+        let rows = builder
+            .build()
+            .unwrap()
+            .map(|batch| batch.unwrap().num_rows())
+            .sum();
+        (rows, schema)
+    }
+
+    #[test]
+    fn test_parquet_roundtrip_row_counts() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name, node_type) in [
+            ("main.go:main:1", "main", NodeType::Function),
+            ("main.go:Run:10", "Run", NodeType::Method),
+            ("main.go:helper:20", "helper", NodeType::Function),
+        ] {
+            let mut node = Node::new(
+                id.to_string(),
+                name.to_string(),
+                node_type,
+                PathBuf::from("main.go"),
+                1,
+                5,
+                "main".to_string(),
+                format!("func {}()", name),
+            );
+            node.tags = vec!["core".to_string()];
+            if name == "Run" {
+                node.parameters = vec![Parameter {
+                    name: "ctx".to_string(),
+                    param_type: "context.Context".to_string(),
+                }];
+            }
+            graph.add_node(node);
+        }
+        for (to, line) in [("Run", 2), ("helper", 3)] {
+            graph.add_edge(Edge::new(
+                "main.go:main:1".to_string(),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}()", to),
+                PathBuf::from("main.go"),
+                line,
+            ));
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let prefix = dir.path().join("graph");
+        save_to_files(&graph, &prefix).unwrap();
+
+        let (nodes_path, edges_path) = output_paths(&prefix);
+        let (node_rows, node_schema) = read_rows(&nodes_path);
+        let (edge_rows, _) = read_rows(&edges_path);
+
+        assert_eq!(node_rows, 3);
+        assert_eq!(edge_rows, 2);
+        assert_eq!(
+            node_schema.field_with_name("type").unwrap().data_type(),
+            &type_dictionary()
+        );
+        assert_eq!(
+            node_schema.field_with_name("line").unwrap().data_type(),
+            &DataType::Int64
+        );
+
+        let batch = ParquetRecordBatchReaderBuilder::try_new(File::open(&nodes_path).unwrap())
+            .unwrap()
+            .build()
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        let parameters = batch
+            .column_by_name("parameters")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert_eq!(parameters.len(), 3);
+        assert_eq!(parameters.value(0), "[]");
+        assert_eq!(
+            parameters.value(1),
+            r#"[{"name":"ctx","param_type":"context.Context"}]"#
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use crate::core::{CodeGraph, EdgeType};
+use anyhow::Result;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+
+/// Save the graph as a PlantUML component diagram
+pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
+    let mut file = File::create(output_path)?;
+    file.write_all(to_plantuml_string(graph).as_bytes())?;
+    Ok(())
+}
+
+/// The graph as `@startuml ... @enduml`: one `package` block per package
+/// holding its nodes as components (stereotyped with the node type), then
+/// call (`-->`), implements (`..|>`) and extends (`--|>`) arrows between
+/// them. Calls to functions outside the graph and imports are left out.
+pub fn to_plantuml_string(graph: &CodeGraph) -> String {
+    let mut out = String::from("@startuml\n");
+
+    // Components are aliased by node position; labels carry the real names
+    let aliases: HashMap<&str, String> = graph
+        .nodes
+        .iter()
+        .enumerate()
+        .map(|(i, node)| (node.id.as_str(), format!("n{}", i)))
+        .collect();
+
+    let mut packages: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+    for (i, node) in graph.nodes.iter().enumerate() {
+        packages.entry(node.package.as_str()).or_default().push(i);
+    }
+
+    for (package, members) in &packages {
+        let indent = if package.is_empty() {
+            ""
+        } else {
+            out.push_str(&format!("package \"{}\" {{\n", escape(package)));
+            "  "
+        };
+        for &i in members {
+            let node = &graph.nodes[i];
+            out.push_str(&format!(
+                "{}component \"{}\" as {} <<{}>>\n",
+                indent,
+                escape(&node.display_name()),
+                aliases[node.id.as_str()],
+                node.node_type
+            ));
+        }
+        if !package.is_empty() {
+            out.push_str("}\n");
+        }
+    }
+
+    let mut seen = HashSet::new();
+    for edge in &graph.edges {
+        let arrow = match edge.edge_type {
+            EdgeType::Calls => "-->",
+            EdgeType::Implements => "..|>",
+            EdgeType::Extends => "--|>",
+            EdgeType::Imports => continue,
+        };
+        let (Some(from), Some(to)) = (
+            aliases.get(edge.from.as_str()),
+            graph.edge_target_id(edge).and_then(|id| aliases.get(id)),
+        ) else {
+            continue;
+        };
+        if seen.insert((from, to, arrow)) {
+            out.push_str(&format!("{} {} {}\n", from, arrow, to));
+        }
+    }
+
+    out.push_str("@enduml\n");
+    out
+}
+
+/// Quote-safe label text
+fn escape(s: &str) -> String {
+    s.replace('"', "'").replace('\n', " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Edge, Node, NodeType};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_plantuml_packages_and_arrows() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name, package) in [
+            ("main.go:main:1", "main", "main"),
+            ("server/run.go:Run:3", "Run", "server"),
+        ] {
+            graph.add_node(Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("main.go"),
+                1,
+                3,
+                package.to_string(),
+                format!("func {}()", name),
+            ));
+        }
+        for callee in ["Run", "Run", "fmt.Println"] {
+            graph.add_edge(Edge::new(
+                "main.go:main:1".to_string(),
+                callee.to_string(),
+                EdgeType::Calls,
+                format!("{}()", callee),
+                PathBuf::from("main.go"),
+                2,
+            ));
+        }
+
+        let uml = to_plantuml_string(&graph);
+        assert!(uml.starts_with("@startuml\n"));
+        assert!(uml.ends_with("@enduml\n"));
+        assert!(uml.contains("package \"main\" {\n  component \"main\" as n0 <<Function>>\n}\n"));
+        assert!(uml.contains("package \"server\" {\n  component \"Run\" as n1 <<Function>>\n}\n"));
+        // Repeated calls collapse to one arrow; external callees are dropped
+        assert_eq!(uml.matches(" --> ").count(), 1);
+        assert!(uml.contains("n0 --> n1\n"));
+        assert!(!uml.contains("Println"));
+    }
+}
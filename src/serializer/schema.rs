@@ -0,0 +1,290 @@
+use crate::core::CUSTOM_NODE_TYPES;
+use serde_json::{json, Value};
+
+/// `$id` of the emitted schema
+pub const SCHEMA_ID: &str = "https://github.com/shaharia-lab/code-navigator/schemas/graph.json";
+
+/// JSON Schema (draft 2020-12) of a serialized `CodeGraph`, as written by
+/// `export --format json` and stored (compressed) by `index`.
+///
+/// Field names follow the serde attributes: `node_type`/`edge_type` are
+/// serialized as `type`, and enum values are snake_case. Custom node types
+/// are objects (`{"custom": "enum"}`).
+pub fn json_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "$id": SCHEMA_ID,
+        "title": "CodeGraph",
+        "description": "Code graph produced by codenav: functions and types (nodes) and the calls, imports and inheritance between them (edges)",
+        "type": "object",
+        "required": ["metadata", "nodes", "edges"],
+        "properties": {
+            "metadata": { "$ref": "#/$defs/GraphMetadata" },
+            "nodes": { "type": "array", "items": { "$ref": "#/$defs/Node" } },
+            "edges": { "type": "array", "items": { "$ref": "#/$defs/Edge" } }
+        },
+        "$defs": {
+            "GraphMetadata": {
+                "type": "object",
+                "required": ["version", "generated_at", "generator", "language", "root_path", "stats"],
+                "properties": {
+                    "version": { "type": "string" },
+                    "generated_at": { "type": "string", "format": "date-time" },
+                    "generator": { "type": "string" },
+                    "language": { "type": "string" },
+                    "root_path": { "type": "string" },
+                    "stats": { "$ref": "#/$defs/GraphStats" },
+                    "file_metadata": {
+                        "type": "object",
+                        "additionalProperties": { "$ref": "#/$defs/FileMetadata" }
+                    },
+                    "git_commit_hash": { "type": ["string", "null"] },
+                    "generated_files": {
+                        "description": "Files detected as generated code during parsing",
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "uniqueItems": true
+                    }
+                }
+            },
+            "GraphStats": {
+                "type": "object",
+                "required": ["total_nodes", "total_edges", "files_parsed"],
+                "properties": {
+                    "total_nodes": { "type": "integer", "minimum": 0 },
+                    "total_edges": { "type": "integer", "minimum": 0 },
+                    "files_parsed": { "type": "integer", "minimum": 0 },
+                    "isolated_nodes": { "type": "integer", "minimum": 0 }
+                }
+            },
+            "FileMetadata": {
+                "type": "object",
+                "required": ["path", "last_modified", "node_ids"],
+                "properties": {
+                    "path": { "type": "string" },
+                    "last_modified": { "type": "string" },
+                    "node_ids": { "type": "array", "items": { "type": "string" } }
+                }
+            },
+            "Node": {
+                "type": "object",
+                "required": ["id", "name", "type", "file_path", "line", "end_line", "package", "signature"],
+                "properties": {
+                    "id": { "type": "string" },
+                    "name": { "type": "string" },
+                    "type": { "$ref": "#/$defs/NodeType" },
+                    "file_path": { "type": "string" },
+                    "line": { "type": "integer", "minimum": 0 },
+                    "end_line": { "type": "integer", "minimum": 0 },
+                    "package": { "type": "string" },
+                    "signature": { "type": "string" },
+                    "parameters": { "type": "array", "items": { "$ref": "#/$defs/Parameter" } },
+                    "returns": { "type": "array", "items": { "type": "string" } },
+                    "documentation": { "type": "string" },
+                    "tags": { "type": "array", "items": { "type": "string" } },
+                    "metadata": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" }
+                    },
+                    "decision_points": {
+                        "description": "Branches in the body; cyclomatic complexity is this plus one",
+                        "type": "integer",
+                        "minimum": 0
+                    }
+                }
+            },
+            "Parameter": {
+                "type": "object",
+                "required": ["name", "param_type"],
+                "properties": {
+                    "name": { "type": "string" },
+                    "param_type": { "type": "string" }
+                }
+            },
+            "NodeType": {
+                "oneOf": [
+                    {
+                        "type": "string",
+                        "enum": ["function", "method", "http_handler", "middleware", "class"]
+                    },
+                    {
+                        "description": "Non-callable declaration, e.g. {\"custom\": \"enum\"}",
+                        "type": "object",
+                        "required": ["custom"],
+                        "properties": {
+                            "custom": { "type": "string", "enum": CUSTOM_NODE_TYPES }
+                        },
+                        "additionalProperties": false
+                    }
+                ]
+            },
+            "Edge": {
+                "type": "object",
+                "required": ["from", "to", "type", "call_site", "file_path", "line"],
+                "properties": {
+                    "from": { "type": "string", "description": "Source node ID (the file path for imports)" },
+                    "to": { "type": "string", "description": "Target name: callee, base type or imported module" },
+                    "to_id": { "type": "string", "description": "Resolved target node ID, when unambiguous" },
+                    "type": { "$ref": "#/$defs/EdgeType" },
+                    "call_site": { "type": "string" },
+                    "file_path": { "type": "string" },
+                    "line": { "type": "integer", "minimum": 0 },
+                    "metadata": {
+                        "type": "object",
+                        "additionalProperties": { "type": "string" }
+                    }
+                }
+            },
+            "EdgeType": {
+                "type": "string",
+                "enum": ["calls", "imports", "implements", "extends"]
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{CodeGraph, Edge, EdgeType, Node, NodeType, Parameter};
+    use std::path::PathBuf;
+
+    fn enum_values(definition: &Value) -> Vec<String> {
+        definition["enum"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|v| v.as_str().unwrap().to_string())
+            .collect()
+    }
+
+    /// Keys of `value` that the schema definition `name` doesn't declare
+    fn undeclared_keys(schema: &Value, name: &str, value: &Value) -> Vec<String> {
+        let properties = schema["$defs"][name]["properties"].as_object().unwrap();
+        value
+            .as_object()
+            .unwrap()
+            .keys()
+            .filter(|k| !properties.contains_key(*k))
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_schema_is_valid_json_with_enum_variants() {
+        let text = serde_json::to_string_pretty(&json_schema()).unwrap();
+        let schema: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(
+            schema["$schema"],
+            "https://json-schema.org/draft/2020-12/schema"
+        );
+
+        // Enum values match what serde writes for every variant
+        let node_types: Vec<String> = [
+            NodeType::Function,
+            NodeType::Method,
+            NodeType::HttpHandler,
+            NodeType::Middleware,
+            NodeType::Class,
+        ]
+        .iter()
+        .map(|t| {
+            serde_json::to_value(t)
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+        let node_type = &schema["$defs"]["NodeType"]["oneOf"];
+        assert_eq!(enum_values(&node_type[0]), node_types);
+        // Custom subtypes are externally tagged objects
+        assert_eq!(
+            serde_json::to_value(NodeType::Custom("enum".to_string())).unwrap(),
+            json!({ "custom": "enum" })
+        );
+        assert_eq!(
+            enum_values(&node_type[1]["properties"]["custom"]),
+            CUSTOM_NODE_TYPES
+        );
+
+        let edge_types: Vec<String> = [
+            EdgeType::Calls,
+            EdgeType::Imports,
+            EdgeType::Implements,
+            EdgeType::Extends,
+        ]
+        .iter()
+        .map(|t| {
+            serde_json::to_value(t)
+                .unwrap()
+                .as_str()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+        assert_eq!(enum_values(&schema["$defs"]["EdgeType"]), edge_types);
+    }
+
+    #[test]
+    fn test_schema_declares_every_serialized_field() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        let mut node = Node::new(
+            "a.go:f:1".to_string(),
+            "f".to_string(),
+            NodeType::Function,
+            PathBuf::from("a.go"),
+            1,
+            3,
+            "main".to_string(),
+            "func f(x int)".to_string(),
+        );
+        node.parameters.push(Parameter {
+            name: "x".to_string(),
+            param_type: "int".to_string(),
+        });
+        node.documentation = Some("f does things".to_string());
+        graph.add_node(node);
+        let mut edge = Edge::new(
+            "a.go:f:1".to_string(),
+            "f".to_string(),
+            EdgeType::Calls,
+            "f(1)".to_string(),
+            PathBuf::from("a.go"),
+            2,
+        );
+        edge.to_id = Some("a.go:f:1".to_string());
+        graph.add_edge(edge);
+        graph.track_file_metadata(&PathBuf::from("a.go"), "t".to_string());
+
+        let schema = json_schema();
+        let value = serde_json::to_value(&graph).unwrap();
+        for key in schema["required"].as_array().unwrap() {
+            assert!(value.get(key.as_str().unwrap()).is_some());
+        }
+
+        for (name, instance) in [
+            ("GraphMetadata", &value["metadata"]),
+            ("GraphStats", &value["metadata"]["stats"]),
+            ("FileMetadata", &value["metadata"]["file_metadata"]["a.go"]),
+            ("Node", &value["nodes"][0]),
+            ("Parameter", &value["nodes"][0]["parameters"][0]),
+            ("Edge", &value["edges"][0]),
+        ] {
+            assert!(
+                undeclared_keys(&schema, name, instance).is_empty(),
+                "{}: {:?}",
+                name,
+                undeclared_keys(&schema, name, instance)
+            );
+            for key in schema["$defs"][name]["required"].as_array().unwrap() {
+                assert!(
+                    instance.get(key.as_str().unwrap()).is_some(),
+                    "{} missing {}",
+                    name,
+                    key
+                );
+            }
+        }
+    }
+}
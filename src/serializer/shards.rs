@@ -0,0 +1,248 @@
+use super::fast_compressed;
+use crate::core::{CodeGraph, Edge};
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+
+/// Manifest file name inside a shards directory
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Index of a sharded graph, written by `index --shard-by package`.
+///
+/// Each shard holds the nodes of one top-level package and the edges that
+/// start there. Edges crossing into another shard are also recorded in the
+/// target's shard, so callers of a function can be found from its shard
+/// alone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardManifest {
+    pub shard_by: String,
+    pub language: String,
+    pub root_path: String,
+    pub shards: Vec<ShardEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShardEntry {
+    /// Top-level package, see [`shard_key`]
+    pub key: String,
+    /// Graph file, relative to the shards directory
+    pub file: String,
+    pub nodes: usize,
+    pub edges: usize,
+    /// Node names in the shard, sorted, for picking shards by name
+    pub names: Vec<String>,
+}
+
+impl ShardManifest {
+    pub fn load(dir: &Path) -> Result<Self> {
+        let path = dir.join(MANIFEST_FILE);
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read shard manifest {}", path.display()))?;
+        serde_json::from_str(&json)
+            .with_context(|| format!("Invalid shard manifest {}", path.display()))
+    }
+
+    /// The shard holding `package`, if any
+    pub fn shard_for_package(&self, package: &str) -> Option<&ShardEntry> {
+        let key = shard_key(package);
+        self.shards.iter().find(|shard| shard.key == key)
+    }
+
+    /// Shards with at least one node called `name`
+    pub fn shards_with_name(&self, name: &str) -> Vec<&ShardEntry> {
+        self.shards
+            .iter()
+            .filter(|shard| {
+                shard
+                    .names
+                    .binary_search_by(|n| n.as_str().cmp(name))
+                    .is_ok()
+            })
+            .collect()
+    }
+}
+
+/// Top-level package: the first segment of `a/b`, `a.b` or `a::b`;
+/// `_root` for nodes without a package
+pub fn shard_key(package: &str) -> &str {
+    let first = package
+        .split(['/', '.', ':'])
+        .find(|segment| !segment.is_empty())
+        .unwrap_or("");
+    if first.is_empty() {
+        "_root"
+    } else {
+        first
+    }
+}
+
+/// Write one graph file per top-level package plus [`MANIFEST_FILE`] into
+/// `dir`. Edges not starting at a node (imports) go to the shard of the
+/// nodes in their file; those from files without nodes are dropped.
+pub fn save_shards(graph: &CodeGraph, dir: &Path) -> Result<ShardManifest> {
+    std::fs::create_dir_all(dir)
+        .with_context(|| format!("Failed to create shards directory {}", dir.display()))?;
+
+    let mut shards: BTreeMap<&str, CodeGraph> = BTreeMap::new();
+    let mut key_by_id: HashMap<&str, &str> = HashMap::new();
+    let mut key_by_file: HashMap<String, &str> = HashMap::new();
+    for node in &graph.nodes {
+        let key = shard_key(&node.package);
+        key_by_id.insert(&node.id, key);
+        key_by_file
+            .entry(node.file_path.to_string_lossy().to_string())
+            .or_insert(key);
+        shards
+            .entry(key)
+            .or_insert_with(|| empty_like(graph))
+            .add_node(node.clone());
+    }
+
+    for edge in &graph.edges {
+        let Some(&from_key) = key_by_id
+            .get(edge.from.as_str())
+            .or_else(|| key_by_file.get(edge.file_path.to_string_lossy().as_ref()))
+        else {
+            continue;
+        };
+        let mut keys = vec![from_key];
+        for to_key in target_keys(graph, edge, &key_by_id) {
+            if !keys.contains(&to_key) {
+                keys.push(to_key);
+            }
+        }
+        for key in keys {
+            if let Some(shard) = shards.get_mut(key) {
+                shard.add_edge(edge.clone());
+            }
+        }
+    }
+
+    let mut entries = Vec::with_capacity(shards.len());
+    let mut used_files = HashSet::new();
+    for (key, mut shard) in shards {
+        let files: HashSet<String> = shard
+            .nodes
+            .iter()
+            .map(|node| node.file_path.to_string_lossy().to_string())
+            .collect();
+        shard.metadata.file_metadata = graph
+            .metadata
+            .file_metadata
+            .iter()
+            .filter(|(path, _)| files.contains(*path))
+            .map(|(path, meta)| (path.clone(), meta.clone()))
+            .collect();
+        shard.metadata.generated_files = graph
+            .metadata
+            .generated_files
+            .intersection(&files)
+            .cloned()
+            .collect();
+        shard.metadata.stats.files_parsed = files.len();
+
+        let base = sanitize(key);
+        let mut file = format!("{}.bin", base);
+        let mut suffix = 2;
+        while !used_files.insert(file.clone()) {
+            file = format!("{}-{}.bin", base, suffix);
+            suffix += 1;
+        }
+        fast_compressed::save_to_file(&shard, &dir.join(&file).to_string_lossy())?;
+
+        let mut names: Vec<String> = shard.nodes.iter().map(|n| n.name.clone()).collect();
+        names.sort();
+        names.dedup();
+        entries.push(ShardEntry {
+            key: key.to_string(),
+            file,
+            nodes: shard.nodes.len(),
+            edges: shard.edges.len(),
+            names,
+        });
+    }
+
+    let manifest = ShardManifest {
+        shard_by: "package".to_string(),
+        language: graph.metadata.language.clone(),
+        root_path: graph.metadata.root_path.clone(),
+        shards: entries,
+    };
+    let path = dir.join(MANIFEST_FILE);
+    std::fs::write(&path, serde_json::to_string_pretty(&manifest)?)
+        .with_context(|| format!("Failed to write shard manifest {}", path.display()))?;
+    Ok(manifest)
+}
+
+/// Load the shards `select` picks and merge them into one graph. Edges
+/// recorded in both shards of a cross-package call are kept once.
+pub fn load_shards(
+    dir: &Path,
+    manifest: &ShardManifest,
+    select: impl Fn(&ShardEntry) -> bool,
+) -> Result<CodeGraph> {
+    let mut graph = CodeGraph::new(manifest.root_path.clone(), manifest.language.clone());
+    let mut seen_edges = HashSet::new();
+    for entry in manifest.shards.iter().filter(|entry| select(entry)) {
+        let path = dir.join(&entry.file);
+        let mut shard = fast_compressed::load_from_file(&path.to_string_lossy())
+            .with_context(|| format!("Failed to load shard {}", path.display()))?;
+        shard.edges.retain(|edge| seen_edges.insert(edge_key(edge)));
+        graph.merge(shard);
+    }
+    graph.metadata.stats.total_nodes = graph.nodes.len();
+    graph.metadata.stats.total_edges = graph.edges.len();
+    Ok(graph)
+}
+
+/// Shards of the nodes an edge may point at: its resolved target, or every
+/// node with the callee's name
+fn target_keys<'a>(
+    graph: &CodeGraph,
+    edge: &Edge,
+    key_by_id: &HashMap<&str, &'a str>,
+) -> Vec<&'a str> {
+    match edge.to_id.as_deref() {
+        Some(id) => key_by_id.get(id).copied().into_iter().collect(),
+        None => graph
+            .get_nodes_by_name(&edge.to)
+            .iter()
+            .filter_map(|node| key_by_id.get(node.id.as_str()).copied())
+            .collect(),
+    }
+}
+
+fn edge_key(edge: &Edge) -> (String, String, String, String, usize) {
+    (
+        edge.from.clone(),
+        edge.to.clone(),
+        format!("{:?}", edge.edge_type),
+        edge.file_path.to_string_lossy().to_string(),
+        edge.line,
+    )
+}
+
+/// A graph with `graph`'s metadata and no nodes or edges
+fn empty_like(graph: &CodeGraph) -> CodeGraph {
+    let mut shard = CodeGraph::new(
+        graph.metadata.root_path.clone(),
+        graph.metadata.language.clone(),
+    );
+    shard.metadata.generated_at = graph.metadata.generated_at.clone();
+    shard.metadata.git_commit_hash = graph.metadata.git_commit_hash.clone();
+    shard
+}
+
+/// File-name-safe shard key
+fn sanitize(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,59 @@
+/// Render values as a bash array assignment, e.g. `CODENAV_RESULTS=('a' 'b')`,
+/// suitable for `eval` in scripts
+pub fn to_shell_array<S: AsRef<str>>(var_name: &str, values: &[S]) -> String {
+    let quoted: Vec<String> = values.iter().map(|v| shell_quote(v.as_ref())).collect();
+    format!("{}=({})", var_name, quoted.join(" "))
+}
+
+/// Single-quote a value for POSIX shells. Embedded single quotes are
+/// written as `'\''` (close quote, escaped quote, reopen quote).
+pub fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shell_array_syntax() {
+        let names = ["handleLogin", "it's", "$(rm -rf /)", "a b"];
+        let output = to_shell_array("CODENAV_RESULTS", &names);
+
+        assert_eq!(
+            output,
+            r"CODENAV_RESULTS=('handleLogin' 'it'\''s' '$(rm -rf /)' 'a b')"
+        );
+        assert_eq!(
+            to_shell_array::<&str>("CODENAV_RESULTS", &[]),
+            "CODENAV_RESULTS=()"
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_shell_array_roundtrip() {
+        let names = [
+            "handleLogin",
+            "it's",
+            "$(echo injected)",
+            "a b",
+            "back\\slash",
+        ];
+        let script = format!(
+            "{}\nprintf '%s\\n' \"${{CODENAV_RESULTS[@]}}\"",
+            to_shell_array("CODENAV_RESULTS", &names)
+        );
+
+        let output = std::process::Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .output()
+            .expect("bash is required for this test");
+        assert!(output.status.success());
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let roundtrip: Vec<&str> = stdout.lines().collect();
+        assert_eq!(roundtrip, names);
+    }
+}
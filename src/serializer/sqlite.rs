@@ -0,0 +1,182 @@
+use crate::core::CodeGraph;
+use anyhow::{Context, Result};
+use rusqlite::{params, Connection};
+use std::path::Path;
+
+/// Tables mirror the CSV export (`nodes`, `edges`), plus `parameters` with
+/// one row per function parameter. `from`, `to` and `type` are SQL keywords
+/// and must be quoted in queries, e.g.
+/// `SELECT "to", COUNT(*) FROM edges GROUP BY "to" ORDER BY 2 DESC`.
+const SCHEMA: &str = r#"
+CREATE TABLE nodes (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    type TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    line INTEGER NOT NULL,
+    end_line INTEGER NOT NULL,
+    package TEXT NOT NULL,
+    signature TEXT NOT NULL
+);
+CREATE TABLE edges (
+    "from" TEXT NOT NULL,
+    "to" TEXT NOT NULL,
+    type TEXT NOT NULL,
+    call_site TEXT NOT NULL,
+    file_path TEXT NOT NULL,
+    line INTEGER NOT NULL
+);
+CREATE TABLE parameters (
+    node_id TEXT NOT NULL REFERENCES nodes(id),
+    position INTEGER NOT NULL,
+    name TEXT NOT NULL,
+    param_type TEXT NOT NULL
+);
+"#;
+
+/// Indexes for the common lookups and `GROUP BY` hotspot queries; created
+/// after the bulk insert
+const INDEXES: &str = r#"
+CREATE INDEX idx_nodes_name ON nodes(name);
+CREATE INDEX idx_nodes_package ON nodes(package);
+CREATE INDEX idx_edges_to ON edges("to");
+CREATE INDEX idx_parameters_node_id ON parameters(node_id);
+"#;
+
+/// Write the graph to a new SQLite database, replacing `output_path`
+pub fn save_to_file(graph: &CodeGraph, output_path: &Path) -> Result<()> {
+    if output_path.exists() {
+        std::fs::remove_file(output_path)
+            .with_context(|| format!("Failed to replace {}", output_path.display()))?;
+    }
+
+    let mut conn = Connection::open(output_path)
+        .with_context(|| format!("Failed to create {}", output_path.display()))?;
+    conn.execute_batch(SCHEMA)?;
+
+    let tx = conn.transaction()?;
+    {
+        let mut insert_node = tx.prepare(
+            "INSERT INTO nodes (id, name, type, file_path, line, end_line, package, signature)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?;
+        let mut insert_parameter = tx.prepare(
+            "INSERT INTO parameters (node_id, position, name, param_type)
+             VALUES (?1, ?2, ?3, ?4)",
+        )?;
+        for node in &graph.nodes {
+            insert_node
+                .execute(params![
+                    node.id,
+                    node.name,
+                    node.node_type.to_string(),
+                    node.file_path.display().to_string(),
+                    node.line,
+                    node.end_line,
+                    node.package,
+                    node.signature,
+                ])
+                .with_context(|| format!("Failed to insert node {}", node.id))?;
+            for (position, parameter) in node.parameters.iter().enumerate() {
+                insert_parameter.execute(params![
+                    node.id,
+                    position,
+                    parameter.name,
+                    parameter.param_type,
+                ])?;
+            }
+        }
+
+        let mut insert_edge = tx.prepare(
+            r#"INSERT INTO edges ("from", "to", type, call_site, file_path, line)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+        )?;
+        for edge in &graph.edges {
+            insert_edge.execute(params![
+                edge.from,
+                edge.to,
+                format!("{:?}", edge.edge_type),
+                edge.call_site,
+                edge.file_path.display().to_string(),
+                edge.line,
+            ])?;
+        }
+    }
+    tx.commit()?;
+
+    conn.execute_batch(INDEXES)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Edge, EdgeType, Node, NodeType, Parameter};
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_sqlite_export_row_counts_and_hotspot_query() {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        for (id, name) in [
+            ("main.go:main:1", "main"),
+            ("main.go:run:5", "run"),
+            ("main.go:log:9", "log"),
+        ] {
+            let mut node = Node::new(
+                id.to_string(),
+                name.to_string(),
+                NodeType::Function,
+                PathBuf::from("main.go"),
+                1,
+                3,
+                "main".to_string(),
+                format!("func {}(ctx context.Context)", name),
+            );
+            node.parameters.push(Parameter {
+                name: "ctx".to_string(),
+                param_type: "context.Context".to_string(),
+            });
+            graph.add_node(node);
+        }
+        for (from, to) in [
+            ("main.go:main:1", "run"),
+            ("main.go:main:1", "log"),
+            ("main.go:run:5", "log"),
+        ] {
+            graph.add_edge(Edge::new(
+                from.to_string(),
+                to.to_string(),
+                EdgeType::Calls,
+                format!("{}(ctx)", to),
+                PathBuf::from("main.go"),
+                2,
+            ));
+        }
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("graph.db");
+        save_to_file(&graph, &path).unwrap();
+        // Exporting again replaces the database
+        save_to_file(&graph, &path).unwrap();
+
+        let conn = Connection::open(&path).unwrap();
+        let count = |table: &str| -> usize {
+            conn.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+                row.get(0)
+            })
+            .unwrap()
+        };
+        assert_eq!(count("nodes"), graph.nodes.len());
+        assert_eq!(count("edges"), graph.edges.len());
+        assert_eq!(count("parameters"), 3);
+
+        let (hotspot, callers): (String, usize) = conn
+            .query_row(
+                r#"SELECT "to", COUNT(*) FROM edges GROUP BY "to" ORDER BY 2 DESC LIMIT 1"#,
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!((hotspot.as_str(), callers), ("log", 2));
+    }
+}
@@ -0,0 +1,117 @@
+use crate::core::CodeGraph;
+use anyhow::{Context, Result};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use super::{fast_compressed, jsonl};
+
+/// Graph path meaning stdin (when loading) or stdout (when saving)
+pub const STDIO_PATH: &str = "-";
+
+/// Whether a graph path refers to stdin/stdout rather than a file
+pub fn is_stdio(path: &Path) -> bool {
+    path.as_os_str() == STDIO_PATH
+}
+
+/// Load a graph from a byte stream, detecting the format from its content:
+/// a JSON document, JSONL (first line is a `"type"` record) or, otherwise,
+/// the default JSON+LZ4 binary format. A binary graph's length prefix can
+/// start with `{` too, so input that isn't valid JSON is retried as binary.
+pub fn load_from_reader(mut reader: impl Read) -> Result<CodeGraph> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .context("Failed to read graph stream")?;
+
+    let text_start = bytes.iter().position(|b| !b.is_ascii_whitespace());
+    if text_start.is_some_and(|i| bytes[i] == b'{') {
+        let first_line = bytes.split(|&b| b == b'\n').next().unwrap_or_default();
+        let is_jsonl = serde_json::from_slice::<serde_json::Value>(first_line)
+            .is_ok_and(|v| v.get("type").is_some_and(|t| t.is_string()));
+
+        if is_jsonl {
+            return jsonl::load_from_jsonl_reader(bytes.as_slice());
+        }
+
+        return match serde_json::from_slice::<CodeGraph>(&bytes) {
+            Ok(mut graph) => {
+                graph.build_indexes();
+                Ok(graph)
+            }
+            Err(json_err) => fast_compressed::from_bytes(&bytes)
+                .map_err(|_| json_err)
+                .context("Failed to parse JSON graph stream"),
+        };
+    }
+
+    fast_compressed::from_bytes(&bytes)
+}
+
+/// Write a graph to a byte stream in the default JSON+LZ4 binary format
+pub fn save_to_writer(graph: &CodeGraph, mut writer: impl Write) -> Result<()> {
+    writer.write_all(&fast_compressed::to_bytes(graph)?)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::{Node, NodeType};
+    use std::path::PathBuf;
+
+    fn sample_graph() -> CodeGraph {
+        let mut graph = CodeGraph::new("/test".to_string(), "go".to_string());
+        graph.add_node(Node::new(
+            "main.go:handleLogin:3".to_string(),
+            "handleLogin".to_string(),
+            NodeType::Function,
+            PathBuf::from("main.go"),
+            3,
+            9,
+            "main".to_string(),
+            "func handleLogin()".to_string(),
+        ));
+        graph
+    }
+
+    #[test]
+    fn test_stream_roundtrip_and_query() {
+        let mut piped = Vec::new();
+        save_to_writer(&sample_graph(), &mut piped).unwrap();
+
+        let loaded = load_from_reader(piped.as_slice()).unwrap();
+        let found = loaded.get_nodes_by_name("handleLogin");
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].line, 3);
+    }
+
+    #[test]
+    fn test_stream_detects_json_documents() {
+        let json = serde_json::to_vec_pretty(&sample_graph()).unwrap();
+
+        let loaded = load_from_reader(json.as_slice()).unwrap();
+        assert_eq!(loaded.get_nodes_by_name("handleLogin").len(), 1);
+    }
+
+    #[test]
+    fn test_stream_binary_starting_with_brace() {
+        // The LZ4 length prefix is little-endian: pad the graph until its
+        // JSON is 0x7B (`{`) bytes long modulo 256
+        let mut graph = sample_graph();
+        let mut piped = Vec::new();
+        while piped.first() != Some(&b'{') {
+            graph.metadata.root_path.push('x');
+            piped = fast_compressed::to_bytes(&graph).unwrap();
+        }
+
+        let loaded = load_from_reader(piped.as_slice()).unwrap();
+        assert_eq!(loaded.get_nodes_by_name("handleLogin").len(), 1);
+    }
+
+    #[test]
+    fn test_stdio_path() {
+        assert!(is_stdio(Path::new("-")));
+        assert!(!is_stdio(Path::new("codenav.bin")));
+    }
+}
@@ -0,0 +1,114 @@
+use anyhow::{Context, Result};
+use globset::GlobBuilder;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::core::CodeGraph;
+
+/// Node metadata keys written by the parsers and enrichment passes; a sidecar
+/// may not set them, since later passes read them back
+pub const RESERVED_KEYS: &[&str] = &[
+    "receiver",
+    "class",
+    "trait",
+    "template_params",
+    "max_nesting",
+    "fan_in",
+    "fan_out",
+    "method_key",
+    "method_set",
+    "embeds",
+    "body_hash",
+    "last_commit",
+    "last_author",
+];
+
+/// Organizational metadata (ownership, criticality, ...) kept outside the source
+/// tree and merged into node metadata at index time.
+///
+/// ```json
+/// {
+///   "files": { "billing/**": { "owner": "payments-team" } },
+///   "nodes": { "billing.Charge": { "criticality": "high" } }
+/// }
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct SidecarMetadata {
+    /// File glob → key/values applied to every node defined in matching files
+    #[serde(default)]
+    pub files: BTreeMap<String, HashMap<String, String>>,
+
+    /// Qualified name (`package.name`) or node ID → key/values for that node
+    #[serde(default)]
+    pub nodes: BTreeMap<String, HashMap<String, String>>,
+}
+
+impl SidecarMetadata {
+    /// Load a sidecar metadata file (JSON)
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .context(format!("Failed to read metadata file: {}", path.display()))?;
+        serde_json::from_str(&content)
+            .context(format!("Failed to parse metadata file: {}", path.display()))
+    }
+
+    /// Merge sidecar entries into matching nodes. File globs are applied first
+    /// so that per-node entries can override them. Globs are matched against
+    /// both the stored path and the path relative to the graph root, and `*`
+    /// does not cross `/` (use `**` for that).
+    /// Returns the number of nodes that received metadata.
+    pub fn apply(&self, graph: &mut CodeGraph) -> Result<usize> {
+        for (entry, values) in self.files.iter().chain(&self.nodes) {
+            if let Some(key) = values.keys().find(|k| RESERVED_KEYS.contains(&k.as_str())) {
+                anyhow::bail!("Metadata file entry {} sets reserved key '{}'", entry, key);
+            }
+        }
+
+        let file_matchers = self
+            .files
+            .iter()
+            .map(|(pattern, values)| {
+                let matcher = GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .context(format!("Invalid file glob in metadata file: {}", pattern))?
+                    .compile_matcher();
+                Ok((matcher, values))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let root = Path::new(&graph.metadata.root_path).to_path_buf();
+        let mut enriched = 0;
+
+        for node in &mut graph.nodes {
+            let relative = node.file_path.strip_prefix(&root).ok();
+            let mut touched = false;
+
+            for (matcher, values) in &file_matchers {
+                if matcher.is_match(&node.file_path)
+                    || relative.is_some_and(|r| matcher.is_match(r))
+                {
+                    node.metadata
+                        .extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+                    touched = true;
+                }
+            }
+
+            let qualified_name = format!("{}.{}", node.package, node.name);
+            for key in [&qualified_name, &node.id] {
+                if let Some(values) = self.nodes.get(key.as_str()) {
+                    node.metadata
+                        .extend(values.iter().map(|(k, v)| (k.clone(), v.clone())));
+                    touched = true;
+                }
+            }
+
+            if touched {
+                enriched += 1;
+            }
+        }
+
+        Ok(enriched)
+    }
+}
@@ -0,0 +1,32 @@
+package server
+
+import (
+	"log"
+	"net/http"
+)
+
+// Health reports liveness
+func Health(w http.ResponseWriter, r *http.Request) {
+	w.WriteHeader(http.StatusOK)
+}
+
+// Logging logs every request before passing it on
+func Logging(next http.Handler) http.Handler {
+	return http.HandlerFunc(func(w http.ResponseWriter, r *http.Request) {
+		log.Println(r.URL.Path)
+		next.ServeHTTP(w, r)
+	})
+}
+
+// API serves the versioned API
+type API struct{}
+
+// ServeHTTP makes API an http.Handler
+func (a *API) ServeHTTP(w http.ResponseWriter, r *http.Request) {
+	Health(w, r)
+}
+
+// Routes wires the handlers together
+func Routes() {
+	http.Handle("/", Logging(&API{}))
+}
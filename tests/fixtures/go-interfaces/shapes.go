@@ -0,0 +1,38 @@
+package shapes
+
+import "math"
+
+type Shape interface {
+	Area() float64
+	Scale(x, y float64) Shape
+}
+
+type Named interface {
+	Shape
+	Name() string
+}
+
+type Circle struct {
+	R float64
+}
+
+func (c Circle) Area() float64 {
+	return math.Pi * c.R * c.R
+}
+
+func (c *Circle) Scale(x float64, y float64) Shape {
+	return &Circle{R: c.R * x}
+}
+
+func (c Circle) Name() string {
+	return "circle"
+}
+
+// Square has no Scale, so it satisfies neither interface
+type Square struct {
+	Side float64
+}
+
+func (s Square) Area() float64 {
+	return s.Side * s.Side
+}
@@ -0,0 +1,27 @@
+package transport
+
+// Server answers requests
+type Server struct {
+	name string
+}
+
+// Client sends requests
+type Client struct {
+	server *Server
+}
+
+// Handle serves one request
+func (s *Server) Handle(req string) string {
+	return s.name + req
+}
+
+// Handle sends one request
+func (c *Client) Handle(req string) string {
+	return req
+}
+
+// Forward hands a request to the client, then to the server
+func (s *Server) Forward(c *Client, req string) string {
+	c.Handle(req)
+	return s.Handle(req)
+}
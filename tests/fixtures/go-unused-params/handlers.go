@@ -0,0 +1,28 @@
+package handlers
+
+import "context"
+
+type Store struct{}
+
+// Get ignores ctx; the key is all it needs
+func (s *Store) Get(ctx context.Context, key string) string {
+	return lookup(key)
+}
+
+// Put uses every parameter
+func (s *Store) Put(ctx context.Context, key string, value string) error {
+	return save(ctx, key, value)
+}
+
+// Close follows the blank-identifier convention for its unused argument
+func Close(_ context.Context, force bool) {
+	if force {
+		shutdown()
+	}
+}
+
+func lookup(key string) string { return key }
+
+func save(_ context.Context, _, _ string) error { return nil }
+
+func shutdown() {}
@@ -0,0 +1,33 @@
+from fastapi import FastAPI
+import pytest
+
+app = FastAPI()
+
+
+@app.get("/x")
+def read_x():
+    return load_x()
+
+
+@app.route("/items", methods=["POST"])
+def create_item():
+    return {}
+
+
+def load_x():
+    return {"x": 1}
+
+
+@pytest.fixture
+def client():
+    return app
+
+
+class Config:
+    @staticmethod
+    def default():
+        return Config()
+
+    @property
+    def name(self):
+        return "config"
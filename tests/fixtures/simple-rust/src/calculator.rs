@@ -0,0 +1,46 @@
+//! A simple calculator used to exercise the Rust parser
+
+pub struct Calculator {
+    name: String,
+}
+
+impl Calculator {
+    /// Creates a new calculator
+    pub fn new(name: &str) -> Self {
+        Calculator {
+            name: name.to_string(),
+        }
+    }
+
+    /// Adds two numbers and logs the result
+    pub fn add(&self, a: i32, b: i32) -> i32 {
+        let result = a + b;
+        self.log_operation("add", result);
+        result
+    }
+
+    fn log_operation(&self, op: &str, result: i32) {
+        println!("{}: {} = {}", self.name, op, result);
+    }
+}
+
+impl std::fmt::Display for Calculator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Calculator({})", self.name)
+    }
+}
+
+pub fn run() -> i32 {
+    let calc = Calculator::new("basic");
+    helpers::double(calc.add(1, 2))
+}
+
+mod helpers {
+    pub fn double(x: i32) -> i32 {
+        if x > 0 && x < 100 {
+            x * 2
+        } else {
+            0
+        }
+    }
+}
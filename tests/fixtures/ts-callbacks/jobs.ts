@@ -0,0 +1,19 @@
+function doThing(x: number): number {
+  return x * 2;
+}
+
+function render(x: number): string {
+  return x.toFixed(2);
+}
+
+export function processAll(items: number[]): number[] {
+  return items.map((x) => doThing(x));
+}
+
+export function report(items: number[]): string[] {
+  const format = (x: number) => render(x);
+  items.forEach(function (x) {
+    doThing(x);
+  });
+  return items.map(format);
+}
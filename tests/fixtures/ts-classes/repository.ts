@@ -0,0 +1,27 @@
+import * as lifecycle from "./lifecycle";
+
+export interface Repository<T> {
+  find(id: string): T | undefined;
+}
+
+export interface User {
+  id: string;
+  name: string;
+}
+
+export abstract class BaseRepository<T> {
+  protected items = new Map<string, T>();
+}
+
+export class UserRepository
+  extends BaseRepository<User>
+  implements Repository<User>, lifecycle.Disposable
+{
+  find(id: string): User | undefined {
+    return this.items.get(id);
+  }
+
+  dispose(): void {
+    this.items.clear();
+  }
+}
@@ -0,0 +1,16 @@
+export enum Color {
+  Red = "red",
+  Green = "green",
+}
+
+export type Point = { x: number; y: number };
+
+export namespace Geometry {
+  export function distance(a: Point, b: Point): number {
+    return Math.sqrt((a.x - b.x) ** 2 + (a.y - b.y) ** 2);
+  }
+}
+
+export function paint(color: Color): string {
+  return Geometry.distance({ x: 0, y: 0 }, { x: 1, y: 1 }).toFixed(2) + color;
+}